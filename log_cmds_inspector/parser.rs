@@ -0,0 +1,300 @@
+// Copyright 2022 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use chrono::{DateTime, Utc};
+use eyre::{bail, Result, WrapErr};
+use regex::Regex;
+use safe_network::types::log_markers::LogMarker;
+use serde::Serialize;
+use std::{
+    fs::File,
+    io::{BufRead, BufReader},
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+use strum::IntoEnumIterator;
+use walkdir::WalkDir;
+
+// Node log files are named after this, followed by the date they were rotated at
+const LOG_FILE_PREFIX: &str = "sn_node.log";
+// Separates the spans of a log entry from each other, and from the entry's message
+const SPAN_SEPARATOR: &str = "\t ➤ ";
+
+// A cmd/sub-cmd id e.g. "963111461", "963111461.0"
+pub type CmdId = String;
+//  A msg id, e.g. "68fe..b776"
+pub type MsgId = String;
+//  A nodes id, as per log folder, eg sn-node-14
+pub type NodeId = String;
+
+/// A log entry carrying a `LogMarker`, with the details we could parse out of it.
+#[derive(Clone, Debug, Serialize)]
+pub struct LogEntry {
+    /// Node which logged the entry
+    pub node: NodeId,
+    /// Log file the entry was found in
+    pub logfile: PathBuf,
+    /// Line of the log file the entry starts at
+    pub line: u64,
+    /// When the entry was logged, if the timestamp could be parsed
+    pub time: Option<DateTime<Utc>>,
+    /// Level the entry was logged at, e.g. "TRACE"
+    pub level: String,
+    /// Marker found in the entry's message
+    #[serde(serialize_with = "serialize_marker")]
+    pub marker: LogMarker,
+    /// Id of the cmd the entry was logged for, if any
+    pub cmd_id: Option<CmdId>,
+    /// Id of the msg the entry was logged for, if any
+    pub msg_id: Option<MsgId>,
+    /// The entry's message
+    pub message: String,
+}
+
+impl LogEntry {
+    /// Root id of the cmd the entry was logged for, if any
+    pub fn root_cmd_id(&self) -> Option<CmdId> {
+        self.cmd_id.as_deref().map(get_root_cmd_id)
+    }
+}
+
+fn serialize_marker<S: serde::Serializer>(
+    marker: &LogMarker,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&marker.to_string())
+}
+
+// Given a cmd id, return the root id, e.g. the root cmd id of 'abc.1.0.2' is 'abc'.
+pub fn get_root_cmd_id(cmd_id: &str) -> CmdId {
+    let mut root_cmd_id = cmd_id.to_string();
+    root_cmd_id.truncate(cmd_id.find('.').unwrap_or(cmd_id.len()));
+    root_cmd_id
+}
+
+/// Parses log entries out of the lines written by `safe_network::LogFormatter`, keeping those
+/// carrying a `LogMarker`.
+pub struct LogParser {
+    header: Regex,
+    marker: Regex,
+    cmd_id: Regex,
+    cmd_process_id: Regex,
+    msg_id: Regex,
+}
+
+impl LogParser {
+    pub fn new() -> Result<Self> {
+        // Longest names first, so that a marker isn't mistaken for another one it's a prefix of
+        let mut markers: Vec<_> = LogMarker::iter().map(|marker| marker.to_string()).collect();
+        markers.sort_by_key(|marker| std::cmp::Reverse(marker.len()));
+
+        Ok(Self {
+            header: Regex::new(r"^ (TRACE|DEBUG|INFO|WARN|ERROR) (\S+) \[.*\]:$")?,
+            marker: Regex::new(&format!(r"\b({})\b", markers.join("|")))?,
+            cmd_id: Regex::new(r"cmd_id=([0-9.]+)")?,
+            cmd_process_id: Regex::new(r#"(?:CmdProcessStart|CmdProcessEnd) "([0-9.]+)""#)?,
+            msg_id: Regex::new(r"MsgId\(([0-9a-f.]+)\)")?,
+        })
+    }
+
+    /// Parses the entries logged by the given node into the given log file
+    pub fn parse<R: BufRead>(
+        &self,
+        node: &str,
+        logfile: &Path,
+        reader: R,
+    ) -> Result<Vec<LogEntry>> {
+        let mut entries = vec![];
+        // Header of the entry being read, and the lines read of it so far
+        let mut current: Option<(u64, String, String)> = None;
+        let mut body = String::new();
+
+        for (index, line) in reader.lines().enumerate() {
+            let line = line.wrap_err_with(|| format!("Failed to read {}", logfile.display()))?;
+            if let Some(captures) = self.header.captures(&line) {
+                if let Some((line_num, level, time)) = current.take() {
+                    entries.extend(self.entry(node, logfile, line_num, level, &time, &body));
+                }
+                current = Some((
+                    index as u64 + 1,
+                    captures[1].to_string(),
+                    captures[2].to_string(),
+                ));
+                body.clear();
+            } else if current.is_some() {
+                body.push_str(&line);
+                body.push('\n');
+            }
+        }
+        if let Some((line_num, level, time)) = current {
+            entries.extend(self.entry(node, logfile, line_num, level, &time, &body));
+        }
+
+        Ok(entries)
+    }
+
+    fn entry(
+        &self,
+        node: &str,
+        logfile: &Path,
+        line: u64,
+        level: String,
+        time: &str,
+        body: &str,
+    ) -> Option<LogEntry> {
+        // Spans come first, each followed by a separator, and the message last
+        let (spans, message) = body.rsplit_once(SPAN_SEPARATOR).unwrap_or(("", body));
+        let message = message.trim_end().to_string();
+
+        let marker = self.marker.find(&message)?;
+        let marker = LogMarker::from_str(marker.as_str()).ok()?;
+
+        // The innermost cmd id is the one the entry is about
+        let cmd_id = self
+            .cmd_process_id
+            .captures(&message)
+            .or_else(|| self.cmd_id.captures_iter(&message).last())
+            .or_else(|| self.cmd_id.captures_iter(spans).last())
+            .map(|captures| captures[1].to_string());
+        let msg_id = self
+            .msg_id
+            .captures(&message)
+            .or_else(|| self.msg_id.captures_iter(spans).last())
+            .map(|captures| captures[1].to_string());
+
+        Some(LogEntry {
+            node: node.to_string(),
+            logfile: logfile.to_path_buf(),
+            line,
+            time: DateTime::parse_from_rfc3339(time)
+                .ok()
+                .map(|time| time.with_timezone(&Utc)),
+            level,
+            marker,
+            cmd_id,
+            msg_id,
+            message,
+        })
+    }
+}
+
+/// Parses the log files of all the nodes found at the given path, returning their entries
+/// carrying a `LogMarker`, ordered by the time they were logged at.
+pub fn parse_logs(path: &Path) -> Result<Vec<LogEntry>> {
+    let parser = LogParser::new()?;
+    let mut entries = vec![];
+
+    for result in WalkDir::new(path) {
+        let dent = match result {
+            Ok(dent) => dent,
+            Err(err) => {
+                bail!(err)
+            }
+        };
+
+        let is_log_file = dent
+            .file_name()
+            .to_str()
+            .map(|name| name.starts_with(LOG_FILE_PREFIX))
+            .unwrap_or(false);
+        if !dent.file_type().is_file() || !is_log_file {
+            continue;
+        }
+
+        // Nodes log to files within their own dir, named after the node
+        let node_name = dent
+            .path()
+            .parent()
+            .and_then(|dir| dir.file_name())
+            .and_then(|name| name.to_str())
+            .unwrap_or_default()
+            .to_string();
+
+        let file = File::open(dent.path())
+            .wrap_err_with(|| format!("Failed to open {}", dent.path().display()))?;
+        entries.extend(parser.parse(&node_name, dent.path(), BufReader::new(file))?);
+    }
+
+    // Entries without a timestamp are kept in the order they were read in
+    entries.sort_by_key(|entry| entry.time);
+
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{get_root_cmd_id, LogParser};
+    use eyre::Result;
+    use safe_network::types::log_markers::LogMarker;
+    use std::path::Path;
+
+    const LOG: &str = " TRACE 2022-03-10T12:00:00.000100Z [sn/src/node/api/dispatcher.rs:L104]:
+\t ➤ handle_message{name=abc sender=x msg_id=MsgId(68fe..b776)}
+\t ➤ CmdHandlingSpawned HandleMsg MsgId(68fe..b776) cmd_id=963111461.0
+ DEBUG 2022-03-10T12:00:00.000200Z [sn/src/node/core/mod.rs:L1]:
+\t ➤ Not a marker in here
+ TRACE 2022-03-10T12:00:00.000300Z [sn/src/node/api/dispatcher.rs:L312]:
+\t ➤ process_cmd{name=abc cmd_id=963111461.0 cmd=HandleMsg MsgId(68fe..b776)}
+\t ➤ CmdProcessStart \"963111461.0\" - HandleMsg MsgId(68fe..b776)
+ ERROR 2022-03-10T12:00:00.000400Z [sn/src/node/api/dispatcher.rs:L337]:
+\t ➤ process_cmd{name=abc cmd_id=963111461.1 cmd=SendMsg}
+\t ➤ CmdProcessingError SendMsg: Error {
+    multi: \"line\"
+}
+ INFO 2022-03-10T12:00:01.000000Z [sn/src/node/core/mod.rs:L421]:
+\t ➤ SplitSuccess: Prefix(1)
+";
+
+    #[test]
+    fn entries_are_parsed_with_their_ids() -> Result<()> {
+        let parser = LogParser::new()?;
+        let entries = parser.parse("sn-node-1", Path::new("sn_node.log"), LOG.as_bytes())?;
+
+        assert_eq!(entries.len(), 4);
+
+        assert_eq!(entries[0].marker, LogMarker::CmdHandlingSpawned);
+        assert_eq!(entries[0].line, 1);
+        assert_eq!(entries[0].level, "TRACE");
+        assert_eq!(entries[0].cmd_id.as_deref(), Some("963111461.0"));
+        assert_eq!(entries[0].msg_id.as_deref(), Some("68fe..b776"));
+        assert!(entries[0].time.is_some());
+
+        assert_eq!(entries[1].marker, LogMarker::CmdProcessStart);
+        assert_eq!(entries[1].cmd_id.as_deref(), Some("963111461.0"));
+
+        assert_eq!(entries[2].marker, LogMarker::CmdProcessingError);
+        assert_eq!(entries[2].cmd_id.as_deref(), Some("963111461.1"));
+        assert_eq!(entries[2].root_cmd_id().as_deref(), Some("963111461"));
+        assert!(entries[2].message.ends_with('}'));
+
+        assert_eq!(entries[3].marker, LogMarker::SplitSuccess);
+        assert_eq!(entries[3].cmd_id, None);
+        assert_eq!(entries[3].node, "sn-node-1");
+
+        Ok(())
+    }
+
+    #[test]
+    fn markers_which_prefix_others_are_told_apart() -> Result<()> {
+        let parser = LogParser::new()?;
+        let log = " TRACE 2022-03-10T12:00:00.000000Z [sn/src/node/core/comm/mod.rs:L1]:
+\t ➤ SendDirectToNodes to 3 nodes
+";
+        let entries = parser.parse("sn-node-1", Path::new("sn_node.log"), log.as_bytes())?;
+
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].marker, LogMarker::SendDirectToNodes);
+        Ok(())
+    }
+
+    #[test]
+    fn root_cmd_id_is_the_first_part_of_the_id() {
+        assert_eq!(get_root_cmd_id("963111461.0.2"), "963111461");
+        assert_eq!(get_root_cmd_id("963111461"), "963111461");
+    }
+}
@@ -0,0 +1,654 @@
+// Copyright 2022 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use crate::parser::{get_root_cmd_id, CmdId, LogEntry, MsgId, NodeId};
+
+use chrono::{DateTime, Utc};
+use safe_network::types::log_markers::LogMarker;
+use serde::Serialize;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::{self, Display, Formatter},
+};
+
+// Markers of the events shaping the network, shown in the timeline
+const TIMELINE_MARKERS: &[LogMarker] = &[
+    LogMarker::SplitAttempt,
+    LogMarker::SplitSuccess,
+    LogMarker::StillElderAfterSplit,
+    LogMarker::NewPrefix,
+    LogMarker::TriggeringPromotionAndDemotion,
+    LogMarker::DkgSessionComplete,
+    LogMarker::HandlingNewEldersAgreement,
+    LogMarker::NewSignedSap,
+    LogMarker::PromotedToElder,
+    LogMarker::DemotedFromElder,
+];
+
+// Upper bounds of the buckets of the latency histograms, in milliseconds
+const LATENCY_BUCKETS_MS: &[f64] = &[1.0, 5.0, 10.0, 50.0, 100.0, 500.0, 1000.0, 5000.0];
+
+/// Number of times each marker was found, across all the nodes and per node.
+#[derive(Debug, Default, Serialize)]
+pub struct MarkerStats {
+    pub total: BTreeMap<String, usize>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub per_node: BTreeMap<NodeId, BTreeMap<String, usize>>,
+}
+
+pub fn marker_stats(entries: &[LogEntry], per_node: bool) -> MarkerStats {
+    let mut stats = MarkerStats::default();
+    for entry in entries {
+        let marker = entry.marker.to_string();
+        if per_node {
+            *stats
+                .per_node
+                .entry(entry.node.clone())
+                .or_default()
+                .entry(marker.clone())
+                .or_default() += 1;
+        }
+        *stats.total.entry(marker).or_default() += 1;
+    }
+    stats
+}
+
+impl Display for MarkerStats {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(f, "LogMarker Stats: (markers found across all log files): ")?;
+        for (marker, count) in &self.total {
+            writeln!(f, "{} found: {} times", marker, count)?;
+        }
+        for (node, counts) in &self.per_node {
+            writeln!(f)?;
+            writeln!(f, "Node {}:", node)?;
+            if counts.contains_key(&LogMarker::PromotedToElder.to_string()) {
+                writeln!(f, "** Has been an elder **")?;
+            }
+            for (marker, count) in counts {
+                writeln!(f, "  {} found: {} times", marker, count)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// How far a cmd got in its processing.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub enum CmdState {
+    Spawned,
+    Started,
+    Succeeded,
+    Failed,
+}
+
+/// A cmd handled by a node, and the sub-cmds it spawned.
+#[derive(Debug, Serialize)]
+pub struct CmdTrace {
+    pub node: NodeId,
+    pub root_cmd_id: CmdId,
+    pub sub_cmds: BTreeMap<CmdId, CmdState>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub entries: Vec<LogEntry>,
+}
+
+/// Traces the cmds handled by every node, only those rooted at the given cmd id if any.
+/// Log entries are kept in the traces only when a cmd id is given.
+pub fn cmd_traces(entries: &[LogEntry], cmd_id: Option<&str>) -> Vec<CmdTrace> {
+    let root_cmd_id = cmd_id.map(get_root_cmd_id);
+    let mut traces = BTreeMap::<(NodeId, CmdId), CmdTrace>::new();
+
+    for entry in entries {
+        let (cmd_id, root) = match (&entry.cmd_id, entry.root_cmd_id()) {
+            (Some(cmd_id), Some(root)) => (cmd_id, root),
+            _ => continue,
+        };
+        if matches!(&root_cmd_id, Some(wanted) if *wanted != root) {
+            continue;
+        }
+
+        let trace = traces
+            .entry((entry.node.clone(), root.clone()))
+            .or_insert_with(|| CmdTrace {
+                node: entry.node.clone(),
+                root_cmd_id: root,
+                sub_cmds: BTreeMap::new(),
+                entries: vec![],
+            });
+
+        if let Some(state) = cmd_state(&entry.marker) {
+            let current = trace.sub_cmds.entry(cmd_id.clone()).or_insert(state);
+            *current = (*current).max(state);
+        }
+        if root_cmd_id.is_some() {
+            trace.entries.push(entry.clone());
+        }
+    }
+
+    traces.into_values().collect()
+}
+
+fn cmd_state(marker: &LogMarker) -> Option<CmdState> {
+    match marker {
+        LogMarker::CmdHandlingSpawned => Some(CmdState::Spawned),
+        LogMarker::CmdProcessStart => Some(CmdState::Started),
+        LogMarker::CmdProcessEnd => Some(CmdState::Succeeded),
+        LogMarker::CmdProcessingError => Some(CmdState::Failed),
+        _ => None,
+    }
+}
+
+impl Display for CmdTrace {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let count = |state| {
+            self.sub_cmds
+                .values()
+                .filter(|sub_cmd_state| **sub_cmd_state == state)
+                .count()
+        };
+        writeln!(
+            f,
+            "==> Cmd {} at node {}: {} sub-cmds, Succeeded: {}, Failed: {}, not Completed: {}",
+            self.root_cmd_id,
+            self.node,
+            self.sub_cmds.len(),
+            count(CmdState::Succeeded),
+            count(CmdState::Failed),
+            count(CmdState::Spawned) + count(CmdState::Started),
+        )?;
+        for entry in &self.entries {
+            writeln!(f, "{}", format_entry(entry))?;
+        }
+        Ok(())
+    }
+}
+
+/// Cmds which were spawned but not completed, and those which failed.
+#[derive(Debug, Default, Serialize)]
+pub struct IncompleteCmds {
+    pub not_started: Vec<LogEntry>,
+    pub not_completed: Vec<LogEntry>,
+    pub failed: Vec<LogEntry>,
+    pub succeeded: usize,
+}
+
+pub fn incomplete_cmds(entries: &[LogEntry]) -> IncompleteCmds {
+    // Latest state reached by each cmd, along with the entry it was reached at
+    let mut cmds = BTreeMap::<(&NodeId, &CmdId), (CmdState, &LogEntry)>::new();
+    for entry in entries {
+        if let (Some(cmd_id), Some(state)) = (&entry.cmd_id, cmd_state(&entry.marker)) {
+            let current = cmds.entry((&entry.node, cmd_id)).or_insert((state, entry));
+            if state > current.0 {
+                *current = (state, entry);
+            }
+        }
+    }
+
+    let mut report = IncompleteCmds::default();
+    for (state, entry) in cmds.into_values() {
+        match state {
+            CmdState::Spawned => report.not_started.push(entry.clone()),
+            CmdState::Started => report.not_completed.push(entry.clone()),
+            CmdState::Failed => report.failed.push(entry.clone()),
+            CmdState::Succeeded => report.succeeded += 1,
+        }
+    }
+    report
+}
+
+impl IncompleteCmds {
+    pub fn is_empty(&self) -> bool {
+        self.not_started.is_empty() && self.not_completed.is_empty() && self.failed.is_empty()
+    }
+}
+
+impl Display for IncompleteCmds {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Cmds handled which Failed: {}, Succeeded: {}, not Started: {}, not Completed: {}",
+            self.failed.len(),
+            self.succeeded,
+            self.not_started.len(),
+            self.not_completed.len()
+        )?;
+        for (title, entries) in [
+            ("Some cmds were spawned but not started", &self.not_started),
+            (
+                "Some cmds were started but not completed",
+                &self.not_completed,
+            ),
+            ("Some cmds produced errors", &self.failed),
+        ] {
+            if !entries.is_empty() {
+                writeln!(f, "\n!!! ERROR !!!: {}:", title)?;
+                for entry in entries {
+                    writeln!(f, "{}", format_entry(entry))?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A msg followed across the nodes which handled it, and the cmds it caused.
+#[derive(Debug, Serialize)]
+pub struct MsgTrace {
+    pub msg_id: MsgId,
+    pub nodes: BTreeSet<NodeId>,
+    pub first_seen: Option<DateTime<Utc>>,
+    pub last_seen: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub entries: Vec<LogEntry>,
+}
+
+/// Traces msgs across nodes, only the one with the given msg id if any. Log entries of the
+/// cmds a msg caused on each node are part of its trace, and are only kept when a msg id is
+/// given.
+pub fn msg_traces(entries: &[LogEntry], msg_id: Option<&str>) -> Vec<MsgTrace> {
+    // Cmds the msgs caused on each node
+    let mut caused_cmds = BTreeMap::<&MsgId, BTreeSet<(&NodeId, CmdId)>>::new();
+    for entry in entries {
+        if let (Some(id), Some(root)) = (&entry.msg_id, entry.root_cmd_id()) {
+            if msg_id.is_none() || msg_id == Some(id.as_str()) {
+                let _ = caused_cmds
+                    .entry(id)
+                    .or_default()
+                    .insert((&entry.node, root));
+            }
+        }
+    }
+
+    let mut traces = BTreeMap::<&MsgId, MsgTrace>::new();
+    for entry in entries {
+        let mut ids: BTreeSet<&MsgId> = entry
+            .msg_id
+            .iter()
+            .filter(|id| msg_id.is_none() || msg_id == Some(id.as_str()))
+            .collect();
+        if msg_id.is_some() {
+            if let Some(root) = entry.root_cmd_id() {
+                ids.extend(
+                    caused_cmds
+                        .iter()
+                        .filter(|(_, cmds)| cmds.contains(&(&entry.node, root.clone())))
+                        .map(|(id, _)| *id),
+                );
+            }
+        }
+
+        for id in ids {
+            let trace = traces.entry(id).or_insert_with(|| MsgTrace {
+                msg_id: id.clone(),
+                nodes: BTreeSet::new(),
+                first_seen: None,
+                last_seen: None,
+                entries: vec![],
+            });
+            let _ = trace.nodes.insert(entry.node.clone());
+            if trace.first_seen.is_none() {
+                trace.first_seen = entry.time;
+            }
+            if entry.time.is_some() {
+                trace.last_seen = entry.time;
+            }
+            if msg_id.is_some() {
+                trace.entries.push(entry.clone());
+            }
+        }
+    }
+
+    traces.into_values().collect()
+}
+
+impl Display for MsgTrace {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let elapsed = match (self.first_seen, self.last_seen) {
+            (Some(first), Some(last)) => format!("{} ms", (last - first).num_milliseconds()),
+            _ => "unknown".to_string(),
+        };
+        writeln!(
+            f,
+            "==> Msg {} seen by {} nodes, over {}: {}",
+            self.msg_id,
+            self.nodes.len(),
+            elapsed,
+            self.nodes.iter().cloned().collect::<Vec<_>>().join(", ")
+        )?;
+        for entry in &self.entries {
+            writeln!(f, "{}", format_entry(entry))?;
+        }
+        Ok(())
+    }
+}
+
+/// An event shaping the network, e.g. a split or an elder change.
+#[derive(Debug, Serialize)]
+pub struct TimelineEvent {
+    pub time: Option<DateTime<Utc>>,
+    pub node: NodeId,
+    #[serde(serialize_with = "serialize_marker")]
+    pub marker: LogMarker,
+    pub details: String,
+}
+
+fn serialize_marker<S: serde::Serializer>(
+    marker: &LogMarker,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&marker.to_string())
+}
+
+/// The splits and elder changes seen across the network, in the order they happened in.
+pub fn timeline(entries: &[LogEntry]) -> Vec<TimelineEvent> {
+    entries
+        .iter()
+        .filter(|entry| TIMELINE_MARKERS.contains(&entry.marker))
+        .map(|entry| {
+            let marker = entry.marker.to_string();
+            let details = entry
+                .message
+                .split_once(&marker)
+                .map(|(_, details)| details)
+                .unwrap_or_default()
+                .trim_start_matches(|c: char| c == ':' || c.is_whitespace())
+                .to_string();
+            TimelineEvent {
+                time: entry.time,
+                node: entry.node.clone(),
+                marker: entry.marker.clone(),
+                details,
+            }
+        })
+        .collect()
+}
+
+impl Display for TimelineEvent {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} {} {}",
+            format_time(self.time),
+            self.node,
+            self.marker,
+            self.details
+        )
+    }
+}
+
+/// Latencies between the start and the end of the processing of cmds.
+#[derive(Debug, Default, Serialize)]
+pub struct LatencyStats {
+    pub count: usize,
+    pub mean_ms: f64,
+    pub p50_ms: f64,
+    pub p90_ms: f64,
+    pub p99_ms: f64,
+    pub max_ms: f64,
+    /// Number of cmds per bucket, keyed by the bucket's upper bound in ms, "+Inf" for the last
+    pub histogram: Vec<(String, usize)>,
+}
+
+impl LatencyStats {
+    fn new(mut latencies_ms: Vec<f64>) -> Self {
+        if latencies_ms.is_empty() {
+            return Self::default();
+        }
+        latencies_ms.sort_by(|a, b| a.total_cmp(b));
+
+        let percentile = |p: f64| {
+            let index = ((latencies_ms.len() as f64 * p).ceil() as usize).max(1) - 1;
+            latencies_ms[index.min(latencies_ms.len() - 1)]
+        };
+
+        let mut histogram = vec![];
+        let mut lower = 0;
+        for bound in LATENCY_BUCKETS_MS {
+            let upper = latencies_ms.partition_point(|latency| latency <= bound);
+            histogram.push((bound.to_string(), upper - lower));
+            lower = upper;
+        }
+        histogram.push(("+Inf".to_string(), latencies_ms.len() - lower));
+
+        Self {
+            count: latencies_ms.len(),
+            mean_ms: latencies_ms.iter().sum::<f64>() / latencies_ms.len() as f64,
+            p50_ms: percentile(0.5),
+            p90_ms: percentile(0.9),
+            p99_ms: percentile(0.99),
+            max_ms: latencies_ms[latencies_ms.len() - 1],
+            histogram,
+        }
+    }
+}
+
+impl Display for LatencyStats {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} cmds, mean: {:.3} ms, p50: {:.3} ms, p90: {:.3} ms, p99: {:.3} ms, max: {:.3} ms",
+            self.count, self.mean_ms, self.p50_ms, self.p90_ms, self.p99_ms, self.max_ms
+        )?;
+        let widest = self.histogram.iter().map(|(_, count)| *count).max();
+        for (bound, count) in &self.histogram {
+            // Bars are scaled to the widest bucket
+            let bar_len = widest.map_or(0, |widest| count * 50 / widest.max(1));
+            writeln!(
+                f,
+                "  <= {:>6} ms | {:<50} {}",
+                bound,
+                "#".repeat(bar_len),
+                count
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Latencies of the cmds processed by all the nodes, overall and per kind of cmd.
+#[derive(Debug, Default, Serialize)]
+pub struct LatencyReport {
+    pub overall: LatencyStats,
+    pub per_cmd: BTreeMap<String, LatencyStats>,
+    /// Cmds started but never ended, e.g. as they failed
+    pub not_ended: usize,
+}
+
+pub fn cmd_latencies(entries: &[LogEntry]) -> LatencyReport {
+    let mut starts = BTreeMap::<(&NodeId, &CmdId), DateTime<Utc>>::new();
+    let mut latencies = BTreeMap::<String, Vec<f64>>::new();
+
+    for entry in entries {
+        let (cmd_id, time) = match (&entry.cmd_id, entry.time) {
+            (Some(cmd_id), Some(time)) => (cmd_id, time),
+            _ => continue,
+        };
+        match entry.marker {
+            LogMarker::CmdProcessStart => {
+                let _ = starts.insert((&entry.node, cmd_id), time);
+            }
+            LogMarker::CmdProcessEnd => {
+                if let Some(start) = starts.remove(&(&entry.node, cmd_id)) {
+                    let latency = (time - start)
+                        .to_std()
+                        .map(|latency| latency.as_secs_f64() * 1000.0)
+                        .unwrap_or_default();
+                    latencies
+                        .entry(cmd_kind(&entry.message))
+                        .or_default()
+                        .push(latency);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    LatencyReport {
+        overall: LatencyStats::new(latencies.values().flatten().copied().collect()),
+        per_cmd: latencies
+            .into_iter()
+            .map(|(kind, latencies)| (kind, LatencyStats::new(latencies)))
+            .collect(),
+        not_ended: starts.len(),
+    }
+}
+
+// Kind of the cmd described in a CmdProcessStart/CmdProcessEnd message, e.g. "HandleMsg"
+fn cmd_kind(message: &str) -> String {
+    message
+        .split_once(" - ")
+        .and_then(|(_, cmd)| cmd.split(|c: char| !c.is_alphanumeric()).next())
+        .filter(|kind| !kind.is_empty())
+        .unwrap_or("Unknown")
+        .to_string()
+}
+
+impl Display for LatencyReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(f, "All cmds: {}", self.overall)?;
+        for (kind, stats) in &self.per_cmd {
+            writeln!(f, "{}: {}", kind, stats)?;
+        }
+        writeln!(f, "Cmds started but not ended: {}", self.not_ended)
+    }
+}
+
+fn format_time(time: Option<DateTime<Utc>>) -> String {
+    time.map(|time| time.to_rfc3339())
+        .unwrap_or_else(|| "<unknown time>".to_string())
+}
+
+fn format_entry(entry: &LogEntry) -> String {
+    format!(
+        "{} {}:{}: {}",
+        format_time(entry.time),
+        entry.logfile.display(),
+        entry.line,
+        entry.message
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{cmd_latencies, incomplete_cmds, msg_traces, timeline};
+    use crate::parser::LogParser;
+    use eyre::Result;
+    use safe_network::types::log_markers::LogMarker;
+    use std::path::Path;
+
+    const NODE_1_LOG: &str = " TRACE 2022-03-10T12:00:00.000000Z [sn/src/node/api/mod.rs:L478]:
+\t ➤ handle_message{name=abc msg_id=MsgId(68fe..b776)}
+\t ➤ CmdHandlingSpawned HandleMsg MsgId(68fe..b776) cmd_id=1.0
+ TRACE 2022-03-10T12:00:00.001000Z [sn/src/node/api/dispatcher.rs:L312]:
+\t ➤ process_cmd{name=abc cmd_id=1.0}
+\t ➤ CmdProcessStart \"1.0\" - HandleMsg MsgId(68fe..b776)
+ TRACE 2022-03-10T12:00:00.002000Z [sn/src/node/api/dispatcher.rs:L104]:
+\t ➤ process_cmd{name=abc cmd_id=1.0}
+\t ➤ CmdHandlingSpawned SendMsg MsgId(aaaa..bbbb) cmd_id=1.0.0
+ TRACE 2022-03-10T12:00:00.004000Z [sn/src/node/api/dispatcher.rs:L324]:
+\t ➤ process_cmd{name=abc cmd_id=1.0}
+\t ➤ CmdProcessEnd \"1.0\" - HandleMsg MsgId(68fe..b776)
+ INFO 2022-03-10T12:00:01.000000Z [sn/src/node/core/mod.rs:L407]:
+\t ➤ PromotedToElder: Prefix()
+";
+
+    const NODE_2_LOG: &str = " TRACE 2022-03-10T12:00:00.003000Z [sn/src/node/api/mod.rs:L478]:
+\t ➤ handle_message{name=def msg_id=MsgId(68fe..b776)}
+\t ➤ CmdHandlingSpawned HandleMsg MsgId(68fe..b776) cmd_id=2.0
+ TRACE 2022-03-10T12:00:00.005000Z [sn/src/node/api/dispatcher.rs:L312]:
+\t ➤ process_cmd{name=def cmd_id=2.0}
+\t ➤ CmdProcessStart \"2.0\" - HandleMsg MsgId(68fe..b776)
+ INFO 2022-03-10T12:00:02.000000Z [sn/src/node/core/mod.rs:L421]:
+\t ➤ SplitSuccess: Prefix(1)
+";
+
+    fn entries() -> Result<Vec<crate::parser::LogEntry>> {
+        let parser = LogParser::new()?;
+        let mut entries = parser.parse(
+            "sn-node-1",
+            Path::new("1/sn_node.log"),
+            NODE_1_LOG.as_bytes(),
+        )?;
+        entries.extend(parser.parse(
+            "sn-node-2",
+            Path::new("2/sn_node.log"),
+            NODE_2_LOG.as_bytes(),
+        )?);
+        entries.sort_by_key(|entry| entry.time);
+        Ok(entries)
+    }
+
+    #[test]
+    fn msgs_are_traced_across_nodes_with_the_cmds_they_caused() -> Result<()> {
+        let entries = entries()?;
+        let traces = msg_traces(&entries, Some("68fe..b776"));
+
+        assert_eq!(traces.len(), 1);
+        let trace = &traces[0];
+        assert_eq!(trace.nodes.len(), 2);
+        // All entries but the ones of the elder change and split, in time order
+        assert_eq!(trace.entries.len(), 6);
+        assert!(trace
+            .entries
+            .windows(2)
+            .all(|pair| pair[0].time <= pair[1].time));
+        // The sub-cmd sending another msg is part of the trace
+        assert!(trace
+            .entries
+            .iter()
+            .any(|entry| entry.cmd_id.as_deref() == Some("1.0.0")));
+
+        Ok(())
+    }
+
+    #[test]
+    fn cmd_latencies_pair_starts_with_ends() -> Result<()> {
+        let report = cmd_latencies(&entries()?);
+
+        assert_eq!(report.overall.count, 1);
+        assert!((report.overall.max_ms - 3.0).abs() < f64::EPSILON);
+        assert_eq!(report.per_cmd["HandleMsg"].count, 1);
+        assert_eq!(report.not_ended, 1);
+        assert_eq!(
+            report
+                .overall
+                .histogram
+                .iter()
+                .map(|(_, count)| count)
+                .sum::<usize>(),
+            1
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn incomplete_cmds_are_reported() -> Result<()> {
+        let report = incomplete_cmds(&entries()?);
+
+        assert_eq!(report.succeeded, 1);
+        assert_eq!(report.not_started.len(), 1);
+        assert_eq!(report.not_completed.len(), 1);
+        assert!(report.failed.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn timeline_holds_splits_and_elder_changes() -> Result<()> {
+        let events = timeline(&entries()?);
+
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].marker, LogMarker::PromotedToElder);
+        assert_eq!(events[0].details, "Prefix()");
+        assert_eq!(events[1].marker, LogMarker::SplitSuccess);
+        assert_eq!(events[1].node, "sn-node-2");
+
+        Ok(())
+    }
+}
@@ -286,6 +286,10 @@ async fn handle_event(index: usize, node: &mut NodeApi, event: Event) -> bool {
             "Node #{} adults changed - remaining: {:?}, added: {:?}, removed: {:?}",
             index, remaining, added, removed
         ),
+        Event::LeftSection => {
+            info!("Node #{} left its section", index);
+            return false;
+        }
     }
 
     true
@@ -30,7 +30,9 @@
 use color_eyre::{Section, SectionExt};
 use eyre::{eyre, Result, WrapErr};
 use file_rotate::{compression::Compression, suffix::AppendCount, ContentLimit, FileRotate};
-use safe_network::node::{add_connection_info, set_connection_info, Config, Error, NodeApi};
+use safe_network::node::{
+    add_connection_info, migrate_chunk_store, set_connection_info, Config, Error, Event, NodeApi,
+};
 
 #[cfg(not(feature = "tokio-console"))]
 use safe_network::LogFormatter;
@@ -39,17 +41,24 @@ use std::{fmt::Debug, fs::File, io, path::Path};
 use std::{io::Write, process::exit};
 use structopt::{clap, StructOpt};
 use tokio::sync::RwLockReadGuard;
-use tokio::time::{sleep, Duration};
+use tokio::time::{sleep, timeout, Duration};
 use tracing::{self, error, info, trace, warn};
 
+#[cfg(not(feature = "tokio-console"))]
+use tracing::Subscriber;
 use tracing_appender::non_blocking::WorkerGuard;
+#[cfg(all(feature = "otlp", not(feature = "tokio-console")))]
+use tracing_subscriber::layer::SubscriberExt;
 #[cfg(not(feature = "tokio-console"))]
-use tracing_subscriber::filter::EnvFilter;
+use tracing_subscriber::{filter::EnvFilter, registry::LookupSpan, util::SubscriberInitExt};
 
 #[cfg(not(feature = "tokio-console"))]
 const MODULE_NAME: &str = "safe_network";
 const BOOTSTRAP_RETRY_TIME: u64 = 3; // in minutes
 
+// How long we give ourselves to leave our section gracefully once asked to terminate
+const LEAVE_TIMEOUT: Duration = Duration::from_secs(60);
+
 fn main() -> Result<()> {
     color_eyre::install()?;
     #[cfg(feature = "tokio-console")]
@@ -217,22 +226,24 @@ async fn run_node() -> Result<()> {
                 .with_writer(non_blocking);
 
             if config.json_logs {
-                builder.json().init();
+                init_subscriber(builder.json().finish())?;
             } else {
-                builder.event_format(LogFormatter::default()).init();
+                init_subscriber(builder.event_format(LogFormatter::default()).finish())?;
             }
 
             Some(guard)
         } else {
             println!("Starting logging to stdout");
 
-            tracing_subscriber::fmt()
-                .with_thread_names(true)
-                .with_ansi(false)
-                .with_env_filter(EnvFilter::from_default_env())
-                .with_target(false)
-                .event_format(LogFormatter::default())
-                .init();
+            init_subscriber(
+                tracing_subscriber::fmt()
+                    .with_thread_names(true)
+                    .with_ansi(false)
+                    .with_env_filter(EnvFilter::from_default_env())
+                    .with_target(false)
+                    .event_format(LogFormatter::default())
+                    .finish(),
+            )?;
 
             None
         };
@@ -254,6 +265,18 @@ async fn run_node() -> Result<()> {
         }
     }
 
+    if config.migrate_chunk_store {
+        let migrated = migrate_chunk_store(&config)
+            .await
+            .wrap_err("Failed to migrate the chunk store")?;
+        println!(
+            "Migrated {} chunks to the {} chunk store",
+            migrated,
+            config.chunk_store().unwrap_or_default()
+        );
+        exit(0);
+    }
+
     let message = format!(
         "Running {} v{}",
         Config::clap().get_name(),
@@ -338,14 +361,90 @@ async fn run_node() -> Result<()> {
             });
     }
 
-    // This just keeps the node going as long as routing goes
-    while let Some(event) = event_stream.next().await {
-        trace!("Routing event! {:?}", event);
+    // This just keeps the node going as long as routing goes, or until we're asked to stop
+    let terminated = terminated();
+    tokio::pin!(terminated);
+    loop {
+        tokio::select! {
+            event = event_stream.next() => match event {
+                Some(Event::LeftSection) => {
+                    info!("Left our section, shutting down");
+                    break;
+                }
+                Some(event) => trace!("Routing event! {:?}", event),
+                None => break,
+            },
+            result = &mut terminated => {
+                result.wrap_err("Failed to listen for the termination signal")?;
+                info!("Termination signal received, leaving our section before shutting down");
+                // Being asked to terminate again means we shouldn't wait for the leave to complete
+                tokio::select! {
+                    result = timeout(LEAVE_TIMEOUT, node.leave()) => match result {
+                        Ok(Ok(summary)) => info!("Left our section: {:?}", summary),
+                        Ok(Err(error)) => warn!("Could not leave our section gracefully: {:?}", error),
+                        Err(_) => warn!("Could not leave our section within {:?}", LEAVE_TIMEOUT),
+                    },
+                    result = interrupted() => {
+                        result.wrap_err("Failed to listen for the termination signal")?;
+                        warn!("Termination signal received again, shutting down without leaving");
+                    }
+                }
+                break;
+            }
+        }
     }
 
+    #[cfg(feature = "otlp")]
+    safe_network::otlp::shutdown();
+
     Ok(())
 }
 
+// Install the subscriber, along with a layer exporting spans over OTLP if built with the feature
+#[cfg(not(feature = "tokio-console"))]
+fn init_subscriber<S>(subscriber: S) -> Result<()>
+where
+    S: Subscriber + for<'span> LookupSpan<'span> + Send + Sync + 'static,
+{
+    #[cfg(feature = "otlp")]
+    let subscriber = subscriber.with(
+        safe_network::otlp::layer("sn_node").wrap_err("Failed to set up the export of spans")?,
+    );
+    subscriber.try_init()?;
+    Ok(())
+}
+
+// Resolves once the node is asked to terminate, i.e. on SIGTERM
+#[cfg(unix)]
+async fn terminated() -> io::Result<()> {
+    use tokio::signal::unix::{signal, SignalKind};
+    let _signal = signal(SignalKind::terminate())?.recv().await;
+    Ok(())
+}
+
+#[cfg(not(unix))]
+async fn terminated() -> io::Result<()> {
+    std::future::pending().await
+}
+
+// Resolves once the node is asked to terminate while leaving, i.e. on SIGTERM or SIGINT
+#[cfg(unix)]
+async fn interrupted() -> io::Result<()> {
+    use tokio::signal::unix::{signal, SignalKind};
+    let mut terminate = signal(SignalKind::terminate())?;
+    let mut interrupt = signal(SignalKind::interrupt())?;
+    tokio::select! {
+        _ = terminate.recv() => {}
+        _ = interrupt.recv() => {}
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+async fn interrupted() -> io::Result<()> {
+    std::future::pending().await
+}
+
 fn update() -> Result<Status, Box<dyn (::std::error::Error)>> {
     info!("Checking for updates...");
     let target = self_update::get_target();
@@ -0,0 +1,363 @@
+// Copyright 2022 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+//! Fault injection, only compiled in with the `chaos` feature.
+//!
+//! Faults are configured through the `SN_CHAOS_CONFIG` env var, pointing to a JSON file with the
+//! fields of [`ChaosConfig`], and/or the `SN_CHAOS` env var, a comma separated list of
+//! `field=value` pairs which take precedence over the file, e.g.
+//! `SN_CHAOS="msg_drop=0.05,msg_delay=0.1,msg_delay_max_ms=2000,seed=42"`.
+//!
+//! All the decisions are taken from a single seeded rng, so given the same seed (which is
+//! always logged at startup) a node will see the same sequence of faults.
+
+use lazy_static::lazy_static;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::{path::Path, sync::Mutex, time::Duration};
+
+const SN_CHAOS: &str = "SN_CHAOS";
+const SN_CHAOS_CONFIG: &str = "SN_CHAOS_CONFIG";
+
+const DEFAULT_MSG_DELAY_MAX_MS: u64 = 1000;
+
+lazy_static! {
+    static ref CHAOS: Chaos = Chaos::new(ChaosConfig::from_env());
+}
+
+/// The fault injector configured for this process.
+pub(crate) fn chaos() -> &'static Chaos {
+    &CHAOS
+}
+
+/// Probabilities (from 0.0 to 1.0) of each fault being injected.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct ChaosConfig {
+    /// Seed for the rng, a random one is used if not set.
+    pub(crate) seed: Option<u64>,
+    /// An outgoing msg is silently dropped.
+    pub(crate) msg_drop: f64,
+    /// An outgoing msg, and so all the ones queued behind it, is delayed.
+    pub(crate) msg_delay: f64,
+    /// Upper bound for the delays, and for the time a reordered msg is held back.
+    pub(crate) msg_delay_max_ms: u64,
+    /// An outgoing msg is sent twice.
+    pub(crate) msg_duplicate: f64,
+    /// An outgoing msg is held back, letting the following ones overtake it.
+    pub(crate) msg_reorder: f64,
+    /// A chunk write fails with an I/O error.
+    pub(crate) chunk_write_fail: f64,
+    /// A chunk is stored with one of its bytes flipped.
+    pub(crate) chunk_corrupt: f64,
+}
+
+impl Default for ChaosConfig {
+    fn default() -> Self {
+        Self {
+            seed: None,
+            msg_drop: 0.0,
+            msg_delay: 0.0,
+            msg_delay_max_ms: DEFAULT_MSG_DELAY_MAX_MS,
+            msg_duplicate: 0.0,
+            msg_reorder: 0.0,
+            chunk_write_fail: 0.0,
+            chunk_corrupt: 0.0,
+        }
+    }
+}
+
+impl ChaosConfig {
+    /// Reads the config from the file at `SN_CHAOS_CONFIG` (if any), then applies the
+    /// overrides found in `SN_CHAOS`. Errors are logged and the offending setting ignored.
+    pub(crate) fn from_env() -> Self {
+        let mut config = match std::env::var(SN_CHAOS_CONFIG) {
+            Ok(path) => match Self::from_file(Path::new(&path)) {
+                Ok(config) => {
+                    warn!("Chaos config read from {:?}: {:?}", path, config);
+                    config
+                }
+                Err(error) => {
+                    warn!(
+                        "There was an error reading the chaos config file set in {:?}. No faults will be read from it: {}",
+                        SN_CHAOS_CONFIG, error
+                    );
+                    Self::default()
+                }
+            },
+            Err(_) => Self::default(),
+        };
+
+        if let Ok(overrides) = std::env::var(SN_CHAOS) {
+            for (field, error) in config.apply_overrides(&overrides) {
+                warn!(
+                    "There was an error parsing {:?} from the {:?} env var, it will be ignored: {}",
+                    field, SN_CHAOS, error
+                );
+            }
+            warn!("Chaos config set from env var {:?}: {:?}", SN_CHAOS, config);
+        }
+
+        config
+    }
+
+    fn from_file(path: &Path) -> Result<Self, String> {
+        let content = std::fs::read(path).map_err(|err| err.to_string())?;
+        serde_json::from_slice(&content).map_err(|err| err.to_string())
+    }
+
+    /// Applies a comma separated list of `field=value` pairs, returning the ones which
+    /// couldn't be applied along with the reason.
+    fn apply_overrides(&mut self, overrides: &str) -> Vec<(String, String)> {
+        let mut errors = vec![];
+        for pair in overrides
+            .split(',')
+            .map(str::trim)
+            .filter(|p| !p.is_empty())
+        {
+            let (field, value) = match pair.split_once('=') {
+                Some((field, value)) => (field.trim(), value.trim()),
+                None => {
+                    errors.push((pair.to_string(), "expected `field=value`".to_string()));
+                    continue;
+                }
+            };
+
+            let result = match field {
+                "seed" => value
+                    .parse()
+                    .map(|seed| self.seed = Some(seed))
+                    .map_err(|e| e.to_string()),
+                "msg_delay_max_ms" => value
+                    .parse()
+                    .map(|ms| self.msg_delay_max_ms = ms)
+                    .map_err(|e| e.to_string()),
+                "msg_drop" => parse_probability(value).map(|p| self.msg_drop = p),
+                "msg_delay" => parse_probability(value).map(|p| self.msg_delay = p),
+                "msg_duplicate" => parse_probability(value).map(|p| self.msg_duplicate = p),
+                "msg_reorder" => parse_probability(value).map(|p| self.msg_reorder = p),
+                "chunk_write_fail" => parse_probability(value).map(|p| self.chunk_write_fail = p),
+                "chunk_corrupt" => parse_probability(value).map(|p| self.chunk_corrupt = p),
+                _ => Err("unknown field".to_string()),
+            };
+
+            if let Err(error) = result {
+                errors.push((field.to_string(), error));
+            }
+        }
+        errors
+    }
+}
+
+fn parse_probability(value: &str) -> Result<f64, String> {
+    let p: f64 = value
+        .parse()
+        .map_err(|e: std::num::ParseFloatError| e.to_string())?;
+    if (0.0..=1.0).contains(&p) {
+        Ok(p)
+    } else {
+        Err(format!("{} is not a probability between 0.0 and 1.0", p))
+    }
+}
+
+/// A fault to inject on an outgoing msg.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum MsgFault {
+    Drop,
+    Delay(Duration),
+    Duplicate,
+    Reorder(Duration),
+}
+
+/// A fault to inject on a chunk write.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum ChunkFault {
+    FailWrite,
+    Corrupt,
+}
+
+/// Decides which faults are injected, as per its [`ChaosConfig`].
+pub(crate) struct Chaos {
+    config: ChaosConfig,
+    rng: Mutex<StdRng>,
+}
+
+impl Chaos {
+    pub(crate) fn new(config: ChaosConfig) -> Self {
+        let seed = config.seed.unwrap_or_else(rand::random);
+        warn!("Chaos fault injection enabled with seed {}", seed);
+        Self {
+            config,
+            rng: Mutex::new(StdRng::seed_from_u64(seed)),
+        }
+    }
+
+    /// Picks at most one fault for an outgoing msg.
+    pub(crate) fn msg_fault(&self) -> Option<MsgFault> {
+        let config = &self.config;
+        let mut rng = self
+            .rng
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let max_delay = config.msg_delay_max_ms.max(1);
+
+        if rng.gen_bool(clamp(config.msg_drop)) {
+            Some(MsgFault::Drop)
+        } else if rng.gen_bool(clamp(config.msg_delay)) {
+            Some(MsgFault::Delay(Duration::from_millis(
+                rng.gen_range(1, max_delay + 1),
+            )))
+        } else if rng.gen_bool(clamp(config.msg_duplicate)) {
+            Some(MsgFault::Duplicate)
+        } else if rng.gen_bool(clamp(config.msg_reorder)) {
+            Some(MsgFault::Reorder(Duration::from_millis(
+                rng.gen_range(1, max_delay + 1),
+            )))
+        } else {
+            None
+        }
+    }
+
+    /// Picks at most one fault for a chunk write.
+    pub(crate) fn chunk_fault(&self) -> Option<ChunkFault> {
+        let config = &self.config;
+        let mut rng = self
+            .rng
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        if rng.gen_bool(clamp(config.chunk_write_fail)) {
+            Some(ChunkFault::FailWrite)
+        } else if rng.gen_bool(clamp(config.chunk_corrupt)) {
+            Some(ChunkFault::Corrupt)
+        } else {
+            None
+        }
+    }
+
+    /// Returns a copy of `bytes` with one random byte flipped.
+    pub(crate) fn corrupt(&self, bytes: &[u8]) -> Vec<u8> {
+        let mut corrupted = bytes.to_vec();
+        if corrupted.is_empty() {
+            // nothing to flip, so corrupt by appending instead
+            corrupted.push(0);
+        } else {
+            let mut rng = self
+                .rng
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            let index = rng.gen_range(0, corrupted.len());
+            corrupted[index] = !corrupted[index];
+        }
+        corrupted
+    }
+}
+
+// A config read from a file could hold anything, while `gen_bool` panics outside of [0, 1].
+fn clamp(p: f64) -> f64 {
+    if p.is_nan() {
+        0.0
+    } else {
+        p.clamp(0.0, 1.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use eyre::Result;
+
+    #[test]
+    fn overrides_are_applied() {
+        let mut config = ChaosConfig::default();
+        let errors =
+            config.apply_overrides("seed=7, msg_drop=0.5,msg_delay_max_ms=30,chunk_corrupt=1");
+
+        assert!(errors.is_empty(), "{:?}", errors);
+        assert_eq!(config.seed, Some(7));
+        assert_eq!(config.msg_drop, 0.5);
+        assert_eq!(config.msg_delay_max_ms, 30);
+        assert_eq!(config.chunk_corrupt, 1.0);
+        assert_eq!(config.msg_reorder, 0.0);
+    }
+
+    #[test]
+    fn invalid_overrides_are_reported_and_ignored() {
+        let mut config = ChaosConfig::default();
+        let errors = config.apply_overrides("msg_drop=1.5,bogus=1,msg_delay,msg_duplicate=0.2");
+
+        let fields: Vec<_> = errors.into_iter().map(|(field, _)| field).collect();
+        assert_eq!(fields, vec!["msg_drop", "bogus", "msg_delay"]);
+        assert_eq!(config.msg_drop, 0.0);
+        assert_eq!(config.msg_duplicate, 0.2);
+    }
+
+    #[test]
+    fn config_file_can_be_partial() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("chaos.json");
+        std::fs::write(&path, r#"{ "seed": 3, "chunk_write_fail": 0.25 }"#)?;
+
+        let config = ChaosConfig::from_file(&path).map_err(|e| eyre::eyre!(e))?;
+        assert_eq!(
+            config,
+            ChaosConfig {
+                seed: Some(3),
+                chunk_write_fail: 0.25,
+                ..ChaosConfig::default()
+            }
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn same_seed_gives_same_faults() {
+        let config = ChaosConfig {
+            seed: Some(42),
+            msg_drop: 0.2,
+            msg_delay: 0.2,
+            msg_duplicate: 0.2,
+            msg_reorder: 0.2,
+            chunk_write_fail: 0.3,
+            chunk_corrupt: 0.3,
+            ..ChaosConfig::default()
+        };
+
+        let faults = |chaos: Chaos| {
+            (0..100)
+                .map(|_| (chaos.msg_fault(), chaos.chunk_fault()))
+                .collect::<Vec<_>>()
+        };
+
+        let first = faults(Chaos::new(config.clone()));
+        assert_eq!(first, faults(Chaos::new(config)));
+        assert!(first.iter().any(|(msg, _)| msg.is_some()));
+        assert!(first.iter().any(|(msg, _)| msg.is_none()));
+    }
+
+    #[test]
+    fn no_faults_by_default() {
+        let chaos = Chaos::new(ChaosConfig::default());
+        for _ in 0..100 {
+            assert_eq!(chaos.msg_fault(), None);
+            assert_eq!(chaos.chunk_fault(), None);
+        }
+    }
+
+    #[test]
+    fn corrupt_changes_the_bytes() {
+        let chaos = Chaos::new(ChaosConfig::default());
+        let bytes = vec![1, 2, 3, 4];
+        let corrupted = chaos.corrupt(&bytes);
+
+        assert_eq!(corrupted.len(), bytes.len());
+        assert_ne!(corrupted, bytes);
+        assert_ne!(chaos.corrupt(&[]), Vec::<u8>::new());
+    }
+}
@@ -0,0 +1,344 @@
+// Copyright 2022 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use crate::client::{ChunkCacheConfig, Result};
+use crate::types::Chunk;
+
+use bytes::Bytes;
+use std::{
+    io::ErrorKind,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::SystemTime,
+};
+use tokio::{fs, sync::Mutex};
+use tracing::{debug, trace, warn};
+use xor_name::XorName;
+
+// Extension of the temporary files Chunks are written to before being moved into place.
+const TMP_FILE_EXTENSION: &str = "tmp";
+
+/// Size-bounded cache of Chunks stored on disk, which is shared by all the
+/// clients using the same directory, even across processes.
+///
+/// Each Chunk is kept in a file named after the hex encoded Chunk name. When the total size
+/// goes above the configured maximum, the least recently used Chunks are evicted.
+#[derive(Clone, Debug)]
+pub struct ChunkCache {
+    dir: PathBuf,
+    max_size: u64,
+    // Total size of the cached Chunks as of the last scan of the directory, plus the size of
+    // the Chunks stored since. It's `None` until the first scan. Other clients sharing the
+    // directory can make it fall behind, so it's corrected whenever we scan.
+    size: Arc<Mutex<Option<u64>>>,
+}
+
+/// Current usage of a [`ChunkCache`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChunkCacheStats {
+    /// Directory where the cached Chunks are stored.
+    pub dir: PathBuf,
+    /// Number of Chunks currently cached.
+    pub chunks: usize,
+    /// Total size in bytes of the cached Chunks.
+    pub size: u64,
+    /// Maximum total size in bytes of the cached Chunks.
+    pub max_size: u64,
+}
+
+// A cached Chunk file as found when scanning the cache directory.
+struct CachedFile {
+    path: PathBuf,
+    size: u64,
+    last_used: SystemTime,
+}
+
+impl ChunkCache {
+    /// Create a cache from its config. The directory is only created when the first Chunk is stored.
+    pub fn new(config: &ChunkCacheConfig) -> Self {
+        Self {
+            dir: config.dir.clone(),
+            max_size: config.max_size,
+            size: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Return the Chunk with the given name if it's cached.
+    ///
+    /// Chunks whose content doesn't match their name are removed from the cache and not returned.
+    pub async fn get(&self, name: &XorName) -> Option<Chunk> {
+        let path = self.chunk_path(name);
+        let bytes = match fs::read(&path).await {
+            Ok(bytes) => bytes,
+            Err(err) => {
+                if err.kind() != ErrorKind::NotFound {
+                    warn!("Failed to read cached Chunk {:?}: {:?}", name, err);
+                }
+                return None;
+            }
+        };
+
+        let chunk = Chunk::new(Bytes::from(bytes));
+        if chunk.name() != name {
+            warn!(
+                "Cached Chunk {:?} doesn't match its content, removing it from the cache",
+                name
+            );
+            let _ = fs::remove_file(&path).await;
+            return None;
+        }
+
+        // Mark it as recently used so it's the last to be evicted
+        if let Err(err) = touch(&path).await {
+            debug!(
+                "Failed to update last use of cached Chunk {:?}: {:?}",
+                name, err
+            );
+        }
+
+        Some(chunk)
+    }
+
+    /// Store a Chunk in the cache, evicting the least recently used Chunks if needed
+    /// to stay within the maximum size.
+    pub async fn insert(&self, chunk: &Chunk) -> Result<()> {
+        if chunk.payload_size() as u64 > self.max_size {
+            trace!("Chunk {:?} is too big to be cached", chunk.name());
+            return Ok(());
+        }
+
+        fs::create_dir_all(&self.dir).await?;
+
+        // Write to a temporary file first so other processes never read partial Chunks
+        let path = self.chunk_path(chunk.name());
+        let tmp_path = path.with_extension(TMP_FILE_EXTENSION);
+        fs::write(&tmp_path, chunk.value()).await?;
+        fs::rename(&tmp_path, &path).await?;
+
+        // Only scan the directory once we're likely to be over the maximum
+        let stored = chunk.value().len() as u64;
+        let mut size = self.size.lock().await;
+        *size = match *size {
+            Some(size) if size + stored <= self.max_size => Some(size + stored),
+            _ => Some(self.evict().await?),
+        };
+
+        Ok(())
+    }
+
+    /// Remove all the Chunks from the cache.
+    pub async fn clear(&self) -> Result<()> {
+        let mut size = self.size.lock().await;
+        for file in self.cached_files().await? {
+            remove_cached_file(&file.path).await?;
+        }
+        *size = Some(0);
+
+        Ok(())
+    }
+
+    /// Return the current usage of the cache.
+    pub async fn stats(&self) -> Result<ChunkCacheStats> {
+        let files = self.cached_files().await?;
+
+        Ok(ChunkCacheStats {
+            dir: self.dir.clone(),
+            chunks: files.len(),
+            size: files.iter().map(|file| file.size).sum(),
+            max_size: self.max_size,
+        })
+    }
+
+    // Remove the least recently used Chunks until the total size is within the maximum,
+    // returning the total size left.
+    async fn evict(&self) -> Result<u64> {
+        let mut files = self.cached_files().await?;
+        let mut size: u64 = files.iter().map(|file| file.size).sum();
+        if size <= self.max_size {
+            return Ok(size);
+        }
+
+        files.sort_by_key(|file| file.last_used);
+        for file in files {
+            if size <= self.max_size {
+                break;
+            }
+            trace!("Evicting Chunk file from cache: {}", file.path.display());
+            remove_cached_file(&file.path).await?;
+            size = size.saturating_sub(file.size);
+        }
+
+        Ok(size)
+    }
+
+    async fn cached_files(&self) -> Result<Vec<CachedFile>> {
+        let mut entries = match fs::read_dir(&self.dir).await {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == ErrorKind::NotFound => return Ok(vec![]),
+            Err(err) => return Err(err.into()),
+        };
+
+        let mut files = vec![];
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+            if path.extension().is_some() {
+                // Not a Chunk, e.g. a temporary file being written by another client
+                continue;
+            }
+            let metadata = match entry.metadata().await {
+                Ok(metadata) if metadata.is_file() => metadata,
+                // It may have been evicted by another client in the meantime
+                _ => continue,
+            };
+            files.push(CachedFile {
+                path,
+                size: metadata.len(),
+                last_used: metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH),
+            });
+        }
+
+        Ok(files)
+    }
+
+    fn chunk_path(&self, name: &XorName) -> PathBuf {
+        self.dir.join(hex::encode(name))
+    }
+}
+
+async fn touch(path: &Path) -> Result<()> {
+    let file = fs::OpenOptions::new().write(true).open(path).await?;
+    file.into_std().await.set_modified(SystemTime::now())?;
+    Ok(())
+}
+
+async fn remove_cached_file(path: &Path) -> Result<()> {
+    match fs::remove_file(path).await {
+        // It may have been evicted by another client in the meantime
+        Err(err) if err.kind() != ErrorKind::NotFound => Err(err.into()),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use eyre::Result;
+    use tempfile::tempdir;
+
+    fn random_chunk(size: usize) -> Chunk {
+        let bytes: Vec<u8> = (0..size).map(|_| rand::random::<u8>()).collect();
+        Chunk::new(Bytes::from(bytes))
+    }
+
+    fn cache_at(dir: &Path, max_size: u64) -> ChunkCache {
+        ChunkCache::new(&ChunkCacheConfig {
+            dir: dir.to_path_buf(),
+            max_size,
+        })
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn insert_and_get() -> Result<()> {
+        let temp_dir = tempdir()?;
+        let cache = cache_at(temp_dir.path(), 1024);
+
+        let chunk = random_chunk(100);
+        assert_eq!(cache.get(chunk.name()).await, None);
+
+        cache.insert(&chunk).await?;
+        assert_eq!(cache.get(chunk.name()).await, Some(chunk));
+
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn corrupted_chunk_is_discarded() -> Result<()> {
+        let temp_dir = tempdir()?;
+        let cache = cache_at(temp_dir.path(), 1024);
+
+        let chunk = random_chunk(100);
+        cache.insert(&chunk).await?;
+
+        let path = temp_dir.path().join(hex::encode(chunk.name()));
+        fs::write(&path, b"not the original content").await?;
+
+        assert_eq!(cache.get(chunk.name()).await, None);
+        assert!(!path.exists());
+
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn least_recently_used_chunks_are_evicted() -> Result<()> {
+        let temp_dir = tempdir()?;
+        let cache = cache_at(temp_dir.path(), 250);
+
+        let first = random_chunk(100);
+        let second = random_chunk(100);
+        cache.insert(&first).await?;
+        cache.insert(&second).await?;
+
+        // Make sure the first one is now the most recently used
+        let past = SystemTime::now() - std::time::Duration::from_secs(60);
+        std::fs::File::options()
+            .write(true)
+            .open(temp_dir.path().join(hex::encode(second.name())))?
+            .set_modified(past)?;
+        assert!(cache.get(first.name()).await.is_some());
+
+        let third = random_chunk(100);
+        cache.insert(&third).await?;
+
+        assert!(cache.get(first.name()).await.is_some());
+        assert!(cache.get(second.name()).await.is_none());
+        assert!(cache.get(third.name()).await.is_some());
+
+        let stats = cache.stats().await?;
+        assert_eq!(stats.chunks, 2);
+        assert_eq!(stats.size, 200);
+        assert_eq!(stats.max_size, 250);
+
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn caches_sharing_a_dir_stay_within_max_size() -> Result<()> {
+        let temp_dir = tempdir()?;
+        let cache = cache_at(temp_dir.path(), 250);
+        let other_cache = cache_at(temp_dir.path(), 250);
+
+        cache.insert(&random_chunk(100)).await?;
+        cache.insert(&random_chunk(100)).await?;
+        other_cache.insert(&random_chunk(100)).await?;
+        other_cache.insert(&random_chunk(100)).await?;
+        cache.insert(&random_chunk(100)).await?;
+
+        let stats = cache.stats().await?;
+        assert!(stats.size <= 250);
+        assert_eq!(stats.chunks, 2);
+
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn clear_removes_all_chunks() -> Result<()> {
+        let temp_dir = tempdir()?;
+        let cache = cache_at(temp_dir.path(), 1024);
+
+        cache.insert(&random_chunk(100)).await?;
+        cache.insert(&random_chunk(100)).await?;
+        assert_eq!(cache.stats().await?.chunks, 2);
+
+        cache.clear().await?;
+        let stats = cache.stats().await?;
+        assert_eq!(stats.chunks, 0);
+        assert_eq!(stats.size, 0);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,314 @@
+// Copyright 2022 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+//! Self-encryption of files one chunk at a time, so the memory used doesn't depend on the file size.
+//!
+//! `self_encryption::encrypt` needs the whole content in memory. The chunks produced here are
+//! identical to the ones it produces, but the file is read twice instead: a first pass obtains the
+//! hashes of all the source chunks, which the encryption of each chunk depends on, and a second
+//! pass reads, encrypts and hands out each chunk.
+//!
+//! `self_encryption` doesn't expose the encryption of a single chunk, so it's replicated here. The
+//! crate is pinned to the version this matches, and `chunks_match_self_encryption` checks the
+//! chunks are still the same as the ones it produces.
+
+use crate::client::{Error, Result};
+use crate::types::Chunk;
+
+use aes::{
+    cipher::{block_padding::Pkcs7, BlockEncryptMut, KeyIvInit},
+    Aes128,
+};
+use brotli::enc::BrotliEncoderParams;
+use bytes::Bytes;
+use self_encryption::{
+    ChunkInfo, COMPRESSION_QUALITY, MAX_CHUNK_SIZE, MIN_CHUNK_SIZE, MIN_ENCRYPTABLE_BYTES,
+};
+use std::{io::Cursor, path::Path, sync::Arc};
+use tokio::{
+    fs::File,
+    io::{AsyncRead, AsyncReadExt, AsyncSeekExt},
+    task,
+};
+use xor_name::{XorName, XOR_NAME_LEN};
+
+type Aes128CbcEnc = cbc::Encryptor<Aes128>;
+
+const KEY_SIZE: usize = 16;
+const IV_SIZE: usize = 16;
+const PAD_SIZE: usize = (XOR_NAME_LEN * 3) - KEY_SIZE - IV_SIZE;
+
+/// Self-encrypts a file from disk, one chunk at a time.
+pub(crate) struct FileEncryptor {
+    file: File,
+    file_size: usize,
+    src_hashes: Arc<Vec<XorName>>,
+    next_index: usize,
+}
+
+impl FileEncryptor {
+    /// Opens the file and obtains the hashes of all its source chunks.
+    /// The file must be at least [`MIN_ENCRYPTABLE_BYTES`] long.
+    pub(crate) async fn open(path: &Path) -> Result<Self> {
+        let mut file = File::open(path).await?;
+        let file_size = file.metadata().await?.len() as usize;
+        if file_size < MIN_ENCRYPTABLE_BYTES {
+            return Err(Error::TooSmallForSelfEncryption);
+        }
+
+        let src_hashes = hash_chunks(&mut file, file_size).await?;
+        let _ = file.rewind().await?;
+
+        Ok(Self {
+            file,
+            file_size,
+            src_hashes: Arc::new(src_hashes),
+            next_index: 0,
+        })
+    }
+
+    /// Reads and encrypts the next chunk of the file, returning `None` once all were returned.
+    pub(crate) async fn next_chunk(&mut self) -> Result<Option<(ChunkInfo, Chunk)>> {
+        let index = self.next_index;
+        if index >= self.src_hashes.len() {
+            return Ok(None);
+        }
+
+        let mut content = vec![0; chunk_size(self.file_size, index)];
+        let _ = self.file.read_exact(&mut content).await?;
+        self.next_index += 1;
+
+        let src_hashes = self.src_hashes.clone();
+        let encrypted = task::spawn_blocking(move || {
+            encrypt_chunk(index, Bytes::from(content), src_hashes.as_ref())
+        })
+        .await
+        .map_err(|err| self_encryption::Error::Generic(err.to_string()))??;
+
+        Ok(Some(encrypted))
+    }
+}
+
+/// Reads all the source chunks of `file_size` bytes of content, returning their hashes.
+pub(crate) async fn hash_chunks(
+    reader: &mut (impl AsyncRead + Unpin),
+    file_size: usize,
+) -> Result<Vec<XorName>> {
+    let mut hashes = vec![];
+    for index in 0..chunk_count(file_size) {
+        let mut content = vec![0; chunk_size(file_size, index)];
+        let _ = reader.read_exact(&mut content).await?;
+        hashes.push(XorName::from_content(&content));
+    }
+
+    Ok(hashes)
+}
+
+/// Encrypts the source chunk at `index`, as `self_encryption::encrypt` does.
+pub(crate) fn encrypt_chunk(
+    index: usize,
+    content: Bytes,
+    src_hashes: &[XorName],
+) -> Result<(ChunkInfo, Chunk)> {
+    let (pad, key, iv) = pad_key_iv(index, src_hashes);
+
+    let mut compressed = vec![];
+    let params = BrotliEncoderParams {
+        quality: COMPRESSION_QUALITY,
+        ..Default::default()
+    };
+    let _size =
+        brotli::BrotliCompress(&mut Cursor::new(content.as_ref()), &mut compressed, &params)
+            .map_err(|_| Error::SelfEncryption(self_encryption::Error::Compression))?;
+
+    let cipher = Aes128CbcEnc::new(key.as_ref().into(), iv.as_ref().into());
+    let encrypted = cipher.encrypt_padded_vec_mut::<Pkcs7>(&compressed);
+    let encrypted: Vec<u8> = encrypted
+        .iter()
+        .zip(pad.iter().cycle())
+        .map(|(&a, &b)| a ^ b)
+        .collect();
+
+    let chunk = Chunk::new(Bytes::from(encrypted));
+    let info = ChunkInfo {
+        index,
+        dst_hash: *chunk.name(),
+        src_hash: src_hashes[index],
+        src_size: content.len(),
+    };
+
+    Ok((info, chunk))
+}
+
+// The pad, key and iv of a chunk are derived from its own hash and the ones of the two previous chunks.
+fn pad_key_iv(
+    index: usize,
+    src_hashes: &[XorName],
+) -> ([u8; PAD_SIZE], [u8; KEY_SIZE], [u8; IV_SIZE]) {
+    let count = src_hashes.len();
+    let (n_1, n_2) = match index {
+        0 => (count - 1, count - 2),
+        1 => (0, count - 1),
+        n => (n - 1, n - 2),
+    };
+
+    let mut pad = [0u8; PAD_SIZE];
+    for (pad_byte, byte) in pad
+        .iter_mut()
+        .zip(src_hashes[index].iter().chain(src_hashes[n_2].iter()))
+    {
+        *pad_byte = *byte;
+    }
+
+    let mut key = [0u8; KEY_SIZE];
+    let mut iv = [0u8; IV_SIZE];
+    for (key_byte, byte) in key
+        .iter_mut()
+        .chain(iv.iter_mut())
+        .zip(src_hashes[n_1].iter())
+    {
+        *key_byte = *byte;
+    }
+
+    (pad, key, iv)
+}
+
+// Number of source chunks a content of `file_size` bytes is split into.
+fn chunk_count(file_size: usize) -> usize {
+    if file_size < MIN_ENCRYPTABLE_BYTES {
+        0
+    } else if file_size < 3 * MAX_CHUNK_SIZE {
+        3
+    } else {
+        file_size.div_ceil(MAX_CHUNK_SIZE)
+    }
+}
+
+// Size of the source chunk at `index`. Chunks are contiguous, so the content can be read sequentially.
+fn chunk_size(file_size: usize, index: usize) -> usize {
+    if file_size < MIN_ENCRYPTABLE_BYTES {
+        return 0;
+    }
+    if file_size < 3 * MAX_CHUNK_SIZE {
+        return if index < 2 {
+            file_size / 3
+        } else {
+            file_size - (2 * (file_size / 3))
+        };
+    }
+
+    let count = chunk_count(file_size);
+    if index < count - 2 {
+        return MAX_CHUNK_SIZE;
+    }
+    let remainder = file_size % MAX_CHUNK_SIZE;
+    let penultimate = index == count - 2;
+    if remainder == 0 {
+        MAX_CHUNK_SIZE
+    } else if remainder < MIN_CHUNK_SIZE {
+        if penultimate {
+            MAX_CHUNK_SIZE - MIN_CHUNK_SIZE
+        } else {
+            MIN_CHUNK_SIZE + remainder
+        }
+    } else if penultimate {
+        MAX_CHUNK_SIZE
+    } else {
+        remainder
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::utils::random_bytes;
+    use eyre::Result;
+    use itertools::Itertools;
+    use self_encryption::DataMap;
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn chunks_match_self_encryption() -> Result<()> {
+        let sizes = [
+            MIN_ENCRYPTABLE_BYTES,
+            MAX_CHUNK_SIZE + 7,
+            3 * MAX_CHUNK_SIZE,
+            3 * MAX_CHUNK_SIZE + MIN_CHUNK_SIZE / 2,
+            4 * MAX_CHUNK_SIZE + 3 * MIN_CHUNK_SIZE,
+            MIN_ENCRYPTABLE_BYTES + rand::random::<usize>() % (5 * MAX_CHUNK_SIZE),
+        ];
+
+        for size in sizes {
+            let bytes = random_bytes(size);
+            let (data_map, encrypted_chunks) = self_encryption::encrypt(bytes.clone())?;
+
+            let mut file = NamedTempFile::new()?;
+            file.write_all(&bytes)?;
+            let mut encryptor = FileEncryptor::open(file.path()).await?;
+
+            let mut infos = vec![];
+            let mut chunks = vec![];
+            while let Some((info, chunk)) = encryptor.next_chunk().await? {
+                infos.push(info);
+                chunks.push(chunk);
+            }
+
+            assert_eq!(
+                infos,
+                data_map.infos(),
+                "chunk infos differ for size {}",
+                size
+            );
+            let expected_chunks = encrypted_chunks
+                .into_iter()
+                .sorted_by_key(|chunk| chunk.index)
+                .map(|chunk| Chunk::new(chunk.content))
+                .collect_vec();
+            assert_eq!(chunks, expected_chunks, "chunks differ for size {}", size);
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn chunks_are_decrypted_by_self_encryption() -> Result<()> {
+        let bytes = random_bytes(3 * MAX_CHUNK_SIZE + MIN_CHUNK_SIZE);
+        let mut file = NamedTempFile::new()?;
+        file.write_all(&bytes)?;
+        let mut encryptor = FileEncryptor::open(file.path()).await?;
+
+        let mut infos = vec![];
+        let mut chunks = vec![];
+        while let Some((info, chunk)) = encryptor.next_chunk().await? {
+            chunks.push(self_encryption::EncryptedChunk {
+                index: info.index,
+                content: chunk.value().clone(),
+            });
+            infos.push(info);
+        }
+
+        let decrypted = self_encryption::decrypt_full_set(&DataMap::new(infos), &chunks)?;
+        assert_eq!(decrypted, bytes);
+
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn too_small_file_is_rejected() -> Result<()> {
+        let mut file = NamedTempFile::new()?;
+        file.write_all(&random_bytes(MIN_ENCRYPTABLE_BYTES - 1))?;
+
+        assert!(matches!(
+            FileEncryptor::open(file.path()).await,
+            Err(Error::TooSmallForSelfEncryption)
+        ));
+
+        Ok(())
+    }
+}
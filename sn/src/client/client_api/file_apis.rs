@@ -7,24 +7,33 @@
 // permissions and limitations relating to use of the SAFE Network Software.
 
 use super::{
-    data::{encrypt_large, to_chunk, LargeFile, SmallFile},
+    data::{encrypt_large, pack, to_chunk, FileEncryptor, LargeFile, SmallFile},
+    uploads::{ChunkUploads, UploadOptions},
     Client,
 };
 use crate::{
-    client::{client_api::data::DataMapLevel, utils::encryption, Error, Result},
-    messaging::data::{DataCmd, DataQuery, QueryResponse},
-    types::{BytesAddress, Chunk, ChunkAddress, Encryption, PublicKey, Scope},
+    client::{client_api::data::DataMapLevel, utils::encryption, Error, Result, UploadJournal},
+    messaging::data::{DataQuery, QueryResponse},
+    types::{BytesAddress, Chunk, ChunkAddress, Encryption, Keypair, Scope},
 };
 
 use bincode::deserialize;
 use bytes::Bytes;
 use futures::future::join_all;
 use itertools::Itertools;
-use self_encryption::{self, ChunkInfo, DataMap, EncryptedChunk};
-use tokio::task;
-use tracing::trace;
+use self_encryption::{self, ChunkInfo, DataMap, EncryptedChunk, MIN_ENCRYPTABLE_BYTES};
+use std::path::Path;
+use tokio::{
+    fs,
+    io::{AsyncRead, AsyncWrite, AsyncWriteExt},
+    task,
+};
+use tracing::{trace, warn};
 use xor_name::XorName;
 
+// Number of Chunks fetched and decrypted at a time when streaming a file into a writer.
+const DOWNLOAD_BATCH_SIZE: usize = 8;
+
 struct HeadChunk {
     chunk: Chunk,
     address: BytesAddress,
@@ -119,6 +128,15 @@ impl Client {
             return Ok(chunk.clone());
         }
 
+        // then check the on-disk cache, which is shared with other clients
+        if let Some(cache) = &self.disk_chunks_cache {
+            if let Some(chunk) = cache.get(name).await {
+                trace!("Chunk retrieved from on-disk cache: {:?}", name);
+                let _ = self.chunks_cache.write().await.insert(chunk.clone());
+                return Ok(chunk);
+            }
+        }
+
         let res = self
             .send_query(DataQuery::GetChunk(ChunkAddress(*name)))
             .await?;
@@ -133,6 +151,16 @@ impl Client {
 
         let _ = self.chunks_cache.write().await.insert(chunk.clone());
 
+        if let Some(cache) = &self.disk_chunks_cache {
+            // Failing to cache it shouldn't fail the retrieval
+            if let Err(err) = cache.insert(&chunk).await {
+                warn!(
+                    "Failed to store Chunk {:?} in on-disk cache: {:?}",
+                    name, err
+                );
+            }
+        }
+
         Ok(chunk)
     }
 
@@ -140,10 +168,10 @@ impl Client {
     #[instrument(skip_all, level = "trace")]
     pub fn chunk_bytes(&self, bytes: Bytes, scope: Scope) -> Result<(BytesAddress, Vec<Chunk>)> {
         if let Ok(file) = LargeFile::new(bytes.clone()) {
-            Self::encrypt_large(file, scope, self.public_key())
+            Self::encrypt_large(file, scope, &self.keypair)
         } else {
             let file = SmallFile::new(bytes)?;
-            let (address, chunk) = Self::package_small(file, scope, self.public_key())?;
+            let (address, chunk) = Self::package_small(file, scope, &self.keypair)?;
             Ok((address, vec![chunk]))
         }
     }
@@ -154,9 +182,9 @@ impl Client {
     fn encrypt_large(
         file: LargeFile,
         scope: Scope,
-        public_key: PublicKey,
+        owner: &Keypair,
     ) -> Result<(BytesAddress, Vec<Chunk>)> {
-        let owner = encryption(scope, public_key);
+        let owner = encryption(scope, owner);
         encrypt_large(file.bytes(), owner.as_ref())
     }
 
@@ -166,11 +194,11 @@ impl Client {
     fn package_small(
         file: SmallFile,
         scope: Scope,
-        public_key: PublicKey,
+        owner: &Keypair,
     ) -> Result<(BytesAddress, Chunk)> {
-        let encryption = encryption(scope, public_key);
+        let encryption = encryption(scope, owner);
         let chunk = to_chunk(file.bytes(), encryption.as_ref())?;
-        if chunk.value().len() >= self_encryption::MIN_ENCRYPTABLE_BYTES {
+        if chunk.value().len() >= MIN_ENCRYPTABLE_BYTES {
             return Err(Error::SmallFilePaddingNeeded);
         }
         let name = *chunk.name();
@@ -186,11 +214,23 @@ impl Client {
     /// form of immutable chunks, without any batching.
     #[instrument(skip(self, bytes), level = "debug")]
     pub async fn upload(&self, bytes: Bytes, scope: Scope) -> Result<BytesAddress> {
+        self.upload_with_options(bytes, scope, &UploadOptions::default())
+            .await
+    }
+
+    /// Same as [`Client::upload`], with the Chunks being stored as set in the [`UploadOptions`].
+    #[instrument(skip(self, bytes), level = "debug")]
+    pub async fn upload_with_options(
+        &self,
+        bytes: Bytes,
+        scope: Scope,
+        options: &UploadOptions,
+    ) -> Result<BytesAddress> {
         if let Ok(file) = LargeFile::new(bytes.clone()) {
-            self.upload_large(file, scope).await
+            self.upload_large(file, scope, options).await
         } else {
             let file = SmallFile::new(bytes)?;
-            self.upload_small(file, scope).await
+            self.upload_small(file, scope, options).await
         }
     }
 
@@ -213,16 +253,17 @@ impl Client {
 
     /// Calculates a LargeFile's/SmallFile's address from self encrypted chunks,
     /// without storing them onto the network.
-    #[instrument(skip(bytes), level = "debug")]
-    pub fn calculate_address(bytes: Bytes, scope: Scope) -> Result<BytesAddress> {
-        // we use just a random BLS public key as the owner
-        let public_key = PublicKey::Bls(bls::SecretKey::random().public_key());
+    ///
+    /// Private data is encrypted with a key derived from the `owner`'s keypair,
+    /// so its address depends on who stores it.
+    #[instrument(skip(bytes, owner), level = "debug")]
+    pub fn calculate_address(bytes: Bytes, scope: Scope, owner: &Keypair) -> Result<BytesAddress> {
         if let Ok(file) = LargeFile::new(bytes.clone()) {
-            let (head_address, _all_chunks) = Self::encrypt_large(file, scope, public_key)?;
+            let (head_address, _all_chunks) = Self::encrypt_large(file, scope, owner)?;
             Ok(head_address)
         } else {
             let file = SmallFile::new(bytes)?;
-            let (address, _chunk) = Self::package_small(file, scope, public_key)?;
+            let (address, _chunk) = Self::package_small(file, scope, owner)?;
             Ok(address)
         }
     }
@@ -230,41 +271,260 @@ impl Client {
     /// Directly writes a [`LargeFile`] to the network in the
     /// form of immutable self encrypted chunks, without any batching.
     #[instrument(skip_all, level = "trace")]
-    async fn upload_large(&self, large: LargeFile, scope: Scope) -> Result<BytesAddress> {
-        let (head_address, all_chunks) = Self::encrypt_large(large, scope, self.public_key())?;
-
-        let tasks = all_chunks.into_iter().map(|chunk| {
-            let writer = self.clone();
-            task::spawn(async move { writer.send_cmd(DataCmd::StoreChunk(chunk)).await })
-        });
-
-        let respones = join_all(tasks)
-            .await
-            .into_iter()
-            .flatten() // swallows errors
+    async fn upload_large(
+        &self,
+        large: LargeFile,
+        scope: Scope,
+        options: &UploadOptions,
+    ) -> Result<BytesAddress> {
+        let (data_map, encrypted_chunks) = self_encryption::encrypt(large.bytes())?;
+        let src_sizes = data_map
+            .infos()
+            .iter()
+            .map(|info| info.src_size as u64)
             .collect_vec();
 
-        for res in respones {
-            // fail with any issue here
-            let _ = res?;
+        let mut uploads = ChunkUploads::new(self, options, None);
+        for encrypted in encrypted_chunks {
+            let bytes = src_sizes[encrypted.index];
+            uploads.push(Chunk::new(encrypted.content), bytes).await?;
         }
 
+        // The DataMap chunks don't carry any of the content
+        let owner = encryption(scope, &self.keypair);
+        let (head_address, head_chunks) = pack(data_map, vec![], owner.as_ref())?;
+        for chunk in head_chunks {
+            uploads.push(chunk, 0).await?;
+        }
+        uploads.finish().await?;
+
         Ok(head_address)
     }
 
     /// Directly writes a [`SmallFile`] to the network in the
     /// form of a single chunk, without any batching.
     #[instrument(skip_all, level = "trace")]
-    async fn upload_small(&self, small: SmallFile, scope: Scope) -> Result<BytesAddress> {
-        let (address, chunk) = Self::package_small(small, scope, self.public_key())?;
-        self.send_cmd(DataCmd::StoreChunk(chunk)).await?;
+    async fn upload_small(
+        &self,
+        small: SmallFile,
+        scope: Scope,
+        options: &UploadOptions,
+    ) -> Result<BytesAddress> {
+        let bytes = small.bytes().len() as u64;
+        let (address, chunk) = Self::package_small(small, scope, &self.keypair)?;
+
+        let mut uploads = ChunkUploads::new(self, options, None);
+        uploads.push(chunk, bytes).await?;
+        uploads.finish().await?;
+
         Ok(address)
     }
 
+    /// Writes the content of a file to the network, reading and self-encrypting it one chunk
+    /// at a time, so files larger than the available memory can be uploaded.
+    ///
+    /// The resulting address is the same [`Client::upload`] returns for the same content.
+    #[instrument(skip(self), level = "debug")]
+    pub async fn upload_from_path(&self, path: &Path, scope: Scope) -> Result<BytesAddress> {
+        self.upload_path(path, scope, None, &UploadOptions::default())
+            .await
+    }
+
+    /// Same as [`Client::upload_from_path`], with the Chunks being stored as set in the [`UploadOptions`].
+    #[instrument(skip(self), level = "debug")]
+    pub async fn upload_from_path_with_options(
+        &self,
+        path: &Path,
+        scope: Scope,
+        options: &UploadOptions,
+    ) -> Result<BytesAddress> {
+        self.upload_path(path, scope, None, options).await
+    }
+
+    /// Writes the file an [`UploadJournal`] was started for to the network, recording in the
+    /// journal every Chunk as it's stored.
+    ///
+    /// Chunks the journal already records as stored are not sent again, so an upload which failed
+    /// halfway can be resumed with the same journal. A new journal simply uploads the whole file.
+    #[instrument(skip(self), level = "debug")]
+    pub async fn resume_upload(&self, journal: &mut UploadJournal) -> Result<BytesAddress> {
+        self.resume_upload_with_options(journal, &UploadOptions::default())
+            .await
+    }
+
+    /// Same as [`Client::resume_upload`], with the Chunks being stored as set in the [`UploadOptions`].
+    ///
+    /// The content of the Chunks already stored is reported as done once the upload resumes.
+    #[instrument(skip(self), level = "debug")]
+    pub async fn resume_upload_with_options(
+        &self,
+        journal: &mut UploadJournal,
+        options: &UploadOptions,
+    ) -> Result<BytesAddress> {
+        if let Some(address) = journal.address() {
+            trace!("Upload of {} already completed", journal.source().display());
+            return Ok(address);
+        }
+
+        let path = journal.source().to_path_buf();
+        let scope = journal.scope();
+        let address = self
+            .upload_path(&path, scope, Some(&mut *journal), options)
+            .await?;
+        journal.record_completed(address).await?;
+
+        Ok(address)
+    }
+
+    /// Writes the content read from `reader` to the network, without holding it all in memory.
+    ///
+    /// Self-encryption needs to read the content twice, so it's first spooled into a temporary file.
+    #[instrument(skip(self, reader), level = "debug")]
+    pub async fn upload_stream(
+        &self,
+        reader: &mut (impl AsyncRead + Unpin),
+        scope: Scope,
+    ) -> Result<BytesAddress> {
+        let spool = tempfile::NamedTempFile::new()?;
+        let mut file = fs::File::create(spool.path()).await?;
+        let _ = tokio::io::copy(reader, &mut file).await?;
+        file.flush().await?;
+        drop(file);
+
+        self.upload_from_path(spool.path(), scope).await
+    }
+
+    /// Calculates the address the content of a file would be stored at,
+    /// reading it one chunk at a time and without storing anything onto the network.
+    ///
+    /// As with [`Client::calculate_address`], the address of private data depends on the `owner`.
+    #[instrument(skip(owner), level = "debug")]
+    pub async fn calculate_address_from_path(
+        path: &Path,
+        scope: Scope,
+        owner: &Keypair,
+    ) -> Result<BytesAddress> {
+        let (head_address, _chunk_sizes) = Self::chunk_sizes_from_path(path, scope, owner).await?;
+        Ok(head_address)
+    }
+
+    /// Calculates the address the content of a file would be stored at, along with the sizes
+    /// of all the chunks it would be stored as, e.g. to get a quote of the cost of storing it.
+    /// The file is read one chunk at a time and nothing is stored onto the network.
+    #[instrument(skip(owner), level = "debug")]
+    pub async fn chunk_sizes_from_path(
+        path: &Path,
+        scope: Scope,
+        owner: &Keypair,
+    ) -> Result<(BytesAddress, Vec<usize>)> {
+        let file_size = fs::metadata(path).await?.len() as usize;
+        if file_size < MIN_ENCRYPTABLE_BYTES {
+            let file = SmallFile::new(Bytes::from(fs::read(path).await?))?;
+            let (address, chunk) = Self::package_small(file, scope, owner)?;
+            return Ok((address, vec![chunk.value().len()]));
+        }
+
+        let mut encryptor = FileEncryptor::open(path).await?;
+        let mut infos = vec![];
+        let mut chunk_sizes = vec![];
+        while let Some((info, chunk)) = encryptor.next_chunk().await? {
+            infos.push(info);
+            chunk_sizes.push(chunk.value().len());
+        }
+
+        let owner = encryption(scope, owner);
+        let (head_address, head_chunks) = pack(DataMap::new(infos), vec![], owner.as_ref())?;
+        chunk_sizes.extend(head_chunks.iter().map(|chunk| chunk.value().len()));
+
+        Ok((head_address, chunk_sizes))
+    }
+
+    /// Reads the content stored at `address` from the network into `writer`, fetching and
+    /// decrypting a few chunks at a time, so files larger than the available memory can be read.
+    ///
+    /// Returns the number of bytes written.
+    #[instrument(skip(self, writer), level = "debug")]
+    pub async fn read_to_writer(
+        &self,
+        address: BytesAddress,
+        writer: &mut (impl AsyncWrite + Unpin),
+    ) -> Result<u64> {
+        let chunk = self.get_chunk(address.name()).await?;
+
+        // As in `read_bytes`, if it's not a LargeFile we assume it's a SmallFile
+        let data_map = match self
+            .unpack_head_chunk(HeadChunk {
+                chunk: chunk.clone(),
+                address,
+            })
+            .await
+        {
+            Ok(data_map) => data_map,
+            Err(_) => {
+                let bytes = self.get_bytes(chunk, address.scope())?;
+                writer.write_all(&bytes).await?;
+                writer.flush().await?;
+                return Ok(bytes.len() as u64);
+            }
+        };
+
+        let mut written = 0;
+        for infos in &data_map.infos().into_iter().chunks(DOWNLOAD_BATCH_SIZE) {
+            let encrypted_chunks = Self::try_get_chunks(self, infos.collect_vec()).await?;
+            let bytes =
+                self_encryption::decrypt_range(&data_map, &encrypted_chunks, 0, usize::MAX)?;
+            writer.write_all(&bytes).await?;
+            written += bytes.len() as u64;
+        }
+        writer.flush().await?;
+
+        Ok(written)
+    }
+
     // --------------------------------------------
     // ---------- Private helpers -----------------
     // --------------------------------------------
 
+    // Streams a file to the network, skipping the Chunks the journal, if any, records as stored.
+    async fn upload_path(
+        &self,
+        path: &Path,
+        scope: Scope,
+        journal: Option<&mut UploadJournal>,
+        options: &UploadOptions,
+    ) -> Result<BytesAddress> {
+        let mut uploads = ChunkUploads::new(self, options, journal);
+
+        let file_size = fs::metadata(path).await?.len() as usize;
+        if file_size < MIN_ENCRYPTABLE_BYTES {
+            let bytes = Bytes::from(fs::read(path).await?);
+            let (address, chunks) = self.chunk_bytes(bytes, scope)?;
+            for chunk in chunks {
+                uploads.push(chunk, file_size as u64).await?;
+            }
+            uploads.finish().await?;
+            return Ok(address);
+        }
+
+        let mut encryptor = FileEncryptor::open(path).await?;
+        let mut infos = vec![];
+        while let Some((info, chunk)) = encryptor.next_chunk().await? {
+            let bytes = info.src_size as u64;
+            infos.push(info);
+            uploads.push(chunk, bytes).await?;
+        }
+
+        // Only the DataMap chunks are left to be stored
+        let owner = encryption(scope, &self.keypair);
+        let (head_address, head_chunks) = pack(DataMap::new(infos), vec![], owner.as_ref())?;
+        for chunk in head_chunks {
+            uploads.push(chunk, 0).await?;
+        }
+        uploads.finish().await?;
+
+        Ok(head_address)
+    }
+
     // Gets and decrypts chunks from the network using nothing else but the data map,
     // then returns the raw data.
     async fn read_all(&self, data_map: DataMap) -> Result<Bytes> {
@@ -370,7 +630,7 @@ impl Client {
         if matches!(scope, Scope::Public) {
             Ok(chunk.value().clone())
         } else {
-            let owner = encryption(scope, self.public_key()).ok_or(Error::NoEncryptionObject)?;
+            let owner = encryption(scope, &self.keypair).ok_or(Error::NoEncryptionObject)?;
             Ok(owner.decrypt(chunk.value().clone())?)
         }
     }
@@ -380,23 +640,52 @@ impl Client {
 mod tests {
     use crate::client::utils::test_utils::create_test_client_with;
     use crate::client::{
-        client_api::file_apis::LargeFile,
+        client_api::file_apis::{LargeFile, SmallFile, DOWNLOAD_BATCH_SIZE},
         utils::test_utils::{create_test_client, init_test_logger},
-        Client,
+        ChunkState, Client, UploadEvent, UploadJournal, UploadOptions,
     };
     use crate::types::log_markers::LogMarker;
     use crate::types::{utils::random_bytes, BytesAddress, Keypair, Scope};
 
     use bytes::Bytes;
-    use eyre::Result;
+    use eyre::{bail, eyre, Result};
     use futures::future::join_all;
     use rand::rngs::OsRng;
-    use std::time::Duration;
+    use std::{collections::BTreeSet, io::Write, time::Duration};
     use tokio::time::Instant;
     use tracing::{instrument::Instrumented, Instrument};
 
     const LARGE_FILE_SIZE_MIN: usize = self_encryption::MIN_ENCRYPTABLE_BYTES;
 
+    // Test the Chunk a private SmallFile is stored in only holds its content encrypted.
+    #[test]
+    fn private_small_file_is_encrypted() -> Result<()> {
+        use crate::client::utils::encryption;
+        use crate::types::Encryption;
+
+        let keypair = Keypair::new_ed25519(&mut OsRng);
+        let bytes = random_bytes(LARGE_FILE_SIZE_MIN / 2);
+
+        let (address, chunk) =
+            Client::package_small(SmallFile::new(bytes.clone())?, Scope::Private, &keypair)?;
+        assert_eq!(address, BytesAddress::Private(*chunk.name()));
+        assert_ne!(chunk.value(), &bytes);
+        assert!(!chunk
+            .value()
+            .windows(bytes.len())
+            .any(|window| window == bytes.as_ref()));
+
+        let owner = encryption(Scope::Private, &keypair).ok_or_else(|| eyre!("No encryption"))?;
+        assert_eq!(owner.decrypt(chunk.value().clone())?, bytes);
+
+        // Someone else can't decrypt it
+        let other = Keypair::new_ed25519(&mut OsRng);
+        let other = encryption(Scope::Private, &other).ok_or_else(|| eyre!("No encryption"))?;
+        assert_ne!(other.decrypt(chunk.value().clone()).ok(), Some(bytes));
+
+        Ok(())
+    }
+
     #[test]
     fn deterministic_chunking() -> Result<()> {
         init_test_logger();
@@ -405,13 +694,13 @@ mod tests {
 
         use crate::client::client_api::data::encrypt_large;
         use crate::client::utils::encryption;
-        let owner = encryption(Scope::Private, keypair.public_key());
+        let owner = encryption(Scope::Private, &keypair);
         let (first_address, mut first_chunks) = encrypt_large(file.clone(), owner.as_ref())?;
 
         first_chunks.sort();
 
         for _ in 0..100 {
-            let owner = encryption(Scope::Private, keypair.public_key());
+            let owner = encryption(Scope::Private, &keypair);
             let (head_address, mut all_chunks) = encrypt_large(file.clone(), owner.as_ref())?;
             assert_eq!(first_address, head_address);
             all_chunks.sort();
@@ -421,6 +710,43 @@ mod tests {
         Ok(())
     }
 
+    // Test the chunks calculated from a file are the ones its content is stored as.
+    #[tokio::test(flavor = "multi_thread")]
+    async fn chunk_sizes_from_path_match_stored_chunks() -> Result<()> {
+        use crate::client::client_api::data::encrypt_large;
+        use crate::client::utils::encryption;
+
+        let keypair = Keypair::new_ed25519(&mut OsRng);
+        for size in [
+            LARGE_FILE_SIZE_MIN / 2,
+            3 * self_encryption::MAX_CHUNK_SIZE + 1,
+        ] {
+            let bytes = random_bytes(size);
+            let mut file = tempfile::NamedTempFile::new()?;
+            file.write_all(&bytes)?;
+
+            let (address, mut chunk_sizes) =
+                Client::chunk_sizes_from_path(file.path(), Scope::Private, &keypair).await?;
+            let (expected_address, chunks) = if size < LARGE_FILE_SIZE_MIN {
+                let (address, chunk) =
+                    Client::package_small(SmallFile::new(bytes)?, Scope::Private, &keypair)?;
+                (address, vec![chunk])
+            } else {
+                let owner = encryption(Scope::Private, &keypair);
+                encrypt_large(bytes, owner.as_ref())?
+            };
+            let mut expected_sizes: Vec<_> =
+                chunks.iter().map(|chunk| chunk.value().len()).collect();
+
+            assert_eq!(address, expected_address);
+            chunk_sizes.sort_unstable();
+            expected_sizes.sort_unstable();
+            assert_eq!(chunk_sizes, expected_sizes);
+        }
+
+        Ok(())
+    }
+
     // Test storing and reading min sized LargeFile.
     #[tokio::test(flavor = "multi_thread")]
     async fn store_and_read_3kb() -> Result<()> {
@@ -441,7 +767,7 @@ mod tests {
         // Test storing private file with the same value.
         // Should not conflict and return same address
         let address = client
-            .upload_large(file.clone(), Scope::Private)
+            .upload_large(file.clone(), Scope::Private, &UploadOptions::default())
             .instrument(tracing::info_span!(
                 "checking no conflict on same private upload"
             ))
@@ -450,7 +776,7 @@ mod tests {
 
         // Test storing public file with the same value. Should not conflict.
         let public_address = client
-            .upload_large(file.clone(), Scope::Public)
+            .upload_large(file.clone(), Scope::Public, &UploadOptions::default())
             .instrument(tracing::info_span!("checking no conflict on public upload"))
             .await?;
 
@@ -533,6 +859,178 @@ mod tests {
         Ok(())
     }
 
+    // Test streaming a file to the network and back, for sizes across the self-encryption thresholds.
+    #[tokio::test(flavor = "multi_thread")]
+    async fn upload_from_path_and_read_to_writer() -> Result<()> {
+        init_test_logger();
+        let _outer_span = tracing::info_span!("upload_from_path_and_read_to_writer").entered();
+        let client = create_test_client().await?;
+
+        for size in [
+            LARGE_FILE_SIZE_MIN - 1,
+            LARGE_FILE_SIZE_MIN,
+            3 * 1024 * 1024 + 100,
+        ] {
+            let _outer_span = tracing::info_span!("size:", size).entered();
+            let bytes = random_bytes(size);
+            let mut file = tempfile::NamedTempFile::new()?;
+            file.write_all(&bytes)?;
+
+            // Streaming results in the same address as uploading the bytes at once
+            let expected_address =
+                Client::calculate_address(bytes.clone(), Scope::Public, &client.keypair())?;
+            let address = client.upload_from_path(file.path(), Scope::Public).await?;
+            assert_eq!(address, expected_address);
+            assert_eq!(
+                Client::calculate_address_from_path(file.path(), Scope::Public, &client.keypair())
+                    .await?,
+                expected_address
+            );
+
+            let mut read_data = vec![];
+            let written = client.read_to_writer(address, &mut read_data).await?;
+            assert_eq!(written, size as u64);
+            compare(bytes, Bytes::from(read_data))?;
+        }
+
+        Ok(())
+    }
+
+    // Test streaming back a file made of more Chunks than are fetched at once.
+    #[tokio::test(flavor = "multi_thread")]
+    async fn read_to_writer_in_several_batches() -> Result<()> {
+        init_test_logger();
+        let _outer_span = tracing::info_span!("read_to_writer_in_several_batches").entered();
+        let client = create_test_client().await?;
+
+        // Enough for the last batch to only be partially filled
+        let size = (2 * DOWNLOAD_BATCH_SIZE + 1) * self_encryption::MAX_CHUNK_SIZE + 100;
+        let bytes = random_bytes(size);
+        let mut file = tempfile::NamedTempFile::new()?;
+        file.write_all(&bytes)?;
+
+        let address = client.upload_from_path(file.path(), Scope::Public).await?;
+
+        let mut read_data = vec![];
+        let written = client.read_to_writer(address, &mut read_data).await?;
+        assert_eq!(written, size as u64);
+        compare(bytes, Bytes::from(read_data))?;
+
+        Ok(())
+    }
+
+    // Test an interrupted upload is resumed without storing again the Chunks it already stored.
+    #[tokio::test(flavor = "multi_thread")]
+    async fn interrupted_upload_is_resumed() -> Result<()> {
+        init_test_logger();
+        let _outer_span = tracing::info_span!("interrupted_upload_is_resumed").entered();
+        let client = create_test_client().await?;
+        let journals_dir = tempfile::tempdir()?;
+
+        let bytes = random_bytes(5 * 1024 * 1024 + 100);
+        let mut file = tempfile::NamedTempFile::new()?;
+        file.write_all(&bytes)?;
+
+        // Interrupt the upload as soon as a first Chunk was stored
+        let (progress, mut events) = tokio::sync::mpsc::unbounded_channel();
+        let options = UploadOptions {
+            concurrency: 1,
+            retries: 0,
+            progress: Some(progress),
+        };
+        let mut journal =
+            UploadJournal::create(journals_dir.path(), file.path(), Scope::Public).await?;
+        {
+            let upload = client.resume_upload_with_options(&mut journal, &options);
+            tokio::pin!(upload);
+            loop {
+                tokio::select! {
+                    biased;
+                    Some(event) = events.recv() => if let UploadEvent::ChunkStored(_) = event {
+                        break;
+                    },
+                    result = &mut upload => bail!("Upload wasn't interrupted: {:?}", result),
+                }
+            }
+        }
+        drop(options);
+
+        let mut journal =
+            UploadJournal::open(journals_dir.path(), file.path(), Scope::Public).await?;
+        let (expected_address, chunks) = client.chunk_bytes(bytes.clone(), Scope::Public)?;
+        let already_stored: BTreeSet<_> = chunks
+            .iter()
+            .map(|chunk| *chunk.name())
+            .filter(|name| journal.chunk_state(name) == Some(ChunkState::Stored))
+            .collect();
+        assert!(!already_stored.is_empty());
+
+        let (progress, mut events) = tokio::sync::mpsc::unbounded_channel();
+        let options = UploadOptions {
+            progress: Some(progress),
+            ..Default::default()
+        };
+        let address = client
+            .resume_upload_with_options(&mut journal, &options)
+            .await?;
+        drop(options);
+        assert_eq!(address, expected_address);
+
+        let mut stored = BTreeSet::new();
+        while let Some(event) = events.recv().await {
+            if let UploadEvent::ChunkStored(name) = event {
+                let _ = stored.insert(name);
+            }
+        }
+        assert!(stored.is_disjoint(&already_stored));
+        assert_eq!(stored.len() + already_stored.len(), chunks.len());
+
+        let mut read_data = vec![];
+        let _ = client.read_to_writer(address, &mut read_data).await?;
+        compare(bytes, Bytes::from(read_data))?;
+
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn upload_with_options_reports_progress() -> Result<()> {
+        init_test_logger();
+        let _outer_span = tracing::info_span!("upload_with_options_reports_progress").entered();
+        let client = create_test_client().await?;
+
+        let size = 3 * 1024 * 1024 + 100;
+        let bytes = random_bytes(size);
+        let (progress, mut events) = tokio::sync::mpsc::unbounded_channel();
+        let options = UploadOptions {
+            concurrency: 2,
+            retries: 1,
+            progress: Some(progress),
+        };
+
+        let address = client
+            .upload_with_options(bytes.clone(), Scope::Public, &options)
+            .await?;
+        drop(options);
+
+        let (_, chunks) = client.chunk_bytes(bytes.clone(), Scope::Public)?;
+        let mut stored = 0;
+        let mut bytes_done = 0;
+        while let Some(event) = events.recv().await {
+            match event {
+                UploadEvent::ChunkStored(_) => stored += 1,
+                UploadEvent::BytesDone(bytes) => bytes_done += bytes,
+                UploadEvent::ChunkFailed { .. } => {}
+            }
+        }
+        assert_eq!(stored, chunks.len());
+        assert_eq!(bytes_done, size as u64);
+
+        let read_data = client.read_bytes(address).await?;
+        compare(bytes, read_data)?;
+
+        Ok(())
+    }
+
     // Test storing and reading 5mb file. Try and read from many clients and ensure we do not overwelm nodes.
     #[tokio::test(flavor = "multi_thread")]
     async fn store_and_read_5mb_from_many_clients() -> Result<()> {
@@ -741,7 +1239,9 @@ mod tests {
             .map(|(i, client)| {
                 tokio::spawn(async move {
                     let file = LargeFile::new(random_bytes(LARGE_FILE_SIZE_MIN))?;
-                    let _ = client.upload_large(file, Scope::Public).await?;
+                    let _ = client
+                        .upload_large(file, Scope::Public, &UploadOptions::default())
+                        .await?;
                     println!("Iter: {}", i);
                     let res: Result<()> = Ok(());
                     res
@@ -774,7 +1274,9 @@ mod tests {
         for i in 0..1000_usize {
             let file = LargeFile::new(random_bytes(LARGE_FILE_SIZE_MIN))?;
             let now = Instant::now();
-            let _ = client.upload_large(file, Scope::Public).await?;
+            let _ = client
+                .upload_large(file, Scope::Public, &UploadOptions::default())
+                .await?;
             let elapsed = now.elapsed();
             println!("Iter: {}, in {} millis", i, elapsed.as_millis());
         }
@@ -794,7 +1296,7 @@ mod tests {
         let bytes = random_bytes(size);
 
         // we'll also test we can calculate address offline using `calculate_address` API
-        let expected_address = Client::calculate_address(bytes.clone(), scope)?;
+        let expected_address = Client::calculate_address(bytes.clone(), scope, &client.keypair())?;
 
         // we use upload_and_verify since it uploads and also confirms it was uploaded
         let (address, read_data) = client.upload_and_verify(bytes.clone(), scope).await?;
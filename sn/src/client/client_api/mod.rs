@@ -11,10 +11,12 @@ mod data;
 mod file_apis;
 mod queries;
 mod register_apis;
+mod uploads;
 
 pub use register_apis::RegisterWriteAheadLog;
+pub use uploads::{UploadEvent, UploadOptions, DEFAULT_UPLOAD_CONCURRENCY};
 
-use crate::client::{connections::Session, errors::Error, ClientConfig};
+use crate::client::{connections::Session, errors::Error, ChunkCache, ClientConfig};
 use crate::messaging::{
     data::{CmdError, DataQuery, RegisterQuery, ServiceMsg},
     ServiceAuth, WireMsg,
@@ -56,6 +58,7 @@ pub struct Client {
     pub(crate) query_timeout: Duration,
     pub(crate) cmd_timeout: Duration,
     chunks_cache: Arc<RwLock<ChunksCache>>,
+    disk_chunks_cache: Option<ChunkCache>,
 }
 
 /// Easily manage connections to/from The Safe Network with the client and its APIs.
@@ -167,6 +170,7 @@ impl Client {
             query_timeout: config.query_timeout,
             cmd_timeout: config.cmd_timeout,
             chunks_cache: Arc::new(RwLock::new(ChunksCache::default())),
+            disk_chunks_cache: config.chunk_cache.as_ref().map(ChunkCache::new),
         };
 
         // TODO: The message being sent below is a temporary solution to fetch network info for
@@ -9,10 +9,10 @@
 use super::Client;
 use crate::client::{connections::QueryResult, errors::Error};
 use crate::messaging::{
-    data::{DataQuery, ServiceMsg},
+    data::{DataKind, DataQuery, QueryResponse, ServiceMsg},
     ServiceAuth, WireMsg,
 };
-use crate::types::{PublicKey, Signature};
+use crate::types::{PublicKey, Signature, Token};
 use bytes::Bytes;
 use rand::Rng;
 use tracing::{debug, info_span};
@@ -37,6 +37,30 @@ impl Client {
         self.send_query_with_retry_count(query, 1.0).await
     }
 
+    /// Get a quote of the cost of storing data of the given size and kind on the network.
+    ///
+    /// The cost is quoted by the Elders of a random section, as priced after how full
+    /// its Adults are, hence quotes may vary from one call to another.
+    #[instrument(skip(self), level = "debug")]
+    pub async fn get_store_cost(
+        &self,
+        bytes_len: u64,
+        data_kind: DataKind,
+    ) -> Result<Token, Error> {
+        let query = DataQuery::GetStoreCost {
+            dst: xor_name::rand::random(),
+            bytes: bytes_len,
+            kind: data_kind,
+        };
+        let query_result = self.send_query(query).await?;
+        match query_result.response {
+            QueryResponse::GetStoreCost((res, op_id)) => {
+                res.map_err(|err| Error::ErrorMsg { source: err, op_id })
+            }
+            _ => Err(Error::ReceivedUnexpectedEvent),
+        }
+    }
+
     // Send a Query to the network and await a response.
     // Queries are automatically retried if the timeout is hit
     // This function is a private helper.
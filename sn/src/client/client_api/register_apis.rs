@@ -14,13 +14,26 @@ use crate::messaging::data::{
     RegisterQuery, SignedRegisterCreate, SignedRegisterDelete, SignedRegisterEdit,
 };
 use crate::types::{
-    register::{Entry, EntryHash, Permissions, Policy, Register, User},
+    register::{Entry, EntryHash, EntryNode, Permissions, Policy, Register, RegisterOp, User},
     RegisterAddress as Address,
 };
 
+use futures::stream::{self, Stream};
 use std::collections::BTreeSet;
+use tokio::{sync::mpsc::channel, time::Duration};
+use uluru::LRUCache;
 use xor_name::XorName;
 
+// How often the subscription to a watched Register is renewed with the Elders,
+// which has to be more often than they expire it.
+const REGISTER_SUBSCRIPTION_RENEWAL: Duration = Duration::from_secs(120);
+
+// Max number of edits of a watched Register waiting to be consumed.
+const REGISTER_WATCH_CHANNEL_SIZE: usize = 100;
+
+// Every Elder the edit is sent to pushes it to us, so we remember the last ones relayed.
+type RecentEdits = LRUCache<EntryHash, 100>;
+
 /// Register Write Ahead Log
 ///
 /// Batches up register write operation before publishing them up to the network, in order.
@@ -205,6 +218,94 @@ impl Client {
         }
     }
 
+    /// Get all the entries of a Register on the Network, walking its history from the
+    /// latest entry (or entries if branching) down to the first ones written.
+    ///
+    /// Every entry is listed before the entries it was written on top of,
+    /// which together make up the Register's Merkle DAG.
+    #[instrument(skip(self), level = "debug")]
+    pub async fn get_register_history(&self, address: Address) -> Result<Vec<EntryNode>, Error> {
+        let query = DataQuery::Register(RegisterQuery::GetHistory(address));
+        let query_result = self.send_query(query).await?;
+        match query_result.response {
+            QueryResponse::GetRegisterHistory((res, op_id)) => {
+                res.map_err(|err| Error::ErrorMsg { source: err, op_id })
+            }
+            _ => Err(Error::ReceivedUnexpectedEvent),
+        }
+    }
+
+    //----------------------
+    // Watch Register
+    //---------------------
+
+    /// Watch the edits made to a Register on the Network.
+    ///
+    /// The Elders holding the Register push every edit made to it from now on,
+    /// for as long as the returned stream is not dropped. Edits are pushed as soon as
+    /// the Elders accept them, before they are applied by the Adults storing the Register,
+    /// so they are a notice that the Register changed rather than a proof of its content.
+    #[instrument(skip(self), level = "debug")]
+    pub async fn watch_register(
+        &self,
+        address: Address,
+    ) -> Result<impl Stream<Item = RegisterOp<Entry>>, Error> {
+        let (sender, receiver) = channel(REGISTER_WATCH_CHANNEL_SIZE);
+        self.session.add_register_watcher(address, sender);
+
+        if let Err(error) = self.subscribe_to_register(address).await {
+            drop(receiver);
+            let _remaining = self.session.prune_register_watchers(&address);
+            return Err(error);
+        }
+
+        let client = self.clone();
+        let _handle = tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(REGISTER_SUBSCRIPTION_RENEWAL).await;
+                if client.session.prune_register_watchers(&address) == 0 {
+                    debug!("Register at {:?} is no longer watched", address);
+                    break;
+                }
+                if let Err(error) = client.subscribe_to_register(address).await {
+                    warn!(
+                        "Failed to renew subscription to Register at {:?}: {:?}",
+                        address, error
+                    );
+                }
+            }
+        });
+
+        let edits = stream::unfold(
+            (receiver, RecentEdits::default()),
+            |(mut receiver, mut recent)| async move {
+                loop {
+                    let op = receiver.recv().await?;
+                    let hash = EntryHash(op.crdt_op.hash());
+                    if recent.touch(|relayed| *relayed == hash) {
+                        continue;
+                    }
+                    let _evicted = recent.insert(hash);
+                    break Some((op, (receiver, recent)));
+                }
+            },
+        );
+
+        Ok(edits)
+    }
+
+    // Subscribe with the Elders to the edits made to a Register
+    async fn subscribe_to_register(&self, address: Address) -> Result<(), Error> {
+        let query = DataQuery::Register(RegisterQuery::Subscribe(address));
+        let query_result = self.send_query(query).await?;
+        match query_result.response {
+            QueryResponse::SubscribeToRegister((res, op_id)) => res
+                .map(|_| ())
+                .map_err(|err| Error::ErrorMsg { source: err, op_id }),
+            _ => Err(Error::ReceivedUnexpectedEvent),
+        }
+    }
+
     //----------------------
     // Ownership
     //---------------------
@@ -284,7 +385,6 @@ mod tests {
         Error,
     };
     use crate::messaging::data::Error as ErrorMsg;
-    use crate::retry_loop_for_pattern;
     use crate::types::{
         log_markers::LogMarker,
         register::{
@@ -292,7 +392,9 @@ mod tests {
             User,
         },
     };
+    use crate::{retry_loop, retry_loop_for_pattern};
     use eyre::{bail, eyre, Result};
+    use futures::StreamExt;
     use rand::Rng;
     use std::{
         collections::{BTreeMap, BTreeSet},
@@ -668,6 +770,39 @@ mod tests {
         }
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn register_watch() -> Result<()> {
+        init_test_logger();
+        let _outer_span = tracing::info_span!("test__register_watch").entered();
+
+        let client = create_test_client().await?;
+
+        let name = xor_name::rand::random();
+        let tag = 10;
+        let owner = User::Key(client.public_key());
+
+        let (address, batch) = client
+            .create_register(name, tag, public_policy(owner))
+            .await?;
+        client.publish_register_ops(batch).await?;
+
+        // we can only subscribe once the Register is stored
+        let _register = retry_loop!(client.get_register(address));
+        let mut edits = Box::pin(client.watch_register(address).await?);
+
+        let (value_hash, batch) = client
+            .write_to_register(address, random_register_entry(), BTreeSet::new())
+            .await?;
+        client.publish_register_ops(batch).await?;
+
+        let edit = tokio::time::timeout(Duration::from_secs(30), edits.next())
+            .await?
+            .ok_or_else(|| eyre!("The stream of edits ended unexpectedly"))?;
+        assert_eq!(EntryHash(edit.crdt_op.hash()), value_hash);
+
+        Ok(())
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn register_owner() -> Result<()> {
         init_test_logger();
@@ -0,0 +1,177 @@
+// Copyright 2022 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use super::Client;
+use crate::client::{ChunkState, Result, UploadJournal};
+use crate::messaging::data::DataCmd;
+use crate::types::Chunk;
+
+use futures::{
+    future::BoxFuture,
+    stream::{FuturesUnordered, StreamExt},
+};
+use tokio::sync::mpsc::UnboundedSender;
+use tracing::{trace, warn};
+use xor_name::XorName;
+
+/// Default maximum number of Chunks being uploaded at the same time.
+pub const DEFAULT_UPLOAD_CONCURRENCY: usize = 8;
+
+/// Progress of an upload, as reported to [`UploadOptions::progress`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum UploadEvent {
+    /// The network acknowledged a Chunk was stored.
+    ChunkStored(XorName),
+    /// Storing a Chunk failed. It's sent again if `retrying` is set,
+    /// otherwise the whole upload fails with the error.
+    ChunkFailed {
+        /// Name of the Chunk.
+        name: XorName,
+        /// Description of the error.
+        error: String,
+        /// Whether the Chunk is going to be sent again.
+        retrying: bool,
+    },
+    /// This many more bytes of the content being uploaded are now stored.
+    /// Adding them up over an upload gives the size of the content.
+    BytesDone(u64),
+}
+
+/// Options to control how the Chunks of some content are uploaded.
+#[derive(Clone, Debug)]
+pub struct UploadOptions {
+    /// Maximum number of Chunks being uploaded at the same time, which also bounds
+    /// the number of encrypted Chunks held in memory when uploading from a file.
+    pub concurrency: usize,
+    /// Number of times a Chunk is sent again after failing to be stored.
+    pub retries: usize,
+    /// Where to send [`UploadEvent`]s reporting the progress of the upload, if anywhere.
+    pub progress: Option<UnboundedSender<UploadEvent>>,
+}
+
+impl Default for UploadOptions {
+    fn default() -> Self {
+        Self {
+            concurrency: DEFAULT_UPLOAD_CONCURRENCY,
+            retries: 0,
+            progress: None,
+        }
+    }
+}
+
+impl UploadOptions {
+    // Reports an event, if anyone is listening.
+    fn report(&self, event: UploadEvent) {
+        if let Some(progress) = &self.progress {
+            // The receiver may have been dropped, the upload goes on regardless
+            let _ = progress.send(event);
+        }
+    }
+}
+
+// The Chunks of an upload being stored, with at most `UploadOptions::concurrency` in flight.
+// If there is a journal, Chunks recorded as stored in it are skipped, and the others
+// are recorded as pending when sent and as stored when acknowledged.
+pub(crate) struct ChunkUploads<'a> {
+    client: &'a Client,
+    options: &'a UploadOptions,
+    journal: Option<&'a mut UploadJournal>,
+    in_flight: FuturesUnordered<BoxFuture<'a, Result<(XorName, u64)>>>,
+}
+
+impl<'a> ChunkUploads<'a> {
+    pub(crate) fn new(
+        client: &'a Client,
+        options: &'a UploadOptions,
+        journal: Option<&'a mut UploadJournal>,
+    ) -> Self {
+        Self {
+            client,
+            options,
+            journal,
+            in_flight: FuturesUnordered::new(),
+        }
+    }
+
+    // Sends a Chunk carrying `bytes` bytes of the content, first waiting for
+    // an upload in flight to complete if there are already too many.
+    pub(crate) async fn push(&mut self, chunk: Chunk, bytes: u64) -> Result<()> {
+        if let Some(journal) = self.journal.as_deref_mut() {
+            if journal.chunk_state(chunk.name()) == Some(ChunkState::Stored) {
+                trace!("Chunk {:?} already stored, skipping it", chunk.name());
+                self.options.report(UploadEvent::BytesDone(bytes));
+                return Ok(());
+            }
+            journal.record_pending(*chunk.name()).await?;
+        }
+
+        while self.in_flight.len() >= self.options.concurrency.max(1) {
+            self.complete_next().await?;
+        }
+
+        let client = self.client;
+        let options = self.options;
+        self.in_flight.push(Box::pin(async move {
+            let name = store_chunk(client, chunk, options).await?;
+            Ok((name, bytes))
+        }));
+
+        Ok(())
+    }
+
+    // Waits for all the uploads in flight to complete.
+    pub(crate) async fn finish(mut self) -> Result<()> {
+        while !self.in_flight.is_empty() {
+            self.complete_next().await?;
+        }
+        Ok(())
+    }
+
+    async fn complete_next(&mut self) -> Result<()> {
+        if let Some(res) = self.in_flight.next().await {
+            let (name, bytes) = res?;
+            if let Some(journal) = self.journal.as_deref_mut() {
+                journal.record_stored(name).await?;
+            }
+            self.options.report(UploadEvent::ChunkStored(name));
+            if bytes > 0 {
+                self.options.report(UploadEvent::BytesDone(bytes));
+            }
+        }
+        Ok(())
+    }
+}
+
+// Sends a Chunk to the network, retrying as many times as the options allow.
+async fn store_chunk(client: &Client, chunk: Chunk, options: &UploadOptions) -> Result<XorName> {
+    let name = *chunk.name();
+    let mut attempt = 0;
+    loop {
+        match client.send_cmd(DataCmd::StoreChunk(chunk.clone())).await {
+            Ok(()) => return Ok(name),
+            Err(err) => {
+                let retrying = attempt < options.retries;
+                warn!(
+                    "Failed to store Chunk {:?} (attempt #{}): {:?}",
+                    name,
+                    attempt + 1,
+                    err
+                );
+                options.report(UploadEvent::ChunkFailed {
+                    name,
+                    error: err.to_string(),
+                    retrying,
+                });
+                if !retrying {
+                    return Err(err);
+                }
+                attempt += 1;
+            }
+        }
+    }
+}
@@ -28,7 +28,12 @@ pub const DEFAULT_OPERATION_TIMEOUT: Duration = Duration::from_secs(120);
 /// Larger PUT operations may need larger ae wait time
 pub const DEFAULT_ACK_WAIT: Duration = Duration::from_secs(10);
 
+/// Default maximum total size of the Chunks kept in the on-disk cache: 1GB.
+pub const DEFAULT_CHUNK_CACHE_MAX_SIZE: u64 = 1024 * 1024 * 1024;
+
 const DEFAULT_ROOT_DIR_NAME: &str = "root_dir";
+const DEFAULT_CHUNK_CACHE_DIR_NAME: &str = "cache";
+const DEFAULT_UPLOAD_JOURNAL_DIR_NAME: &str = "uploads";
 const SN_QUERY_TIMEOUT: &str = "SN_QUERY_TIMEOUT";
 const SN_CMD_TIMEOUT: &str = "SN_CMD_TIMEOUT";
 const SN_AE_WAIT: &str = "SN_AE_WAIT";
@@ -50,6 +55,29 @@ pub struct ClientConfig {
     pub cmd_timeout: Duration,
     /// The amount of time to wait after a cmd is sent for AE flows to complete.
     pub cmd_ack_wait: Duration,
+    /// The on-disk cache of retrieved Chunks, disabled if not set.
+    pub chunk_cache: Option<ChunkCacheConfig>,
+}
+
+/// Configuration of the on-disk cache of Chunks.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct ChunkCacheConfig {
+    /// Directory where the cached Chunks are stored.
+    pub dir: PathBuf,
+    /// Maximum total size in bytes of the cached Chunks.
+    pub max_size: u64,
+}
+
+impl Default for ChunkCacheConfig {
+    /// A cache at `~/.safe/client/cache`, with a maximum size of [`DEFAULT_CHUNK_CACHE_MAX_SIZE`].
+    fn default() -> Self {
+        Self {
+            dir: project_dirs()
+                .unwrap_or_default()
+                .join(DEFAULT_CHUNK_CACHE_DIR_NAME),
+            max_size: DEFAULT_CHUNK_CACHE_MAX_SIZE,
+        }
+    }
 }
 
 impl ClientConfig {
@@ -63,6 +91,8 @@ impl ClientConfig {
     /// port).
     ///
     /// If `query_timeout` is not specified, [`DEFAULT_OPERATION_TIMEOUT`] will be used.
+    ///
+    /// The on-disk Chunks cache is disabled, it can be enabled by setting `chunk_cache`.
     pub async fn new(
         root_dir: Option<&Path>,
         local_addr: Option<SocketAddr>,
@@ -152,6 +182,7 @@ impl ClientConfig {
             query_timeout,
             cmd_timeout,
             cmd_ack_wait,
+            chunk_cache: None,
         }
     }
 }
@@ -182,6 +213,13 @@ fn default_dir() -> PathBuf {
         .join(DEFAULT_ROOT_DIR_NAME)
 }
 
+/// Directory where upload journals are kept, within the project's data directory.
+pub(crate) fn default_upload_journal_dir() -> PathBuf {
+    project_dirs()
+        .unwrap_or_default()
+        .join(DEFAULT_UPLOAD_JOURNAL_DIR_NAME)
+}
+
 fn project_dirs() -> Result<PathBuf> {
     let mut home_dir = dirs_next::home_dir()
         .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "Home directory not found"))?;
@@ -275,6 +313,7 @@ mod tests {
             query_timeout: expected_query_timeout,
             cmd_timeout: expected_cmd_timeout,
             cmd_ack_wait: expected_cmd_ack_wait,
+            chunk_cache: None,
         };
         assert_eq!(format!("{:?}", config), format!("{:?}", expected_config));
         assert_eq!(serialize(&config)?, serialize(&expected_config)?);
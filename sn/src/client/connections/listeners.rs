@@ -8,21 +8,21 @@
 
 use super::Session;
 
-use crate::at_least_one_correct_elder;
 use crate::client::{
     connections::{
         messaging::{send_msg, NUM_OF_ELDERS_SUBSET_FOR_QUERIES},
-        PendingCmdAcks,
+        PendingCmdAcks, RegisterWatchers,
     },
     Error, Result,
 };
 use crate::messaging::{
-    data::{CmdError, ServiceMsg},
+    data::{CmdError, DataQuery, RegisterQuery, ServiceMsg, SignedRegisterEdit},
     system::{KeyedSig, SectionAuth, SystemMsg},
     AuthorityProof, DstLocation, MsgId, MsgKind, MsgType, ServiceAuth, WireMsg,
 };
 use crate::node::SectionAuthorityProvider;
 use crate::types::{log_markers::LogMarker, utils::compare_and_write_prefix_map_to_disk, Peer};
+use crate::{at_least_one_correct_elder, elder_count};
 
 use bytes::Bytes;
 use itertools::Itertools;
@@ -200,6 +200,21 @@ impl Session {
         }
     }
 
+    // Relay a Register edit pushed by an Elder to all those watching the Register
+    fn relay_register_edit(watchers: RegisterWatchers, edit: SignedRegisterEdit) {
+        let address = *edit.dst_address();
+        if let Some(senders) = watchers.get(&address) {
+            for sender in senders.iter() {
+                let result = sender.try_send(edit.op.edit.clone());
+                if result.is_err() {
+                    trace!("Error relaying edit of {:?} on a channel: {:?}. (It has likely been dropped)", address, result);
+                }
+            }
+        } else {
+            trace!("Ignoring edit of {:?} which is no longer watched", address);
+        }
+    }
+
     // Handle msgs intended for client consumption (re: queries + cmds)
     #[instrument(skip(session), level = "debug")]
     fn handle_client_msg(
@@ -215,6 +230,7 @@ impl Session {
         );
         let queries = session.pending_queries.clone();
         let cmds = session.pending_cmds;
+        let register_watchers = session.register_watchers;
 
         let _handle = tokio::spawn(async move {
             match msg {
@@ -267,6 +283,9 @@ impl Session {
                     );
                     Self::send_cmd_response(cmds, correlation_id, src_peer.addr(), None);
                 }
+                ServiceMsg::RegisterEdited(edit) => {
+                    Self::relay_register_edit(register_watchers, edit);
+                }
                 _ => {
                     warn!("Ignoring unexpected msg type received: {:?}", msg);
                 }
@@ -426,6 +445,9 @@ impl Session {
 
         let (target_count, dst_address_of_bounced_msg) = match service_msg.clone() {
             ServiceMsg::Cmd(cmd) => (at_least_one_correct_elder(), cmd.dst_name()),
+            ServiceMsg::Query(query @ DataQuery::Register(RegisterQuery::Subscribe(_))) => {
+                (elder_count(), query.dst_name())
+            }
             ServiceMsg::Query(query) => (NUM_OF_ELDERS_SUBSET_FOR_QUERIES, query.dst_name()),
             _ => {
                 warn!(
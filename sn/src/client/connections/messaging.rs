@@ -10,10 +10,14 @@ use super::{QueryResult, Session};
 
 use crate::client::{connections::CmdResponse, Error, Result};
 use crate::messaging::{
-    data::{CmdError, DataQuery, QueryResponse},
+    data::{CmdError, DataQuery, QueryResponse, RegisterQuery},
     DstLocation, MsgId, MsgKind, ServiceAuth, WireMsg,
 };
-use crate::types::{prefix_map::NetworkPrefixMap, Peer, PeerLinks, PublicKey, SendToOneError};
+use crate::types::{
+    prefix_map::NetworkPrefixMap,
+    register::{Entry, RegisterOp},
+    Peer, PeerLinks, PublicKey, RegisterAddress, SendToOneError,
+};
 use crate::{at_least_one_correct_elder, elder_count};
 
 use backoff::{backoff::Backoff, ExponentialBackoff};
@@ -62,6 +66,7 @@ impl Session {
             pending_queries: Arc::new(DashMap::default()),
             incoming_err_sender: Arc::new(err_sender),
             pending_cmds: Arc::new(DashMap::default()),
+            register_watchers: Arc::new(DashMap::default()),
             endpoint,
             network: Arc::new(prefix_map),
             genesis_key,
@@ -196,7 +201,12 @@ impl Session {
 
         let dst = query.dst_name();
 
-        let (section_pk, elders) = self.get_query_elders(dst).await?;
+        let (section_pk, elders) = if let DataQuery::Register(RegisterQuery::Subscribe(_)) = query {
+            // Any of the Elders may be the one relaying an edit, so we subscribe with all of them
+            self.get_all_elders(dst)?
+        } else {
+            self.get_query_elders(dst).await?
+        };
         let elders_len = elders.len();
         let msg_id = MsgId::new();
 
@@ -272,8 +282,9 @@ impl Session {
                 | (response @ Some(QueryResponse::GetRegister((Err(_), _))), None)
                 | (response @ Some(QueryResponse::GetRegisterPolicy((Err(_), _))), None)
                 | (response @ Some(QueryResponse::GetRegisterOwner((Err(_), _))), None)
-                | (response @ Some(QueryResponse::GetRegisterUserPermissions((Err(_), _))), None) =>
-                {
+                | (response @ Some(QueryResponse::GetRegisterUserPermissions((Err(_), _))), None)
+                | (response @ Some(QueryResponse::SubscribeToRegister((Err(_), _))), None)
+                | (response @ Some(QueryResponse::GetStoreCost((Err(_), _))), None) => {
                     debug!("QueryResponse error received (but may be overridden by a non-error response from another elder): {:#?}", &response);
                     error_response = response;
                     discarded_responses += 1;
@@ -520,6 +531,43 @@ impl Session {
         Ok((section_pk, elders))
     }
 
+    fn get_all_elders(&self, dst: XorName) -> Result<(bls::PublicKey, Vec<Peer>)> {
+        match self.network.closest_or_opposite(&dst, None) {
+            Some(sap) => Ok((sap.section_key(), sap.elders_vec())),
+            None => Err(Error::NoNetworkKnowledge),
+        }
+    }
+
+    /// Add a channel to relay the edits of the given Register through.
+    pub(crate) fn add_register_watcher(
+        &self,
+        address: RegisterAddress,
+        sender: Sender<RegisterOp<Entry>>,
+    ) {
+        self.register_watchers
+            .entry(address)
+            .or_default()
+            .push(sender);
+    }
+
+    /// Remove the channels of the given Register which are no longer listened to,
+    /// returning how many are still in use.
+    pub(crate) fn prune_register_watchers(&self, address: &RegisterAddress) -> usize {
+        let remaining = match self.register_watchers.get_mut(address) {
+            Some(mut watchers) => {
+                watchers.retain(|sender| !sender.is_closed());
+                watchers.len()
+            }
+            None => return 0,
+        };
+
+        if remaining == 0 {
+            let _prior = self.register_watchers.remove(address);
+        }
+
+        remaining
+    }
+
     async fn get_cmd_elders(&self, dst_address: XorName) -> Result<(bls::PublicKey, Vec<Peer>)> {
         // Get DataSection elders details.
         let (mut elders, section_pk) =
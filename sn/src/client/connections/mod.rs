@@ -13,7 +13,11 @@ use crate::messaging::{
     data::{CmdError, OperationId, QueryResponse},
     MsgId,
 };
-use crate::types::{prefix_map::NetworkPrefixMap, PeerLinks};
+use crate::types::{
+    prefix_map::NetworkPrefixMap,
+    register::{Entry, RegisterOp},
+    PeerLinks, RegisterAddress,
+};
 
 use dashmap::DashMap;
 use qp2p::Endpoint;
@@ -30,6 +34,9 @@ type QueryResponseSender = Sender<QueryResponse>;
 type CmdResponse = (std::net::SocketAddr, Option<CmdError>);
 type PendingCmdAcks = Arc<DashMap<MsgId, Sender<CmdResponse>>>;
 
+// Channels to relay the edits pushed by Elders for each of the Registers being watched
+type RegisterWatchers = Arc<DashMap<RegisterAddress, Vec<Sender<RegisterOp<Entry>>>>>;
+
 #[derive(Debug)]
 pub struct QueryResult {
     pub response: QueryResponse,
@@ -47,6 +54,8 @@ pub(super) struct Session {
     incoming_err_sender: Arc<Sender<CmdError>>,
     // Channels for sending CmdAck to upper layers
     pending_cmds: PendingCmdAcks,
+    // Channels for sending the edits of watched Registers to upper layers
+    register_watchers: RegisterWatchers,
     /// All elders we know about from AE messages
     network: Arc<NetworkPrefixMap>,
     /// Network's genesis key
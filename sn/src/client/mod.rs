@@ -25,16 +25,25 @@
 //!
 //! TODO: update once data types are crdt compliant
 //!
+mod chunk_cache;
 mod config_handler;
 mod connections;
 mod errors;
+mod upload_journal;
 
 // Export public API.
-pub use client_api::{Client, RegisterWriteAheadLog};
-pub use config_handler::{ClientConfig, DEFAULT_ACK_WAIT, DEFAULT_OPERATION_TIMEOUT};
+pub use chunk_cache::{ChunkCache, ChunkCacheStats};
+pub use client_api::{
+    Client, RegisterWriteAheadLog, UploadEvent, UploadOptions, DEFAULT_UPLOAD_CONCURRENCY,
+};
+pub use config_handler::{
+    ChunkCacheConfig, ClientConfig, DEFAULT_ACK_WAIT, DEFAULT_CHUNK_CACHE_MAX_SIZE,
+    DEFAULT_OPERATION_TIMEOUT,
+};
 pub use errors::ErrorMsg;
 pub use errors::{Error, Result};
 pub use qp2p::Config as QuicP2pConfig;
+pub use upload_journal::{ChunkState, UploadJournal};
 
 /// Client trait and related constants.
 pub mod client_api;
@@ -0,0 +1,284 @@
+// Copyright 2022 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use crate::client::{config_handler::default_upload_journal_dir, Result};
+use crate::types::{BytesAddress, Scope};
+
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    io::ErrorKind,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+use tokio::{
+    fs,
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+};
+use tracing::{debug, warn};
+use xor_name::XorName;
+
+/// State of a Chunk in an [`UploadJournal`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChunkState {
+    /// The Chunk was produced and is being sent to the network.
+    Pending,
+    /// The network acknowledged the Chunk was stored.
+    Stored,
+}
+
+// The source file as it was when the journal was started. If it changed since,
+// the recorded Chunks are not the ones it produces anymore.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+struct JournalHeader {
+    source: PathBuf,
+    scope: Scope,
+    size: u64,
+    modified: SystemTime,
+}
+
+// A line of the journal file, the first one being the header.
+#[derive(Debug, Serialize, Deserialize)]
+enum JournalRecord {
+    Header(JournalHeader),
+    Pending(XorName),
+    Stored(XorName),
+    Completed(BytesAddress),
+}
+
+/// Local record of the Chunks of a file being uploaded, and whether the network already stored them.
+///
+/// The journal is persisted as one record per line appended to a file, so it survives the
+/// process being interrupted. An upload started with a journal can then be resumed with
+/// [`Client::resume_upload`](crate::client::Client::resume_upload), which only sends the Chunks
+/// not recorded as stored.
+#[derive(Debug)]
+pub struct UploadJournal {
+    path: PathBuf,
+    header: JournalHeader,
+    chunks: BTreeMap<XorName, ChunkState>,
+    address: Option<BytesAddress>,
+}
+
+impl UploadJournal {
+    /// The directory journals are kept in by default: `~/.safe/client/uploads`.
+    pub fn default_dir() -> PathBuf {
+        default_upload_journal_dir()
+    }
+
+    /// Start a new journal in `dir` for uploading `source`, discarding any existing one for it.
+    pub async fn create(dir: &Path, source: &Path, scope: Scope) -> Result<Self> {
+        let header = header_for(source, scope).await?;
+        let path = journal_path(dir, &header);
+
+        fs::create_dir_all(dir).await?;
+        let mut line = serde_json::to_string(&JournalRecord::Header(header.clone()))?;
+        line.push('\n');
+        fs::write(&path, line).await?;
+
+        Ok(Self {
+            path,
+            header,
+            chunks: BTreeMap::new(),
+            address: None,
+        })
+    }
+
+    /// Load the journal in `dir` for uploading `source`, so the upload can be resumed.
+    ///
+    /// A new journal is started if there is none, or if `source` changed since it was recorded.
+    pub async fn open(dir: &Path, source: &Path, scope: Scope) -> Result<Self> {
+        let header = header_for(source, scope).await?;
+        let path = journal_path(dir, &header);
+
+        let file = match fs::File::open(&path).await {
+            Ok(file) => file,
+            Err(err) if err.kind() == ErrorKind::NotFound => {
+                return Self::create(dir, source, scope).await
+            }
+            Err(err) => return Err(err.into()),
+        };
+
+        let mut journal = Self {
+            path,
+            header: header.clone(),
+            chunks: BTreeMap::new(),
+            address: None,
+        };
+        let mut lines = BufReader::new(file).lines();
+        while let Some(line) = lines.next_line().await? {
+            match serde_json::from_str(&line) {
+                Ok(JournalRecord::Header(recorded)) if recorded == header => {}
+                Ok(JournalRecord::Header(_)) => {
+                    debug!(
+                        "{} changed since its upload journal was recorded, starting over",
+                        source.display()
+                    );
+                    return Self::create(dir, source, scope).await;
+                }
+                Ok(JournalRecord::Pending(name)) => {
+                    let _ = journal.chunks.entry(name).or_insert(ChunkState::Pending);
+                }
+                Ok(JournalRecord::Stored(name)) => {
+                    let _ = journal.chunks.insert(name, ChunkState::Stored);
+                }
+                Ok(JournalRecord::Completed(address)) => journal.address = Some(address),
+                // The last line may have been partially written when the process was interrupted
+                Err(err) => warn!(
+                    "Ignoring invalid record in upload journal {}: {:?}",
+                    journal.path.display(),
+                    err
+                ),
+            }
+        }
+
+        Ok(journal)
+    }
+
+    /// Remove the journal in `dir` for uploading `source`, if there is one.
+    pub async fn discard(dir: &Path, source: &Path, scope: Scope) -> Result<()> {
+        let header = header_for(source, scope).await?;
+        match fs::remove_file(journal_path(dir, &header)).await {
+            Err(err) if err.kind() != ErrorKind::NotFound => Err(err.into()),
+            _ => Ok(()),
+        }
+    }
+
+    /// The file being uploaded.
+    pub fn source(&self) -> &Path {
+        &self.header.source
+    }
+
+    /// The scope the file is being uploaded with.
+    pub fn scope(&self) -> Scope {
+        self.header.scope
+    }
+
+    /// The recorded state of a Chunk, `None` if it wasn't produced yet.
+    pub fn chunk_state(&self, name: &XorName) -> Option<ChunkState> {
+        self.chunks.get(name).copied()
+    }
+
+    /// The address of the uploaded file, only set once all its Chunks were stored.
+    pub fn address(&self) -> Option<BytesAddress> {
+        self.address
+    }
+
+    /// Record a Chunk as being sent to the network.
+    pub(crate) async fn record_pending(&mut self, name: XorName) -> Result<()> {
+        if self.chunks.contains_key(&name) {
+            return Ok(());
+        }
+        self.append(&JournalRecord::Pending(name)).await?;
+        let _ = self.chunks.insert(name, ChunkState::Pending);
+        Ok(())
+    }
+
+    /// Record a Chunk as stored by the network.
+    pub(crate) async fn record_stored(&mut self, name: XorName) -> Result<()> {
+        self.append(&JournalRecord::Stored(name)).await?;
+        let _ = self.chunks.insert(name, ChunkState::Stored);
+        Ok(())
+    }
+
+    /// Record the upload as completed, with all the Chunks of the file stored at `address`.
+    pub(crate) async fn record_completed(&mut self, address: BytesAddress) -> Result<()> {
+        self.append(&JournalRecord::Completed(address)).await?;
+        self.address = Some(address);
+        Ok(())
+    }
+
+    async fn append(&self, record: &JournalRecord) -> Result<()> {
+        let mut line = serde_json::to_string(record)?;
+        line.push('\n');
+
+        let mut file = fs::OpenOptions::new().append(true).open(&self.path).await?;
+        file.write_all(line.as_bytes()).await?;
+        file.flush().await?;
+        Ok(())
+    }
+}
+
+async fn header_for(source: &Path, scope: Scope) -> Result<JournalHeader> {
+    let source = fs::canonicalize(source).await?;
+    let metadata = fs::metadata(&source).await?;
+
+    Ok(JournalHeader {
+        source,
+        scope,
+        size: metadata.len(),
+        modified: metadata.modified()?,
+    })
+}
+
+// There is a journal per source file and scope, named after them.
+fn journal_path(dir: &Path, header: &JournalHeader) -> PathBuf {
+    let id = format!("{:?}:{}", header.scope, header.source.display());
+    dir.join(hex::encode(XorName::from_content(id.as_bytes())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use eyre::Result;
+    use tempfile::tempdir;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn recorded_state_is_loaded_back() -> Result<()> {
+        let temp_dir = tempdir()?;
+        let source = temp_dir.path().join("source");
+        std::fs::write(&source, b"some content")?;
+        let journals_dir = temp_dir.path().join("journals");
+
+        let stored = xor_name::rand::random();
+        let pending = xor_name::rand::random();
+        let address = BytesAddress::Public(xor_name::rand::random());
+
+        let mut journal = UploadJournal::create(&journals_dir, &source, Scope::Public).await?;
+        journal.record_pending(stored).await?;
+        journal.record_pending(pending).await?;
+        journal.record_stored(stored).await?;
+
+        let mut journal = UploadJournal::open(&journals_dir, &source, Scope::Public).await?;
+        assert_eq!(journal.chunk_state(&stored), Some(ChunkState::Stored));
+        assert_eq!(journal.chunk_state(&pending), Some(ChunkState::Pending));
+        assert_eq!(journal.chunk_state(&xor_name::rand::random()), None);
+        assert_eq!(journal.address(), None);
+
+        journal.record_completed(address).await?;
+        let journal = UploadJournal::open(&journals_dir, &source, Scope::Public).await?;
+        assert_eq!(journal.address(), Some(address));
+
+        // Journals of different scopes are independent
+        let journal = UploadJournal::open(&journals_dir, &source, Scope::Private).await?;
+        assert_eq!(journal.chunk_state(&stored), None);
+
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn journal_is_reset_when_source_changes() -> Result<()> {
+        let temp_dir = tempdir()?;
+        let source = temp_dir.path().join("source");
+        std::fs::write(&source, b"some content")?;
+        let journals_dir = temp_dir.path().join("journals");
+
+        let name = xor_name::rand::random();
+        let mut journal = UploadJournal::create(&journals_dir, &source, Scope::Public).await?;
+        journal.record_stored(name).await?;
+
+        std::fs::write(&source, b"some other content")?;
+        let journal = UploadJournal::open(&journals_dir, &source, Scope::Public).await?;
+        assert_eq!(journal.chunk_state(&name), None);
+
+        UploadJournal::discard(&journals_dir, &source, Scope::Public).await?;
+        assert_eq!(std::fs::read_dir(&journals_dir)?.count(), 0);
+
+        Ok(())
+    }
+}
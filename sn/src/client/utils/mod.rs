@@ -10,37 +10,89 @@
 #[cfg(any(test, feature = "test-utils"))]
 pub mod test_utils;
 
-use crate::types::{Encryption, PublicKey, Result as TypesResult, Scope};
-use bytes::Bytes;
+use crate::types::{
+    Encryption, Error as TypesError, Keypair, PublicKey, Result as TypesResult, Scope, Signature,
+};
+use aes::{
+    cipher::{block_padding::Pkcs7, BlockDecryptMut, BlockEncryptMut, KeyIvInit},
+    Aes256,
+};
+use bytes::{BufMut, Bytes, BytesMut};
 use rand::{self, distributions::Alphanumeric, rngs::OsRng, Rng};
+use xor_name::XorName;
 
-struct DummyEncryption {
+type Aes256CbcEnc = cbc::Encryptor<Aes256>;
+type Aes256CbcDec = cbc::Decryptor<Aes256>;
+
+const IV_SIZE: usize = 16;
+
+// What the owner signs to derive the key their private data is encrypted with. Signatures are
+// deterministic, so the same keypair always derives the same key.
+const PRIVATE_DATA_KEY_SEED: &[u8] = b"safe_network private data encryption key";
+
+// Encrypts private data with a key derived from the owner's keypair, with AES-256 in CBC mode.
+//
+// The iv is derived from the key and the content, so the same content of the same owner always
+// results in the same Chunks, and the address of private data can be calculated without storing it.
+// It's prepended to the encrypted content.
+struct OwnerEncryption {
     public_key: PublicKey,
+    key: XorName,
 }
 
-impl DummyEncryption {
-    pub(crate) fn new(public_key: PublicKey) -> Self {
-        Self { public_key }
+impl OwnerEncryption {
+    fn new(owner: &Keypair) -> Self {
+        let signature = match owner.sign(PRIVATE_DATA_KEY_SEED) {
+            Signature::Ed25519(sig) => sig.to_bytes().to_vec(),
+            Signature::Bls(sig) => sig.to_bytes().to_vec(),
+            Signature::BlsShare(share) => share.share.to_bytes().to_vec(),
+        };
+
+        Self {
+            public_key: owner.public_key(),
+            key: XorName::from_content(&signature),
+        }
     }
 }
 
-impl Encryption for DummyEncryption {
+impl Encryption for OwnerEncryption {
     fn public_key(&self) -> &PublicKey {
         &self.public_key
     }
+
     fn encrypt(&self, data: Bytes) -> TypesResult<Bytes> {
-        Ok(data)
+        let iv = XorName::from_content_parts(&[&self.key.0, &data]);
+        let iv = &iv[..IV_SIZE];
+        let cipher = Aes256CbcEnc::new(self.key.0.as_ref().into(), iv.into());
+
+        let mut encrypted = BytesMut::with_capacity(IV_SIZE + data.len() + IV_SIZE);
+        encrypted.put_slice(iv);
+        encrypted.put_slice(&cipher.encrypt_padded_vec_mut::<Pkcs7>(&data));
+        Ok(encrypted.freeze())
     }
+
     fn decrypt(&self, encrypted_data: Bytes) -> TypesResult<Bytes> {
-        Ok(encrypted_data)
+        if encrypted_data.len() < IV_SIZE {
+            return Err(TypesError::FailedToDecrypt(
+                "the data is shorter than the iv".to_string(),
+            ));
+        }
+        let (iv, encrypted) = encrypted_data.split_at(IV_SIZE);
+        let cipher = Aes256CbcDec::new(self.key.0.as_ref().into(), iv.into());
+
+        cipher
+            .decrypt_padded_vec_mut::<Pkcs7>(encrypted)
+            .map(Bytes::from)
+            .map_err(|err| TypesError::FailedToDecrypt(err.to_string()))
     }
 }
 
-///
-pub fn encryption(scope: Scope, public_key: PublicKey) -> Option<impl Encryption> {
+/// The encryption data of the given scope is stored with, if any: private data is encrypted with
+/// a key only the `owner` can derive, public data is stored as is.
+pub fn encryption(scope: Scope, owner: &Keypair) -> Option<impl Encryption> {
     match scope {
         Scope::Public => None,
-        Scope::Private => Some(DummyEncryption::new(public_key)),
+        Scope::Private => Some(OwnerEncryption::new(owner)),
     }
 }
 
@@ -0,0 +1,130 @@
+// Copyright 2022 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use super::ChunkBackend;
+use crate::dbs::{Error, Result};
+use crate::types::ChunkAddress;
+
+use bytes::Bytes;
+use std::{
+    fs,
+    io::ErrorKind,
+    path::{Path, PathBuf},
+};
+use walkdir::WalkDir;
+use xor_name::XorName;
+
+const BIT_TREE_DEPTH: usize = 20;
+
+/// Stores every chunk in its own file, within a tree of directories
+/// named after the first bits of the chunks' names.
+pub(crate) struct FilesBackend {
+    bit_tree_depth: usize,
+    path: PathBuf,
+}
+
+impl FilesBackend {
+    pub(crate) fn new(path: PathBuf) -> Self {
+        Self {
+            bit_tree_depth: BIT_TREE_DEPTH,
+            path,
+        }
+    }
+
+    // Helper that returns the prefix tree path of depth `bit_count` for a given xorname
+    // Example:
+    // - with a xorname with starting bits `010001110110....`
+    // - and a bit_count of `6`
+    // returns the path `CHUNK_STORE_PATH/0/1/0/0/0/1`
+    // If the provided bit count is larger than `self.bit_tree_depth`, uses `self.bit_tree_depth`
+    // to stay within the prefix tree path
+    fn prefix_tree_path(&self, xorname: XorName, bit_count: usize) -> PathBuf {
+        let bin = format!("{:b}", xorname);
+        let prefix_dir_path: PathBuf = bin
+            .chars()
+            .take(std::cmp::min(bit_count, self.bit_tree_depth))
+            .map(|c| format!("{}", c))
+            .collect();
+
+        let mut path = self.path.clone();
+        path.push(prefix_dir_path);
+        path
+    }
+
+    pub(super) fn address_to_filepath(&self, addr: &ChunkAddress) -> Result<PathBuf> {
+        let xorname = *addr.name();
+        let filename = addr.encode_to_zbase32()?;
+        let mut path = self.prefix_tree_path(xorname, self.bit_tree_depth);
+        path.push(filename);
+        Ok(path)
+    }
+
+    fn filepath_to_address(&self, path: &Path) -> Result<ChunkAddress> {
+        let filename = path
+            .file_name()
+            .ok_or(Error::NoFilename)?
+            .to_str()
+            .ok_or(Error::InvalidFilename)?;
+        Ok(ChunkAddress::decode_from_zbase32(filename)?)
+    }
+}
+
+impl ChunkBackend for FilesBackend {
+    fn put(&self, addr: &ChunkAddress, value: &[u8]) -> Result<bool> {
+        let filepath = self.address_to_filepath(addr)?;
+        if let Some(dirs) = filepath.parent() {
+            fs::create_dir_all(dirs)?;
+        }
+        let is_new = !filepath.exists();
+        fs::write(filepath, value)?;
+        Ok(is_new)
+    }
+
+    fn get(&self, addr: &ChunkAddress) -> Result<Bytes> {
+        let filepath = self.address_to_filepath(addr)?;
+        match fs::read(filepath) {
+            Ok(value) => Ok(Bytes::from(value)),
+            Err(error) if error.kind() == ErrorKind::NotFound => {
+                Err(Error::ChunkNotFound(*addr.name()))
+            }
+            Err(error) => Err(error.into()),
+        }
+    }
+
+    fn remove(&self, addr: &ChunkAddress) -> Result<usize> {
+        let filepath = self.address_to_filepath(addr)?;
+        let meta = match fs::metadata(&filepath) {
+            Ok(meta) => meta,
+            Err(error) if error.kind() == ErrorKind::NotFound => {
+                return Err(Error::ChunkNotFound(*addr.name()))
+            }
+            Err(error) => return Err(error.into()),
+        };
+        fs::remove_file(filepath)?;
+        Ok(meta.len() as usize)
+    }
+
+    fn contains(&self, addr: &ChunkAddress) -> Result<bool> {
+        Ok(self.address_to_filepath(addr)?.exists())
+    }
+
+    fn addresses(&self) -> Result<Vec<ChunkAddress>> {
+        WalkDir::new(&self.path)
+            .into_iter()
+            .filter_map(|e| match e {
+                Ok(direntry) => Some(direntry),
+                Err(err) => {
+                    warn!("ChunkStore: failed to process file entry: {}", err);
+                    None
+                }
+            })
+            .filter(|e| e.file_type().is_file())
+            .map(|e| self.filepath_to_address(e.path()))
+            .collect()
+    }
+}
@@ -0,0 +1,699 @@
+// Copyright 2022 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+mod files;
+mod packed;
+
+use self::{files::FilesBackend, packed::PackedBackend};
+use super::{Error, Result};
+
+use crate::types::{Chunk, ChunkAddress};
+use crate::UsedSpace;
+
+use bytes::Bytes;
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt::{self, Display, Formatter},
+    io,
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+};
+use xor_name::Prefix;
+
+const CHUNK_DB_DIR: &str = "chunkdb";
+const PACKED_CHUNK_DB_DIR: &str = "chunkdb_packed";
+const CHUNK_QUARANTINE_DIR: &str = "chunkdb_quarantine";
+const CHUNK_MIGRATION_DIR: &str = "chunkdb_migrating";
+
+/// Where and how a `ChunkStore` keeps the chunks on disk.
+///
+/// The backends are synchronous, the `ChunkStore` runs them off the async runtime.
+pub(crate) trait ChunkBackend: Send + Sync {
+    /// Stores the value of the chunk at the given address, overwriting any previous one.
+    /// Returns `true` if there was no chunk stored at that address yet.
+    fn put(&self, addr: &ChunkAddress, value: &[u8]) -> Result<bool>;
+
+    /// Returns the value stored at the given address, or `Error::ChunkNotFound`
+    fn get(&self, addr: &ChunkAddress) -> Result<Bytes>;
+
+    /// Removes the chunk at the given address, returning the size of its value
+    fn remove(&self, addr: &ChunkAddress) -> Result<usize>;
+
+    fn contains(&self, addr: &ChunkAddress) -> Result<bool>;
+
+    fn addresses(&self) -> Result<Vec<ChunkAddress>>;
+
+    /// Returns the number of chunks stored
+    fn len(&self) -> Result<usize> {
+        Ok(self.addresses()?.len())
+    }
+
+    /// Reclaims the disk space left behind by removed chunks, returning the number of bytes freed
+    fn compact(&self) -> Result<u64> {
+        Ok(0)
+    }
+}
+
+/// The available `ChunkStore` backends
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChunkStoreKind {
+    /// One file per chunk, within a tree of directories named after the chunks' names
+    #[default]
+    Files,
+    /// Chunks packed into append-only segment files, indexed in a sled db
+    Packed,
+}
+
+impl ChunkStoreKind {
+    /// Returns the kind of the `ChunkStore` found at `root`, if there is any
+    pub(crate) fn detect(root: &Path) -> Option<Self> {
+        if root.join(PACKED_CHUNK_DB_DIR).exists() {
+            Some(Self::Packed)
+        } else if root.join(CHUNK_DB_DIR).exists() {
+            Some(Self::Files)
+        } else {
+            None
+        }
+    }
+
+    fn dir(&self) -> &'static str {
+        match self {
+            Self::Files => CHUNK_DB_DIR,
+            Self::Packed => PACKED_CHUNK_DB_DIR,
+        }
+    }
+
+    fn open_backend(&self, path: PathBuf) -> Result<Arc<dyn ChunkBackend>> {
+        match self {
+            Self::Files => Ok(Arc::new(FilesBackend::new(path))),
+            Self::Packed => Ok(Arc::new(PackedBackend::new(path)?)),
+        }
+    }
+}
+
+impl Display for ChunkStoreKind {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::Files => write!(formatter, "files"),
+            Self::Packed => write!(formatter, "packed"),
+        }
+    }
+}
+
+impl FromStr for ChunkStoreKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "files" => Ok(Self::Files),
+            "packed" => Ok(Self::Packed),
+            other => Err(format!(
+                "Unknown chunk store '{}', expected 'files' or 'packed'",
+                other
+            )),
+        }
+    }
+}
+
+/// A disk store for chunks
+#[derive(Clone)]
+pub(crate) struct ChunkStore {
+    kind: ChunkStoreKind,
+    backend: Arc<dyn ChunkBackend>,
+    quarantine_path: PathBuf,
+    used_space: UsedSpace,
+    // Kept up to date as chunks come and go, so it's cheap to know how many we hold
+    chunk_count: Arc<AtomicUsize>,
+}
+
+impl ChunkStore {
+    /// Creates a new `ChunkStore` at location `root`
+    ///
+    /// If the location specified already contains a ChunkStore, it is simply used,
+    /// whatever its kind, otherwise a `ChunkStoreKind::Files` one is created
+    ///
+    /// Used space of the dir is tracked
+    ///
+    /// Chunks found to be corrupted are moved to `root/CHUNK_QUARANTINE_DIR`
+    pub(crate) fn new<P: AsRef<Path>>(root: P, used_space: UsedSpace) -> Result<Self> {
+        let kind = ChunkStoreKind::detect(root.as_ref()).unwrap_or_default();
+        Self::with_kind(root, kind, used_space)
+    }
+
+    /// Creates a new `ChunkStore` of the given kind at location `root`
+    pub(crate) fn with_kind<P: AsRef<Path>>(
+        root: P,
+        kind: ChunkStoreKind,
+        used_space: UsedSpace,
+    ) -> Result<Self> {
+        let backend = kind.open_backend(root.as_ref().join(kind.dir()))?;
+        let chunk_count = Arc::new(AtomicUsize::new(backend.len()?));
+        Ok(ChunkStore {
+            kind,
+            backend,
+            quarantine_path: root.as_ref().join(CHUNK_QUARANTINE_DIR),
+            used_space,
+            chunk_count,
+        })
+    }
+
+    // ---------------------- helper methods ----------------------
+
+    // Runs a backend operation on the blocking thread pool
+    async fn blocking<T, F>(&self, op: F) -> Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&dyn ChunkBackend) -> Result<T> + Send + 'static,
+    {
+        let backend = self.backend.clone();
+        tokio::task::spawn_blocking(move || op(backend.as_ref()))
+            .await
+            .map_err(|error| Error::Io(io::Error::other(error)))?
+    }
+
+    // ---------------------- api methods ----------------------
+
+    pub(crate) fn kind(&self) -> ChunkStoreKind {
+        self.kind
+    }
+
+    pub(crate) fn can_add(&self, size: usize) -> bool {
+        self.used_space.can_add(size)
+    }
+
+    pub(crate) async fn write_chunk(&self, data: &Chunk) -> Result<ChunkAddress> {
+        let addr = *data.address();
+
+        #[cfg(feature = "chaos")]
+        let corrupted = {
+            use crate::chaos::{chaos, ChunkFault};
+            match chaos().chunk_fault() {
+                Some(ChunkFault::FailWrite) => {
+                    warn!("Chaos: failing write of chunk {:?}", addr.name());
+                    return Err(Error::Io(std::io::Error::other(
+                        "chaos: injected chunk write failure",
+                    )));
+                }
+                Some(ChunkFault::Corrupt) => {
+                    warn!("Chaos: corrupting chunk {:?}", addr.name());
+                    Some(Bytes::from(chaos().corrupt(data.value())))
+                }
+                None => None,
+            }
+        };
+
+        #[cfg(feature = "chaos")]
+        let value = corrupted.unwrap_or_else(|| data.value().clone());
+        #[cfg(not(feature = "chaos"))]
+        let value = data.value().clone();
+
+        let size = value.len();
+        let is_new = self
+            .blocking(move |backend| backend.put(&addr, &value))
+            .await?;
+
+        self.used_space.increase(size);
+        if is_new {
+            let _ = self.chunk_count.fetch_add(1, Ordering::Relaxed);
+        }
+
+        Ok(addr)
+    }
+
+    #[allow(dead_code)]
+    pub(crate) async fn delete_chunk(&self, addr: &ChunkAddress) -> Result<()> {
+        let addr = *addr;
+        let size = self.blocking(move |backend| backend.remove(&addr)).await?;
+        self.used_space.decrease(size);
+        let _ = self.chunk_count.fetch_sub(1, Ordering::Relaxed);
+        Ok(())
+    }
+
+    /// Reads the chunk at the given address, verifying its content matches the address
+    pub(crate) async fn read_chunk(&self, addr: &ChunkAddress) -> Result<Chunk> {
+        let name = *addr;
+        let bytes = self.blocking(move |backend| backend.get(&name)).await?;
+        let chunk = Chunk::new(bytes);
+        if chunk.address() != addr {
+            return Err(Error::ChunkCorrupted(*addr.name()));
+        }
+        Ok(chunk)
+    }
+
+    /// Moves the chunk out of the store into a file in the quarantine dir,
+    /// so it can be inspected but it's no longer served nor accounted as used space
+    pub(crate) async fn quarantine_chunk(&self, addr: &ChunkAddress) -> Result<()> {
+        let quarantine_filepath = self.quarantine_path.join(addr.encode_to_zbase32()?);
+
+        let name = *addr;
+        let bytes = self.blocking(move |backend| backend.get(&name)).await?;
+        tokio::fs::create_dir_all(&self.quarantine_path).await?;
+        tokio::fs::write(quarantine_filepath, bytes).await?;
+
+        let size = self.blocking(move |backend| backend.remove(&name)).await?;
+        self.used_space.decrease(size);
+        let _ = self.chunk_count.fetch_sub(1, Ordering::Relaxed);
+        Ok(())
+    }
+
+    pub(crate) fn chunk_file_exists(&self, addr: &ChunkAddress) -> Result<bool> {
+        self.backend.contains(addr)
+    }
+
+    /// Number of chunks held, without going through the whole store
+    pub(crate) fn chunk_count(&self) -> usize {
+        self.chunk_count.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn list_all_chunk_addresses(&self) -> Result<Vec<ChunkAddress>> {
+        self.backend.addresses()
+    }
+
+    #[allow(unused)]
+    /// quickly find chunks related or not to a section, might be useful when adults change sections
+    /// not used yet
+    pub(crate) fn list_chunk_addresses_with_prefix(
+        &self,
+        prefix: Prefix,
+    ) -> Result<Vec<ChunkAddress>> {
+        let addresses = self
+            .list_all_chunk_addresses()?
+            .into_iter()
+            .filter(|addr| prefix.matches(addr.name()))
+            .collect();
+        Ok(addresses)
+    }
+
+    /// Reclaims the disk space left behind by removed chunks, if the backend needs it.
+    /// Returns the number of bytes freed.
+    pub(crate) async fn compact(&self) -> Result<u64> {
+        self.blocking(|backend| backend.compact()).await
+    }
+}
+
+/// Makes sure the `ChunkStore` found at `root`, if any, is of the expected kind,
+/// setting up an empty one of that kind otherwise
+pub(crate) async fn prepare_chunk_store(root: &Path, expected: ChunkStoreKind) -> Result<()> {
+    match ChunkStoreKind::detect(root) {
+        Some(found) if found != expected => Err(Error::ChunkStoreKindMismatch { found, expected }),
+        Some(_) => Ok(()),
+        None => Ok(tokio::fs::create_dir_all(root.join(expected.dir())).await?),
+    }
+}
+
+/// Moves the chunks of the `ChunkStore` found at `root` into a new one of the given kind,
+/// removing the former once done. Returns the number of chunks migrated.
+///
+/// Chunks found to be corrupted are quarantined instead of migrated.
+/// This is meant to be run offline, while no node is using the store.
+pub(crate) async fn migrate_chunk_store(root: &Path, to: ChunkStoreKind) -> Result<usize> {
+    let from = match ChunkStoreKind::detect(root) {
+        Some(from) if from != to => from,
+        _ => {
+            info!("No chunk store to migrate to {} at {:?}", to, root);
+            return Ok(0);
+        }
+    };
+    info!("Migrating the {} chunk store at {:?} to {}", from, root, to);
+
+    // Build the new store aside, so an interrupted migration leaves the current one untouched
+    let migration_path = root.join(CHUNK_MIGRATION_DIR);
+    if migration_path.exists() {
+        tokio::fs::remove_dir_all(&migration_path).await?;
+    }
+
+    let source = ChunkStore::with_kind(root, from, UsedSpace::new(usize::MAX))?;
+    let target = to.open_backend(migration_path.clone())?;
+
+    let mut migrated = 0;
+    for addr in source.list_all_chunk_addresses()? {
+        let chunk = match source.read_chunk(&addr).await {
+            Ok(chunk) => chunk,
+            Err(Error::ChunkCorrupted(_)) => {
+                warn!("Not migrating corrupted chunk {:?}, quarantining it", addr);
+                source.quarantine_chunk(&addr).await?;
+                continue;
+            }
+            Err(error) => return Err(error),
+        };
+
+        let backend = target.clone();
+        let _is_new =
+            tokio::task::spawn_blocking(move || backend.put(chunk.address(), chunk.value()))
+                .await
+                .map_err(|error| Error::Io(io::Error::other(error)))??;
+        migrated += 1;
+    }
+    // the packed backend holds its index open until dropped
+    drop(source);
+    drop(target);
+
+    let target_path = root.join(to.dir());
+    if target_path.exists() {
+        tokio::fs::remove_dir_all(&target_path).await?;
+    }
+    tokio::fs::rename(&migration_path, &target_path).await?;
+    tokio::fs::remove_dir_all(root.join(from.dir())).await?;
+
+    info!("Migrated {} chunks to the {} chunk store", migrated, to);
+    Ok(migrated)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::types::utils::random_bytes;
+
+    use super::*;
+    use futures::future::join_all;
+    use rayon::prelude::*;
+    use tempfile::tempdir;
+
+    fn init_chunk_disk_store() -> ChunkStore {
+        let root = tempdir().expect("Failed to create temporary directory for chunk disk store");
+        ChunkStore::new(root.path(), UsedSpace::new(usize::MAX))
+            .expect("Failed to create chunk disk store")
+    }
+
+    #[tokio::test]
+    #[ignore]
+    async fn test_write_read_chunk() {
+        let store = init_chunk_disk_store();
+        // test that a range of different chunks return the written chunk
+        for _ in 0..10 {
+            let chunk = Chunk::new(random_bytes(100));
+
+            let addr = store
+                .write_chunk(&chunk)
+                .await
+                .expect("Failed to write chunk.");
+
+            let read_chunk = store
+                .read_chunk(&addr)
+                .await
+                .expect("Failed to read chunk.");
+
+            assert_eq!(chunk.value(), read_chunk.value());
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_write_read_async_multiple_chunks() {
+        let store = init_chunk_disk_store();
+        let size = 100;
+        let chunks: Vec<Chunk> = std::iter::repeat_with(|| Chunk::new(random_bytes(size)))
+            .take(7)
+            .collect();
+        write_and_read_chunks(&chunks, store).await;
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_write_read_async_multiple_identical_chunks() {
+        let store = init_chunk_disk_store();
+        let chunks: Vec<Chunk> = std::iter::repeat(Chunk::new(Bytes::from("test_concurrent")))
+            .take(7)
+            .collect();
+        write_and_read_chunks(&chunks, store).await;
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_read_corrupted_chunk() -> Result<()> {
+        let store = init_chunk_disk_store();
+        let chunk = Chunk::new(random_bytes(100));
+        let addr = store.write_chunk(&chunk).await?;
+
+        // flip the content of the chunk on disk
+        let _is_new = store.backend.put(&addr, &random_bytes(100))?;
+
+        match store.read_chunk(&addr).await {
+            Err(Error::ChunkCorrupted(name)) => assert_eq!(&name, addr.name()),
+            other => panic!("Unexpected result reading corrupted chunk: {:?}", other),
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_quarantine_chunk() -> Result<()> {
+        let root = tempdir()?;
+        let store = ChunkStore::new(root.path(), UsedSpace::new(150))?;
+        let chunk = Chunk::new(random_bytes(100));
+        let addr = store.write_chunk(&chunk).await?;
+        assert!(!store.can_add(100));
+
+        store.quarantine_chunk(&addr).await?;
+
+        assert!(!store.chunk_file_exists(&addr)?);
+        assert!(store.list_all_chunk_addresses()?.is_empty());
+        assert!(store.can_add(100));
+        let filename = addr.encode_to_zbase32()?;
+        assert!(root
+            .path()
+            .join(CHUNK_QUARANTINE_DIR)
+            .join(filename)
+            .exists());
+
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_packed_write_read_async_multiple_chunks() -> Result<()> {
+        // the segment files are only created once, so the dir must outlive the store
+        let root = tempdir()?;
+        let store = ChunkStore::with_kind(
+            root.path(),
+            ChunkStoreKind::Packed,
+            UsedSpace::new(usize::MAX),
+        )?;
+        let chunks: Vec<Chunk> = std::iter::repeat_with(|| Chunk::new(random_bytes(100)))
+            .take(7)
+            .collect();
+        write_and_read_chunks(&chunks, store).await;
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_packed_quarantine_chunk() -> Result<()> {
+        let root = tempdir()?;
+        let store =
+            ChunkStore::with_kind(root.path(), ChunkStoreKind::Packed, UsedSpace::new(150))?;
+        let chunk = Chunk::new(random_bytes(100));
+        let addr = store.write_chunk(&chunk).await?;
+        let _is_new = store.backend.put(&addr, &random_bytes(100))?;
+
+        assert!(matches!(
+            store.read_chunk(&addr).await,
+            Err(Error::ChunkCorrupted(_))
+        ));
+        store.quarantine_chunk(&addr).await?;
+
+        assert!(!store.chunk_file_exists(&addr)?);
+        assert!(matches!(
+            store.read_chunk(&addr).await,
+            Err(Error::ChunkNotFound(_))
+        ));
+        assert!(store.can_add(100));
+        let filename = addr.encode_to_zbase32()?;
+        assert!(root
+            .path()
+            .join(CHUNK_QUARANTINE_DIR)
+            .join(filename)
+            .exists());
+
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_packed_store_persists_across_reopening() -> Result<()> {
+        let root = tempdir()?;
+        let chunk = Chunk::new(random_bytes(100));
+        {
+            let store = ChunkStore::with_kind(
+                root.path(),
+                ChunkStoreKind::Packed,
+                UsedSpace::new(usize::MAX),
+            )?;
+            let _addr = store.write_chunk(&chunk).await?;
+        }
+
+        // the kind of an existing store is detected
+        assert_eq!(
+            ChunkStoreKind::detect(root.path()),
+            Some(ChunkStoreKind::Packed)
+        );
+        let store = ChunkStore::new(root.path(), UsedSpace::new(usize::MAX))?;
+        assert_eq!(store.list_all_chunk_addresses()?, vec![*chunk.address()]);
+        assert_eq!(
+            store.read_chunk(chunk.address()).await?.value(),
+            chunk.value()
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_packed_compaction_reclaims_removed_chunks() -> Result<()> {
+        let root = tempdir()?;
+        let path = root.path().join(PACKED_CHUNK_DB_DIR);
+        let backend = PackedBackend::new(path.clone())?;
+
+        let chunks: Vec<Chunk> = std::iter::repeat_with(|| Chunk::new(random_bytes(1000)))
+            .take(10)
+            .collect();
+        for chunk in &chunks {
+            let _is_new = backend.put(chunk.address(), chunk.value())?;
+        }
+        // nothing to reclaim from the active segment
+        assert_eq!(backend.compact()?, 0);
+        drop(backend);
+
+        // reopening starts appending to the latest segment, so seal it by starting a new one
+        std::fs::write(path.join("segment-00000001"), [])?;
+        let backend = PackedBackend::new(path.clone())?;
+        for chunk in &chunks[..6] {
+            let _size = backend.remove(chunk.address())?;
+        }
+
+        assert!(backend.compact()? >= 6 * 1000);
+        assert!(!path.join("segment-00000000").exists());
+        for chunk in &chunks[..6] {
+            assert!(!backend.contains(chunk.address())?);
+        }
+        for chunk in &chunks[6..] {
+            assert_eq!(&backend.get(chunk.address())?, chunk.value());
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_packed_compaction_sealing_the_active_segment() -> Result<()> {
+        let root = tempdir()?;
+        let path = root.path().join(PACKED_CHUNK_DB_DIR);
+        // segments hold four chunks
+        let backend = PackedBackend::with_max_segment_size(path.clone(), 4 * 1036)?;
+
+        let chunks: Vec<Chunk> = std::iter::repeat_with(|| Chunk::new(random_bytes(1000)))
+            .take(7)
+            .collect();
+        for chunk in &chunks {
+            let _is_new = backend.put(chunk.address(), chunk.value())?;
+        }
+        // the first segment is half garbage, and the active one holds a single live chunk
+        for chunk in chunks[..2].iter().chain(&chunks[4..6]) {
+            let _size = backend.remove(chunk.address())?;
+        }
+
+        // the active segment gets full with the first chunk moved, and is sealed by the second
+        assert!(backend.compact()? >= 2 * 1000);
+        assert!(!path.join("segment-00000000").exists());
+        assert!(path.join("segment-00000001").exists());
+        for chunk in chunks[2..4].iter().chain(&chunks[6..]) {
+            assert_eq!(&backend.get(chunk.address())?, chunk.value());
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_chunk_count_follows_the_chunks_held() -> Result<()> {
+        for kind in [ChunkStoreKind::Files, ChunkStoreKind::Packed] {
+            let root = tempdir()?;
+            let chunks: Vec<Chunk> = std::iter::repeat_with(|| Chunk::new(random_bytes(100)))
+                .take(4)
+                .collect();
+            {
+                let store = ChunkStore::with_kind(root.path(), kind, UsedSpace::new(usize::MAX))?;
+                for chunk in &chunks {
+                    let _addr = store.write_chunk(chunk).await?;
+                }
+                // overwriting a chunk doesn't count it twice
+                let _addr = store.write_chunk(&chunks[0]).await?;
+                assert_eq!(store.chunk_count(), 4);
+
+                store.delete_chunk(chunks[0].address()).await?;
+                store.quarantine_chunk(chunks[1].address()).await?;
+                assert_eq!(store.chunk_count(), 2);
+            }
+
+            // the count is picked up from the chunks found when reopening
+            let store = ChunkStore::new(root.path(), UsedSpace::new(usize::MAX))?;
+            assert_eq!(store.chunk_count(), 2);
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_migrate_files_to_packed_and_back() -> Result<()> {
+        let root = tempdir()?;
+        let store = ChunkStore::new(root.path(), UsedSpace::new(usize::MAX))?;
+        let chunks: Vec<Chunk> = std::iter::repeat_with(|| Chunk::new(random_bytes(100)))
+            .take(5)
+            .collect();
+        for chunk in &chunks {
+            let _addr = store.write_chunk(chunk).await?;
+        }
+        // corrupted chunks are quarantined rather than migrated
+        let _is_new = store.backend.put(chunks[0].address(), &random_bytes(100))?;
+        drop(store);
+
+        assert_eq!(
+            migrate_chunk_store(root.path(), ChunkStoreKind::Packed).await?,
+            4
+        );
+        assert_eq!(
+            ChunkStoreKind::detect(root.path()),
+            Some(ChunkStoreKind::Packed)
+        );
+        assert!(!root.path().join(CHUNK_DB_DIR).exists());
+        assert!(root
+            .path()
+            .join(CHUNK_QUARANTINE_DIR)
+            .join(chunks[0].address().encode_to_zbase32()?)
+            .exists());
+
+        assert_eq!(
+            migrate_chunk_store(root.path(), ChunkStoreKind::Files).await?,
+            4
+        );
+        assert_eq!(
+            ChunkStoreKind::detect(root.path()),
+            Some(ChunkStoreKind::Files)
+        );
+        let store = ChunkStore::new(root.path(), UsedSpace::new(usize::MAX))?;
+        for chunk in &chunks[1..] {
+            assert_eq!(
+                store.read_chunk(chunk.address()).await?.value(),
+                chunk.value()
+            );
+        }
+
+        Ok(())
+    }
+
+    async fn write_and_read_chunks(chunks: &[Chunk], store: ChunkStore) {
+        // write all chunks
+        let tasks = chunks.iter().map(|c| store.write_chunk(c));
+        let results = join_all(tasks).await;
+
+        // read all chunks
+        let tasks = results.iter().flatten().map(|addr| store.read_chunk(addr));
+        let results = join_all(tasks).await;
+        let read_chunks: Vec<&Chunk> = results.iter().flatten().collect();
+
+        // verify all written were read
+        assert!(chunks
+            .par_iter()
+            .all(|c| read_chunks.iter().any(|r| r.value() == c.value())))
+    }
+}
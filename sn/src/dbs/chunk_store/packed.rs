@@ -0,0 +1,286 @@
+// Copyright 2022 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use super::ChunkBackend;
+use crate::dbs::{deserialise, serialise, Error, Result, SLED_FLUSH_TIME_MS};
+use crate::types::ChunkAddress;
+
+use bytes::Bytes;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    fs::{self, File, OpenOptions},
+    io::{Read, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+    sync::{RwLock, RwLockWriteGuard},
+};
+use xor_name::{XorName, XOR_NAME_LEN};
+
+const INDEX_DB_DIR: &str = "index";
+const SEGMENT_FILE_PREFIX: &str = "segment-";
+// Size a segment can grow to before a new one is started
+const MAX_SEGMENT_SIZE: u64 = 64 * 1024 * 1024;
+// Sealed segments where removed or overwritten chunks take at least this share of the
+// space are compacted
+const COMPACTION_THRESHOLD: f64 = 0.5;
+// Every chunk is prefixed by its name and the length of its value, so the segments can be
+// inspected, or the index rebuilt, without anything else
+const RECORD_HEADER_LEN: u64 = XOR_NAME_LEN as u64 + 4;
+
+// Where the value of a chunk is found
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+struct Location {
+    segment: u64,
+    offset: u64,
+    len: u32,
+}
+
+// The segment chunks are being appended to
+struct ActiveSegment {
+    id: u64,
+    file: File,
+    size: u64,
+}
+
+/// Packs chunks into append-only segment files, indexing where each chunk is found in a sled db.
+///
+/// Removing a chunk only drops it from the index, the space it takes is reclaimed by compacting
+/// the segments, which copies the chunks still indexed into the active segment.
+pub(crate) struct PackedBackend {
+    path: PathBuf,
+    index: sled::Db,
+    max_segment_size: u64,
+    // Readers share the lock, while appending or compacting takes it exclusively, so that
+    // segments aren't removed while they are being read
+    active: RwLock<ActiveSegment>,
+}
+
+impl PackedBackend {
+    pub(crate) fn new(path: PathBuf) -> Result<Self> {
+        Self::with_max_segment_size(path, MAX_SEGMENT_SIZE)
+    }
+
+    pub(super) fn with_max_segment_size(path: PathBuf, max_segment_size: u64) -> Result<Self> {
+        fs::create_dir_all(&path)?;
+        let index = sled::Config::default()
+            .path(path.join(INDEX_DB_DIR))
+            .flush_every_ms(SLED_FLUSH_TIME_MS)
+            .open()?;
+
+        let id = segment_ids(&path)?.into_iter().max().unwrap_or_default();
+        let active = open_segment(&path, id)?;
+
+        Ok(Self {
+            path,
+            index,
+            max_segment_size,
+            active: RwLock::new(active),
+        })
+    }
+
+    fn lock_active(&self) -> RwLockWriteGuard<'_, ActiveSegment> {
+        self.active
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn location(&self, name: &XorName) -> Result<Option<Location>> {
+        match self.index.get(name)? {
+            Some(value) => Ok(Some(deserialise(&value)?)),
+            None => Ok(None),
+        }
+    }
+
+    // Appends a chunk to the active segment, starting a new segment if it's full
+    fn append(&self, active: &mut ActiveSegment, name: &XorName, value: &[u8]) -> Result<Location> {
+        let record_len = RECORD_HEADER_LEN + value.len() as u64;
+        if active.size > 0 && active.size + record_len > self.max_segment_size {
+            *active = open_segment(&self.path, active.id + 1)?;
+        }
+
+        let len = u32::try_from(value.len())
+            .map_err(|_| Error::Serialize("Chunk too large to be packed".to_string()))?;
+        active.file.write_all(&name.0)?;
+        active.file.write_all(&len.to_le_bytes())?;
+        active.file.write_all(value)?;
+
+        let location = Location {
+            segment: active.id,
+            offset: active.size + RECORD_HEADER_LEN,
+            len,
+        };
+        active.size += record_len;
+
+        Ok(location)
+    }
+
+    fn read_at(&self, location: Location) -> Result<Vec<u8>> {
+        let mut file = File::open(segment_path(&self.path, location.segment))?;
+        let _ = file.seek(SeekFrom::Start(location.offset))?;
+        let mut value = vec![0; location.len as usize];
+        file.read_exact(&mut value)?;
+        Ok(value)
+    }
+}
+
+impl ChunkBackend for PackedBackend {
+    fn put(&self, addr: &ChunkAddress, value: &[u8]) -> Result<bool> {
+        let mut active = self.lock_active();
+        let location = self.append(&mut active, addr.name(), value)?;
+        let prior = self.index.insert(addr.name(), serialise(&location)?)?;
+        Ok(prior.is_none())
+    }
+
+    fn get(&self, addr: &ChunkAddress) -> Result<Bytes> {
+        let _active = self
+            .active
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let location = self
+            .location(addr.name())?
+            .ok_or(Error::ChunkNotFound(*addr.name()))?;
+        Ok(Bytes::from(self.read_at(location)?))
+    }
+
+    fn remove(&self, addr: &ChunkAddress) -> Result<usize> {
+        let _active = self.lock_active();
+        match self.index.remove(addr.name())? {
+            Some(value) => {
+                let location: Location = deserialise(&value)?;
+                Ok(location.len as usize)
+            }
+            None => Err(Error::ChunkNotFound(*addr.name())),
+        }
+    }
+
+    fn contains(&self, addr: &ChunkAddress) -> Result<bool> {
+        Ok(self.index.contains_key(addr.name())?)
+    }
+
+    fn addresses(&self) -> Result<Vec<ChunkAddress>> {
+        self.index
+            .iter()
+            .keys()
+            .map(|key| {
+                let key = key?;
+                let name: [u8; XOR_NAME_LEN] = key
+                    .as_ref()
+                    .try_into()
+                    .map_err(|_| Error::CouldNotParseDbKey(key.to_vec()))?;
+                Ok(ChunkAddress(XorName(name)))
+            })
+            .collect()
+    }
+
+    fn len(&self) -> Result<usize> {
+        Ok(self.index.len())
+    }
+
+    fn compact(&self) -> Result<u64> {
+        // Only the segments sealed by now are compacted, as the chunks moved out of them are
+        // appended to the active segment, which may itself be sealed in the meantime
+        let sealed_below = self.lock_active().id;
+
+        // Find out which chunks are still indexed in each sealed segment
+        let mut live: BTreeMap<u64, Vec<(XorName, Location)>> = BTreeMap::new();
+        for entry in self.index.iter() {
+            let (key, value) = entry?;
+            let name: [u8; XOR_NAME_LEN] = key
+                .as_ref()
+                .try_into()
+                .map_err(|_| Error::CouldNotParseDbKey(key.to_vec()))?;
+            let location: Location = deserialise(&value)?;
+            if location.segment < sealed_below {
+                live.entry(location.segment)
+                    .or_default()
+                    .push((XorName(name), location));
+            }
+        }
+
+        let mut compacted = vec![];
+        let mut freed = 0;
+        for id in segment_ids(&self.path)? {
+            if id >= sealed_below {
+                continue;
+            }
+
+            let chunks = live.remove(&id).unwrap_or_default();
+            let size = fs::metadata(segment_path(&self.path, id))?.len();
+            let live_size: u64 = chunks
+                .iter()
+                .map(|(_, location)| RECORD_HEADER_LEN + location.len as u64)
+                .sum();
+            let garbage = size.saturating_sub(live_size);
+            if size > 0 && (garbage as f64) < size as f64 * COMPACTION_THRESHOLD {
+                continue;
+            }
+
+            debug!(
+                "Compacting chunk store segment {}, {} of its {} bytes are garbage",
+                id, garbage, size
+            );
+            // The lock is taken for each chunk moved rather than for the whole pass,
+            // so reads can go on in between
+            for (name, location) in chunks {
+                let mut active = self.lock_active();
+                // It may have been removed or overwritten since the index was scanned
+                if self.location(&name)? != Some(location) {
+                    continue;
+                }
+                let value = self.read_at(location)?;
+                let location = self.append(&mut active, &name, &value)?;
+                let _prior = self.index.insert(name, serialise(&location)?)?;
+            }
+            compacted.push(id);
+            freed += garbage;
+        }
+
+        if compacted.is_empty() {
+            return Ok(0);
+        }
+
+        // Make sure nothing points into the compacted segments anymore before removing them
+        let active = self.lock_active();
+        active.file.sync_data()?;
+        let _flushed = self.index.flush()?;
+        for id in compacted {
+            fs::remove_file(segment_path(&self.path, id))?;
+        }
+
+        Ok(freed)
+    }
+}
+
+fn segment_path(path: &Path, id: u64) -> PathBuf {
+    path.join(format!("{}{:08}", SEGMENT_FILE_PREFIX, id))
+}
+
+fn segment_ids(path: &Path) -> Result<Vec<u64>> {
+    let mut ids = vec![];
+    for entry in fs::read_dir(path)? {
+        let filename = entry?.file_name();
+        if let Some(id) = filename
+            .to_str()
+            .and_then(|filename| filename.strip_prefix(SEGMENT_FILE_PREFIX))
+            .and_then(|id| id.parse().ok())
+        {
+            ids.push(id);
+        }
+    }
+    ids.sort_unstable();
+    Ok(ids)
+}
+
+fn open_segment(path: &Path, id: u64) -> Result<ActiveSegment> {
+    let file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(segment_path(path, id))?;
+    let size = file.metadata()?.len();
+    Ok(ActiveSegment { id, file, size })
+}
@@ -6,6 +6,7 @@
 // KIND, either express or implied. Please review the Licences for the specific language governing
 // permissions and limitations relating to use of the SAFE Network Software.
 
+use super::ChunkStoreKind;
 use crate::messaging::data::Error as ErrorMsg;
 use crate::types::{convert_dt_error_to_error_msg, DataAddress, PublicKey, ReplicatedDataAddress};
 use std::io;
@@ -50,6 +51,17 @@ pub enum Error {
     /// Chunk not found.
     #[error("Chunk not found: {0:?}")]
     ChunkNotFound(XorName),
+    /// Chunk content doesn't match its address.
+    #[error("Chunk corrupted: {0:?}")]
+    ChunkCorrupted(XorName),
+    /// The chunk store on disk is not of the configured kind.
+    #[error("Found a {found} chunk store where a {expected} one is configured, run the node with --migrate-chunk-store to migrate it")]
+    ChunkStoreKindMismatch {
+        /// Kind of the chunk store found on disk
+        found: ChunkStoreKind,
+        /// Kind of the chunk store configured
+        expected: ChunkStoreKind,
+    },
     /// Chunk already exists for this node
     #[error("Data already exists at this node")]
     DataExists,
@@ -107,6 +119,8 @@ pub(crate) fn convert_to_error_msg(error: Error) -> ErrorMsg {
         Error::DataIdNotFound(address) => ErrorMsg::DataNotFound(address),
         Error::NoSuchData(address) => ErrorMsg::DataNotFound(address),
         Error::ChunkNotFound(xorname) => ErrorMsg::ChunkNotFound(xorname),
+        // the requester shall get it from another holder
+        Error::ChunkCorrupted(xorname) => ErrorMsg::ChunkNotFound(xorname),
         Error::TempDirCreationFailed(_) => ErrorMsg::FailedToWriteFile,
         Error::DataExists => ErrorMsg::DataExists,
         Error::NetworkData(error) => convert_dt_error_to_error_msg(error),
@@ -8,10 +8,15 @@
 
 use super::{deserialise, serialise, Error, Result};
 use serde::{de::DeserializeOwned, Serialize};
-use sled::{Db, Tree};
+use sled::{Batch, Db, Tree};
 use std::{fmt::Debug, marker::PhantomData};
 use xor_name::XorName;
 
+// Events are keyed by their index in the whole log. The last snapshot is stored under this key,
+// along with the number of events folded into it under the other one.
+const SNAPSHOT_KEY: &str = "snapshot";
+const SNAPSHOT_OFFSET_KEY: &str = "snapshot_offset";
+
 /// Disk storage for events and similar.
 #[derive(Clone, Debug)]
 pub(crate) struct EventStore<TEvent: Debug + Serialize + DeserializeOwned> {
@@ -31,30 +36,54 @@ where
         })
     }
 
-    /// Get all events stored in db
+    /// Get all events stored in db, including the ones folded into the last snapshot
+    pub(crate) fn get_log(&self) -> Result<Vec<TEvent>> {
+        self.events_from(0)
+    }
+
+    /// Get all events stored in db since the last snapshot
     pub(crate) fn get_all(&self) -> Result<Vec<TEvent>> {
+        self.events_from(self.snapshot_offset()?)
+    }
+
+    fn events_from(&self, offset: usize) -> Result<Vec<TEvent>> {
         let iter = self.tree.iter();
 
         let mut events = vec![];
         for (_, res) in iter.enumerate() {
             let (key, val) = res?;
+            if key.as_ref() == SNAPSHOT_KEY.as_bytes()
+                || key.as_ref() == SNAPSHOT_OFFSET_KEY.as_bytes()
+            {
+                continue;
+            }
             let db_key = String::from_utf8(key.to_vec())
-                .map_err(|_| Error::CouldNotParseDbKey(key.to_vec()))?;
+                .ok()
+                .and_then(|key| key.parse::<usize>().ok())
+                .ok_or_else(|| Error::CouldNotParseDbKey(key.to_vec()))?;
+            if db_key < offset {
+                continue;
+            }
 
             let value: TEvent = deserialise(&val)?;
             events.push((db_key, value))
         }
 
-        events.sort_by(|(key_a, _), (key_b, _)| key_a.partial_cmp(key_b).unwrap());
+        events.sort_by_key(|(key, _)| *key);
 
         let events: Vec<TEvent> = events.into_iter().map(|(_, val)| val).collect();
 
         Ok(events)
     }
 
+    /// Number of events stored since the last snapshot
+    pub(crate) fn len(&self) -> Result<usize> {
+        Ok(self.log_len()? - self.snapshot_offset()?)
+    }
+
     /// append a new entry
     pub(crate) fn append(&self, event: TEvent) -> Result<()> {
-        let key = &self.tree.len().to_string();
+        let key = &self.log_len()?.to_string();
         if self.tree.get(key)?.is_some() {
             return Err(Error::DataExists);
         }
@@ -64,6 +93,40 @@ where
 
         Ok(())
     }
+
+    /// Get the snapshot the events were last folded into, if any
+    pub(crate) fn get_snapshot<TSnapshot: DeserializeOwned>(&self) -> Result<Option<TSnapshot>> {
+        match self.tree.get(SNAPSHOT_KEY)? {
+            Some(snapshot) => Ok(Some(deserialise(&snapshot)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Atomically replace any previous snapshot with a snapshot of the state all the events
+    /// stored build up. The events are kept, but only the ones appended afterwards are
+    /// returned by `get_all`.
+    pub(crate) fn snapshot<TSnapshot: Serialize>(&self, snapshot: &TSnapshot) -> Result<()> {
+        let mut batch = Batch::default();
+        batch.insert(SNAPSHOT_KEY, serialise(snapshot)?);
+        batch.insert(SNAPSHOT_OFFSET_KEY, serialise(&self.log_len()?)?);
+        self.tree.apply_batch(batch)?;
+        Ok(())
+    }
+
+    // Number of events stored, including the ones folded into the last snapshot
+    fn log_len(&self) -> Result<usize> {
+        let has_snapshot = self.tree.contains_key(SNAPSHOT_KEY)?;
+        let has_offset = self.tree.contains_key(SNAPSHOT_OFFSET_KEY)?;
+        Ok(self.tree.len() - usize::from(has_snapshot) - usize::from(has_offset))
+    }
+
+    // Number of events folded into the last snapshot
+    fn snapshot_offset(&self) -> Result<usize> {
+        match self.tree.get(SNAPSHOT_OFFSET_KEY)? {
+            Some(offset) => deserialise(&offset),
+            None => Ok(0),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -98,4 +161,46 @@ mod test {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn snapshot() -> Result<()> {
+        let id: XorName = xor_name::rand::random();
+        let tmp_dir = tempdir()?;
+        let db = sled::open(tmp_dir.path().join("Token")).map_err(Error::Sled)?;
+        let store = EventStore::<Token>::new(&id, db)?;
+
+        for nano in 0..12 {
+            store.append(Token::from_nano(nano))?;
+        }
+        // the events are returned in the order they were appended
+        let events: Vec<u64> = store
+            .get_all()?
+            .iter()
+            .map(|token| token.as_nano())
+            .collect();
+        assert_eq!(events, (0..12).collect::<Vec<_>>());
+
+        let total: u64 = events.iter().sum();
+        store.snapshot(&total)?;
+        assert_eq!(store.len()?, 0);
+        assert!(store.get_all()?.is_empty());
+
+        store.append(Token::from_nano(100))?;
+        assert_eq!(store.get_snapshot::<u64>()?, Some(total));
+        assert_eq!(store.len()?, 1);
+        match store.get_all()?.as_slice() {
+            [token] => assert_eq!(token.as_nano(), 100),
+            other => panic!("Unexpected events after the snapshot: {:?}", other),
+        }
+
+        // the events folded into the snapshot are still in the log
+        let log: Vec<u64> = store
+            .get_log()?
+            .iter()
+            .map(|token| token.as_nano())
+            .collect();
+        assert_eq!(log, (0..12).chain([100]).collect::<Vec<_>>());
+
+        Ok(())
+    }
 }
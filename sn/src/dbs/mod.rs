@@ -13,7 +13,8 @@ mod event_store;
 mod lru_cache;
 mod used_space;
 
-pub(crate) use chunk_store::ChunkStore;
+pub use chunk_store::ChunkStoreKind;
+pub(crate) use chunk_store::{migrate_chunk_store, prepare_chunk_store, ChunkStore};
 pub(crate) use encoding::{deserialise, serialise};
 pub(crate) use errors::{convert_to_error_msg, Error, Result};
 pub(crate) use event_store::EventStore;
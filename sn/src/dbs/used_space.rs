@@ -29,6 +29,14 @@ impl UsedSpace {
         }
     }
 
+    pub(crate) fn used(&self) -> usize {
+        self.used_space.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn max_capacity(&self) -> usize {
+        self.max_capacity
+    }
+
     pub(crate) fn increase(&self, size: usize) {
         let _ = self.used_space.fetch_add(size, Ordering::Relaxed);
     }
@@ -41,14 +41,18 @@
 #[macro_use]
 extern crate tracing;
 
+#[cfg(feature = "chaos")]
+mod chaos;
 pub mod client;
 mod dbs;
+#[cfg(feature = "otlp")]
+pub mod otlp;
 
 #[cfg(test)]
 /// Helpers for analysis of testnet logs
 mod testnet_grep;
 
-pub use dbs::UsedSpace;
+pub use dbs::{ChunkStoreKind, UsedSpace};
 
 pub mod messaging;
 pub mod node;
@@ -18,7 +18,7 @@ pub use self::{
     cmd::DataCmd,
     data_exchange::{MetadataExchange, RegisterStoreExport, ReplicatedRegisterLog, StorageLevel},
     errors::{Error, Result},
-    query::DataQuery,
+    query::{DataKind, DataQuery},
     register::{
         CreateRegister, DeleteRegister, EditRegister, ExtendRegister, RegisterCmd, RegisterQuery,
         SignedRegisterCreate, SignedRegisterDelete, SignedRegisterEdit, SignedRegisterExtend,
@@ -26,8 +26,8 @@ pub use self::{
 };
 
 use crate::types::{
-    register::{Entry, EntryHash, Permissions, Policy, Register, User},
-    Chunk, ChunkAddress, DataAddress,
+    register::{Entry, EntryHash, EntryNode, Permissions, Policy, Register, User},
+    Chunk, ChunkAddress, DataAddress, RegisterAddress, Token,
 };
 use crate::{
     messaging::{data::Error as ErrorMsg, MsgId},
@@ -36,6 +36,7 @@ use crate::{
 use bytes::Bytes;
 use serde::{Deserialize, Serialize};
 use std::{collections::BTreeSet, convert::TryFrom};
+use strum_macros::IntoStaticStr;
 use tiny_keccak::{Hasher, Sha3};
 use xor_name::XorName;
 
@@ -71,7 +72,7 @@ pub struct ServiceError {
 /// Network service messages that clients or nodes send in order to use the services,
 /// communicate and carry out the tasks.
 #[allow(clippy::large_enum_variant)]
-#[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
+#[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize, IntoStaticStr)]
 pub enum ServiceMsg {
     /// Messages that lead to mutation.
     ///
@@ -113,6 +114,12 @@ pub enum ServiceMsg {
         /// [`Cmd`]: Self::Cmd
         correlation_id: MsgId,
     },
+    /// An edit of a [`Register`] the client subscribed to with [`RegisterQuery::Subscribe`].
+    ///
+    /// It's pushed by the Elders as soon as they accept the edit, before their Adults
+    /// apply it, so it shall be taken as a notice to read the [`Register`] again rather
+    /// than as a proof of its current content.
+    RegisterEdited(SignedRegisterEdit),
 }
 
 impl ServiceMsg {
@@ -161,6 +168,16 @@ pub enum QueryResponse {
     GetRegisterPolicy((Result<Policy>, OperationId)),
     /// Response to [`RegisterQuery::GetUserPermissions`].
     GetRegisterUserPermissions((Result<Permissions>, OperationId)),
+    /// Response to [`RegisterQuery::GetHistory`].
+    GetRegisterHistory((Result<Vec<EntryNode>>, OperationId)),
+    /// Response to [`RegisterQuery::Subscribe`], with the address of the [`Register`]
+    /// subscribed to.
+    SubscribeToRegister((Result<RegisterAddress>, OperationId)),
+    //
+    // ===== Storage =====
+    //
+    /// Response to [`DataQuery::GetStoreCost`].
+    GetStoreCost((Result<Token>, OperationId)),
     //
     // ===== Other =====
     //
@@ -180,6 +197,9 @@ impl QueryResponse {
             ReadRegister((result, _op_id)) => result.is_ok(),
             GetRegisterPolicy((result, _op_id)) => result.is_ok(),
             GetRegisterUserPermissions((result, _op_id)) => result.is_ok(),
+            GetRegisterHistory((result, _op_id)) => result.is_ok(),
+            SubscribeToRegister((result, _op_id)) => result.is_ok(),
+            GetStoreCost((result, _op_id)) => result.is_ok(),
             FailedToCreateOperationId => false,
         }
     }
@@ -217,7 +237,15 @@ impl QueryResponse {
                 Ok(_) => false,
                 Err(error) => matches!(*error, ErrorMsg::DataNotFound(_)),
             },
-            FailedToCreateOperationId => false,
+            GetRegisterHistory((result, _op_id)) => match result {
+                Ok(_) => false,
+                Err(error) => matches!(*error, ErrorMsg::DataNotFound(_)),
+            },
+            SubscribeToRegister((result, _op_id)) => match result {
+                Ok(_) => false,
+                Err(error) => matches!(*error, ErrorMsg::DataNotFound(_)),
+            },
+            GetStoreCost(_) | FailedToCreateOperationId => false,
         }
     }
 
@@ -251,7 +279,10 @@ impl QueryResponse {
             | GetRegisterOwner((_, operation_id))
             | ReadRegister((_, operation_id))
             | GetRegisterPolicy((_, operation_id))
-            | GetRegisterUserPermissions((_, operation_id)) => Ok(*operation_id),
+            | GetRegisterUserPermissions((_, operation_id))
+            | GetRegisterHistory((_, operation_id))
+            | SubscribeToRegister((_, operation_id))
+            | GetStoreCost((_, operation_id)) => Ok(*operation_id),
             FailedToCreateOperationId => Err(Error::NoOperationId),
         }
     }
@@ -303,6 +334,9 @@ try_from!(User, GetRegisterOwner);
 try_from!(BTreeSet<(EntryHash, Entry)>, ReadRegister);
 try_from!(Policy, GetRegisterPolicy);
 try_from!(Permissions, GetRegisterUserPermissions);
+try_from!(Vec<EntryNode>, GetRegisterHistory);
+try_from!(RegisterAddress, SubscribeToRegister);
+try_from!(Token, GetStoreCost);
 
 #[cfg(test)]
 mod tests {
@@ -11,6 +11,7 @@ use super::{
 };
 use crate::types::{ChunkAddress, ReplicatedDataAddress as DataAddress};
 use serde::{Deserialize, Serialize};
+use tiny_keccak::{Hasher, Sha3};
 use xor_name::XorName;
 
 /// Data queries - retrieving data and inspecting their structure.
@@ -32,6 +33,33 @@ pub enum DataQuery {
     ///
     /// [`Register`]: crate::types::register::Register
     Register(RegisterQuery),
+    /// Request a quote of the cost of storing data, as priced by the Elders
+    /// of the section `dst` belongs to.
+    ///
+    /// This should eventually lead to a [`GetStoreCost`] response.
+    ///
+    /// [`GetStoreCost`]: QueryResponse::GetStoreCost
+    GetStoreCost {
+        /// Name determining the section which quotes the cost
+        dst: XorName,
+        /// Size in bytes of the data to store
+        bytes: u64,
+        /// Kind of the data to store
+        kind: DataKind,
+    },
+}
+
+/// Kinds of data which can be stored on the network, each of them priced differently.
+#[derive(Hash, Eq, PartialEq, PartialOrd, Ord, Clone, Copy, Serialize, Deserialize, Debug)]
+pub enum DataKind {
+    /// Immutable [`Chunk`] of data.
+    ///
+    /// [`Chunk`]: crate::types::Chunk
+    Chunk,
+    /// [`Register`], which grows with every entry written to it.
+    ///
+    /// [`Register`]: crate::types::register::Register
+    Register,
 }
 
 impl DataQuery {
@@ -42,6 +70,10 @@ impl DataQuery {
         match self {
             GetChunk(_) => Ok(QueryResponse::GetChunk(Err(error))),
             Register(q) => q.error(error),
+            GetStoreCost { .. } => Ok(QueryResponse::GetStoreCost((
+                Err(error),
+                self.operation_id()?,
+            ))),
         }
     }
 
@@ -51,14 +83,16 @@ impl DataQuery {
         match self {
             GetChunk(address) => *address.name(),
             Register(q) => q.dst_name(),
+            GetStoreCost { dst, .. } => *dst,
         }
     }
 
-    /// Returns the address of the data
-    pub fn address(&self) -> DataAddress {
+    /// Returns the address of the data, if the query targets stored data
+    pub fn address(&self) -> Option<DataAddress> {
         match self {
-            DataQuery::GetChunk(address) => DataAddress::Chunk(*address),
-            DataQuery::Register(read) => DataAddress::Register(read.dst_address()),
+            DataQuery::GetChunk(address) => Some(DataAddress::Chunk(*address)),
+            DataQuery::Register(read) => Some(DataAddress::Register(read.dst_address())),
+            DataQuery::GetStoreCost { .. } => None,
         }
     }
 
@@ -70,6 +104,14 @@ impl DataQuery {
         match self {
             DataQuery::GetChunk(address) => chunk_operation_id(address),
             DataQuery::Register(read) => read.operation_id(),
+            DataQuery::GetStoreCost { .. } => {
+                let bytes = crate::types::utils::encode(&self).map_err(|_| Error::NoOperationId)?;
+                let mut hasher = Sha3::v256();
+                let mut output = [0; 32];
+                hasher.update(bytes.as_bytes());
+                hasher.finalize(&mut output);
+                Ok(output)
+            }
         }
     }
 }
@@ -70,6 +70,22 @@ pub enum RegisterQuery {
     ///
     /// [`GetRegisterOwner`]: QueryResponse::GetRegisterOwner
     GetOwner(Address),
+    /// Retrieve all the entries of the [`Register`] at the given address, walking its
+    /// history from the current entries down to the first ones.
+    ///
+    /// This should eventually lead to a [`GetRegisterHistory`] response.
+    ///
+    /// [`GetRegisterHistory`]: QueryResponse::GetRegisterHistory
+    GetHistory(Address),
+    /// Subscribe to the edits made to the [`Register`] at the given address.
+    ///
+    /// This should eventually lead to a [`SubscribeToRegister`] response. From then on, the
+    /// Elders push every edit of the [`Register`] in a [`RegisterEdited`] msg, until the
+    /// subscription expires, hence it has to be renewed by sending this query again.
+    ///
+    /// [`SubscribeToRegister`]: QueryResponse::SubscribeToRegister
+    /// [`RegisterEdited`]: super::ServiceMsg::RegisterEdited
+    Subscribe(Address),
 }
 
 /// A [`Register`] cmd that is stored in a log on Adults.
@@ -285,6 +301,14 @@ impl RegisterQuery {
                 Err(error),
                 self.operation_id()?,
             ))),
+            RegisterQuery::GetHistory(_) => Ok(QueryResponse::GetRegisterHistory((
+                Err(error),
+                self.operation_id()?,
+            ))),
+            RegisterQuery::Subscribe(_) => Ok(QueryResponse::SubscribeToRegister((
+                Err(error),
+                self.operation_id()?,
+            ))),
         }
     }
 
@@ -296,7 +320,9 @@ impl RegisterQuery {
             | RegisterQuery::GetPolicy(ref address)
             | RegisterQuery::GetUserPermissions { ref address, .. }
             | RegisterQuery::GetEntry { ref address, .. }
-            | RegisterQuery::GetOwner(ref address) => *address,
+            | RegisterQuery::GetOwner(ref address)
+            | RegisterQuery::GetHistory(ref address)
+            | RegisterQuery::Subscribe(ref address) => *address,
         }
     }
 
@@ -308,7 +334,9 @@ impl RegisterQuery {
             | RegisterQuery::GetPolicy(ref address)
             | RegisterQuery::GetUserPermissions { ref address, .. }
             | RegisterQuery::GetEntry { ref address, .. }
-            | RegisterQuery::GetOwner(ref address) => *address.name(),
+            | RegisterQuery::GetOwner(ref address)
+            | RegisterQuery::GetHistory(ref address)
+            | RegisterQuery::Subscribe(ref address) => *address.name(),
         }
     }
 
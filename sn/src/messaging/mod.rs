@@ -39,6 +39,8 @@ mod msg_id;
 mod msg_kind;
 // SectionAuthorityProvider
 mod sap;
+// Context of the distributed trace msgs are sent as part of
+mod trace_context;
 
 pub use self::{
     authority::{
@@ -50,4 +52,5 @@ pub use self::{
     msg_kind::MsgKind,
     sap::SectionAuthorityProvider,
     serialisation::{MsgType, NodeMsgAuthority, WireMsg},
+    trace_context::TraceContext,
 };
@@ -110,7 +110,8 @@ impl MsgType {
                     | SystemMsg::JoinRequest(_)
                     | SystemMsg::JoinAsRelocatedRequest(_)
                     | SystemMsg::Propose { .. }
-                    | SystemMsg::StartConnectivityTest(_),
+                    | SystemMsg::StartConnectivityTest(_)
+                    | SystemMsg::NodeEvent(NodeEvent::Leaving),
                 ..
             } => JOIN_RELOCATE_MSG_PRIORITY,
 
@@ -11,7 +11,7 @@ use crate::messaging::{
     data::{ServiceError, ServiceMsg},
     system::SystemMsg,
     AuthorityProof, DstLocation, Error, MsgId, MsgKind, MsgType, NodeMsgAuthority, Result,
-    ServiceAuth,
+    ServiceAuth, TraceContext,
 };
 use bls::PublicKey as BlsPublicKey;
 use bytes::Bytes;
@@ -212,6 +212,20 @@ impl WireMsg {
         &self.header.msg_envelope.dst_location
     }
 
+    /// Return the context of the trace this message was sent as part of, if any.
+    /// Messages only carry one when the `otlp` feature is enabled.
+    #[cfg(feature = "otlp")]
+    pub fn trace_context(&self) -> Option<&TraceContext> {
+        self.header.msg_envelope.trace_context.as_ref()
+    }
+
+    /// Return the context of the trace this message was sent as part of, if any.
+    /// Messages only carry one when the `otlp` feature is enabled.
+    #[cfg(not(feature = "otlp"))]
+    pub fn trace_context(&self) -> Option<&TraceContext> {
+        None
+    }
+
     /// Return the source section PublicKey for this
     /// message if it's a NodeMsg
     pub fn src_section_pk(&self) -> Option<BlsPublicKey> {
@@ -361,4 +375,50 @@ mod tests {
 
         Ok(())
     }
+
+    #[cfg(feature = "otlp")]
+    #[test]
+    fn serialisation_trace_context() -> Result<()> {
+        use opentelemetry::propagation::Injector;
+
+        let mut rng = OsRng;
+        let src_client_keypair = Keypair::new_ed25519(&mut rng);
+        let dst_location = DstLocation::Node {
+            name: xor_name::rand::random(),
+            section_pk: SecretKey::random().public_key(),
+        };
+
+        let client_msg =
+            ServiceMsg::Query(DataQuery::GetChunk(ChunkAddress(xor_name::rand::random())));
+        let payload = WireMsg::serialize_msg_payload(&client_msg)?;
+        let msg_kind = MsgKind::ServiceMsg(ServiceAuth {
+            public_key: src_client_keypair.public_key(),
+            signature: src_client_keypair.sign(&payload),
+        });
+
+        let mut wire_msg = WireMsg::new_msg(MsgId::new(), payload, msg_kind, dst_location)?;
+        // No span is being traced here, so there's no context to carry
+        assert_eq!(wire_msg.trace_context(), None);
+        let untraced = wire_msg.serialize()?;
+
+        let mut trace_context = TraceContext::default();
+        trace_context.set(
+            "traceparent",
+            "00-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-01".to_string(),
+        );
+        wire_msg.header.msg_envelope.trace_context = Some(trace_context.clone());
+        let traced = wire_msg.serialize()?;
+
+        let deserialized = WireMsg::from(traced)?;
+        assert_eq!(deserialized, wire_msg);
+        assert_eq!(deserialized.trace_context(), Some(&trace_context));
+
+        // Without a context, the envelope is the same as that of nodes built without the feature
+        let field_name = b"trace_context";
+        assert!(!untraced
+            .windows(field_name.len())
+            .any(|window| window == field_name));
+
+        Ok(())
+    }
 }
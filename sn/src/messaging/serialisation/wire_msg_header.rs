@@ -6,6 +6,8 @@
 // KIND, either express or implied. Please review the Licences for the specific language governing
 // permissions and limitations relating to use of the SAFE Network Software.
 
+#[cfg(feature = "otlp")]
+use crate::messaging::TraceContext;
 use crate::messaging::{DstLocation, Error, MsgId, MsgKind, Result};
 use bincode::{
     config::{BigEndian, FixintEncoding, WithOtherEndian, WithOtherIntEncoding},
@@ -40,6 +42,11 @@ pub struct MsgEnvelope {
     pub msg_id: MsgId,
     pub msg_kind: MsgKind,
     pub dst_location: DstLocation,
+    // Context of the trace the msg was sent as part of. Left out of the envelope when there's
+    // none, so that nodes built without the feature can still read it.
+    #[cfg(feature = "otlp")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trace_context: Option<TraceContext>,
 }
 
 // The first two fields in the header. This is not part of the public interface.
@@ -81,6 +88,8 @@ impl WireMsgHeader {
                 msg_id,
                 msg_kind,
                 dst_location,
+                #[cfg(feature = "otlp")]
+                trace_context: TraceContext::current(),
             },
         }
     }
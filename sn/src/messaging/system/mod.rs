@@ -32,12 +32,13 @@ use std::{
     collections::{BTreeMap, BTreeSet},
     net::SocketAddr,
 };
+use strum_macros::IntoStaticStr;
 use xor_name::{Prefix, XorName};
 
 use super::authority::SectionAuth as SectionAuthProof;
 use super::AuthorityProof;
 
-#[derive(Clone, PartialEq, Serialize, Deserialize, custom_debug::Debug)]
+#[derive(Clone, PartialEq, Serialize, Deserialize, custom_debug::Debug, IntoStaticStr)]
 #[allow(clippy::large_enum_variant)]
 /// Message sent over the among nodes
 pub enum SystemMsg {
@@ -13,8 +13,8 @@ use crate::messaging::{
     EndUser, MsgId, ServiceAuth,
 };
 use crate::types::{
-    register::{Entry, EntryHash, Permissions, Policy, Register, User},
-    Chunk, PublicKey, ReplicatedData, ReplicatedDataAddress,
+    register::{Entry, EntryHash, EntryNode, Permissions, Policy, Register, User},
+    Chunk, PublicKey, RegisterAddress, ReplicatedData, ReplicatedDataAddress, Token,
 };
 
 use serde::{Deserialize, Serialize};
@@ -72,6 +72,8 @@ pub enum NodeEvent {
     },
     /// Inform Adults of a possible suspect node
     SuspiciousNodesDetected(BTreeSet<XorName>),
+    /// Sent by an Adult about to leave the section, for the Elders to propose it as `Left`
+    Leaving,
 }
 
 /// Query originating at a node
@@ -124,6 +126,15 @@ pub enum NodeQueryResponse {
     ReadRegister((Result<BTreeSet<(EntryHash, Entry)>>, OperationId)),
     /// Response to [`RegisterQuery::GetUserPermissions`].
     GetRegisterUserPermissions((Result<Permissions>, OperationId)),
+    /// Response to [`RegisterQuery::GetHistory`].
+    GetRegisterHistory((Result<Vec<EntryNode>>, OperationId)),
+    /// Response to [`RegisterQuery::Subscribe`].
+    SubscribeToRegister((Result<RegisterAddress>, OperationId)),
+    //
+    // ===== Storage =====
+    //
+    /// Response to [`DataQuery::GetStoreCost`].
+    GetStoreCost((Result<Token>, OperationId)),
     //
     // ===== Other =====
     //
@@ -142,6 +153,9 @@ impl NodeQueryResponse {
             ReadRegister(res) => QueryResponse::ReadRegister(res),
             GetRegisterPolicy(res) => QueryResponse::GetRegisterPolicy(res),
             GetRegisterUserPermissions(res) => QueryResponse::GetRegisterUserPermissions(res),
+            GetRegisterHistory(res) => QueryResponse::GetRegisterHistory(res),
+            SubscribeToRegister(res) => QueryResponse::SubscribeToRegister(res),
+            GetStoreCost(res) => QueryResponse::GetStoreCost(res),
             FailedToCreateOperationId => QueryResponse::FailedToCreateOperationId,
         }
     }
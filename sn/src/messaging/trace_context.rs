@@ -0,0 +1,83 @@
+// Copyright 2022 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use tracing::Span;
+
+/// Context of the distributed trace a msg was sent as part of, in the W3C Trace Context format.
+/// It ties the spans of the msg's handling to the span it was sent from, on another node or client.
+///
+/// Msgs only carry it when the `otlp` feature is enabled, without it there's never any context.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TraceContext(BTreeMap<String, String>);
+
+impl TraceContext {
+    /// Context of the trace of the current span, if it's being traced
+    pub fn current() -> Option<Self> {
+        Self::of(&Span::current())
+    }
+
+    /// Context of the trace of the given span, if it's being traced
+    #[cfg(feature = "otlp")]
+    pub fn of(span: &Span) -> Option<Self> {
+        use opentelemetry::{propagation::TextMapPropagator, trace::TraceContextExt};
+        use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+        let context = span.context();
+        if !context.span().span_context().is_valid() {
+            return None;
+        }
+
+        let mut trace_context = Self::default();
+        propagator().inject_context(&context, &mut trace_context);
+        Some(trace_context)
+    }
+
+    /// Context of the trace of the given span, if it's being traced
+    #[cfg(not(feature = "otlp"))]
+    pub fn of(_span: &Span) -> Option<Self> {
+        None
+    }
+
+    /// Make the given span part of this trace, as a child of the span the context was taken from
+    #[cfg(feature = "otlp")]
+    pub fn set_as_parent_of(&self, span: &Span) {
+        use opentelemetry::propagation::TextMapPropagator;
+        use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+        span.set_parent(propagator().extract(self));
+    }
+
+    /// Make the given span part of this trace, as a child of the span the context was taken from
+    #[cfg(not(feature = "otlp"))]
+    pub fn set_as_parent_of(&self, _span: &Span) {}
+}
+
+#[cfg(feature = "otlp")]
+fn propagator() -> opentelemetry::sdk::propagation::TraceContextPropagator {
+    opentelemetry::sdk::propagation::TraceContextPropagator::new()
+}
+
+#[cfg(feature = "otlp")]
+impl opentelemetry::propagation::Injector for TraceContext {
+    fn set(&mut self, key: &str, value: String) {
+        let _prev = self.0.insert(key.to_string(), value);
+    }
+}
+
+#[cfg(feature = "otlp")]
+impl opentelemetry::propagation::Extractor for TraceContext {
+    fn get(&self, key: &str) -> Option<&str> {
+        self.0.get(key).map(String::as_str)
+    }
+
+    fn keys(&self) -> Vec<&str> {
+        self.0.keys().map(String::as_str).collect()
+    }
+}
@@ -0,0 +1,188 @@
+// Copyright 2022 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use super::{Error, Result};
+use crate::ChunkStoreKind;
+
+use serde::{Deserialize, Serialize};
+use std::{net::SocketAddr, time::SystemTime};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use xor_name::{Prefix, XorName};
+
+/// Name of the socket the admin interface is served at, within the node's root dir.
+pub const ADMIN_SOCKET_FILENAME: &str = "admin.sock";
+
+// Requests and responses are small summaries, anything bigger is not ours
+const MAX_FRAME_LEN: u32 = 16 * 1024 * 1024;
+
+/// Requests the admin interface answers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AdminRequest {
+    /// Who the node is and where it stands in the network
+    Status,
+    /// The members of the node's section
+    Peers,
+    /// How much data the node holds
+    Storage,
+    /// The latest events the node emitted
+    Events,
+    /// Hand the node's data over and leave its section, for it to be shut down
+    Leave,
+}
+
+/// Responses of the admin interface, one per kind of `AdminRequest`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum AdminResponse {
+    /// Response to `AdminRequest::Status`
+    Status(NodeStatus),
+    /// Response to `AdminRequest::Peers`
+    Peers(SectionPeers),
+    /// Response to `AdminRequest::Storage`
+    Storage(StorageUsage),
+    /// Response to `AdminRequest::Events`
+    Events(Vec<RecordedEvent>),
+    /// Response to `AdminRequest::Leave`
+    Left(LeaveSummary),
+    /// The request couldn't be answered
+    Error(String),
+}
+
+/// Who a node is and where it stands in the network.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct NodeStatus {
+    /// Name of the node
+    pub name: XorName,
+    /// Age of the node
+    pub age: u8,
+    /// Whether the node is an elder of its section
+    pub is_elder: bool,
+    /// Address the node is reachable at
+    pub addr: SocketAddr,
+    /// Prefix of the node's section
+    pub prefix: Prefix,
+    /// Current key of the node's section
+    pub section_key: bls::PublicKey,
+    /// Genesis key of the network
+    pub genesis_key: bls::PublicKey,
+    /// Length of the node's section chain
+    pub section_chain_len: u64,
+    /// Number of members of the node's section
+    pub section_size: usize,
+}
+
+/// A member of a section.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SectionMember {
+    /// Name of the member
+    pub name: XorName,
+    /// Address the member is reachable at
+    pub addr: SocketAddr,
+    /// Age of the member
+    pub age: u8,
+    /// Whether the member is an elder of the section
+    pub is_elder: bool,
+}
+
+/// The members of a node's section.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SectionPeers {
+    /// Prefix of the section
+    pub prefix: Prefix,
+    /// Current key of the section
+    pub section_key: bls::PublicKey,
+    /// Members of the section, elders included
+    pub members: Vec<SectionMember>,
+}
+
+/// How much data a node holds.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct StorageUsage {
+    /// Bytes of storage in use
+    pub used_space: usize,
+    /// Upper limit of the storage in bytes
+    pub max_capacity: usize,
+    /// Chunks held
+    pub chunks: usize,
+    /// Registers held
+    pub registers: usize,
+    /// How chunks are stored on disk
+    pub chunk_store: ChunkStoreKind,
+}
+
+/// What a node handed over before leaving its section.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct LeaveSummary {
+    /// Chunks handed over
+    pub chunks: usize,
+    /// Registers handed over
+    pub registers: usize,
+    /// Adults the data was handed over to
+    pub recipients: usize,
+    /// Whether the section's Elders agreed the node had left before we stopped waiting for it
+    pub confirmed: bool,
+}
+
+/// An event emitted by a node.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RecordedEvent {
+    /// When the event was emitted
+    pub time: SystemTime,
+    /// Description of the event
+    pub event: String,
+}
+
+/// Send a request to the admin interface of the node running with the given root dir.
+#[cfg(unix)]
+pub async fn admin_request(
+    root_dir: &std::path::Path,
+    request: AdminRequest,
+) -> Result<AdminResponse> {
+    let mut stream = tokio::net::UnixStream::connect(root_dir.join(ADMIN_SOCKET_FILENAME)).await?;
+    write_frame(&mut stream, &request).await?;
+    read_frame(&mut stream)
+        .await?
+        .ok_or_else(|| Error::AdminInterface("Connection closed without a response".to_string()))
+}
+
+/// Reads a length prefixed, bincode encoded, value, or `None` if the stream has ended.
+pub(super) async fn read_frame<T, R>(reader: &mut R) -> Result<Option<T>>
+where
+    T: for<'de> Deserialize<'de>,
+    R: AsyncRead + Unpin,
+{
+    let len = match reader.read_u32().await {
+        Ok(len) => len,
+        Err(error) if error.kind() == std::io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(error) => return Err(error.into()),
+    };
+    if len > MAX_FRAME_LEN {
+        return Err(Error::AdminInterface(format!(
+            "Frame of {} bytes exceeds the maximum of {} bytes",
+            len, MAX_FRAME_LEN
+        )));
+    }
+
+    let mut bytes = vec![0; len as usize];
+    let _ = reader.read_exact(&mut bytes).await?;
+    Ok(Some(bincode::deserialize(&bytes)?))
+}
+
+/// Writes a value, bincode encoded and prefixed by its length.
+pub(super) async fn write_frame<T, W>(writer: &mut W, value: &T) -> Result<()>
+where
+    T: Serialize,
+    W: AsyncWrite + Unpin,
+{
+    let bytes = bincode::serialize(value)?;
+    let len = u32::try_from(bytes.len())
+        .map_err(|_| Error::AdminInterface("Frame too large".to_string()))?;
+    writer.write_u32(len).await?;
+    writer.write_all(&bytes).await?;
+    writer.flush().await?;
+    Ok(())
+}
@@ -0,0 +1,65 @@
+// Copyright 2022 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use super::dispatcher::Dispatcher;
+use crate::node::{
+    admin::{read_frame, write_frame, AdminRequest, AdminResponse, ADMIN_SOCKET_FILENAME},
+    Event, Result,
+};
+
+use std::{fs::Permissions, os::unix::fs::PermissionsExt, path::Path, sync::Arc};
+use tokio::net::{UnixListener, UnixStream};
+
+/// Serve the admin interface over a unix socket in the node's root dir. Only the owner of the
+/// node's process can connect to it, so the interface is never exposed to the network.
+pub(super) async fn run_admin_server(root_dir: &Path, dispatcher: Arc<Dispatcher>) -> Result<()> {
+    let socket_path = root_dir.join(ADMIN_SOCKET_FILENAME);
+    // A previous run of the node may have left its socket behind
+    if socket_path.exists() {
+        tokio::fs::remove_file(&socket_path).await?;
+    }
+
+    let listener = UnixListener::bind(&socket_path)?;
+    tokio::fs::set_permissions(&socket_path, Permissions::from_mode(0o600)).await?;
+    info!("Serving admin interface at {}", socket_path.display());
+
+    let _handle = tokio::spawn(async move {
+        loop {
+            let stream = match listener.accept().await {
+                Ok((stream, _)) => stream,
+                Err(error) => {
+                    warn!("Failed to accept admin connection: {error}");
+                    continue;
+                }
+            };
+
+            let dispatcher = dispatcher.clone();
+            let _handle = tokio::spawn(async move {
+                if let Err(error) = serve(stream, dispatcher).await {
+                    debug!("Failed to serve admin connection: {error}");
+                }
+            });
+        }
+    });
+
+    Ok(())
+}
+
+// Answers the requests made over the connection until it's closed
+async fn serve(mut stream: UnixStream, dispatcher: Arc<Dispatcher>) -> Result<()> {
+    while let Some(request) = read_frame::<AdminRequest, _>(&mut stream).await? {
+        trace!("Admin request received: {:?}", request);
+        let response = dispatcher.handle_admin_request(request).await;
+        write_frame(&mut stream, &response).await?;
+        // Only once the response is out, as the node is shut down on this event
+        if matches!(response, AdminResponse::Left(_)) {
+            dispatcher.node.send_event(Event::LeftSection).await;
+        }
+    }
+    Ok(())
+}
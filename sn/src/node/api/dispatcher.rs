@@ -9,7 +9,7 @@
 use super::Cmd;
 
 use crate::elder_count;
-use crate::messaging::{system::SystemMsg, MsgKind, WireMsg};
+use crate::messaging::{system::SystemMsg, MsgKind, TraceContext, WireMsg};
 use crate::node::{
     core::{DeliveryStatus, Node, Proposal},
     messages::WireMsgUtils,
@@ -21,11 +21,15 @@ use itertools::Itertools;
 use std::{collections::BTreeSet, sync::Arc, time::Duration};
 use tokio::time::MissedTickBehavior;
 use tokio::{sync::watch, time};
-use tracing::Instrument;
+use tracing::{Instrument, Span};
 
 const PROBE_INTERVAL: Duration = Duration::from_secs(30);
 const LINK_CLEANUP_INTERVAL: Duration = Duration::from_secs(120);
 const DYSFUNCTION_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+// Time between the starts of two passes of the chunk scrubber
+const CHUNK_SCRUB_INTERVAL: Duration = Duration::from_secs(60 * 60);
+// Pause between verifying two chunks, so scrubbing doesn't compete with serving data
+const CHUNK_SCRUB_DELAY: Duration = Duration::from_millis(100);
 
 // A command/subcommand id e.g. "963111461", "963111461.0"
 type CmdId = String;
@@ -63,7 +67,7 @@ impl Dispatcher {
         let _ = tokio::spawn(async {
             let cmd_id: CmdId = cmd_id.unwrap_or_else(|| rand::random::<u32>().to_string());
 
-            self.handle_cmd_and_offshoots(cmd, Some(cmd_id)).await
+            self.handle_cmd_and_offshoots(cmd, Some(cmd_id), None).await
         });
         Ok(())
     }
@@ -72,21 +76,27 @@ impl Dispatcher {
     /// produced during its handling. Trace logs will include the provided cmd id,
     /// and any sub-cmds produced will have it as a common root cmd id.
     /// If a cmd id string is not provided a random one will be generated.
+    /// The cmd is traced as part of the given parent trace, if any, and so are its sub-cmds.
     pub(super) async fn handle_cmd_and_offshoots(
         self: Arc<Self>,
         cmd: Cmd,
         cmd_id: Option<CmdId>,
+        parent: Option<TraceContext>,
     ) -> Result<()> {
         let cmd_id = cmd_id.unwrap_or_else(|| rand::random::<u32>().to_string());
         let cmd_id_clone = cmd_id.clone();
         let cmd_display = cmd.to_string();
         let _task = tokio::spawn(async move {
-            match self.process_cmd(cmd, &cmd_id).await {
+            let span = self.cmd_span(&cmd, &cmd_id, parent.as_ref()).await;
+            let trace_context = TraceContext::of(&span);
+            match self.process_cmd_in_span(cmd, &cmd_id, span).await {
                 Ok(cmds) => {
                     for (sub_cmd_count, cmd) in cmds.into_iter().enumerate() {
                         let sub_cmd_id = format!("{}.{}", &cmd_id, sub_cmd_count);
                         // Error here is only related to queueing, and so a dropped cmd will be logged
-                        let _result = self.clone().spawn_cmd_handling(cmd, sub_cmd_id);
+                        let _result =
+                            self.clone()
+                                .spawn_cmd_handling(cmd, sub_cmd_id, trace_context.clone());
                     }
                 }
                 Err(err) => {
@@ -106,8 +116,13 @@ impl Dispatcher {
 
     // Note: this indirecton is needed. Trying to call `spawn(self.handle_cmds(...))` directly
     // inside `handle_cmds` causes compile error about type check cycle.
-    fn spawn_cmd_handling(self: Arc<Self>, cmd: Cmd, cmd_id: String) -> Result<()> {
-        let _task = tokio::spawn(self.enqueue_and_handle_next_cmd_and_offshoots(cmd, Some(cmd_id)));
+    fn spawn_cmd_handling(
+        self: Arc<Self>,
+        cmd: Cmd,
+        cmd_id: String,
+        parent: Option<TraceContext>,
+    ) -> Result<()> {
+        let _task = tokio::spawn(self.handle_cmd_and_offshoots(cmd, Some(cmd_id), parent));
         Ok(())
     }
 
@@ -196,6 +211,10 @@ impl Dispatcher {
                     }
                 }
 
+                if let Err(error) = dispatcher.node.persist_dysfunction_state().await {
+                    error!("Error persisting dysfunction state: {error}");
+                }
+
                 match dispatcher.node.notify_about_newly_suspect_nodes().await {
                     Ok(suspect_cmds) => {
                         for cmd in suspect_cmds {
@@ -216,6 +235,61 @@ impl Dispatcher {
         });
     }
 
+    pub(super) async fn start_scrubbing_chunks(self: Arc<Self>) {
+        info!("Starting chunk scrubbing");
+        let _handle = tokio::spawn(async move {
+            let dispatcher = self.clone();
+            let mut interval = tokio::time::interval(CHUNK_SCRUB_INTERVAL);
+            interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+            // No need to scrub what we have just started with
+            let _ = interval.tick().await;
+
+            loop {
+                let _ = interval.tick().await;
+
+                // Only Adults get fresh copies replicated to them
+                if dispatcher.node.is_elder().await {
+                    continue;
+                }
+
+                let addresses = match dispatcher.node.stored_chunk_addresses() {
+                    Ok(addresses) => addresses,
+                    Err(error) => {
+                        error!("Error listing chunks to scrub: {error}");
+                        continue;
+                    }
+                };
+
+                debug!("Scrubbing {} chunks", addresses.len());
+                for address in addresses {
+                    time::sleep(CHUNK_SCRUB_DELAY).await;
+
+                    match dispatcher.node.scrub_chunk(&address).await {
+                        Ok(cmds) => {
+                            for cmd in cmds {
+                                if let Err(e) = dispatcher
+                                    .clone()
+                                    .enqueue_and_handle_next_cmd_and_offshoots(cmd, None)
+                                    .await
+                                {
+                                    error!("Error requesting a fresh copy of a chunk: {:?}", e);
+                                }
+                            }
+                        }
+                        Err(error) => error!("Error scrubbing chunk {address:?}: {error}"),
+                    }
+                }
+
+                // A full pass is a good time to reclaim what was quarantined or removed since the last one
+                match dispatcher.node.compact_chunk_store().await {
+                    Ok(0) => {}
+                    Ok(freed) => info!("Compacted the chunk store, freeing {freed} bytes"),
+                    Err(error) => error!("Error compacting the chunk store: {error}"),
+                }
+            }
+        });
+    }
+
     pub(super) async fn write_prefixmap_to_disk(self: Arc<Self>) {
         info!("Writing our PrefixMap to disk");
         self.clone().node.write_prefix_map().await
@@ -223,9 +297,14 @@ impl Dispatcher {
 
     /// Handles a single cmd.
     pub(super) async fn process_cmd(&self, cmd: Cmd, cmd_id: &str) -> Result<Vec<Cmd>> {
-        // Create a tracing span containing info about the current node. This is very useful when
-        // analyzing logs produced by running multiple nodes within the same process, for example
-        // from integration tests.
+        let span = self.cmd_span(&cmd, cmd_id, None).await;
+        self.process_cmd_in_span(cmd, cmd_id, span).await
+    }
+
+    // Create a tracing span containing info about the current node. This is very useful when
+    // analyzing logs produced by running multiple nodes within the same process, for example
+    // from integration tests.
+    async fn cmd_span(&self, cmd: &Cmd, cmd_id: &str, parent: Option<&TraceContext>) -> Span {
         let span = {
             let node = &self.node;
 
@@ -245,6 +324,20 @@ impl Dispatcher {
             )
         };
 
+        // A msg carries the trace it was sent as part of, which its handling then belongs to
+        let parent = match cmd {
+            Cmd::HandleMsg { wire_msg, .. } => wire_msg.trace_context().or(parent),
+            _ => parent,
+        };
+        if let Some(parent) = parent {
+            parent.set_as_parent_of(&span);
+        }
+
+        span
+    }
+
+    // Handles a single cmd, within the given span.
+    async fn process_cmd_in_span(&self, cmd: Cmd, cmd_id: &str, span: Span) -> Result<Vec<Cmd>> {
         async {
             let cmd_display = cmd.to_string();
             trace!(
@@ -133,6 +133,8 @@ pub enum Event {
         /// Removed Adults in our section.
         removed: BTreeSet<XorName>,
     },
+    /// We handed our data over and left our section, the node should now be shut down.
+    LeftSection,
 }
 
 /// Type of messages that are received from a peer
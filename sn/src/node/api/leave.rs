@@ -0,0 +1,96 @@
+// Copyright 2022 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use super::{dispatcher::Dispatcher, Cmd};
+use crate::node::{
+    admin::{AdminRequest, AdminResponse, LeaveSummary},
+    Result,
+};
+use crate::types::{log_markers::LogMarker, ReplicatedDataAddress};
+
+use std::{collections::BTreeSet, time::Duration};
+use tokio::time;
+
+// How long we wait for the Elders to agree we've left, once our data is handed over
+const LEAVE_AGREEMENT_TIMEOUT: Duration = Duration::from_secs(30);
+const LEAVE_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+impl Dispatcher {
+    /// Answer a request made through the admin interface, leaving the section if asked to
+    pub(super) async fn handle_admin_request(&self, request: AdminRequest) -> AdminResponse {
+        match request {
+            AdminRequest::Leave => match self.leave_section().await {
+                Ok(summary) => AdminResponse::Left(summary),
+                Err(error) => {
+                    warn!("Failed to leave the section: {:?}", error);
+                    AdminResponse::Error(error.to_string())
+                }
+            },
+            request => self.node.handle_admin_request(request).await,
+        }
+    }
+
+    /// Leave our section gracefully: announce it to our Elders, hand the data we hold over to
+    /// the Adults who'll be holding it once we're gone, then wait for the Elders to agree
+    /// we've left. Only Adults can leave this way.
+    pub(super) async fn leave_section(&self) -> Result<LeaveSummary> {
+        info!("{}", LogMarker::LeaveStart);
+        let announcement = self.node.announce_leaving().await?;
+        self.handle_cmd_to_completion(announcement).await;
+
+        let targets = self.node.handover_targets().await?;
+        let mut handed_over = BTreeSet::new();
+        for (target, addresses) in &targets {
+            for cmd in self.node.hand_data_over(*target, addresses).await? {
+                self.handle_cmd_to_completion(cmd).await;
+            }
+            handed_over.extend(addresses);
+        }
+
+        let confirmed = time::timeout(LEAVE_AGREEMENT_TIMEOUT, async {
+            while !self.node.has_left().await {
+                time::sleep(LEAVE_CHECK_INTERVAL).await;
+            }
+        })
+        .await
+        .is_ok();
+
+        let summary = LeaveSummary {
+            chunks: handed_over
+                .iter()
+                .filter(|address| matches!(address, ReplicatedDataAddress::Chunk(_)))
+                .count(),
+            registers: handed_over
+                .iter()
+                .filter(|address| matches!(address, ReplicatedDataAddress::Register(_)))
+                .count(),
+            recipients: targets.len(),
+            confirmed,
+        };
+        info!("{}: {:?}", LogMarker::LeaveEnd, summary);
+
+        Ok(summary)
+    }
+
+    // Handles the cmd and all the cmds it gives rise to, unlike `handle_cmd_and_offshoots`
+    // only returning once they've all been handled.
+    async fn handle_cmd_to_completion(&self, cmd: Cmd) {
+        let mut cmds = vec![(cmd, rand::random::<u32>().to_string())];
+        while let Some((cmd, cmd_id)) = cmds.pop() {
+            match self.process_cmd(cmd, &cmd_id).await {
+                Ok(sub_cmds) => cmds.extend(
+                    sub_cmds
+                        .into_iter()
+                        .enumerate()
+                        .map(|(count, cmd)| (cmd, format!("{}.{}", cmd_id, count))),
+                ),
+                Err(error) => error!("Failed to handle cmd {:?} with error {:?}", cmd_id, error),
+            }
+        }
+    }
+}
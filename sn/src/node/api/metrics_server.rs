@@ -0,0 +1,104 @@
+// Copyright 2022 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use super::dispatcher::Dispatcher;
+use crate::node::Result;
+
+use std::{net::SocketAddr, sync::Arc, time::Duration};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+};
+
+const METRICS_PATH: &str = "/metrics";
+// Requests are only a request line and a few headers, anything bigger is not a scrape
+const MAX_REQUEST_SIZE: usize = 8 * 1024;
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Serve the node's metrics over HTTP at `/metrics` on the given address, for Prometheus to scrape.
+pub(super) async fn run_metrics_server(
+    addr: SocketAddr,
+    dispatcher: Arc<Dispatcher>,
+) -> Result<()> {
+    let listener = TcpListener::bind(addr).await?;
+    info!(
+        "Serving metrics at http://{}{}",
+        listener.local_addr()?,
+        METRICS_PATH
+    );
+
+    let _handle = tokio::spawn(async move {
+        loop {
+            let stream = match listener.accept().await {
+                Ok((stream, _)) => stream,
+                Err(error) => {
+                    warn!("Failed to accept metrics connection: {error}");
+                    continue;
+                }
+            };
+
+            let dispatcher = dispatcher.clone();
+            let _handle = tokio::spawn(async move {
+                match tokio::time::timeout(REQUEST_TIMEOUT, serve(stream, dispatcher)).await {
+                    Ok(Err(error)) => debug!("Failed to serve metrics: {error}"),
+                    Err(_) => debug!("Metrics request timed out"),
+                    Ok(Ok(())) => {}
+                }
+            });
+        }
+    });
+
+    Ok(())
+}
+
+async fn serve(mut stream: TcpStream, dispatcher: Arc<Dispatcher>) -> std::io::Result<()> {
+    // Only the request line matters, so read until the end of the headers
+    let mut request = Vec::new();
+    let mut buf = [0; 1024];
+    while !request.windows(4).any(|window| window == b"\r\n\r\n") {
+        let read = stream.read(&mut buf).await?;
+        if read == 0 || request.len() + read > MAX_REQUEST_SIZE {
+            break;
+        }
+        request.extend_from_slice(&buf[..read]);
+    }
+
+    let request = String::from_utf8_lossy(&request);
+    let mut request_line = request
+        .lines()
+        .next()
+        .unwrap_or_default()
+        .split_whitespace();
+    let (method, path) = (request_line.next(), request_line.next());
+
+    let response = match (method, path) {
+        (Some("GET"), Some(METRICS_PATH)) => {
+            let body = dispatcher.node.render_metrics().await;
+            response("200 OK", "text/plain; version=0.0.4; charset=utf-8", &body)
+        }
+        (Some("GET"), Some(_)) => response("404 Not Found", "text/plain", "Not Found\n"),
+        _ => response(
+            "405 Method Not Allowed",
+            "text/plain",
+            "Method Not Allowed\n",
+        ),
+    };
+
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await
+}
+
+fn response(status: &str, content_type: &str, body: &str) -> String {
+    format!(
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        content_type,
+        body.len(),
+        body
+    )
+}
@@ -11,9 +11,13 @@ pub(crate) mod tests;
 
 pub(crate) mod cmds;
 
+#[cfg(unix)]
+mod admin_server;
 pub(super) mod dispatcher;
 pub(super) mod event;
 pub(super) mod event_stream;
+mod leave;
+mod metrics_server;
 
 use self::{
     cmds::Cmd,
@@ -23,7 +27,10 @@ use self::{
 };
 
 use crate::messaging::{system::SystemMsg, DstLocation, WireMsg};
+#[cfg(any(test, feature = "test-utils"))]
+use crate::node::core::SimNetwork;
 use crate::node::{
+    admin::{AdminRequest, AdminResponse, LeaveSummary},
     cfg::keypair_storage::{get_reward_pk, store_network_keypair, store_new_reward_keypair},
     core::{join_network, Comm, MsgEvent, Node},
     ed25519,
@@ -31,15 +38,16 @@ use crate::node::{
     logging::{log_ctx::LogCtx, run_system_logger},
     messages::WireMsgUtils,
     network_knowledge::SectionAuthorityProvider,
-    Config, NodeInfo, Peer, MIN_ADULT_AGE,
+    rng, Config, NodeInfo, Peer, MIN_ADULT_AGE,
 };
 use crate::types::{log_markers::LogMarker, PublicKey as TypesPublicKey};
-use crate::UsedSpace;
+use crate::{dbs, UsedSpace};
 
 use ed25519_dalek::PublicKey;
 use itertools::Itertools;
 use rand::rngs::OsRng;
 use secured_linked_list::SecuredLinkedList;
+use sn_dysfunction::DysfunctionReport;
 use std::{
     collections::BTreeSet,
     net::{Ipv4Addr, SocketAddr},
@@ -64,6 +72,13 @@ pub struct NodeApi {
 
 static EVENT_CHANNEL_SIZE: usize = 20;
 
+// What the comms of the node run over.
+enum Transport {
+    Quic,
+    #[cfg(any(test, feature = "test-utils"))]
+    Sim(SimNetwork),
+}
+
 impl NodeApi {
     ////////////////////////////////////////////////////////////////////////////
     // Public API
@@ -71,10 +86,33 @@ impl NodeApi {
 
     /// Initialize a new node.
     pub async fn new(config: &Config, joining_timeout: Duration) -> Result<(Self, EventStream)> {
+        Self::new_with_transport(config, joining_timeout, Transport::Quic).await
+    }
+
+    /// Initialize a new node, communicating over the given in-memory network instead of sockets.
+    /// The contacts in the config are looked up on that network.
+    #[cfg(any(test, feature = "test-utils"))]
+    pub async fn new_simulated(
+        config: &Config,
+        joining_timeout: Duration,
+        network: &SimNetwork,
+    ) -> Result<(Self, EventStream)> {
+        Self::new_with_transport(config, joining_timeout, Transport::Sim(network.clone())).await
+    }
+
+    async fn new_with_transport(
+        config: &Config,
+        joining_timeout: Duration,
+        transport: Transport,
+    ) -> Result<(Self, EventStream)> {
         let root_dir_buf = config.root_dir()?;
         let root_dir = root_dir_buf.as_path();
         tokio::fs::create_dir_all(root_dir).await?;
 
+        if let Some(kind) = config.chunk_store() {
+            dbs::prepare_chunk_store(root_dir, kind).await?;
+        }
+
         let _reward_key = match get_reward_pk(root_dir).await? {
             Some(public_key) => TypesPublicKey::Ed25519(public_key),
             None => {
@@ -89,7 +127,7 @@ impl NodeApi {
 
         let (api, network_events) = tokio::time::timeout(
             joining_timeout,
-            Self::start_node(config, used_space, root_dir),
+            Self::start_node(config, used_space, root_dir, transport),
         )
         .await
         .map_err(|_| Error::JoinTimeout)??;
@@ -116,6 +154,17 @@ impl NodeApi {
 
         run_system_logger(LogCtx::new(api.dispatcher.clone()), config.resource_logs).await;
 
+        if let Some(metrics_addr) = config.metrics_addr() {
+            metrics_server::run_metrics_server(metrics_addr, api.dispatcher.clone()).await?;
+        }
+
+        // The node can do without its admin interface, e.g. if the root dir makes too long a
+        // socket path, it then can't be asked to leave gracefully nor be inspected though
+        #[cfg(unix)]
+        if let Err(error) = admin_server::run_admin_server(root_dir, api.dispatcher.clone()).await {
+            warn!("Failed to serve the admin interface: {:?}", error);
+        }
+
         Ok((api, network_events))
     }
 
@@ -128,6 +177,7 @@ impl NodeApi {
         config: &Config,
         used_space: UsedSpace,
         root_storage_dir: &Path,
+        transport: Transport,
     ) -> Result<(Self, EventStream)> {
         let (event_tx, event_rx) = mpsc::channel(EVENT_CHANNEL_SIZE);
         let (connection_event_tx, mut connection_event_rx) = mpsc::channel(1);
@@ -136,7 +186,7 @@ impl NodeApi {
             .local_addr
             .unwrap_or_else(|| SocketAddr::from((Ipv4Addr::UNSPECIFIED, 0)));
 
-        let node = if config.is_first() {
+        let mut node = if config.is_first() {
             // Genesis node having a fix age of 255.
             let keypair = ed25519::gen_keypair(&Prefix::default().range_inclusive(), 255);
             let node_name = ed25519::name(&keypair.public);
@@ -147,15 +197,23 @@ impl NodeApi {
                 std::process::id()
             );
 
-            let comm = Comm::first_node(
-                local_addr,
-                config.network_config().clone(),
-                connection_event_tx,
-            )
-            .await?;
+            let comm = match transport {
+                #[cfg(any(test, feature = "test-utils"))]
+                Transport::Sim(network) => {
+                    Comm::first_node_simulated(&network, connection_event_tx)
+                }
+                Transport::Quic => {
+                    Comm::first_node(
+                        local_addr,
+                        config.network_config().clone(),
+                        connection_event_tx,
+                    )
+                    .await?
+                }
+            };
             let info = NodeInfo::new(keypair, comm.our_connection_info());
 
-            let genesis_sk_set = bls::SecretKeySet::random(0, &mut rand::thread_rng());
+            let genesis_sk_set = bls::SecretKeySet::random(0, &mut rng::thread_rng());
             let node = Node::first_node(
                 comm,
                 info,
@@ -208,18 +266,22 @@ impl NodeApi {
             let node_name = ed25519::name(&keypair.public);
             info!("{} Bootstrapping a new node.", node_name);
 
-            let (comm, bootstrap_addr) = Comm::bootstrap(
-                local_addr,
-                config
-                    .hard_coded_contacts
-                    .iter()
-                    .copied()
-                    .collect_vec()
-                    .as_slice(),
-                config.network_config().clone(),
-                connection_event_tx,
-            )
-            .await?;
+            let contacts = config.hard_coded_contacts.iter().copied().collect_vec();
+            let (comm, bootstrap_addr) = match transport {
+                #[cfg(any(test, feature = "test-utils"))]
+                Transport::Sim(network) => {
+                    Comm::bootstrap_simulated(&network, &contacts, connection_event_tx)?
+                }
+                Transport::Quic => {
+                    Comm::bootstrap(
+                        local_addr,
+                        &contacts,
+                        config.network_config().clone(),
+                        connection_event_tx,
+                    )
+                    .await?
+                }
+            };
             info!(
                 "{} Joining as a new node (PID: {}) our socket: {}, bootstrapper was: {}, network's genesis key: {:?}",
                 node_name,
@@ -255,6 +317,10 @@ impl NodeApi {
             node
         };
 
+        if config.metrics_addr().is_some() {
+            node.enable_metrics();
+        }
+
         let dispatcher = Arc::new(Dispatcher::new(node));
         let event_stream = EventStream::new(event_rx);
 
@@ -270,6 +336,7 @@ impl NodeApi {
             .check_for_dysfunction_periodically()
             .await;
         dispatcher.clone().start_cleaning_peer_links().await;
+        dispatcher.clone().start_scrubbing_chunks().await;
         dispatcher.clone().write_prefixmap_to_disk().await;
 
         let api = Self { dispatcher };
@@ -371,6 +438,30 @@ impl NodeApi {
     pub async fn public_key_set(&self) -> Result<bls::PublicKeySet> {
         self.dispatcher.node.public_key_set().await
     }
+
+    /// Answers a request of the admin interface, as it would over the admin socket.
+    pub async fn admin_request(&self, request: AdminRequest) -> AdminResponse {
+        self.dispatcher.handle_admin_request(request).await
+    }
+
+    /// Hands the data this node holds over to the Adults who'll be holding it once we're gone,
+    /// and leaves the section. Only Adults can leave, the node should be shut down afterwards.
+    pub async fn leave(&self) -> Result<LeaveSummary> {
+        self.dispatcher.leave_section().await
+    }
+
+    /// Returns the dysfunction scores of the nodes this node tracks, which only elders do.
+    pub async fn dysfunction_report(&self) -> Result<DysfunctionReport> {
+        self.dispatcher.node.dysfunction_report().await
+    }
+}
+
+/// Migrates the chunk store of the node with the given config to the kind set in it,
+/// returning the number of chunks migrated. The node must not be running.
+pub async fn migrate_chunk_store(config: &Config) -> Result<usize> {
+    let root_dir = config.root_dir()?;
+    let kind = config.chunk_store().unwrap_or_default();
+    Ok(dbs::migrate_chunk_store(&root_dir, kind).await?)
 }
 
 // Listen for incoming connection events and handle them.
@@ -12,12 +12,14 @@ use super::{Cmd, Comm, Dispatcher};
 
 use crate::dbs::UsedSpace;
 use crate::messaging::{
+    data::{CreateRegister, RegisterCmd, SignedRegisterCreate},
     system::{
-        JoinAsRelocatedRequest, JoinRequest, JoinResponse, KeyedSig, MembershipState,
-        NodeState as NodeStateMsg, RelocateDetails, ResourceProofResponse, SectionAuth, SystemMsg,
+        JoinAsRelocatedRequest, JoinRequest, JoinResponse, KeyedSig, MembershipState, NodeCmd,
+        NodeEvent, NodeState as NodeStateMsg, RelocateDetails, ResourceProofResponse, SectionAuth,
+        SystemMsg,
     },
     AuthorityProof, DstLocation, MsgId, MsgKind, MsgType, NodeAuth,
-    SectionAuth as MsgKindSectionAuth, WireMsg,
+    SectionAuth as MsgKindSectionAuth, ServiceAuth, WireMsg,
 };
 use crate::node::{
     core::{
@@ -31,11 +33,16 @@ use crate::node::{
     network_knowledge::{
         test_utils::*, NetworkKnowledge, NodeState, SectionAuthorityProvider, SectionKeyShare,
     },
-    recommended_section_size, supermajority, Error, Event, NodeInfo, Result as RoutingResult,
-    FIRST_SECTION_MAX_AGE, FIRST_SECTION_MIN_AGE, MIN_ADULT_AGE,
+    recommended_section_size, supermajority, Config, Error, Event, NodeApi, NodeInfo,
+    Result as RoutingResult, SimDelivery, SimNetwork, FIRST_SECTION_MAX_AGE, FIRST_SECTION_MIN_AGE,
+    MIN_ADULT_AGE,
 };
-use crate::types::{Keypair, Peer, PublicKey};
-use crate::{elder_count, init_test_logger};
+use crate::types::{
+    register::{Policy, PublicPolicy, User},
+    utils::random_bytes,
+    Chunk, Keypair, Peer, PublicKey, ReplicatedData,
+};
+use crate::{data_copy_count, elder_count, init_test_logger};
 
 use assert_matches::assert_matches;
 use bls_dkg::message::Message;
@@ -47,15 +54,17 @@ use resource_proof::ResourceProof;
 use secured_linked_list::SecuredLinkedList;
 use std::{
     collections::{BTreeMap, BTreeSet, HashSet},
+    future::Future,
     iter,
     net::Ipv4Addr,
     ops::Deref,
     path::Path,
+    sync::Arc,
 };
 use tempfile::tempdir;
 use tokio::{
     sync::mpsc,
-    time::{timeout, Duration},
+    time::{sleep, timeout, Duration},
 };
 use xor_name::{Prefix, XorName};
 
@@ -1034,6 +1043,177 @@ async fn relocation(relocated_peer_role: RelocatedPeerRole) -> Result<()> {
     Ok(())
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn handle_leaving_of_non_elder() -> Result<()> {
+    init_test_logger();
+    let _span = tracing::info_span!("handle_leaving_of_non_elder").entered();
+
+    let (section_auth, mut nodes, sk_set) = create_section_auth();
+    let (section, section_key_share) = create_section(&sk_set, &section_auth).await?;
+    let section_pk = sk_set.public_keys().public_key();
+
+    let leaving_info = gen_info(MIN_ADULT_AGE, None);
+    let node_state = NodeState::joined(leaving_info.peer(), None);
+    let node_state = section_signed(sk_set.secret_key(), node_state)?;
+    assert!(section.update_member(node_state).await);
+
+    let (max_capacity, root_storage_dir) = create_test_max_capacity_and_root_storage()?;
+    let node = Node::new(
+        create_comm().await?,
+        nodes.remove(0),
+        section,
+        Some(section_key_share),
+        mpsc::channel(TEST_EVENT_CHANNEL_SIZE).0,
+        UsedSpace::new(max_capacity),
+        root_storage_dir,
+    )
+    .await?;
+    let dispatcher = Dispatcher::new(node);
+
+    let wire_msg = WireMsg::single_src(
+        &leaving_info,
+        DstLocation::Section {
+            name: leaving_info.name(),
+            section_pk,
+        },
+        SystemMsg::NodeEvent(NodeEvent::Leaving),
+        section_pk,
+    )?;
+    let cmds = dispatcher
+        .process_cmd(
+            Cmd::HandleMsg {
+                sender: leaving_info.peer(),
+                wire_msg,
+                original_bytes: None,
+            },
+            "cmd-id",
+        )
+        .await?;
+
+    let mut offline_left_sent = false;
+    for cmd in cmds {
+        let wire_msg = match cmd {
+            Cmd::SendMsg { wire_msg, .. } => wire_msg,
+            _ => continue,
+        };
+
+        if let Ok(MsgType::System {
+            msg:
+                SystemMsg::Propose {
+                    proposal: crate::messaging::system::Proposal::Offline(node_state),
+                    ..
+                },
+            ..
+        }) = wire_msg.into_msg()
+        {
+            assert_eq!(node_state.name, leaving_info.name());
+            assert_eq!(node_state.state, MembershipState::Left);
+            offline_left_sent = true;
+        }
+    }
+
+    assert!(offline_left_sent);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn leaving_adult_hands_its_data_over() -> Result<()> {
+    init_test_logger();
+    let _span = tracing::info_span!("leaving_adult_hands_its_data_over").entered();
+
+    let (section_auth, _, sk_set) = create_section_auth();
+    let (section, _) = create_section(&sk_set, &section_auth).await?;
+
+    let leaving_info = gen_info(MIN_ADULT_AGE, None);
+    let other_adults: Vec<_> = (0..data_copy_count() + 2)
+        .map(|_| create_peer(MIN_ADULT_AGE))
+        .collect();
+    for peer in iter::once(leaving_info.peer()).chain(other_adults.iter().copied()) {
+        let node_state = section_signed(sk_set.secret_key(), NodeState::joined(peer, None))?;
+        assert!(section.update_member(node_state).await);
+    }
+
+    let (max_capacity, root_storage_dir) = create_test_max_capacity_and_root_storage()?;
+    let node = Node::new(
+        create_comm().await?,
+        leaving_info,
+        section,
+        None,
+        mpsc::channel(TEST_EVENT_CHANNEL_SIZE).0,
+        UsedSpace::new(max_capacity),
+        root_storage_dir,
+    )
+    .await?;
+
+    // A chunk and a register are held by the leaving adult
+    let chunk = ReplicatedData::Chunk(Chunk::new(random_bytes(1024)));
+    let keypair = Keypair::new_ed25519(&mut OsRng);
+    let op = CreateRegister::Empty {
+        name: xor_name::rand::random(),
+        tag: 1,
+        size: u16::MAX,
+        policy: Policy::Public(PublicPolicy {
+            owner: User::Key(keypair.public_key()),
+            permissions: Default::default(),
+        }),
+    };
+    let bytes = bincode::serialize(&op)?;
+    let register = ReplicatedData::RegisterWrite(RegisterCmd::Create {
+        cmd: SignedRegisterCreate {
+            op,
+            auth: ServiceAuth {
+                public_key: keypair.public_key(),
+                signature: keypair.sign(&bytes),
+            },
+        },
+        section_auth: MsgKindSectionAuth {
+            src_name: section_auth.prefix().name(),
+            sig: keyed_signed(sk_set.secret_key(), &bytes),
+        },
+    });
+    for data in [&chunk, &register] {
+        let _ = node.data_storage.store(data).await?;
+    }
+
+    // Each of them is to be sent to the adults which will hold it once we've left
+    let other_adult_names = other_adults.iter().map(Peer::name).collect();
+    let mut expected = BTreeMap::<XorName, BTreeSet<_>>::new();
+    for data in [&chunk, &register] {
+        for holder in node.compute_holders(&data.address(), &other_adult_names) {
+            let _ = expected.entry(holder).or_default().insert(data.address());
+        }
+    }
+
+    let targets = node.handover_targets().await?;
+    assert_eq!(
+        targets
+            .iter()
+            .map(|(target, addresses)| (*target, addresses.iter().copied().collect()))
+            .collect::<BTreeMap<_, BTreeSet<_>>>(),
+        expected
+    );
+
+    for (target, addresses) in &targets {
+        let mut handed_over = BTreeSet::new();
+        for cmd in node.hand_data_over(*target, addresses).await? {
+            match cmd {
+                Cmd::SignOutgoingSystemMsg {
+                    msg: SystemMsg::NodeCmd(NodeCmd::ReplicateData(data_collection)),
+                    dst: DstLocation::Node { name, .. },
+                } => {
+                    assert_eq!(name, *target);
+                    handed_over.extend(data_collection.iter().map(ReplicatedData::address));
+                }
+                cmd => bail!("Unexpected cmd to hand data over: {:?}", cmd),
+            }
+        }
+        assert_eq!(Some(&handed_over), expected.get(target));
+    }
+
+    Ok(())
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn node_msg_to_self() -> Result<()> {
     message_to_self(MessageDst::Node).await
@@ -1381,6 +1561,186 @@ async fn handle_demote_during_split() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn nodes_join_over_simulated_network() -> Result<()> {
+    let delivered = run_over_simulated_network(7, join_over_simulated_network)?;
+    assert!(delivered.iter().any(|msg| msg.kind == "JoinRequest"));
+    Ok(())
+}
+
+#[test]
+fn simulated_network_runs_are_reproducible() -> Result<()> {
+    let first = run_over_simulated_network(11, join_over_simulated_network)?;
+    let second = run_over_simulated_network(11, join_over_simulated_network)?;
+    assert!(!first.is_empty());
+    assert_eq!(first, second);
+
+    let other = run_over_simulated_network(12, join_over_simulated_network)?;
+    assert_ne!(first, other);
+
+    Ok(())
+}
+
+// Runs the test on a single thread with tokio's clock paused, so time is only advanced by the
+// simulated network and the timers of the nodes, returning all the msgs the network delivered.
+fn run_over_simulated_network<T, F>(seed: u64, test: T) -> Result<Vec<SimDelivery>>
+where
+    T: FnOnce(SimNetwork) -> F + Send + 'static,
+    F: Future<Output = Result<()>>,
+{
+    // full nodes need the same stack size `sn_node` gives them
+    let stack_size = 16 * 1024 * 1024;
+    std::thread::Builder::new()
+        .stack_size(stack_size)
+        .spawn(move || {
+            let runtime = SimNetwork::runtime()?;
+            runtime.block_on(async {
+                let network = SimNetwork::new(seed).recording();
+                let _delivery = network.start();
+                test(network.clone()).await?;
+                Ok(network.delivered())
+            })
+        })?
+        .join()
+        .map_err(|_| eyre!("simulated network test panicked"))?
+}
+
+async fn join_over_simulated_network(network: SimNetwork) -> Result<()> {
+    init_test_logger();
+
+    let joining_timeout = Duration::from_secs(60);
+    let root = tempdir()?;
+
+    let mut config = Config {
+        first: true,
+        ..Default::default()
+    };
+    config.set_root_dir(root.path().join("genesis"));
+    let (genesis, _genesis_events) =
+        NodeApi::new_simulated(&config, joining_timeout, &network).await?;
+    let genesis_key = genesis.genesis_key().await;
+
+    let joining_count = 3;
+    let mut nodes = vec![];
+    for i in 0..joining_count {
+        // one at a time, as `testnet` starts them by default
+        sleep(Duration::from_secs(3)).await;
+        let mut config = Config {
+            hard_coded_contacts: iter::once(genesis.our_connection_info().await).collect(),
+            genesis_key: Some(hex::encode(genesis_key.to_bytes())),
+            ..Default::default()
+        };
+        config.set_root_dir(root.path().join(format!("node-{}", i)));
+        let (node, events) = NodeApi::new_simulated(&config, joining_timeout, &network).await?;
+        assert_eq!(node.genesis_key().await, genesis_key);
+        nodes.push((node, events));
+    }
+
+    // no sockets involved, every node lives on the simulated network
+    assert_eq!(network.endpoints().len(), joining_count + 1);
+    for (node, _) in &nodes {
+        assert!(network
+            .endpoints()
+            .contains(&node.our_connection_info().await));
+    }
+
+    // the genesis elder eventually sees all the joined nodes as members
+    let members = timeout(joining_timeout, async {
+        loop {
+            let members: BTreeSet<_> = genesis
+                .our_adults()
+                .await
+                .into_iter()
+                .chain(genesis.our_elders().await)
+                .map(|peer| peer.name())
+                .collect();
+            if members.len() > joining_count {
+                break members;
+            }
+            sleep(Duration::from_millis(100)).await;
+        }
+    })
+    .await
+    .context("joined nodes never became members")?;
+
+    for (node, _) in &nodes {
+        assert!(members.contains(&node.name().await));
+    }
+    assert!(network.now() > Duration::ZERO);
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[tokio::test(flavor = "multi_thread")]
+async fn admin_interface() -> Result<()> {
+    use crate::node::admin::{admin_request, AdminRequest, AdminResponse};
+
+    let prefix = Prefix::default().pushed(true);
+    let (section_auth, mut nodes, sk_set) = gen_section_authority_provider(prefix, elder_count());
+    let (section, section_key_share) = create_section(&sk_set, &section_auth).await?;
+    let node_info = nodes.remove(0);
+    let node_name = node_info.name();
+
+    let (max_capacity, _) = create_test_max_capacity_and_root_storage()?;
+    let root_dir = tempdir()?;
+    let node = Node::new(
+        create_comm().await?,
+        node_info,
+        section,
+        Some(section_key_share),
+        mpsc::channel(TEST_EVENT_CHANNEL_SIZE).0,
+        UsedSpace::new(max_capacity),
+        root_dir.path().to_path_buf(),
+    )
+    .await?;
+    let dispatcher = Arc::new(Dispatcher::new(node));
+    super::admin_server::run_admin_server(root_dir.path(), dispatcher.clone()).await?;
+
+    match admin_request(root_dir.path(), AdminRequest::Status).await? {
+        AdminResponse::Status(status) => {
+            assert_eq!(status.name, node_name);
+            assert_eq!(status.prefix, prefix);
+            assert!(status.is_elder);
+            assert_eq!(status.section_key, sk_set.public_keys().public_key());
+        }
+        response => bail!("Unexpected response to a status request: {:?}", response),
+    }
+
+    match admin_request(root_dir.path(), AdminRequest::Peers).await? {
+        AdminResponse::Peers(peers) => {
+            assert_eq!(peers.members.len(), elder_count());
+            assert!(peers.members.iter().all(|member| member.is_elder));
+        }
+        response => bail!("Unexpected response to a peers request: {:?}", response),
+    }
+
+    match admin_request(root_dir.path(), AdminRequest::Storage).await? {
+        AdminResponse::Storage(usage) => {
+            assert_eq!(usage.max_capacity, max_capacity);
+            assert_eq!(usage.chunks, 0);
+            assert_eq!(usage.registers, 0);
+        }
+        response => bail!("Unexpected response to a storage request: {:?}", response),
+    }
+
+    dispatcher
+        .node
+        .send_event(Event::RelocationStarted {
+            previous_name: node_name,
+        })
+        .await;
+    match admin_request(root_dir.path(), AdminRequest::Events).await? {
+        AdminResponse::Events(events) => {
+            assert_eq!(events.len(), 1);
+            assert!(events[0].event.contains("RelocationStarted"));
+        }
+        response => bail!("Unexpected response to an events request: {:?}", response),
+    }
+
+    Ok(())
+}
+
 fn create_peer(age: u8) -> Peer {
     let name = ed25519::gen_name_with_age(age);
     Peer::new(name, gen_addr())
@@ -7,6 +7,7 @@
 // permissions and limitations relating to use of the SAFE Network Software.
 
 use crate::node::{Error, NetworkConfig, Result};
+use crate::ChunkStoreKind;
 use serde::{Deserialize, Serialize};
 use std::{
     collections::BTreeSet,
@@ -139,6 +140,19 @@ pub struct Config {
     /// Duration of a UPnP port mapping.
     #[structopt(long)]
     pub upnp_lease_duration: Option<u32>,
+    /// How chunks are stored on disk: "files", one file per chunk, or "packed", chunks packed
+    /// into segment files. If not set, the kind of an existing store is used, or "files" for a
+    /// new one.
+    #[structopt(long)]
+    pub chunk_store: Option<ChunkStoreKind>,
+    /// Migrate an existing chunk store to the kind set with `--chunk-store`, then exit without
+    /// starting the node process
+    #[structopt(long)]
+    pub migrate_chunk_store: bool,
+    /// Address to serve the node's metrics at, over HTTP at `/metrics`, for Prometheus to scrape,
+    /// e.g. `127.0.0.1:9100`. Metrics aren't served if not set.
+    #[structopt(long)]
+    pub metrics_addr: Option<SocketAddr>,
     #[structopt(skip)]
     #[allow(missing_docs)]
     pub network_config: NetworkConfig,
@@ -252,6 +266,15 @@ impl Config {
         self.update_only = config.update_only || self.update_only;
         self.clear_data = config.clear_data || self.clear_data;
         self.first = config.first || self.first;
+        self.migrate_chunk_store = config.migrate_chunk_store || self.migrate_chunk_store;
+
+        if let Some(chunk_store) = config.chunk_store {
+            self.chunk_store = Some(chunk_store);
+        }
+
+        if let Some(metrics_addr) = config.metrics_addr {
+            self.metrics_addr = Some(metrics_addr);
+        }
 
         if let Some(local_addr) = config.local_addr {
             self.local_addr = Some(local_addr);
@@ -306,6 +329,16 @@ impl Config {
         self.max_capacity.unwrap_or(DEFAULT_MAX_CAPACITY)
     }
 
+    /// Kind of chunk store the node uses, if set.
+    pub fn chunk_store(&self) -> Option<ChunkStoreKind> {
+        self.chunk_store
+    }
+
+    /// Address to serve the node's metrics at, if set.
+    pub fn metrics_addr(&self) -> Option<SocketAddr> {
+        self.metrics_addr
+    }
+
     /// Root directory for dbs and cached state. If not set, it defaults to
     /// `DEFAULT_ROOT_DIR_NAME` within the project's data directory (see `Config::root_dir` for the
     /// directories on each platform).
@@ -520,7 +553,7 @@ fn smoke() {
     // NOTE: IF this value is being changed due to a change in the config,
     // the change in config also be handled in Config::merge()
     // and in examples/config_handling.rs
-    let expected_size = 472;
+    let expected_size = 480;
 
     assert_eq!(std::mem::size_of::<Config>(), expected_size);
 }
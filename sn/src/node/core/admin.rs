@@ -0,0 +1,112 @@
+// Copyright 2022 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use super::Node;
+use crate::node::{
+    admin::{
+        AdminRequest, AdminResponse, NodeStatus, RecordedEvent, SectionMember, SectionPeers,
+        StorageUsage,
+    },
+    Error, Event, Result,
+};
+
+use std::time::SystemTime;
+
+// Number of the latest events kept for the admin interface
+pub(super) const RECENT_EVENTS_LEN: usize = 100;
+
+impl Node {
+    /// Answer a request made through the admin interface
+    pub(crate) async fn handle_admin_request(&self, request: AdminRequest) -> AdminResponse {
+        let response = match request {
+            AdminRequest::Status => self.admin_status().await.map(AdminResponse::Status),
+            AdminRequest::Peers => Ok(AdminResponse::Peers(self.admin_peers().await)),
+            AdminRequest::Storage => self.admin_storage().await.map(AdminResponse::Storage),
+            AdminRequest::Events => Ok(AdminResponse::Events(
+                self.recent_events.read().await.iter().cloned().collect(),
+            )),
+            // Leaving takes handling cmds to completion, which only the dispatcher can do
+            AdminRequest::Leave => Err(Error::AdminInterface(
+                "Leaving the section must go through the node's dispatcher".to_string(),
+            )),
+        };
+
+        response.unwrap_or_else(|error| {
+            warn!("Failed to answer admin request {:?}: {:?}", request, error);
+            AdminResponse::Error(error.to_string())
+        })
+    }
+
+    /// Keep the event for the admin interface, dropping the oldest kept once there are too many
+    pub(super) async fn record_event(&self, event: &Event) {
+        // Messages are too frequent to be of interest, they'd only push out the other events
+        if matches!(
+            event,
+            Event::MessageReceived { .. } | Event::ServiceMsgReceived { .. }
+        ) {
+            return;
+        }
+
+        let mut recent_events = self.recent_events.write().await;
+        if recent_events.len() >= RECENT_EVENTS_LEN {
+            let _oldest = recent_events.pop_front();
+        }
+        recent_events.push_back(RecordedEvent {
+            time: SystemTime::now(),
+            event: format!("{:?}", event),
+        });
+    }
+
+    async fn admin_status(&self) -> Result<NodeStatus> {
+        let info = self.info.read().await.clone();
+        Ok(NodeStatus {
+            name: info.name(),
+            age: info.age(),
+            is_elder: self.is_elder().await,
+            addr: self.our_connection_info(),
+            prefix: self.network_knowledge.prefix().await,
+            section_key: self.network_knowledge.section_key().await,
+            genesis_key: *self.network_knowledge.genesis_key(),
+            section_chain_len: self.network_knowledge.chain_len().await,
+            section_size: self.network_knowledge.section_size().await,
+        })
+    }
+
+    async fn admin_peers(&self) -> SectionPeers {
+        let elders = self.network_knowledge.authority_provider().await;
+        let members = self
+            .network_knowledge
+            .section_members()
+            .await
+            .into_iter()
+            .map(|member| SectionMember {
+                name: member.name(),
+                addr: member.addr(),
+                age: member.age(),
+                is_elder: elders.contains_elder(&member.name()),
+            })
+            .collect();
+
+        SectionPeers {
+            prefix: elders.prefix(),
+            section_key: elders.section_key(),
+            members,
+        }
+    }
+
+    async fn admin_storage(&self) -> Result<StorageUsage> {
+        let (used_space, max_capacity) = self.used_space();
+        Ok(StorageUsage {
+            used_space,
+            max_capacity,
+            chunks: self.stored_chunk_count(),
+            registers: self.stored_register_addresses().await?.len(),
+            chunk_store: self.chunk_store_kind(),
+        })
+    }
+}
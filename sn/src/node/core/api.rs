@@ -124,6 +124,8 @@ impl Node {
     }
 
     pub(crate) async fn send_event(&self, event: Event) {
+        self.record_event(&event).await;
+
         // Note: cloning the sender to avoid mutable access. Should have negligible cost.
         if self.event_tx.clone().send(event).await.is_err() {
             error!("Event receiver has been closed");
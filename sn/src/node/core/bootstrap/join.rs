@@ -21,7 +21,7 @@ use crate::node::{
     ed25519,
     messages::WireMsgUtils,
     network_knowledge::NetworkKnowledge,
-    Error, NodeInfo, Result, MIN_ADULT_AGE,
+    rng, Error, NodeInfo, Result, MIN_ADULT_AGE,
 };
 use crate::types::{log_markers::LogMarker, prefix_map::NetworkPrefixMap, Peer};
 
@@ -92,6 +92,8 @@ impl<'a> Join<'a> {
             initial_interval: Duration::from_millis(50),
             max_interval: Duration::from_millis(750),
             max_elapsed_time: Some(Duration::from_secs(60)),
+            // randomised with the rng of the node instead
+            randomization_factor: 0.0,
             ..Default::default()
         };
 
@@ -514,7 +516,7 @@ impl<'a> Join<'a> {
             let next_wait = self.backoff.next_backoff();
 
             if let Some(wait) = next_wait {
-                sleep(wait).await;
+                sleep(rng::jitter(wait)).await;
             } else {
                 error!("Waiting before attempting to join again");
 
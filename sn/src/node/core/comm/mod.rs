@@ -10,11 +10,18 @@ mod back_pressure;
 mod link;
 mod listener;
 mod peer_session;
+#[cfg(any(test, feature = "test-utils"))]
+mod sim;
+
+#[cfg(any(test, feature = "test-utils"))]
+pub use self::sim::{SimDelivery, SimNetwork};
 
 use self::back_pressure::BackPressure;
 use self::link::Link;
 use self::listener::{ListenerEvent, MsgListener};
 use self::peer_session::{PeerSession, SendWatcher};
+#[cfg(any(test, feature = "test-utils"))]
+use self::sim::SimEndpoint;
 
 use crate::messaging::{MsgId, WireMsg};
 use crate::node::core::comm::peer_session::SendStatus;
@@ -38,12 +45,31 @@ use tokio::{
 // Communication component of the node to interact with other nodes.
 #[derive(Clone)]
 pub(crate) struct Comm {
-    our_endpoint: Endpoint,
+    transport: Transport,
     msg_listener: MsgListener,
     back_pressure: BackPressure,
     sessions: Arc<RwLock<BTreeMap<Peer, PeerSession>>>,
 }
 
+// What the msgs are actually sent over.
+#[derive(Clone)]
+enum Transport {
+    Quic(Endpoint),
+    #[cfg(any(test, feature = "test-utils"))]
+    Sim(SimEndpoint),
+}
+
+impl Transport {
+    // The qp2p endpoint, none when running over the simulated network.
+    fn quic_endpoint(&self) -> Option<&Endpoint> {
+        match self {
+            Self::Quic(endpoint) => Some(endpoint),
+            #[cfg(any(test, feature = "test-utils"))]
+            Self::Sim(_) => None,
+        }
+    }
+}
+
 impl Comm {
     #[tracing::instrument(skip_all)]
     pub(crate) async fn first_node(
@@ -61,6 +87,35 @@ impl Comm {
         Ok(comm)
     }
 
+    /// Creates comms over the in-memory network, for the first node of it.
+    #[cfg(any(test, feature = "test-utils"))]
+    pub(crate) fn first_node_simulated(
+        network: &SimNetwork,
+        receive_msg: mpsc::Sender<MsgEvent>,
+    ) -> Self {
+        let endpoint = network.add_endpoint(receive_msg.clone());
+        let (comm, _) = setup(Transport::Sim(endpoint), receive_msg);
+        comm
+    }
+
+    /// Creates comms over the in-memory network, picking the first of the
+    /// `bootstrap_nodes` found on it as the node to bootstrap from.
+    #[cfg(any(test, feature = "test-utils"))]
+    pub(crate) fn bootstrap_simulated(
+        network: &SimNetwork,
+        bootstrap_nodes: &[SocketAddr],
+        receive_msg: mpsc::Sender<MsgEvent>,
+    ) -> Result<(Self, SocketAddr)> {
+        let endpoint = network.add_endpoint(receive_msg.clone());
+        let bootstrap_addr = bootstrap_nodes
+            .iter()
+            .find(|addr| endpoint.is_known(addr))
+            .copied()
+            .ok_or(Error::BootstrapFailed)?;
+        let (comm, _) = setup(Transport::Sim(endpoint), receive_msg);
+        Ok((comm, bootstrap_addr))
+    }
+
     #[tracing::instrument(skip_all)]
     pub(crate) async fn bootstrap(
         local_addr: SocketAddr,
@@ -83,7 +138,11 @@ impl Comm {
     }
 
     pub(crate) fn our_connection_info(&self) -> SocketAddr {
-        self.our_endpoint.public_addr()
+        match &self.transport {
+            Transport::Quic(endpoint) => endpoint.public_addr(),
+            #[cfg(any(test, feature = "test-utils"))]
+            Transport::Sim(endpoint) => endpoint.addr(),
+        }
     }
 
     pub(crate) async fn remove_expired(&self) {
@@ -112,13 +171,27 @@ impl Comm {
     /// Tests whether the peer is reachable.
     #[cfg(not(test))]
     pub(crate) async fn is_reachable(&self, peer: &SocketAddr) -> Result<(), Error> {
+        #[cfg(feature = "test-utils")]
+        if let Transport::Sim(endpoint) = &self.transport {
+            return if endpoint.is_known(peer) {
+                Ok(())
+            } else {
+                Err(Error::NodeNotReachable(*peer))
+            };
+        }
+
+        let our_endpoint = match self.transport.quic_endpoint() {
+            Some(endpoint) => endpoint,
+            None => return Err(Error::InvalidState),
+        };
+
         let qp2p_config = qp2p::Config {
             forward_port: false,
             ..Default::default()
         };
 
         let connectivity_endpoint =
-            Endpoint::new_client((self.our_endpoint.local_addr().ip(), 0), qp2p_config)?;
+            Endpoint::new_client((our_endpoint.local_addr().ip(), 0), qp2p_config)?;
 
         let result = connectivity_endpoint
             .is_reachable(peer)
@@ -147,7 +220,12 @@ impl Comm {
     /// Regulates comms with the specified peer
     /// according to the tolerated msgs per s provided by it.
     pub(crate) async fn regulate(&self, peer: &Peer, msgs_per_s: f64) {
-        let session = self.get_or_create(peer).await;
+        let endpoint = match self.transport.quic_endpoint() {
+            Some(endpoint) => endpoint,
+            // sends over the simulated network are not rate limited
+            None => return,
+        };
+        let session = self.get_or_create(endpoint, peer).await;
         session.update_send_rate(msgs_per_s).await;
     }
 
@@ -432,7 +510,7 @@ impl Comm {
         sessions.get(id).cloned()
     }
 
-    async fn get_or_create(&self, peer: &Peer) -> PeerSession {
+    async fn get_or_create(&self, endpoint: &Endpoint, peer: &Peer) -> PeerSession {
         if let Some(session) = self.get(peer).await {
             return session;
         }
@@ -447,7 +525,7 @@ impl Comm {
             Some(session) => session,
             // still not in list, go ahead and create + insert
             None => {
-                let link = Link::new(*peer, self.our_endpoint.clone(), self.msg_listener.clone());
+                let link = Link::new(*peer, endpoint.clone(), self.msg_listener.clone());
                 let session = PeerSession::new(link);
                 let _ = sessions.insert(*peer, session.clone());
                 session
@@ -458,6 +536,12 @@ impl Comm {
     /// Any number of incoming qp2p:Connections can be added.
     /// We will eventually converge to the same one in our comms with the peer.
     async fn add_incoming(&self, peer: &Peer, conn: qp2p::Connection) {
+        let endpoint = match self.transport.quic_endpoint() {
+            Some(endpoint) => endpoint,
+            // there are no qp2p connections over the simulated network
+            None => return,
+        };
+
         {
             let session = self.sessions.read().await;
             if let Some(c) = session.get(peer) {
@@ -474,13 +558,8 @@ impl Comm {
             Some(c) => c.add(conn).await,
             // still not in list, go ahead and insert
             None => {
-                let link = Link::new_with(
-                    *peer,
-                    self.our_endpoint.clone(),
-                    self.msg_listener.clone(),
-                    conn,
-                )
-                .await;
+                let link =
+                    Link::new_with(*peer, endpoint.clone(), self.msg_listener.clone(), conn).await;
                 let session = PeerSession::new(link);
                 let _ = sessions.insert(*peer, session);
             }
@@ -502,7 +581,19 @@ impl Comm {
             recipient,
         );
 
-        let peer = self.get_or_create(&recipient).await;
+        #[cfg(any(test, feature = "test-utils"))]
+        if let Transport::Sim(endpoint) = &self.transport {
+            endpoint.send(recipient.addr(), msg_bytes);
+            self.msg_listener.count_msg().await;
+            return (recipient, Ok(SendWatcher::sent()));
+        }
+
+        let endpoint = match self.transport.quic_endpoint() {
+            Some(endpoint) => endpoint,
+            None => return (recipient, Err(Error::InvalidState)),
+        };
+
+        let peer = self.get_or_create(endpoint, &recipient).await;
         let result = peer.send(msg_id, msg_priority, msg_bytes).await;
 
         (recipient, result)
@@ -515,7 +606,7 @@ fn setup_comms(
     incoming_connections: IncomingConnections,
     receive_msg: mpsc::Sender<MsgEvent>,
 ) -> (Comm, MsgListener) {
-    let (comm, msg_listener) = setup(our_endpoint, receive_msg);
+    let (comm, msg_listener) = setup(Transport::Quic(our_endpoint), receive_msg);
 
     listen(msg_listener.clone(), incoming_connections);
 
@@ -523,7 +614,7 @@ fn setup_comms(
 }
 
 #[tracing::instrument(skip_all)]
-fn setup(our_endpoint: Endpoint, receive_msg: mpsc::Sender<MsgEvent>) -> (Comm, MsgListener) {
+fn setup(transport: Transport, receive_msg: mpsc::Sender<MsgEvent>) -> (Comm, MsgListener) {
     let back_pressure = BackPressure::new();
     let (add_connection, conn_receiver) = mpsc::channel(100);
     let (count_msg, msg_counter) = mpsc::channel(1000);
@@ -531,7 +622,7 @@ fn setup(our_endpoint: Endpoint, receive_msg: mpsc::Sender<MsgEvent>) -> (Comm,
     let msg_listener = MsgListener::new(add_connection, receive_msg, count_msg);
 
     let comm = Comm {
-        our_endpoint,
+        transport,
         msg_listener: msg_listener.clone(),
         back_pressure: back_pressure.clone(),
         sessions: Arc::new(RwLock::new(BTreeMap::new())),
@@ -578,7 +669,11 @@ impl Drop for Comm {
     fn drop(&mut self) {
         // Close all existing connections and stop accepting new ones.
         // FIXME: this may be broken – `Comm` is clone, so this will break any clones?
-        self.our_endpoint.close();
+        match &self.transport {
+            Transport::Quic(endpoint) => endpoint.close(),
+            #[cfg(any(test, feature = "test-utils"))]
+            Transport::Sim(_) => {}
+        }
     }
 }
 
@@ -172,6 +172,13 @@ impl PeerSession {
 
                     break; // this means we will stop all sending to this peer!
                 }
+                #[cfg(feature = "chaos")]
+                if self.inject_fault(&job).await {
+                    self.sent.increment();
+                    self.attempted.increment();
+                    continue;
+                }
+
                 if let Err(err) = self.link.send(job.msg_bytes.clone()).await {
                     job.retries += 1;
                     if err.is_local_close() {
@@ -191,6 +198,57 @@ impl PeerSession {
             }
         }
     }
+
+    /// Injects a random fault (if any) on the send of the job.
+    /// Returns true if the job was taken care of, i.e. it shall not be sent as usual.
+    #[cfg(feature = "chaos")]
+    async fn inject_fault(&self, job: &SendJob) -> bool {
+        use crate::chaos::{chaos, MsgFault};
+
+        let fault = match chaos().msg_fault() {
+            Some(fault) => fault,
+            None => return false,
+        };
+        warn!("Chaos: injecting {:?} on msg {:?}", fault, job.msg_id);
+
+        match fault {
+            MsgFault::Drop => {
+                // the msg is lost on the way, as far as we can tell it was sent
+                job.reporter.send(SendStatus::Sent);
+                true
+            }
+            MsgFault::Delay(delay) => {
+                tokio::time::sleep(delay).await;
+                false
+            }
+            MsgFault::Duplicate => {
+                if let Err(err) = self.link.send(job.msg_bytes.clone()).await {
+                    warn!(
+                        "Chaos: failed to send duplicate of {:?}: {:?}",
+                        job.msg_id, err
+                    );
+                }
+                false
+            }
+            MsgFault::Reorder(delay) => {
+                // send it later, off this loop, so that the msgs behind it overtake it
+                let link = self.link.clone();
+                let msg_id = job.msg_id;
+                let msg_bytes = job.msg_bytes.clone();
+                job.reporter.send(SendStatus::Sent);
+                let _handle = tokio::spawn(async move {
+                    tokio::time::sleep(delay).await;
+                    if let Err(err) = link.send(msg_bytes).await {
+                        warn!(
+                            "Chaos: failed to send reordered msg {:?}: {:?}",
+                            msg_id, err
+                        );
+                    }
+                });
+                true
+            }
+        }
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -263,6 +321,14 @@ pub(crate) struct SendWatcher {
 }
 
 impl SendWatcher {
+    /// A watcher for a msg handed straight over to the transport, i.e. already sent.
+    #[cfg(any(test, feature = "test-utils"))]
+    pub(crate) fn sent() -> Self {
+        let (watcher, reporter) = status_watching();
+        reporter.send(SendStatus::Sent);
+        watcher
+    }
+
     /// Reads current status
     #[allow(unused)]
     pub(crate) fn status(&self) -> SendStatus {
@@ -0,0 +1,553 @@
+// Copyright 2022 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use super::MsgEvent;
+
+use crate::messaging::{MsgType, WireMsg};
+use crate::node::rng;
+use crate::types::Peer;
+
+use bytes::Bytes;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::{
+    cmp::{Ordering, Reverse},
+    collections::{BTreeMap, BTreeSet, BinaryHeap},
+    io,
+    net::{Ipv4Addr, SocketAddr},
+    sync::{Arc, Condvar, Mutex},
+    time::Duration,
+};
+use tokio::{
+    runtime::Runtime,
+    sync::{mpsc, Notify},
+    task::JoinHandle,
+    time::{self, Instant},
+};
+
+const DEFAULT_MIN_LATENCY: Duration = Duration::from_millis(1);
+const DEFAULT_MAX_LATENCY: Duration = Duration::from_millis(50);
+// First port handed out to simulated endpoints.
+const FIRST_PORT: u16 = 10_000;
+
+/// An in-memory network, letting many nodes run within one process without any sockets.
+///
+/// Every msg sent through it is given a latency drawn from a seeded rng, and is queued to be
+/// delivered at the time it would arrive at. Msgs are then delivered strictly in order of
+/// arrival time (ties broken by send order), the scheduler sleeping until each arrival.
+///
+/// Time is tokio's clock, counted from the creation of the network. On the runtime built by
+/// [`runtime`](Self::runtime), the scheduler sleeping is what advances the clock, so the timers
+/// of the nodes and the deliveries of msgs are interleaved in virtual time, without waiting for
+/// real. Given the same seed, such a run delivers the same msgs at the same times.
+///
+/// Delivery is driven either one msg at a time with [`step`](Self::step), or by a background
+/// task started with [`start`](Self::start).
+#[derive(Clone)]
+pub struct SimNetwork {
+    state: Arc<Mutex<SimState>>,
+    msg_queued: Arc<Notify>,
+}
+
+impl std::fmt::Debug for SimNetwork {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let state = self.lock();
+        f.debug_struct("SimNetwork")
+            .field("now", &state.elapsed())
+            .field("endpoints", &state.endpoints.keys())
+            .field("queued", &state.queue.len())
+            .finish()
+    }
+}
+
+struct SimState {
+    rng: StdRng,
+    min_latency: Duration,
+    max_latency: Duration,
+    // the instant the time of the network is counted from
+    start: Instant,
+    next_seq: u64,
+    next_port: u16,
+    endpoints: BTreeMap<SocketAddr, mpsc::Sender<MsgEvent>>,
+    isolated: BTreeSet<SocketAddr>,
+    queue: BinaryHeap<Reverse<ScheduledMsg>>,
+    // the msgs delivered so far, if they are being recorded
+    delivered: Option<Vec<SimDelivery>>,
+}
+
+impl SimState {
+    fn elapsed(&self) -> Duration {
+        Instant::now().saturating_duration_since(self.start)
+    }
+}
+
+/// A msg delivered by a [`SimNetwork`], as recorded with [`SimNetwork::recording`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SimDelivery {
+    /// Time the msg was delivered at.
+    pub at: Duration,
+    /// Address of the sender.
+    pub src: SocketAddr,
+    /// Address of the recipient.
+    pub dst: SocketAddr,
+    /// Kind of the msg, e.g. `JoinRequest`.
+    pub kind: &'static str,
+}
+
+struct ScheduledMsg {
+    arrival: Duration,
+    seq: u64,
+    src: SocketAddr,
+    dst: SocketAddr,
+    bytes: Bytes,
+}
+
+impl PartialEq for ScheduledMsg {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for ScheduledMsg {}
+
+impl PartialOrd for ScheduledMsg {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for ScheduledMsg {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.arrival, self.seq).cmp(&(other.arrival, other.seq))
+    }
+}
+
+impl SimNetwork {
+    /// Builds a `current_thread` runtime with a paused clock, to run a network and its nodes on.
+    ///
+    /// As with `tokio::time::pause`, the clock jumps to the next timer whenever all tasks are
+    /// waiting. Except it's held while blocking tasks, e.g. file IO, are running, otherwise how
+    /// long they took for real would decide which timers fire before they're done.
+    pub fn runtime() -> io::Result<Runtime> {
+        let blocking = Arc::new(BlockingTasks::default());
+        let (started, stopped, waiting) = (blocking.clone(), blocking.clone(), blocking);
+
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .start_paused(true)
+            // blocking threads then only live as long as their task, so they can be counted
+            .thread_keep_alive(Duration::ZERO)
+            // called when the thread is spawned, from the task spawning the blocking one
+            .thread_name_fn(move || {
+                started.add(1);
+                "sim-blocking".to_string()
+            })
+            .on_thread_stop(move || stopped.add(-1))
+            .on_thread_park(move || waiting.wait_for_none())
+            .build()
+    }
+
+    /// Creates a new network, taking all its random decisions from the given seed.
+    /// Its time starts at the current time of the tokio clock.
+    ///
+    /// The nodes running on the current thread, as all do on a `current_thread` runtime, then
+    /// take their random decisions from the seed too.
+    pub fn new(seed: u64) -> Self {
+        let mut network_rng = StdRng::seed_from_u64(seed);
+        rng::seed(network_rng.gen());
+        Self {
+            state: Arc::new(Mutex::new(SimState {
+                rng: network_rng,
+                min_latency: DEFAULT_MIN_LATENCY,
+                max_latency: DEFAULT_MAX_LATENCY,
+                start: Instant::now(),
+                next_seq: 0,
+                next_port: FIRST_PORT,
+                endpoints: BTreeMap::new(),
+                isolated: BTreeSet::new(),
+                queue: BinaryHeap::new(),
+                delivered: None,
+            })),
+            msg_queued: Arc::new(Notify::new()),
+        }
+    }
+
+    /// Sets the range the latency of every msg is drawn from.
+    pub fn with_latency(self, min: Duration, max: Duration) -> Self {
+        {
+            let mut state = self.lock();
+            state.min_latency = min.min(max);
+            state.max_latency = max.max(min);
+        }
+        self
+    }
+
+    /// Records every msg delivered, to be retrieved with [`delivered`](Self::delivered).
+    pub fn recording(self) -> Self {
+        self.lock().delivered = Some(vec![]);
+        self
+    }
+
+    /// The msgs delivered so far, empty unless [`recording`](Self::recording).
+    pub fn delivered(&self) -> Vec<SimDelivery> {
+        self.lock().delivered.clone().unwrap_or_default()
+    }
+
+    /// Current time of the network.
+    pub fn now(&self) -> Duration {
+        self.lock().elapsed()
+    }
+
+    /// Number of msgs sent but not yet delivered.
+    pub fn queued(&self) -> usize {
+        self.lock().queue.len()
+    }
+
+    /// Addresses of all the endpoints on the network.
+    pub fn endpoints(&self) -> BTreeSet<SocketAddr> {
+        self.lock().endpoints.keys().copied().collect()
+    }
+
+    /// Cuts the endpoint off the network, all msgs from or to it are lost until reconnected.
+    pub fn isolate(&self, addr: SocketAddr) {
+        let _ = self.lock().isolated.insert(addr);
+    }
+
+    /// Reconnects an endpoint previously isolated.
+    pub fn reconnect(&self, addr: SocketAddr) {
+        let _ = self.lock().isolated.remove(&addr);
+    }
+
+    /// Removes the endpoint from the network, as if the node behind it was killed.
+    pub fn remove(&self, addr: SocketAddr) {
+        let _ = self.lock().endpoints.remove(&addr);
+    }
+
+    /// Delivers the next msg in the queue, first sleeping until its arrival. Msgs sent in the
+    /// meantime which arrive earlier are delivered first.
+    /// Msgs from or to isolated or removed endpoints are dropped.
+    /// Returns false if there was no msg left to deliver.
+    pub async fn step(&self) -> bool {
+        let (msg, recipient) = loop {
+            let arrival = {
+                let mut state = self.lock();
+                let arrival = match state.queue.peek() {
+                    Some(Reverse(msg)) => state.start + msg.arrival,
+                    None => return false,
+                };
+                if arrival <= Instant::now() {
+                    if let Some(Reverse(msg)) = state.queue.pop() {
+                        let reachable = !state.isolated.contains(&msg.src)
+                            && !state.isolated.contains(&msg.dst);
+                        let recipient =
+                            state.endpoints.get(&msg.dst).filter(|_| reachable).cloned();
+                        break (msg, recipient);
+                    }
+                }
+                arrival
+            };
+
+            tokio::select! {
+                biased;
+                _ = time::sleep_until(arrival) => {}
+                _ = self.msg_queued.notified() => {}
+            }
+        };
+
+        let recipient = match recipient {
+            Some(recipient) => recipient,
+            None => {
+                trace!(
+                    "Simulated network dropped msg from {} to {}, the recipient is unreachable",
+                    msg.src,
+                    msg.dst
+                );
+                return true;
+            }
+        };
+
+        let wire_msg = match WireMsg::from(msg.bytes.clone()) {
+            Ok(wire_msg) => wire_msg,
+            Err(error) => {
+                debug!("Failed to deserialize simulated msg: {:?}", error);
+                return true;
+            }
+        };
+        let src_name = wire_msg.msg_kind().src().name();
+
+        if let Some(delivered) = self.lock().delivered.as_mut() {
+            let kind = match wire_msg.into_msg() {
+                Ok(MsgType::System { msg, .. }) => (&msg).into(),
+                Ok(MsgType::Service { msg, .. }) => (&msg).into(),
+                Err(_) => "Invalid",
+            };
+            delivered.push(SimDelivery {
+                at: msg.arrival,
+                src: msg.src,
+                dst: msg.dst,
+                kind,
+            });
+        }
+
+        let _res = recipient
+            .send(MsgEvent::Received {
+                sender: Peer::new(src_name, msg.src),
+                wire_msg,
+                original_bytes: msg.bytes,
+            })
+            .await;
+
+        true
+    }
+
+    /// Keeps delivering msgs as they are sent, for as long as the network is alive.
+    pub async fn run(self) {
+        loop {
+            if !self.step().await {
+                self.msg_queued.notified().await;
+            }
+            // give the recipient a chance to handle the msg before delivering the next one
+            tokio::task::yield_now().await;
+        }
+    }
+
+    /// Spawns a task delivering msgs as they are sent.
+    pub fn start(&self) -> JoinHandle<()> {
+        tokio::spawn(self.clone().run())
+    }
+
+    /// Adds a new endpoint to the network, with an address of its own.
+    /// Msgs delivered to it are passed on to `receive_msg`.
+    pub(crate) fn add_endpoint(&self, receive_msg: mpsc::Sender<MsgEvent>) -> SimEndpoint {
+        let mut state = self.lock();
+        let addr = SocketAddr::from((Ipv4Addr::LOCALHOST, state.next_port));
+        state.next_port += 1;
+        let _ = state.endpoints.insert(addr, receive_msg);
+
+        SimEndpoint {
+            addr,
+            network: self.clone(),
+        }
+    }
+
+    fn is_known(&self, addr: &SocketAddr) -> bool {
+        self.lock().endpoints.contains_key(addr)
+    }
+
+    fn enqueue(&self, src: SocketAddr, dst: SocketAddr, bytes: Bytes) {
+        {
+            let mut state = self.lock();
+            let latency = if state.min_latency == state.max_latency {
+                state.min_latency
+            } else {
+                let (min, max) = (state.min_latency, state.max_latency);
+                state.rng.gen_range(min, max)
+            };
+            let msg = ScheduledMsg {
+                arrival: state.elapsed() + latency,
+                seq: state.next_seq,
+                src,
+                dst,
+                bytes,
+            };
+            state.next_seq += 1;
+            state.queue.push(Reverse(msg));
+        }
+        self.msg_queued.notify_one();
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, SimState> {
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+// Count of the threads of the blocking tasks of a runtime, which can be waited on to reach zero.
+#[derive(Default)]
+struct BlockingTasks {
+    count: Mutex<isize>,
+    none: Condvar,
+}
+
+impl BlockingTasks {
+    fn add(&self, delta: isize) {
+        let mut count = self
+            .count
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        *count += delta;
+        if *count <= 0 {
+            self.none.notify_all();
+        }
+    }
+
+    fn wait_for_none(&self) {
+        let count = self
+            .count
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let _count = self
+            .none
+            .wait_while(count, |count| *count > 0)
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+    }
+}
+
+/// The handle a `Comm` uses to send over a [`SimNetwork`].
+#[derive(Clone)]
+pub(crate) struct SimEndpoint {
+    addr: SocketAddr,
+    network: SimNetwork,
+}
+
+impl SimEndpoint {
+    pub(crate) fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Whether there is an endpoint at the address (reachable or not).
+    pub(crate) fn is_known(&self, addr: &SocketAddr) -> bool {
+        self.network.is_known(addr)
+    }
+
+    /// Queues the msg for delivery. As with a real network, there is no
+    /// telling whether it will actually arrive.
+    pub(crate) fn send(&self, dst: SocketAddr, bytes: Bytes) {
+        self.network.enqueue(self.addr, dst, bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::messaging::data::{DataQuery, ServiceMsg};
+    use crate::messaging::{DstLocation, MsgId, MsgKind, ServiceAuth};
+    use crate::types::{ChunkAddress, Keypair};
+    use eyre::{eyre, Result};
+    use rand::rngs::OsRng;
+
+    fn test_msg_bytes() -> Result<Bytes> {
+        let src_keypair = Keypair::new_ed25519(&mut OsRng);
+        let query = DataQuery::GetChunk(ChunkAddress(xor_name::rand::random()));
+        let payload = WireMsg::serialize_msg_payload(&ServiceMsg::Query(query))?;
+        let auth = ServiceAuth {
+            public_key: src_keypair.public_key(),
+            signature: src_keypair.sign(&payload),
+        };
+        let wire_msg = WireMsg::new_msg(
+            MsgId::new(),
+            payload,
+            MsgKind::ServiceMsg(auth),
+            DstLocation::Node {
+                name: xor_name::rand::random(),
+                section_pk: bls::SecretKey::random().public_key(),
+            },
+        )?;
+        Ok(wire_msg.serialize()?)
+    }
+
+    // Sends `count` msgs from a to b, returning the order b received them in, and the final time.
+    async fn deliveries(seed: u64, count: usize) -> Result<(Vec<Bytes>, Duration)> {
+        let network = SimNetwork::new(seed);
+        let (tx_a, _rx_a) = mpsc::channel(count);
+        let (tx_b, mut rx_b) = mpsc::channel(count);
+        let a = network.add_endpoint(tx_a);
+        let b = network.add_endpoint(tx_b);
+
+        let mut sent = vec![];
+        for _ in 0..count {
+            let bytes = test_msg_bytes()?;
+            a.send(b.addr(), bytes.clone());
+            sent.push(bytes);
+        }
+        while network.step().await {}
+
+        let mut received = vec![];
+        while let Ok(MsgEvent::Received { original_bytes, .. }) = rx_b.try_recv() {
+            // map back to the send index, so runs with different msgs can be compared
+            let index = sent
+                .iter()
+                .position(|bytes| bytes == &original_bytes)
+                .ok_or_else(|| eyre!("received a msg never sent"))?;
+            received.push(Bytes::from(index.to_be_bytes().to_vec()));
+        }
+
+        Ok((received, network.now()))
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn delivery_is_deterministic_given_the_seed() -> Result<()> {
+        let (first, first_time) = deliveries(7, 20).await?;
+        let (second, second_time) = deliveries(7, 20).await?;
+
+        assert_eq!(first.len(), 20);
+        assert_eq!(first, second);
+        assert_eq!(first_time, second_time);
+
+        // latencies differ, so msgs get reordered
+        let in_send_order: Vec<_> = (0..20_usize)
+            .map(|i| Bytes::from(i.to_be_bytes().to_vec()))
+            .collect();
+        assert_ne!(first, in_send_order);
+
+        Ok(())
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn virtual_time_advances_with_deliveries() -> Result<()> {
+        let latency = Duration::from_millis(30);
+        let network = SimNetwork::new(0).with_latency(latency, latency);
+        let (tx_a, _rx_a) = mpsc::channel(1);
+        let (tx_b, mut rx_b) = mpsc::channel(1);
+        let a = network.add_endpoint(tx_a);
+        let b = network.add_endpoint(tx_b);
+
+        a.send(b.addr(), test_msg_bytes()?);
+        assert_eq!(network.now(), Duration::ZERO);
+        assert!(network.step().await);
+        assert_eq!(network.now(), latency);
+
+        let sender = match rx_b.recv().await {
+            Some(MsgEvent::Received { sender, .. }) => sender,
+            None => return Err(eyre!("msg not delivered")),
+        };
+        assert_eq!(sender.addr(), a.addr());
+
+        assert!(!network.step().await);
+        assert_eq!(network.now(), latency);
+
+        Ok(())
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn isolated_endpoints_lose_msgs() -> Result<()> {
+        let network = SimNetwork::new(0);
+        let (tx_a, _rx_a) = mpsc::channel(1);
+        let (tx_b, mut rx_b) = mpsc::channel(1);
+        let a = network.add_endpoint(tx_a);
+        let b = network.add_endpoint(tx_b);
+
+        network.isolate(b.addr());
+        a.send(b.addr(), test_msg_bytes()?);
+        assert!(network.step().await);
+        assert!(rx_b.try_recv().is_err());
+
+        network.reconnect(b.addr());
+        a.send(b.addr(), test_msg_bytes()?);
+        assert!(network.step().await);
+        assert!(rx_b.try_recv().is_ok());
+
+        network.remove(b.addr());
+        assert!(!a.is_known(&b.addr()));
+        a.send(b.addr(), test_msg_bytes()?);
+        assert!(network.step().await);
+        assert_eq!(network.queued(), 0);
+
+        Ok(())
+    }
+}
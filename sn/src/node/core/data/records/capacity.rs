@@ -7,8 +7,9 @@
 // permissions and limitations relating to use of the SAFE Network Software.
 
 use crate::{
-    messaging::data::StorageLevel,
+    messaging::data::{DataKind, StorageLevel},
     node::{Prefix, XorName},
+    types::Token,
 };
 use itertools::Itertools;
 use std::{
@@ -20,6 +21,12 @@ use tokio::sync::RwLock;
 // The number of separate copies of a chunk which should be maintained.
 pub(crate) const MIN_LEVEL_WHEN_FULL: u8 = 9; // considered full when >= 90 %.
 
+// Cost of storing a KiB of data in a section whose Adults are all empty.
+const NANOS_PER_KIB: u64 = 1_000;
+// Registers keep growing with every entry written to them,
+// so they are priced as if holding at least this many bytes.
+const MIN_REGISTER_BYTES: u64 = 64 * 1024;
+
 /// A util for sharing the
 /// info on data capacity among the
 /// chunk storing nodes in the section.
@@ -67,6 +74,11 @@ impl Capacity {
         (total / num_adults) as u8
     }
 
+    /// Cost of storing data of the given size and kind in the section.
+    pub(crate) async fn store_cost(&self, bytes: u64, kind: DataKind) -> Token {
+        store_cost(bytes, kind, self.avg_usage().await)
+    }
+
     /// Storage levels of nodes in the section.
     pub(super) async fn levels(&self) -> BTreeMap<XorName, StorageLevel> {
         let mut map = BTreeMap::new();
@@ -156,3 +168,52 @@ impl Capacity {
         }
     }
 }
+
+/// Cost of storing data of the given size and kind in a section whose Adults are, on average,
+/// at the given storage level. The cost doubles with every level, so that storing data gets
+/// pricier as the section fills up.
+fn store_cost(bytes: u64, kind: DataKind, avg_level: u8) -> Token {
+    let bytes = match kind {
+        DataKind::Chunk => bytes,
+        DataKind::Register => bytes.max(MIN_REGISTER_BYTES),
+    };
+    let kibs = bytes.div_ceil(1024).max(1);
+    let multiplier = 1_u64 << avg_level.min(StorageLevel::MAX);
+
+    Token::from_nano(
+        kibs.saturating_mul(NANOS_PER_KIB)
+            .saturating_mul(multiplier),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn store_cost_is_charged_per_kib() {
+        assert_eq!(store_cost(0, DataKind::Chunk, 0), Token::from_nano(1_000));
+        assert_eq!(
+            store_cost(1024, DataKind::Chunk, 0),
+            Token::from_nano(1_000)
+        );
+        assert_eq!(
+            store_cost(1025, DataKind::Chunk, 0),
+            Token::from_nano(2_000)
+        );
+        assert_eq!(
+            store_cost(1, DataKind::Register, 0),
+            Token::from_nano(64_000)
+        );
+    }
+
+    #[test]
+    fn store_cost_doubles_with_every_level() {
+        let empty = store_cost(4096, DataKind::Chunk, 0);
+        let half_full = store_cost(4096, DataKind::Chunk, 5);
+        assert_eq!(half_full.as_nano(), empty.as_nano() * 32);
+
+        let full = store_cost(u64::MAX, DataKind::Chunk, StorageLevel::MAX);
+        assert_eq!(full, Token::from_nano(u64::MAX));
+    }
+}
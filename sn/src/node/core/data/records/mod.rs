@@ -7,13 +7,16 @@
 // permissions and limitations relating to use of the SAFE Network Software.
 
 mod capacity;
+mod subscriptions;
 
 pub(crate) use self::capacity::{Capacity, MIN_LEVEL_WHEN_FULL};
 
 use crate::{
     data_copy_count,
     messaging::{
-        data::{CmdError, DataQuery, MetadataExchange, StorageLevel},
+        data::{
+            CmdError, DataQuery, Error as ErrorMsg, MetadataExchange, QueryResponse, StorageLevel,
+        },
         system::{NodeCmd, NodeQuery, SystemMsg},
         AuthorityProof, DstLocation, EndUser, MsgId, ServiceAuth, WireMsg,
     },
@@ -58,7 +61,7 @@ impl Node {
         auth: AuthorityProof<ServiceAuth>,
         origin: Peer,
     ) -> Result<Vec<Cmd>> {
-        let address = query.address();
+        let address = query.address().ok_or(Error::InvalidState)?;
         let operation_id = query.operation_id()?;
         trace!(
             "{:?} preparing to query adults for data at {:?} with op_id: {:?}",
@@ -178,6 +181,35 @@ impl Node {
         changed
     }
 
+    /// Quote the cost of storing data in our section, priced after the storage levels
+    /// reported by our Adults.
+    pub(crate) async fn quote_store_cost(
+        &self,
+        query: &DataQuery,
+        msg_id: MsgId,
+        origin: Peer,
+    ) -> Result<Vec<Cmd>> {
+        let operation_id = query.operation_id()?;
+        let response = if let DataQuery::GetStoreCost { bytes, kind, .. } = query {
+            let cost = self.capacity.store_cost(*bytes, *kind).await;
+            trace!(
+                "{:?} {} for storing {} bytes of {:?} data, to {:?}",
+                LogMarker::StoreCostQuoted,
+                cost,
+                bytes,
+                kind,
+                origin
+            );
+            QueryResponse::GetStoreCost((Ok(cost), operation_id))
+        } else {
+            query.error(ErrorMsg::InvalidOperation(
+                "Not a storage cost query".to_string(),
+            ))?
+        };
+
+        self.send_query_response(response, origin, msg_id).await
+    }
+
     pub(crate) async fn full_adults(&self) -> BTreeSet<XorName> {
         self.capacity.full_adults().await
     }
@@ -0,0 +1,107 @@
+// Copyright 2022 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use crate::{
+    messaging::{
+        data::{ServiceMsg, SignedRegisterEdit},
+        DstLocation, EndUser, MsgId, WireMsg,
+    },
+    node::{
+        core::{Cmd, Node, MAX_SUBSCRIBERS_PER_REGISTER},
+        Result,
+    },
+    types::{log_markers::LogMarker, Peer, RegisterAddress},
+};
+use dashmap::DashSet;
+
+impl Node {
+    /// Subscribe the given peers to the edits of the Register at the given address,
+    /// renewing the subscription of those who were already subscribed.
+    pub(crate) async fn add_register_subscribers(
+        &self,
+        address: RegisterAddress,
+        peers: &DashSet<Peer>,
+    ) {
+        for peer in peers.iter().map(|peer| *peer) {
+            let subscription = (address, peer);
+            if self
+                .register_subscriptions
+                .get(&subscription)
+                .await
+                .is_none()
+                && self.count_register_subscribers(address).await >= MAX_SUBSCRIBERS_PER_REGISTER
+            {
+                warn!("Not subscribing {peer:?} to {address:?}, there are more than {MAX_SUBSCRIBERS_PER_REGISTER} subscribed already");
+                continue;
+            }
+            // Setting it again renews the expiry of this peer's subscription only
+            let _prior_value = self
+                .register_subscriptions
+                .set(subscription, (), None)
+                .await;
+        }
+
+        trace!(
+            "{:?} {:?}, now with {} subscribers",
+            LogMarker::RegisterSubscribed,
+            address,
+            self.count_register_subscribers(address).await
+        );
+    }
+
+    // Number of peers whose subscription to the given Register hasn't expired.
+    async fn count_register_subscribers(&self, address: RegisterAddress) -> usize {
+        self.register_subscriptions
+            .count(|((subscribed, _), item)| *subscribed == address && !item.expired())
+            .await
+    }
+
+    /// Push the given edit to the peers subscribed to the edited Register.
+    pub(crate) async fn notify_register_subscribers(
+        &self,
+        edit: SignedRegisterEdit,
+    ) -> Result<Vec<Cmd>> {
+        self.register_subscriptions.remove_expired().await;
+
+        let address = *edit.dst_address();
+        let subscribers: Vec<Peer> = self
+            .register_subscriptions
+            .get_items()
+            .await
+            .into_keys()
+            .filter(|(subscribed, _)| *subscribed == address)
+            .map(|(_, peer)| peer)
+            .collect();
+        if subscribers.is_empty() {
+            return Ok(vec![]);
+        }
+
+        trace!(
+            "{:?} {:?}, notifying {} subscribers",
+            LogMarker::RegisterSubscribersNotified,
+            address,
+            subscribers.len()
+        );
+
+        let msg = ServiceMsg::RegisterEdited(edit);
+        let (msg_kind, payload) = self.ed_sign_client_msg(&msg).await?;
+
+        let mut cmds = vec![];
+        for peer in subscribers {
+            let dst = DstLocation::EndUser(EndUser(peer.name()));
+            let wire_msg = WireMsg::new_msg(MsgId::new(), payload.clone(), msg_kind.clone(), dst)?;
+
+            cmds.push(Cmd::SendMsg {
+                recipients: vec![peer],
+                wire_msg,
+            });
+        }
+
+        Ok(cmds)
+    }
+}
@@ -9,11 +9,10 @@
 use crate::dbs::{convert_to_error_msg, ChunkStore, Error, Result};
 use crate::messaging::system::NodeQueryResponse;
 use crate::types::{log_markers::LogMarker, Chunk, ChunkAddress};
-use crate::UsedSpace;
+use crate::{ChunkStoreKind, UsedSpace};
 
 use std::{
     fmt::{self, Display, Formatter},
-    io::ErrorKind,
     path::Path,
 };
 use tracing::info;
@@ -31,6 +30,14 @@ impl ChunkStorage {
         })
     }
 
+    pub(crate) fn kind(&self) -> ChunkStoreKind {
+        self.db.kind()
+    }
+
+    pub(crate) fn count(&self) -> usize {
+        self.db.chunk_count()
+    }
+
     pub(crate) fn keys(&self) -> Result<Vec<ChunkAddress>> {
         self.db.list_all_chunk_addresses()
     }
@@ -44,17 +51,30 @@ impl ChunkStorage {
     pub(crate) async fn get_chunk(&self, address: &ChunkAddress) -> Result<Chunk> {
         debug!("Getting chunk {:?}", address);
 
+        self.db.read_chunk(address).await
+    }
+
+    /// Verify the stored chunk still matches its address, moving it to quarantine otherwise.
+    /// Returns `true` if the chunk was found corrupted.
+    pub(crate) async fn scrub(&self, address: &ChunkAddress) -> Result<bool> {
         match self.db.read_chunk(address).await {
-            Ok(res) => Ok(res),
-            Err(error) => match error {
-                Error::Io(io_error) if io_error.kind() == ErrorKind::NotFound => {
-                    Err(Error::ChunkNotFound(*address.name()))
-                }
-                something_else => Err(something_else),
-            },
+            Ok(_) => Ok(false),
+            Err(Error::ChunkCorrupted(_)) => {
+                warn!("{:?} {:?}", LogMarker::CorruptedChunkQuarantined, address);
+                self.db.quarantine_chunk(address).await?;
+                Ok(true)
+            }
+            // it may have been removed since we listed it
+            Err(Error::ChunkNotFound(_)) => Ok(false),
+            Err(other) => Err(other),
         }
     }
 
+    /// Reclaim the disk space left behind by removed chunks, returning the number of bytes freed
+    pub(crate) async fn compact(&self) -> Result<u64> {
+        self.db.compact().await
+    }
+
     // Read chunk from local store and return NodeQueryResponse
     pub(crate) async fn get(&self, address: &ChunkAddress) -> NodeQueryResponse {
         trace!("{:?}", LogMarker::ChunkQueryReceviedAtAdult);
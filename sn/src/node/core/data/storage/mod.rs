@@ -12,13 +12,16 @@ mod registers;
 use crate::{
     dbs::Result,
     messaging::{
-        data::{DataQuery, RegisterStoreExport, StorageLevel},
+        data::{DataQuery, Error as ErrorMsg, RegisterStoreExport, StorageLevel},
         system::{NodeCmd, NodeQueryResponse, SystemMsg},
         DstLocation,
     },
     node::core::{Cmd, Node},
-    types::{register::User, ReplicatedData, ReplicatedDataAddress as DataAddress},
-    UsedSpace,
+    types::{
+        register::User, ChunkAddress, RegisterAddress, ReplicatedData,
+        ReplicatedDataAddress as DataAddress,
+    },
+    ChunkStoreKind, UsedSpace,
 };
 
 pub(crate) use chunks::ChunkStorage;
@@ -91,6 +94,15 @@ impl DataStorage {
         match query {
             DataQuery::GetChunk(addr) => self.chunks.get(addr).await,
             DataQuery::Register(read) => self.registers.read(read, requester).await,
+            DataQuery::GetStoreCost { .. } => match query.operation_id() {
+                Ok(op_id) => NodeQueryResponse::GetStoreCost((
+                    Err(ErrorMsg::InvalidOperation(
+                        "Storage costs are quoted by Elders".to_string(),
+                    )),
+                    op_id,
+                )),
+                Err(_) => NodeQueryResponse::FailedToCreateOperationId,
+            },
         }
     }
 
@@ -121,7 +133,7 @@ impl DataStorage {
         }
     }
 
-    async fn keys(&self) -> Result<Vec<DataAddress>> {
+    pub(crate) async fn keys(&self) -> Result<Vec<DataAddress>> {
         let chunk_keys = self.chunks.keys()?.into_iter().map(DataAddress::Chunk);
         let reg_keys = self
             .registers
@@ -192,6 +204,77 @@ impl Node {
         Ok(cmds)
     }
 
+    /// Addresses of all the chunks we currently hold
+    pub(crate) fn stored_chunk_addresses(&self) -> Result<Vec<ChunkAddress>> {
+        self.data_storage.chunks.keys()
+    }
+
+    /// Number of chunks we currently hold
+    pub(crate) fn stored_chunk_count(&self) -> usize {
+        self.data_storage.chunks.count()
+    }
+
+    /// Addresses of all the Registers we currently hold
+    pub(crate) async fn stored_register_addresses(&self) -> Result<Vec<RegisterAddress>> {
+        self.data_storage.registers.keys().await
+    }
+
+    /// Ratio of our storage capacity in use
+    pub(crate) fn used_space_ratio(&self) -> f64 {
+        self.data_storage.used_space.ratio()
+    }
+
+    /// Bytes of storage in use, and our storage capacity
+    pub(crate) fn used_space(&self) -> (usize, usize) {
+        let used_space = &self.data_storage.used_space;
+        (used_space.used(), used_space.max_capacity())
+    }
+
+    /// Kind of store our chunks are kept in
+    pub(crate) fn chunk_store_kind(&self) -> ChunkStoreKind {
+        self.data_storage.chunks.kind()
+    }
+
+    /// Reclaim the disk space left behind by the chunks we no longer hold
+    pub(crate) async fn compact_chunk_store(&self) -> Result<u64, crate::node::Error> {
+        Ok(self.data_storage.chunks.compact().await?)
+    }
+
+    /// Verify a chunk we hold. If it's corrupted it's quarantined, and the other
+    /// holders in our section are asked to replicate a fresh copy to us.
+    pub(crate) async fn scrub_chunk(
+        &self,
+        address: &ChunkAddress,
+    ) -> Result<Vec<Cmd>, crate::node::Error> {
+        if !self.data_storage.chunks.scrub(address).await? {
+            return Ok(vec![]);
+        }
+
+        let our_name = self.info.read().await.name();
+        let data_address = ReplicatedDataAddress::Chunk(*address);
+        let other_adults = self
+            .network_knowledge
+            .adults()
+            .await
+            .iter()
+            .map(|peer| peer.name())
+            .filter(|name| *name != our_name)
+            .collect();
+        let holders = self.compute_holders(&data_address, &other_adults);
+        info!("Requesting a fresh copy of corrupted chunk {address:?} from {holders:?}");
+
+        let section_pk = self.network_knowledge.section_key().await;
+        let cmds = holders
+            .into_iter()
+            .map(|name| Cmd::SignOutgoingSystemMsg {
+                msg: SystemMsg::NodeCmd(NodeCmd::FetchReplicateData(vec![data_address])),
+                dst: DstLocation::Node { name, section_pk },
+            })
+            .collect();
+
+        Ok(cmds)
+    }
+
     // on adults
     async fn get_replica_targets(
         &self,
@@ -28,6 +28,7 @@ use rayon::prelude::*;
 use sled::Db;
 use std::{
     fmt::{self, Display, Formatter},
+    mem::size_of,
     path::Path,
     sync::Arc,
 };
@@ -40,8 +41,13 @@ use xor_name::{XorName, XOR_NAME_LEN};
 const REG_DB_NAME: &str = "register";
 const KEY_DB_NAME: &str = "addresses";
 const CACHE_SIZE: u16 = 100;
+// Number of cmds logged for a Register before they are folded into a snapshot of it
+const SNAPSHOT_INTERVAL: usize = 100;
 
 type RegOpStore = EventStore<RegisterCmd>;
+// Snapshots are only used to load Registers faster, they are never replicated as their entries
+// can't be verified. The section auth is kept along, as the Create cmd carrying it gets folded in.
+type StoredSnapshot = (Register, SectionAuth);
 type Cache = LruCache<CacheEntry>;
 
 /// Operations over the data type Register.
@@ -157,9 +163,10 @@ impl RegisterStorage {
         entry: Arc<CacheEntry>,
     ) -> Result<ReplicatedRegisterLog> {
         let mut address = None;
+        // the whole signed log is replicated, so every cmd can be verified when applied
         let op_log = entry
             .store
-            .get_all()?
+            .get_log()?
             .into_iter()
             .filter_map(|stored_cmd| {
                 // only spread signed data
@@ -272,6 +279,12 @@ impl RegisterStorage {
         // nested loops, slow..
         for data in registers {
             let key = data.address.id()?;
+            // the cmds we already hold aren't applied again, e.g. an Extend would be applied twice
+            let held = match self.try_load_cache_entry(&key).await {
+                Ok(entry) => entry.store.get_log()?,
+                Err(Error::KeyNotFound(_)) => vec![],
+                Err(e) => return Err(e),
+            };
             for replicated_cmd in data.op_log {
                 if replicated_cmd.dst_address() != data.address {
                     warn!(
@@ -280,7 +293,21 @@ impl RegisterStorage {
                     );
                     continue;
                 }
-                let _ = self.apply(replicated_cmd).await?;
+                if held.contains(&replicated_cmd) {
+                    continue;
+                }
+                match self.apply(replicated_cmd).await {
+                    Ok(()) => {}
+                    Err(
+                        error @ (Error::InvalidSignature(_)
+                        | Error::NetworkData(_)
+                        | Error::DataExists),
+                    ) => warn!(
+                        "Corrupt ReplicatedRegisterLog, rejected cmd of {}: {:?}",
+                        key, error
+                    ),
+                    Err(error) => return Err(error),
+                }
             }
         }
 
@@ -291,7 +318,7 @@ impl RegisterStorage {
 
     pub(crate) async fn write(&self, cmd: RegisterCmd) -> Result<()> {
         // rough estimate ignoring the extra space used by sled
-        let required_space = std::mem::size_of::<RegisterCmd>();
+        let required_space = size_of::<RegisterCmd>();
         if !self.used_space.can_add(required_space) {
             return Err(Error::NotEnoughSpace);
         }
@@ -300,7 +327,7 @@ impl RegisterStorage {
 
     async fn apply(&self, cmd: RegisterCmd) -> Result<()> {
         // rough estimate ignoring the extra space used by sled
-        let required_space = std::mem::size_of::<RegisterCmd>();
+        let required_space = size_of::<RegisterCmd>();
 
         let address = cmd.dst_address();
         let key = address.id()?;
@@ -353,22 +380,16 @@ impl RegisterStorage {
                 let entry = self.try_load_cache_entry(&key).await?;
 
                 info!("Editing Register");
-                entry
-                    .state
-                    .read()
-                    .await
-                    .check_permissions(Action::Write, Some(User::Key(public_key)))?;
-                let result = entry
-                    .state
-                    .write()
-                    .await
-                    .apply_op(edit)
-                    .map_err(Error::NetworkData);
+                // held until the cmd is logged, so a snapshot can't be taken in between
+                let mut register = entry.state.write().await;
+                register.check_permissions(Action::Write, Some(User::Key(public_key)))?;
+                let result = register.apply_op(edit).map_err(Error::NetworkData);
 
                 if result.is_ok() {
                     entry.store.append(cmd)?;
                     self.used_space.increase(required_space);
                     trace!("Editing Register success!");
+                    self.snapshot_if_due(&entry, &register)?;
                 } else {
                     trace!("Editing Register failed!");
                 }
@@ -424,9 +445,9 @@ impl RegisterStorage {
                 let ExtendRegister { extend_with, .. } = op;
 
                 let entry = self.try_load_cache_entry(&key).await?;
+                let mut write = entry.state.write().await;
                 entry.store.append(cmd)?;
 
-                let mut write = entry.state.write().await;
                 let prev = write.cap();
                 write.increment_cap(extend_with);
 
@@ -437,6 +458,7 @@ impl RegisterStorage {
                 );
 
                 self.used_space.increase(required_space);
+                self.snapshot_if_due(&entry, &write)?;
                 Ok(())
             }
         }
@@ -467,6 +489,8 @@ impl RegisterStorage {
                 self.get_user_permissions(*address, *user, requester, operation_id)
                     .await
             }
+            GetHistory(address) => self.get_history(*address, requester, operation_id).await,
+            Subscribe(address) => self.subscribe(*address, requester, operation_id).await,
         }
     }
 
@@ -522,6 +546,35 @@ impl RegisterStorage {
         NodeQueryResponse::ReadRegister((result.map_err(convert_to_error_msg), operation_id))
     }
 
+    async fn get_history(
+        &self,
+        address: Address,
+        requester: User,
+        operation_id: OperationId,
+    ) -> NodeQueryResponse {
+        let result = match self.get_register(&address, Action::Read, requester).await {
+            Ok(register) => Ok(register.history()),
+            Err(error) => Err(convert_to_error_msg(error)),
+        };
+
+        NodeQueryResponse::GetRegisterHistory((result, operation_id))
+    }
+
+    // The Elders record the subscription only if the requester is allowed to read the Register
+    async fn subscribe(
+        &self,
+        address: Address,
+        requester: User,
+        operation_id: OperationId,
+    ) -> NodeQueryResponse {
+        let result = match self.get_register(&address, Action::Read, requester).await {
+            Ok(_) => Ok(address),
+            Err(error) => Err(convert_to_error_msg(error)),
+        };
+
+        NodeQueryResponse::SubscribeToRegister((result, operation_id))
+    }
+
     async fn get_owner(
         &self,
         address: Address,
@@ -606,7 +659,7 @@ impl RegisterStorage {
     // as with addition this estimate ignores the extra space used by sled
     // (that estimate can fall victim to a race condition if someone writes to a register that is being deleted)
     async fn drop_register_key(&self, key: XorName) -> Result<()> {
-        let regcmd_size = std::mem::size_of::<RegisterCmd>();
+        let regcmd_size = size_of::<RegisterCmd>();
         let reg_tree = self.reg_db.open_tree(key)?;
         let len = reg_tree.len();
         let key_used_space = len * regcmd_size;
@@ -620,6 +673,28 @@ impl RegisterStorage {
         Ok(())
     }
 
+    // folds the cmds logged for a register into a snapshot of it once there are enough of them,
+    // so loading it only replays the cmds logged since
+    // must be called holding the write lock on the register state
+    fn snapshot_if_due(&self, entry: &CacheEntry, register: &Register) -> Result<()> {
+        if entry.store.len()? < SNAPSHOT_INTERVAL {
+            return Ok(());
+        }
+        self.snapshot(entry, register)
+    }
+
+    // folds the cmds logged for a register, and any prior snapshot, into a snapshot of its state
+    // the cmds are kept for replication, so the used space isn't affected, as a rough estimate
+    // must be called holding the write lock on the register state
+    fn snapshot(&self, entry: &CacheEntry, register: &Register) -> Result<()> {
+        entry
+            .store
+            .snapshot(&(register.clone(), entry.section_auth.clone()))?;
+
+        debug!("Snapshotted register {:?}", register.address());
+        Ok(())
+    }
+
     // gets entry from the cache, or populates cache from disk if expired
     async fn try_load_cache_entry(&self, key: &XorName) -> Result<Arc<CacheEntry>> {
         let entry = self.cache.get(key).await;
@@ -631,8 +706,9 @@ impl RegisterStorage {
 
         // read from disk
         let store = self.get_or_create_store(key)?;
-        let mut hydrated_register = None;
-        // apply all ops
+        // start from the last snapshot, if any
+        let mut hydrated_register = store.get_snapshot::<StoredSnapshot>()?;
+        // apply all ops logged since
         use RegisterCmd::*;
         for stored_cmd in store.get_all()? {
             match stored_cmd {
@@ -708,17 +784,20 @@ impl Display for RegisterStorage {
 
 #[cfg(test)]
 mod test {
-    use super::RegisterStorage;
+    use super::{RegisterStorage, SNAPSHOT_INTERVAL};
 
     use crate::messaging::SectionAuth;
     use crate::node::{Error, Result};
-    use crate::types::register::{EntryHash, PrivatePolicy};
-    use crate::types::DataAddress;
+    use crate::types::register::{Action, EntryHash, PrivatePolicy, Register};
     use crate::types::{register::User, Keypair};
+    use crate::types::{DataAddress, RegisterAddress};
     use crate::UsedSpace;
     use crate::{
         messaging::{
-            data::{CreateRegister, RegisterCmd, RegisterQuery, SignedRegisterCreate},
+            data::{
+                CreateRegister, EditRegister, RegisterCmd, RegisterQuery, SignedRegisterCreate,
+                SignedRegisterEdit,
+            },
             system::NodeQueryResponse,
             ServiceAuth,
         },
@@ -770,6 +849,134 @@ mod test {
         Ok(())
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_register_snapshot() -> Result<()> {
+        let tmp_dir = tempdir()?;
+        let (cmd, keypair) = create_public_register_w_keypair()?;
+        let address = cmd.dst_address();
+
+        let register = {
+            let store = RegisterStorage::new(tmp_dir.path(), UsedSpace::new(usize::MAX))?;
+            store.write(cmd).await?;
+            // the create cmd and the first edits are folded into a snapshot, leaving 5 edits logged
+            edit_register(&store, address, &keypair, SNAPSHOT_INTERVAL + 4).await?;
+
+            let entry = store.try_load_cache_entry(&address.id()?).await?;
+            assert_eq!(entry.store.len()?, 5);
+            let register = entry.state.read().await.clone();
+            register
+        };
+        assert_eq!(register.size(), SNAPSHOT_INTERVAL as u64 + 4);
+
+        // the register is loaded from the snapshot and the edits logged since
+        let store = RegisterStorage::new(tmp_dir.path(), UsedSpace::new(usize::MAX))?;
+        let loaded = store
+            .get_register(&address, Action::Read, User::Key(keypair.public_key()))
+            .await?;
+        assert_eq!(loaded, register);
+
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_register_export_snapshot() -> Result<()> {
+        let store = new_store()?;
+        let (cmd, keypair) = create_public_register_w_keypair()?;
+        let address = cmd.dst_address();
+        store.write(cmd.clone()).await?;
+        edit_register(&store, address, &keypair, SNAPSHOT_INTERVAL + 4).await?;
+
+        // the cmds folded into the snapshot are shipped along with the ones logged after it
+        let export = store.get_data_of(Prefix::new(0, cmd.name())).await?;
+        match export.0.as_slice() {
+            [replica] => assert_eq!(replica.op_log.len(), SNAPSHOT_INTERVAL + 5),
+            other => panic!("Unexpected export: {:?}", other),
+        }
+
+        let new_store = new_store()?;
+        new_store.update(export).await?;
+
+        let user = User::Key(keypair.public_key());
+        let register = store.get_register(&address, Action::Read, user).await?;
+        let replica = new_store.get_register(&address, Action::Read, user).await?;
+        assert_eq!(replica, register);
+
+        // the replica can't be created again either
+        let res = new_store.write(cmd).await;
+        assert_eq!(
+            res.err().unwrap().to_string(),
+            Error::DataExists.to_string(),
+            "Should not be able to create twice!"
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_register_snapshot_replicated_to_held_register() -> Result<()> {
+        let store = new_store()?;
+        let (cmd, keypair) = create_public_register_w_keypair()?;
+        let address = cmd.dst_address();
+        let prefix = Prefix::new(0, cmd.name());
+        store.write(cmd).await?;
+        edit_register(&store, address, &keypair, 1).await?;
+
+        // the other store already holds the register when the snapshot is replicated to it
+        let new_store = new_store()?;
+        new_store.update(store.get_data_of(prefix).await?).await?;
+        edit_register(&store, address, &keypair, SNAPSHOT_INTERVAL + 4).await?;
+        new_store.update(store.get_data_of(prefix).await?).await?;
+
+        // the edits folded into the snapshot aren't lost, nor applied twice
+        let user = User::Key(keypair.public_key());
+        let register = store.get_register(&address, Action::Read, user).await?;
+        let replica = new_store.get_register(&address, Action::Read, user).await?;
+        assert_eq!(replica.size(), SNAPSHOT_INTERVAL as u64 + 5);
+        assert_eq!(replica, register);
+
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_register_unsigned_entry_is_rejected() -> Result<()> {
+        let store = new_store()?;
+        let (cmd, keypair) = create_public_register_w_keypair()?;
+        let address = cmd.dst_address();
+        store.write(cmd.clone()).await?;
+        edit_register(&store, address, &keypair, SNAPSHOT_INTERVAL).await?;
+        let user = User::Key(keypair.public_key());
+        let register = store.get_register(&address, Action::Read, user).await?;
+
+        // an extra entry in the replicated log, which no client signed
+        let mut export = store.get_data_of(Prefix::new(0, cmd.name())).await?;
+        let mut forged = register.clone();
+        let children = forged.read().into_iter().map(|(hash, _)| hash).collect();
+        let (_, edit) = forged.write(b"forged".to_vec(), children)?;
+        let op = EditRegister { address, edit };
+        let (_, other_keypair) = random_user();
+        let auth = ServiceAuth {
+            public_key: keypair.public_key(),
+            signature: other_keypair.sign(&bincode::serialize(&op)?),
+        };
+        export.0[0]
+            .op_log
+            .push(RegisterCmd::Edit(SignedRegisterEdit { op, auth }));
+
+        // the receiver only holds what the owner wrote, whether it held the register or not
+        let new_store = new_store()?;
+        new_store.update(export.clone()).await?;
+        let replica = new_store.get_register(&address, Action::Read, user).await?;
+        assert_eq!(replica, register);
+
+        store.update(export).await?;
+        assert_eq!(
+            store.get_register(&address, Action::Read, user).await?,
+            register
+        );
+
+        Ok(())
+    }
+
     async fn register_write<F>(create_register: F) -> Result<()>
     where
         F: Fn() -> Result<(RegisterCmd, User)>,
@@ -986,6 +1193,42 @@ mod test {
         Ok((create_reg_w_policy(policy, keypair)?, authority))
     }
 
+    fn create_public_register_w_keypair() -> Result<(RegisterCmd, Keypair)> {
+        let (authority, keypair) = random_user();
+        let policy = Policy::Public(PublicPolicy {
+            owner: authority,
+            permissions: Default::default(),
+        });
+        Ok((create_reg_w_policy(policy, keypair.clone())?, keypair))
+    }
+
+    // writes the given number of entries to the register, one after the other
+    async fn edit_register(
+        store: &RegisterStorage,
+        address: RegisterAddress,
+        keypair: &Keypair,
+        count: usize,
+    ) -> Result<()> {
+        let user = User::Key(keypair.public_key());
+        for i in 0..count {
+            let mut register: Register = store.get_register(&address, Action::Read, user).await?;
+            let children = register.read().into_iter().map(|(hash, _)| hash).collect();
+            let (_, edit) = register.write(i.to_le_bytes().to_vec(), children)?;
+
+            let op = EditRegister { address, edit };
+            let signature = keypair.sign(&bincode::serialize(&op)?);
+            let auth = ServiceAuth {
+                public_key: keypair.public_key(),
+                signature,
+            };
+            store
+                .write(RegisterCmd::Edit(SignedRegisterEdit { op, auth }))
+                .await?;
+        }
+
+        Ok(())
+    }
+
     fn create_reg_w_policy(policy: Policy, keypair: Keypair) -> Result<RegisterCmd> {
         let op = CreateRegister::Empty {
             name: xor_name::rand::random(),
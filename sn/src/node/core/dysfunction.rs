@@ -0,0 +1,70 @@
+// Copyright 2022 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use super::Node;
+use crate::node::Result;
+
+use sn_dysfunction::{DysfunctionDetection, DysfunctionReport, DysfunctionTrackers};
+use std::path::Path;
+use tokio::fs;
+
+// Where the issues tracked against other nodes are kept between restarts
+const DYSFUNCTION_TRACKERS_FILENAME: &str = "dysfunction_trackers";
+/// Where the latest dysfunction report is dumped for operators to inspect
+pub const DYSFUNCTION_REPORT_FILENAME: &str = "dysfunction_report.json";
+
+/// Restore the issues tracked against other nodes before the node was last stopped.
+pub(super) async fn load_dysfunction_trackers(
+    root_storage_dir: &Path,
+    dysfunction_tracking: &DysfunctionDetection,
+) -> Result<()> {
+    let path = root_storage_dir.join(DYSFUNCTION_TRACKERS_FILENAME);
+    if !path.exists() {
+        return Ok(());
+    }
+
+    let bytes = fs::read(&path).await?;
+    let trackers: DysfunctionTrackers = bincode::deserialize(&bytes)?;
+    dysfunction_tracking.import_trackers(trackers).await?;
+
+    info!("Restored dysfunction trackers from {}", path.display());
+    Ok(())
+}
+
+impl Node {
+    /// Report the dysfunction scores of the nodes we track
+    pub(crate) async fn dysfunction_report(&self) -> Result<DysfunctionReport> {
+        Ok(self.dysfunction_tracking.report().await?)
+    }
+
+    /// Persist the issues tracked against other nodes, so they survive a restart,
+    /// and dump the current dysfunction report alongside them.
+    pub(crate) async fn persist_dysfunction_state(&self) -> Result<()> {
+        let trackers = self.dysfunction_tracking.export_trackers().await;
+        write_atomically(
+            &self.root_storage_dir.join(DYSFUNCTION_TRACKERS_FILENAME),
+            &bincode::serialize(&trackers)?,
+        )
+        .await?;
+
+        let report = self.dysfunction_report().await?;
+        write_atomically(
+            &self.root_storage_dir.join(DYSFUNCTION_REPORT_FILENAME),
+            &serde_json::to_vec_pretty(&report)?,
+        )
+        .await
+    }
+}
+
+// Writes to a temporary file first, so a crash never leaves a truncated file behind
+async fn write_atomically(path: &Path, bytes: &[u8]) -> Result<()> {
+    let tmp_path = path.with_extension("tmp");
+    fs::write(&tmp_path, bytes).await?;
+    fs::rename(&tmp_path, path).await?;
+    Ok(())
+}
@@ -0,0 +1,125 @@
+// Copyright 2022 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use super::{messaging::REPLICATION_BATCH_SIZE, Node};
+use crate::{
+    messaging::{
+        system::{NodeCmd, NodeEvent, SystemMsg},
+        DstLocation, NodeMsgAuthority,
+    },
+    node::{api::cmds::Cmd, Error, Result},
+    types::ReplicatedDataAddress,
+};
+
+use std::collections::{BTreeMap, BTreeSet};
+use xor_name::XorName;
+
+impl Node {
+    /// Announce to our Elders that we're leaving the section
+    pub(crate) async fn announce_leaving(&self) -> Result<Cmd> {
+        // Elders would first have to hand their duties over, which we don't support yet
+        if self.is_elder().await {
+            return Err(Error::CannotLeaveAsElder);
+        }
+        self.send_msg_to_our_elders(SystemMsg::NodeEvent(NodeEvent::Leaving))
+            .await
+    }
+
+    /// The data we hold, grouped by the Adults who should hold it once we've left
+    pub(crate) async fn handover_targets(
+        &self,
+    ) -> Result<BTreeMap<XorName, Vec<ReplicatedDataAddress>>> {
+        let our_name = self.info.read().await.name();
+        let other_adults: BTreeSet<_> = self
+            .network_knowledge
+            .adults()
+            .await
+            .iter()
+            .map(|peer| peer.name())
+            .filter(|name| *name != our_name)
+            .collect();
+
+        let mut targets = BTreeMap::<XorName, Vec<ReplicatedDataAddress>>::new();
+        for address in self.data_storage.keys().await? {
+            for holder in self.compute_holders(&address, &other_adults) {
+                targets.entry(holder).or_default().push(address);
+            }
+        }
+
+        Ok(targets)
+    }
+
+    /// Cmds replicating the data at the given addresses to the given Adult, in batches
+    pub(crate) async fn hand_data_over(
+        &self,
+        target: XorName,
+        addresses: &[ReplicatedDataAddress],
+    ) -> Result<Vec<Cmd>> {
+        let section_pk = self.network_knowledge.section_key().await;
+        let mut cmds = vec![];
+        for batch in addresses.chunks(REPLICATION_BATCH_SIZE) {
+            let mut data_collection = vec![];
+            for address in batch {
+                match self.data_storage.get_from_local_store(address).await {
+                    Ok(data) => data_collection.push(data),
+                    Err(error) => warn!("Could not hand {address:?} over: {error}"),
+                }
+            }
+            if data_collection.is_empty() {
+                continue;
+            }
+
+            cmds.push(Cmd::SignOutgoingSystemMsg {
+                msg: SystemMsg::NodeCmd(NodeCmd::ReplicateData(data_collection)),
+                dst: DstLocation::Node {
+                    name: target,
+                    section_pk,
+                },
+            });
+        }
+
+        Ok(cmds)
+    }
+
+    /// Whether our section no longer counts us as a member
+    pub(crate) async fn has_left(&self) -> bool {
+        let our_name = self.info.read().await.name();
+        !self.network_knowledge.is_section_member(&our_name).await
+    }
+
+    /// On Elders, propose an Adult announcing it's leaving as `Left`
+    pub(crate) async fn handle_leaving_node(
+        &self,
+        name: XorName,
+        msg_authority: &NodeMsgAuthority,
+    ) -> Result<Vec<Cmd>> {
+        if !matches!(msg_authority, NodeMsgAuthority::Node(_)) {
+            warn!("Ignoring leave announcement not signed by the leaving node {name}");
+            return Ok(vec![]);
+        }
+        if self.is_not_elder().await {
+            error!("Received unexpected leave announcement while Adult");
+            return Ok(vec![]);
+        }
+        if self
+            .network_knowledge
+            .authority_provider()
+            .await
+            .contains_elder(&name)
+        {
+            warn!("Ignoring leave announcement from Elder {name}");
+            return Ok(vec![]);
+        }
+        if !self.network_knowledge.is_section_member(&name).await {
+            debug!("Ignoring leave announcement from {name}, not a member of our section");
+            return Ok(vec![]);
+        }
+
+        self.cast_offline_proposals(&BTreeSet::from([name])).await
+    }
+}
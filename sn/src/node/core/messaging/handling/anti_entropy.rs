@@ -15,7 +15,7 @@ use crate::node::{
     core::Node,
     messages::{NodeMsgAuthorityUtils, WireMsgUtils},
     network_knowledge::SectionAuthorityProvider,
-    Error, Result,
+    rng, Error, Result,
 };
 use crate::types::{log_markers::LogMarker, Peer, PublicKey};
 
@@ -102,6 +102,7 @@ impl Node {
                     LogMarker::AeResendAfterRetry,
                     msg_to_resend
                 );
+                self.count_anti_entropy(LogMarker::AeResendAfterRetry);
 
                 self.create_or_wait_for_backoff(&sender).await;
 
@@ -170,6 +171,7 @@ impl Node {
                 }
                 Some(elder) => {
                     trace!("{}", LogMarker::AeResendAfterAeRedirect);
+                    self.count_anti_entropy(LogMarker::AeResendAfterAeRedirect);
 
                     self.create_or_wait_for_backoff(&elder).await;
 
@@ -264,7 +266,7 @@ impl Node {
                 if next_wait > Duration::from_secs(1) {
                     backoff.reset();
                 }
-                Some(next_wait)
+                Some(rng::jitter(next_wait))
             } else {
                 // TODO: we've done all backoffs and are _still_ getting messages?
                 // we should probably penalise the node here.
@@ -277,7 +279,12 @@ impl Node {
                 tokio::time::sleep(sleep_time).await;
             }
         } else {
-            let _res = ae_backoff_guard.insert((*peer, ExponentialBackoff::default()));
+            let backoff = ExponentialBackoff {
+                // randomised with the rng of the node instead
+                randomization_factor: 0.0,
+                ..Default::default()
+            };
+            let _res = ae_backoff_guard.insert((*peer, backoff));
         }
     }
 
@@ -351,6 +358,7 @@ impl Node {
                         self.network_knowledge.section_key().await,
                     )?;
                     trace!("{}", LogMarker::AeSendRedirect);
+                    self.count_anti_entropy(LogMarker::AeSendRedirect);
 
                     return Ok(Some(Cmd::SendMsg {
                         recipients: vec![*sender],
@@ -404,6 +412,7 @@ impl Node {
                     &signed_sap.value.section_key()
                 );
                 trace!("{}", LogMarker::AeSendRetryAsOutdated);
+                self.count_anti_entropy(LogMarker::AeSendRetryAsOutdated);
 
                 SystemMsg::AntiEntropyRetry {
                     section_auth: signed_sap.value.to_msg(),
@@ -427,6 +436,7 @@ impl Node {
                     .await;
 
                 trace!("{}", LogMarker::AeSendRetryDstPkFail);
+                self.count_anti_entropy(LogMarker::AeSendRetryDstPkFail);
 
                 SystemMsg::AntiEntropyRetry {
                     section_auth: signed_sap.value.to_msg(),
@@ -477,6 +487,7 @@ impl Node {
         )?;
 
         trace!("{} in ae_redirect", LogMarker::AeSendRedirect);
+        self.count_anti_entropy(LogMarker::AeSendRedirect);
 
         Ok(Cmd::SendMsg {
             recipients: vec![sender],
@@ -44,7 +44,7 @@ use std::collections::BTreeSet;
 use tokio::time::Duration;
 use xor_name::XorName;
 
-const REPLICATION_BATCH_SIZE: usize = 50;
+pub(crate) const REPLICATION_BATCH_SIZE: usize = 50;
 const REPLICATION_MSG_THROTTLE_DURATION: Duration = Duration::from_secs(5);
 
 // Message handling
@@ -60,6 +60,7 @@ impl Node {
 
         // Apply backpressure if needed.
         if let Some(load_report) = self.comm.tolerated_msgs_per_s(&sender).await {
+            self.record_tolerated_msgs_per_s(load_report);
             let msg_src = wire_msg.msg_kind().src();
             if !msg_src.is_end_user() {
                 trace!("Sending BackPressure: {}", load_report);
@@ -100,6 +101,9 @@ impl Node {
                 dst_location,
                 msg,
             } => {
+                let kind: &'static str = (&msg).into();
+                self.count_msg_handled("system", kind);
+
                 // Let's now verify the section key in the msg authority is trusted
                 // based on our current knowledge of the network and sections chains.
                 let mut known_keys: Vec<BlsPublicKey> = self
@@ -203,6 +207,9 @@ impl Node {
                 dst_location,
                 auth,
             } => {
+                let kind: &'static str = (&msg).into();
+                self.count_msg_handled("service", kind);
+
                 let dst_name = match msg.dst_address() {
                     Some(name) => name,
                     None => {
@@ -728,6 +735,10 @@ impl Node {
 
                 return self.replicate_data_of_suspicious_nodes(suspects).await;
             }
+            SystemMsg::NodeEvent(NodeEvent::Leaving) => {
+                info!("{} from {}", LogMarker::LeaveRequestReceived, src_name);
+                return self.handle_leaving_node(src_name, &msg_authority).await;
+            }
             SystemMsg::NodeCmd(NodeCmd::ReplicateData(data_collection)) => {
                 info!("ReplicateData MsgId: {:?}", msg_id);
                 return if self.is_elder().await {
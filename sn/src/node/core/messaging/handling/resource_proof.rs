@@ -10,11 +10,12 @@ use crate::messaging::system::{JoinResponse, ResourceProofResponse, SystemMsg};
 use crate::node::{
     api::cmds::Cmd,
     core::{Node, RESOURCE_PROOF_DATA_SIZE, RESOURCE_PROOF_DIFFICULTY},
-    ed25519, Error, Result,
+    ed25519, rng, Error, Result,
 };
 use crate::types::{log_markers::LogMarker, Peer};
 
 use ed25519_dalek::Verifier;
+use rand::Rng;
 use xor_name::XorName;
 
 // Resource signed
@@ -47,7 +48,7 @@ impl Node {
     }
 
     pub(crate) async fn send_resource_proof_challenge(&self, peer: Peer) -> Result<Cmd> {
-        let nonce: [u8; 32] = rand::random();
+        let nonce: [u8; 32] = rng::thread_rng().gen();
         let serialized =
             bincode::serialize(&(peer.name(), &nonce)).map_err(|_| Error::InvalidMessage)?;
         let response = SystemMsg::JoinResponse(Box::new(JoinResponse::ResourceChallenge {
@@ -8,7 +8,9 @@
 
 use crate::data_copy_count;
 use crate::messaging::{
-    data::{CmdError, DataCmd, DataQuery, Error as ErrorMsg, ServiceMsg},
+    data::{
+        CmdError, DataCmd, DataQuery, Error as ErrorMsg, QueryResponse, RegisterCmd, ServiceMsg,
+    },
     system::{NodeQueryResponse, SystemMsg},
     AuthorityProof, DstLocation, EndUser, MsgId, ServiceAuth, WireMsg,
 };
@@ -137,6 +139,11 @@ impl Node {
             return Ok(cmds);
         }
 
+        if let QueryResponse::SubscribeToRegister((Ok(address), _)) = &query_response {
+            self.add_register_subscribers(*address, &waiting_peers)
+                .await;
+        }
+
         let msg = ServiceMsg::QueryResponse {
             response: query_response,
             correlation_id,
@@ -175,6 +182,10 @@ impl Node {
             // These reads/writes are for adult nodes...
             ServiceMsg::Cmd(DataCmd::Register(cmd)) => ReplicatedData::RegisterWrite(cmd),
             ServiceMsg::Cmd(DataCmd::StoreChunk(chunk)) => ReplicatedData::Chunk(chunk),
+            ServiceMsg::Query(query @ DataQuery::GetStoreCost { .. }) => {
+                // Storage costs are quoted by us Elders, no need to query our Adults
+                return self.quote_store_cost(&query, msg_id, origin).await;
+            }
             ServiceMsg::Query(query) => {
                 return self
                     .read_data_from_adults(query, msg_id, auth, origin)
@@ -185,6 +196,11 @@ impl Node {
                 return Ok(vec![]);
             }
         };
+        // subscribers of the Register are to be notified of the edit
+        let edit = match &data {
+            ReplicatedData::RegisterWrite(RegisterCmd::Edit(edit)) => Some(edit.clone()),
+            _ => None,
+        };
         // build the replication cmds
         let mut cmds = self.replicate_data(data).await?;
         // make sure the expected replication factor is achieved
@@ -198,6 +214,9 @@ impl Node {
             return self.send_cmd_error_response(error, origin, msg_id).await;
         }
         cmds.extend(self.send_cmd_ack(origin, msg_id).await?);
+        if let Some(edit) = edit {
+            cmds.extend(self.notify_register_subscribers(edit).await?);
+        }
         Ok(cmds)
     }
 
@@ -9,4 +9,4 @@
 mod handling;
 mod sending;
 
-pub(crate) use handling::handle_proposal;
+pub(crate) use handling::{handle_proposal, REPLICATION_BATCH_SIZE};
@@ -7,7 +7,7 @@
 // permissions and limitations relating to use of the SAFE Network Software.
 
 use crate::messaging::{
-    data::{CmdError, ServiceMsg},
+    data::{CmdError, QueryResponse, ServiceMsg},
     DstLocation, EndUser, MsgId, MsgKind, ServiceAuth, WireMsg,
 };
 use crate::node::{api::cmds::Cmd, core::Node, Result};
@@ -39,6 +39,20 @@ impl Node {
         self.send_cmd_response(target, the_ack_msg).await
     }
 
+    /// Forms a QueryResponse msg to send back to the client
+    pub(crate) async fn send_query_response(
+        &self,
+        response: QueryResponse,
+        target: Peer,
+        msg_id: MsgId,
+    ) -> Result<Vec<Cmd>> {
+        let the_response_msg = ServiceMsg::QueryResponse {
+            response,
+            correlation_id: msg_id,
+        };
+        self.send_cmd_response(target, the_response_msg).await
+    }
+
     /// Forms a cmd to send a cmd response error/ack to the client
     async fn send_cmd_response(&self, target: Peer, msg: ServiceMsg) -> Result<Vec<Cmd>> {
         let dst = DstLocation::EndUser(EndUser(target.name()));
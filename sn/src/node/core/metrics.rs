@@ -0,0 +1,272 @@
+// Copyright 2022 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use super::Node;
+use crate::types::log_markers::LogMarker;
+
+use std::{
+    collections::BTreeMap,
+    fmt::Write,
+    sync::{Arc, RwLock},
+};
+
+// Prefix of the names of all the metrics we export
+const METRIC_NAME_PREFIX: &str = "sn_node";
+
+const MSGS_HANDLED: &str = "msgs_handled_total";
+const ANTI_ENTROPY: &str = "anti_entropy_total";
+const TOLERATED_MSGS_PER_S: &str = "tolerated_msgs_per_s";
+const USED_SPACE_RATIO: &str = "used_space_ratio";
+const CHUNKS_STORED: &str = "chunks_stored";
+const REGISTERS_STORED: &str = "registers_stored";
+const DKG_SESSIONS: &str = "dkg_sessions";
+const DYSFUNCTION_SCORE: &str = "dysfunction_score";
+const DYSFUNCTION_MEAN_SCORE: &str = "dysfunction_mean_score";
+
+#[derive(Clone, Copy, Debug)]
+enum MetricType {
+    Counter,
+    Gauge,
+}
+
+// All the metrics we export, with their type and description
+const METRICS: &[(&str, MetricType, &str)] = &[
+    (
+        MSGS_HANDLED,
+        MetricType::Counter,
+        "Messages handled, per type and kind of message",
+    ),
+    (
+        ANTI_ENTROPY,
+        MetricType::Counter,
+        "Anti-Entropy retries and redirects sent, or acted upon",
+    ),
+    (
+        TOLERATED_MSGS_PER_S,
+        MetricType::Gauge,
+        "Messages per second last reported as tolerated to a peer through back pressure",
+    ),
+    (
+        USED_SPACE_RATIO,
+        MetricType::Gauge,
+        "Ratio of the storage capacity in use",
+    ),
+    (CHUNKS_STORED, MetricType::Gauge, "Chunks held"),
+    (REGISTERS_STORED, MetricType::Gauge, "Registers held"),
+    (DKG_SESSIONS, MetricType::Gauge, "DKG sessions known of"),
+    (
+        DYSFUNCTION_SCORE,
+        MetricType::Gauge,
+        "Weighted dysfunction score of the tracked nodes, per kind of issue",
+    ),
+    (
+        DYSFUNCTION_MEAN_SCORE,
+        MetricType::Gauge,
+        "Mean total dysfunction score of the tracked nodes",
+    ),
+];
+
+type Labels = Vec<(&'static str, String)>;
+
+/// Registry of the node's metrics, which can be rendered in the Prometheus text format.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Metrics {
+    values: Arc<RwLock<BTreeMap<&'static str, BTreeMap<Labels, f64>>>>,
+}
+
+impl Metrics {
+    /// Increment the counter with the given name and labels
+    pub(crate) fn increment(&self, name: &'static str, labels: &[(&'static str, &str)]) {
+        let mut values = self
+            .values
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        *values
+            .entry(name)
+            .or_default()
+            .entry(to_labels(labels))
+            .or_default() += 1.0;
+    }
+
+    /// Set the gauge with the given name and labels
+    pub(crate) fn set(&self, name: &'static str, labels: &[(&'static str, &str)], value: f64) {
+        let mut values = self
+            .values
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let _prev = values
+            .entry(name)
+            .or_default()
+            .insert(to_labels(labels), value);
+    }
+
+    /// Remove all the values of the metric with the given name
+    fn clear(&self, name: &'static str) {
+        let mut values = self
+            .values
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let _prev = values.remove(name);
+    }
+
+    /// Render all the metrics in the Prometheus text exposition format
+    pub(crate) fn render(&self) -> String {
+        let values = self
+            .values
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        let mut output = String::new();
+        for (name, metric_type, help) in METRICS {
+            let full_name = format!("{}_{}", METRIC_NAME_PREFIX, name);
+            let type_name = match metric_type {
+                MetricType::Counter => "counter",
+                MetricType::Gauge => "gauge",
+            };
+            // Writing to a String can't fail
+            let _ = writeln!(output, "# HELP {} {}", full_name, help);
+            let _ = writeln!(output, "# TYPE {} {}", full_name, type_name);
+
+            for (labels, value) in values.get(name).into_iter().flatten() {
+                let _ = writeln!(output, "{}{} {}", full_name, render_labels(labels), value);
+            }
+        }
+
+        output
+    }
+}
+
+fn to_labels(labels: &[(&'static str, &str)]) -> Labels {
+    labels
+        .iter()
+        .map(|(key, value)| (*key, value.to_string()))
+        .collect()
+}
+
+fn render_labels(labels: &Labels) -> String {
+    if labels.is_empty() {
+        return String::new();
+    }
+
+    let labels: Vec<_> = labels
+        .iter()
+        .map(|(key, value)| {
+            let value = value
+                .replace('\\', "\\\\")
+                .replace('"', "\\\"")
+                .replace('\n', "\\n");
+            format!("{}=\"{}\"", key, value)
+        })
+        .collect();
+    format!("{{{}}}", labels.join(","))
+}
+
+impl Node {
+    /// Start keeping the node's metrics, which are otherwise not recorded at all
+    pub(crate) fn enable_metrics(&mut self) {
+        self.metrics = Some(Metrics::default());
+    }
+
+    /// Count a message handled, of the given type and kind
+    pub(crate) fn count_msg_handled(&self, msg_type: &str, kind: &str) {
+        if let Some(metrics) = &self.metrics {
+            metrics.increment(MSGS_HANDLED, &[("type", msg_type), ("kind", kind)]);
+        }
+    }
+
+    /// Count an Anti-Entropy retry or redirect
+    pub(crate) fn count_anti_entropy(&self, marker: LogMarker) {
+        if let Some(metrics) = &self.metrics {
+            metrics.increment(ANTI_ENTROPY, &[("event", &marker.to_string())]);
+        }
+    }
+
+    /// Record the messages per second last reported as tolerated to a peer
+    pub(crate) fn record_tolerated_msgs_per_s(&self, load_report: f64) {
+        if let Some(metrics) = &self.metrics {
+            metrics.set(TOLERATED_MSGS_PER_S, &[], load_report);
+        }
+    }
+
+    /// Sample the gauges of the node's state, and render all the metrics.
+    /// Renders nothing if the metrics aren't enabled.
+    pub(crate) async fn render_metrics(&self) -> String {
+        let metrics = match &self.metrics {
+            Some(metrics) => metrics,
+            None => return String::new(),
+        };
+
+        metrics.set(USED_SPACE_RATIO, &[], self.used_space_ratio());
+        metrics.set(CHUNKS_STORED, &[], self.stored_chunk_count() as f64);
+        match self.stored_register_addresses().await {
+            Ok(addresses) => metrics.set(REGISTERS_STORED, &[], addresses.len() as f64),
+            Err(error) => warn!("Could not count registers stored for metrics: {error}"),
+        }
+
+        let dkg_sessions = self.dkg_sessions.read().await.len();
+        metrics.set(DKG_SESSIONS, &[], dkg_sessions as f64);
+
+        // Nodes may have stopped being tracked since the last time
+        metrics.clear(DYSFUNCTION_SCORE);
+        match self.dysfunction_report().await {
+            Ok(report) => {
+                metrics.set(DYSFUNCTION_MEAN_SCORE, &[], report.mean as f64);
+                for (name, node_report) in report.nodes {
+                    let name = format!("{:x}", name);
+                    for (score_type, score) in node_report.scores {
+                        metrics.set(
+                            DYSFUNCTION_SCORE,
+                            &[("node", &name), ("type", &format!("{:?}", score_type))],
+                            score as f64,
+                        );
+                    }
+                }
+            }
+            Err(error) => warn!("Could not get dysfunction scores for metrics: {error}"),
+        }
+
+        metrics.render()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Metrics, MSGS_HANDLED, TOLERATED_MSGS_PER_S};
+
+    #[test]
+    fn metrics_are_rendered_in_prometheus_format() {
+        let metrics = Metrics::default();
+        metrics.increment(MSGS_HANDLED, &[("type", "system"), ("kind", "NodeCmd")]);
+        metrics.increment(MSGS_HANDLED, &[("type", "system"), ("kind", "NodeCmd")]);
+        metrics.increment(MSGS_HANDLED, &[("type", "service"), ("kind", "Query")]);
+        metrics.set(TOLERATED_MSGS_PER_S, &[], 42.5);
+
+        let rendered = metrics.render();
+        let lines: Vec<_> = rendered.lines().collect();
+
+        assert!(lines.contains(&"# TYPE sn_node_msgs_handled_total counter"));
+        assert!(lines.contains(&"sn_node_msgs_handled_total{type=\"system\",kind=\"NodeCmd\"} 2"));
+        assert!(lines.contains(&"sn_node_msgs_handled_total{type=\"service\",kind=\"Query\"} 1"));
+        assert!(lines.contains(&"# TYPE sn_node_tolerated_msgs_per_s gauge"));
+        assert!(lines.contains(&"sn_node_tolerated_msgs_per_s 42.5"));
+    }
+
+    #[test]
+    fn label_values_are_escaped() {
+        let metrics = Metrics::default();
+        metrics.set(TOLERATED_MSGS_PER_S, &[("peer", "a \"quoted\"\nname")], 1.0);
+
+        assert!(
+            metrics
+                .render()
+                .lines()
+                .any(|line| line
+                    == "sn_node_tolerated_msgs_per_s{peer=\"a \\\"quoted\\\"\\nname\"} 1")
+        );
+    }
+}
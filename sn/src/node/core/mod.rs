@@ -6,31 +6,39 @@
 // KIND, either express or implied. Please review the Licences for the specific language governing
 // permissions and limitations relating to use of the SAFE Network Software.
 
+mod admin;
 mod api;
 mod bootstrap;
 mod comm;
 mod connectivity;
 mod data;
 mod delivery_group;
+mod dysfunction;
+mod leave;
 mod messaging;
+mod metrics;
 mod proposal;
 mod relocation;
 mod split_barrier;
 
 pub(crate) use bootstrap::{join_network, JoiningAsRelocated};
 pub(crate) use comm::{Comm, DeliveryStatus, MsgEvent};
+#[cfg(any(test, feature = "test-utils"))]
+pub use comm::{SimDelivery, SimNetwork};
 pub(crate) use data::MIN_LEVEL_WHEN_FULL;
+pub use dysfunction::DYSFUNCTION_REPORT_FILENAME;
 pub(crate) use proposal::Proposal;
 #[cfg(test)]
 pub(crate) use relocation::{check as relocation_check, ChurnId};
 
-use self::{data::DataStorage, split_barrier::SplitBarrier};
+use self::{data::DataStorage, metrics::Metrics, split_barrier::SplitBarrier};
 
 use super::{
+    admin::RecordedEvent,
     api::cmds::Cmd,
     dkg::DkgVoter,
     network_knowledge::{NetworkKnowledge, SectionKeyShare, SectionKeysProvider},
-    Elders, Event, NodeElderChange, NodeInfo,
+    rng, Elders, Event, NodeElderChange, NodeInfo,
 };
 
 use crate::messaging::{
@@ -42,6 +50,7 @@ use crate::messaging::{
 use crate::node::error::{Error, Result};
 use crate::types::{
     log_markers::LogMarker, utils::compare_and_write_prefix_map_to_disk, Cache, Peer,
+    RegisterAddress,
 };
 use crate::{elder_count, UsedSpace};
 
@@ -52,7 +61,7 @@ use itertools::Itertools;
 use resource_proof::ResourceProof;
 use sn_dysfunction::{DysfunctionDetection, DysfunctionSeverity};
 use std::{
-    collections::{BTreeMap, BTreeSet, HashMap},
+    collections::{BTreeMap, BTreeSet, HashMap, VecDeque},
     net::SocketAddr,
     path::PathBuf,
     sync::Arc,
@@ -78,6 +87,10 @@ pub(crate) const CONCURRENT_JOINS: usize = 7;
 // the section).
 const DATA_QUERY_TIMEOUT: Duration = Duration::from_secs(15);
 
+// How long Elders keep a client subscribed to the edits of a Register. Clients are expected to
+// renew their subscriptions before they expire (see `crate::client::Client::watch_register`).
+const REGISTER_SUBSCRIPTION_DURATION: Duration = Duration::from_secs(60 * 5 /* 5 mins */);
+
 /// How long to keep a cache of a given suspect node. Use to check if it's a newly suspicopus node
 /// and relevant flows should be triggered. (So a throttle on supect flows pehaps)
 const SUSPECT_NODE_RETENTION_DURATION: Duration = Duration::from_secs(60 * 25 /* 25 mins */);
@@ -86,6 +99,8 @@ const SUSPECT_NODE_RETENTION_DURATION: Duration = Duration::from_secs(60 * 25 /*
 pub(crate) const DATA_QUERY_LIMIT: usize = 100;
 // per query we can have this many peers, so the total peers waiting can be QUERY_LIMIT * MAX_WAITING_PEERS_PER_QUERY
 pub(crate) const MAX_WAITING_PEERS_PER_QUERY: usize = 100;
+// per Register we keep this many subscribed peers
+pub(crate) const MAX_SUBSCRIBERS_PER_REGISTER: usize = 100;
 
 // Store up to 100 in use backoffs
 pub(crate) type AeBackoffCache =
@@ -106,6 +121,7 @@ pub(crate) struct Node {
     pub(crate) comm: Comm,
 
     pub(super) data_storage: DataStorage, // Adult only before cache
+    root_storage_dir: PathBuf,
 
     resource_proof: ResourceProof,
     // Network resources
@@ -126,10 +142,16 @@ pub(crate) struct Node {
     capacity: Capacity,
     dysfunction_tracking: DysfunctionDetection,
     pending_data_queries: Arc<Cache<OperationId, Arc<DashSet<Peer>>>>,
+    /// Timed cache of the peers subscribed to the edits of a Register, each expiring on its own
+    register_subscriptions: Arc<Cache<(RegisterAddress, Peer), ()>>,
     /// Timed cache of suspect nodes and their score
     known_suspect_nodes: Arc<Cache<XorName, usize>>,
     // Caches
     ae_backoff_cache: AeBackoffCache,
+    /// Registry of the node's metrics, only kept if they are served
+    metrics: Option<Metrics>,
+    /// Latest events emitted, for the admin interface
+    recent_events: Arc<RwLock<VecDeque<RecordedEvent>>>,
 }
 
 impl Node {
@@ -161,6 +183,12 @@ impl Node {
                 .collect::<Vec<XorName>>(),
             elder_count(),
         );
+        if let Err(error) =
+            dysfunction::load_dysfunction_trackers(&root_storage_dir, &node_dysfunction_detector)
+                .await
+        {
+            warn!("Could not restore dysfunction trackers: {error}");
+        }
         info!(
             "DysfunctionDetection check: {:?}",
             node_dysfunction_detector
@@ -182,13 +210,21 @@ impl Node {
             current_joins_semaphore: Arc::new(Semaphore::new(CONCURRENT_JOINS)),
             resource_proof: ResourceProof::new(RESOURCE_PROOF_DATA_SIZE, RESOURCE_PROOF_DIFFICULTY),
             data_storage,
+            root_storage_dir,
             capacity: Capacity::default(),
             dysfunction_tracking: node_dysfunction_detector,
             pending_data_queries: Arc::new(Cache::with_expiry_duration(DATA_QUERY_TIMEOUT)),
+            register_subscriptions: Arc::new(Cache::with_expiry_duration(
+                REGISTER_SUBSCRIPTION_DURATION,
+            )),
             known_suspect_nodes: Arc::new(Cache::with_expiry_duration(
                 SUSPECT_NODE_RETENTION_DURATION,
             )),
             ae_backoff_cache: AeBackoffCache::default(),
+            metrics: None,
+            recent_events: Arc::new(RwLock::new(VecDeque::with_capacity(
+                admin::RECENT_EVENTS_LEN,
+            ))),
         })
     }
 
@@ -198,11 +234,11 @@ impl Node {
 
     pub(crate) async fn generate_probe_msg(&self) -> Result<Cmd> {
         // Generate a random address not belonging to our Prefix
-        let mut dst = xor_name::rand::random();
+        let mut dst = rng::random_name();
 
         // We don't probe ourselves
         while self.network_knowledge.prefix().await.matches(&dst) {
-            dst = xor_name::rand::random();
+            dst = rng::random_name();
         }
 
         let matching_section = self.network_knowledge.section_by_name(&dst)?;
@@ -16,7 +16,7 @@ use crate::node::{
     ed25519,
     messages::WireMsgUtils,
     network_knowledge::{ElderCandidates, SectionAuthorityProvider, SectionKeyShare},
-    NodeInfo, Result,
+    rng, NodeInfo, Result,
 };
 use crate::types::{log_markers::LogMarker, Peer, PublicKey};
 
@@ -130,7 +130,7 @@ impl Session {
         let mut cmds = vec![];
         match self
             .key_gen
-            .handle_message(&mut rand::thread_rng(), message.clone())
+            .handle_message(&mut rng::thread_rng(), message.clone())
         {
             Ok(responses) => {
                 // Only a valid DkgMessage, which results in some responses, shall reset the ticker.
@@ -254,7 +254,7 @@ impl Session {
 
         trace!("DKG progressing for {:?}", self.elder_candidates);
 
-        match self.key_gen.timed_phase_transition(&mut rand::thread_rng()) {
+        match self.key_gen.timed_phase_transition(&mut rng::thread_rng()) {
             Ok(messages) => {
                 let mut cmds = vec![];
                 cmds.extend(self.broadcast(node, session_id, messages, section_pk)?);
@@ -436,7 +436,7 @@ impl Session {
         let mut cmds = vec![];
         let (responses, unhandleable) = self
             .key_gen
-            .handle_pre_session_messages(&mut rand::thread_rng(), msg_history);
+            .handle_pre_session_messages(&mut rng::thread_rng(), msg_history);
         let add_reset_timer = !responses.is_empty();
 
         cmds.extend(self.broadcast(node, &session_id, responses, section_pk)?);
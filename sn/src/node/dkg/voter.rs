@@ -16,7 +16,7 @@ use crate::node::{
     ed25519,
     messages::WireMsgUtils,
     network_knowledge::{ElderCandidates, SectionAuthorityProvider, SectionKeyShare},
-    supermajority, NodeInfo, Result,
+    rng, supermajority, NodeInfo, Result,
 };
 use crate::types::Peer;
 
@@ -87,7 +87,7 @@ impl DkgVoter {
 
         // Special case: only one participant.
         if elder_candidates.len() == 1 {
-            let secret_key_set = bls::SecretKeySet::random(0, &mut rand::thread_rng());
+            let secret_key_set = bls::SecretKeySet::random(0, &mut rng::thread_rng());
             let section_auth = SectionAuthorityProvider::from_elder_candidates(
                 elder_candidates,
                 secret_key_set.public_keys(),
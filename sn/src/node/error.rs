@@ -115,6 +115,12 @@ pub enum Error {
     /// Bincode error.
     #[error("Bincode error:: {0}")]
     Bincode(#[from] bincode::Error),
+    /// Admin interface error.
+    #[error("Admin interface error: {0}")]
+    AdminInterface(String),
+    /// Elders can't leave their section gracefully.
+    #[error("Elders can't leave their section gracefully, only Adults can")]
+    CannotLeaveAsElder,
     /// Network service message error.
     #[error("Network service message error:: {0}")]
     ServiceMsg(#[from] crate::messaging::data::Error),
@@ -12,6 +12,8 @@
 mod cfg;
 // Node public API
 mod api;
+// Local-only admin interface
+mod admin;
 
 mod core;
 mod dkg;
@@ -20,6 +22,7 @@ mod error;
 mod logging;
 mod messages;
 mod network_knowledge;
+mod rng;
 
 use crate::types::{Peer, PublicKey};
 
@@ -30,20 +33,32 @@ use std::{
     sync::Arc,
 };
 
+#[cfg(unix)]
+pub use self::admin::admin_request;
 pub use self::{
+    admin::{
+        AdminRequest, AdminResponse, LeaveSummary, NodeStatus, RecordedEvent, SectionMember,
+        SectionPeers, StorageUsage, ADMIN_SOCKET_FILENAME,
+    },
     api::{
         event::{Elders, Event, MessageReceived, NodeElderChange},
         event_stream::EventStream,
-        NodeApi,
+        migrate_chunk_store, NodeApi,
     },
     cfg::config_handler::{add_connection_info, set_connection_info, Config},
+    core::DYSFUNCTION_REPORT_FILENAME,
     dkg::SectionAuthUtils,
     error::{Error, Result},
     network_knowledge::node_state::{FIRST_SECTION_MAX_AGE, FIRST_SECTION_MIN_AGE, MIN_ADULT_AGE},
 };
 pub use qp2p::{Config as NetworkConfig, SendStream};
+pub use sn_dysfunction::{
+    DysfunctionReport, DysfunctionSeverity, NodeDysfunctionReport, ScoreType,
+};
 pub use xor_name::{Prefix, XorName, XOR_NAME_LEN}; // TODO remove pub on API update
 
+#[cfg(any(test, feature = "test-utils"))]
+pub use self::core::{SimDelivery, SimNetwork};
 #[cfg(any(test, feature = "test-utils"))]
 pub use test_utils::*;
 
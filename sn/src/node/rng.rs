@@ -0,0 +1,83 @@
+// Copyright 2022 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+//! Source of the random decisions of nodes, e.g. the keys they generate, the Elders they pick
+//! or how long they back off for. It's the thread's rng, unless the current thread was seeded
+//! so a simulated network of nodes sharing it can be run reproducibly.
+
+#[cfg(any(test, feature = "test-utils"))]
+use rand::SeedableRng;
+use rand::{rngs::StdRng, CryptoRng, Rng, RngCore};
+use std::{cell::RefCell, time::Duration};
+use xor_name::XorName;
+
+// How much backoff waits are randomly varied by, either way.
+const BACKOFF_RANDOMIZATION_FACTOR: f64 = 0.5;
+
+thread_local! {
+    static SEEDED: RefCell<Option<StdRng>> = const { RefCell::new(None) };
+}
+
+/// Makes the random decisions of the nodes running on the current thread derive from the seed.
+#[cfg(any(test, feature = "test-utils"))]
+pub(crate) fn seed(seed: u64) {
+    SEEDED.with(|rng| *rng.borrow_mut() = Some(StdRng::seed_from_u64(seed)));
+}
+
+/// The rng of the nodes running on the current thread.
+pub(crate) fn thread_rng() -> NodeRng {
+    NodeRng
+}
+
+/// A random name, e.g. to pick a random destination within a section.
+pub(crate) fn random_name() -> XorName {
+    XorName(thread_rng().gen())
+}
+
+/// Randomly varies a backoff wait, as `ExponentialBackoff` does with its own rng when its
+/// `randomization_factor` isn't zero.
+pub(crate) fn jitter(wait: Duration) -> Duration {
+    wait.mul_f64(thread_rng().gen_range(
+        1.0 - BACKOFF_RANDOMIZATION_FACTOR,
+        1.0 + BACKOFF_RANDOMIZATION_FACTOR,
+    ))
+}
+
+/// Handle to the rng of the current thread, see [`thread_rng`].
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct NodeRng;
+
+impl NodeRng {
+    fn with<R>(self, f: impl FnOnce(&mut dyn RngCore) -> R) -> R {
+        SEEDED.with(|seeded| match seeded.borrow_mut().as_mut() {
+            Some(rng) => f(rng),
+            None => f(&mut rand::thread_rng()),
+        })
+    }
+}
+
+impl RngCore for NodeRng {
+    fn next_u32(&mut self) -> u32 {
+        self.with(|rng| rng.next_u32())
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.with(|rng| rng.next_u64())
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.with(|rng| rng.fill_bytes(dest))
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.with(|rng| rng.try_fill_bytes(dest))
+    }
+}
+
+// Both the seeded and the thread's rngs are cryptographically secure
+impl CryptoRng for NodeRng {}
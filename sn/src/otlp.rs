@@ -0,0 +1,72 @@
+// Copyright 2022 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+//! Export of spans over OTLP. Along with the trace context carried by msgs, it lets a client
+//! operation be followed through all the nodes handling it, in a single trace.
+
+use opentelemetry::{
+    sdk::{trace, Resource},
+    KeyValue,
+};
+use opentelemetry_otlp::WithExportConfig;
+use tracing_core::Subscriber;
+use tracing_opentelemetry::OpenTelemetryLayer;
+use tracing_subscriber::{
+    filter::Targets, layer::SubscriberExt, registry::LookupSpan, util::SubscriberInitExt, Layer,
+};
+
+pub use opentelemetry::trace::TraceError;
+
+/// Layer exporting spans, as those of the given service, to the OTLP collector set with the
+/// `OTEL_EXPORTER_OTLP_ENDPOINT` env var, `http://localhost:4317` by default.
+///
+/// Spans are exported in batches from a task of the current tokio runtime, `shutdown` must be
+/// called before exiting for the last ones not to be lost.
+pub fn layer<S>(service_name: &str) -> Result<OpenTelemetryLayer<S, trace::Tracer>, TraceError>
+where
+    S: Subscriber + for<'span> LookupSpan<'span>,
+{
+    let tracer = opentelemetry_otlp::new_pipeline()
+        .tracing()
+        .with_exporter(opentelemetry_otlp::new_exporter().tonic().with_env())
+        .with_trace_config(
+            trace::config().with_resource(Resource::new(vec![KeyValue::new(
+                "service.name",
+                service_name.to_string(),
+            )])),
+        )
+        .install_batch(opentelemetry::runtime::Tokio)?;
+
+    Ok(tracing_opentelemetry::layer().with_tracer(tracer))
+}
+
+/// Install a global subscriber exporting spans over OTLP as those of the given service, for
+/// clients which don't otherwise set up their logging. It also logs to stdout as per the
+/// `RUST_LOG` env var when it's set, e.g. `safe_network=debug`, which doesn't filter the spans.
+pub fn init(service_name: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let fmt_layer = match std::env::var("RUST_LOG") {
+        Ok(filter) => Some(
+            tracing_subscriber::fmt::layer()
+                .with_thread_names(true)
+                .with_ansi(false)
+                .with_filter(filter.parse::<Targets>()?),
+        ),
+        Err(_) => None,
+    };
+
+    tracing_subscriber::registry()
+        .with(fmt_layer)
+        .with(layer(service_name)?)
+        .try_init()?;
+    Ok(())
+}
+
+/// Export the spans which haven't been yet, and stop exporting.
+pub fn shutdown() {
+    opentelemetry::global::shutdown_tracer_provider()
+}
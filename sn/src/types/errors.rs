@@ -118,6 +118,9 @@ pub enum Error {
     /// The CRDT operation cannot be applied as it targets a different content address.
     #[error("The CRDT operation cannot be applied as it targets a different content address.")]
     CrdtWrongAddress(RegisterAddress),
+    /// Encrypted data couldn't be decrypted with the key
+    #[error("Failed to decrypt data: {0}")]
+    FailedToDecrypt(String),
 }
 
 pub(crate) fn convert_bincode_error(err: bincode::Error) -> Error {
@@ -33,18 +33,22 @@ pub enum LogMarker {
     // Data
     DataStoreReceivedAtElder,
     DataQueryReceviedAtElder,
+    StoreCostQuoted,
     // Chunks
     StoringChunk,
     StoredNewChunk,
     ChunkQueryResponseReceviedFromAdult,
     ChunkQueryReceviedAtElder,
     ChunkQueryReceviedAtAdult,
+    CorruptedChunkQuarantined,
     // Data reorganisation
     DataReorganisationUnderway,
     // Register
     RegisterWrite,
     RegisterQueryReceivedAtElder,
     RegisterQueryReceivedAtAdult,
+    RegisterSubscribed,
+    RegisterSubscribersNotified,
     // Routing cmds
     DispatchHandleMsgCmd,
     DispatchSendMsgCmd,
@@ -96,4 +100,8 @@ pub enum LogMarker {
     // Relocation
     RelocateStart,
     RelocateEnd,
+    // Leaving
+    LeaveStart,
+    LeaveRequestReceived,
+    LeaveEnd,
 }
@@ -14,7 +14,7 @@ pub use metadata::{Action, Entry};
 pub use policy::{
     Permissions, Policy, PrivatePermissions, PrivatePolicy, PublicPermissions, PublicPolicy, User,
 };
-pub use reg_crdt::EntryHash;
+pub use reg_crdt::{EntryHash, EntryNode};
 
 use super::{Error, Result};
 use crate::{types::RegisterAddress as Address, types::Scope};
@@ -173,6 +173,35 @@ impl Register {
         self.crdt.read()
     }
 
+    /// Return the entries the entry corresponding to the provided 'hash' was written on top of,
+    /// i.e. the previous entries it replaced.
+    pub fn children(&self, hash: EntryHash) -> Result<BTreeSet<(EntryHash, Entry)>> {
+        self.crdt.children(hash).ok_or(Error::NoSuchEntry)
+    }
+
+    /// Return the entries written on top of the entry corresponding to the provided 'hash',
+    /// i.e. the later entries which replaced it.
+    pub fn parents(&self, hash: EntryHash) -> Result<BTreeSet<(EntryHash, Entry)>> {
+        self.crdt.parents(hash).ok_or(Error::NoSuchEntry)
+    }
+
+    /// Return all the entries preceding the entry corresponding to the provided 'hash',
+    /// i.e. its children, their own children, and so on down to the first entries written.
+    ///
+    /// They are listed in the same order as [`Register::history`].
+    pub fn ancestors(&self, hash: EntryHash) -> Result<Vec<EntryNode>> {
+        self.crdt.ancestors(hash).ok_or(Error::NoSuchEntry)
+    }
+
+    /// Return all the entries of the register, walking its history from the last entries
+    /// down to the first ones written.
+    ///
+    /// Every entry is listed before the entries it was written on top of. Along with the
+    /// hashes of their children, the entries returned make up the whole Merkle DAG.
+    pub fn history(&self) -> Vec<EntryNode> {
+        self.crdt.history()
+    }
+
     /// Return user permissions, if applicable.
     pub fn permissions(&self, user: User) -> Result<Permissions> {
         self.policy.permissions(user).ok_or(Error::NoSuchEntry)
@@ -385,6 +414,71 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn register_history_traversal() -> eyre::Result<()> {
+        let (_, register) = &mut create_public_reg_replicas(1)[0];
+
+        // entry1 <- (entry2, entry3) <- entry4, with entry2 and entry3 written concurrently
+        let (entry1_hash, _) = register.write(random_register_entry(), BTreeSet::new())?;
+        let (entry2_hash, _) =
+            register.write(random_register_entry(), BTreeSet::from([entry1_hash]))?;
+        let (entry3_hash, _) =
+            register.write(random_register_entry(), BTreeSet::from([entry1_hash]))?;
+        let children = BTreeSet::from([entry2_hash, entry3_hash]);
+        let (entry4_hash, _) = register.write(random_register_entry(), children.clone())?;
+
+        let history = register.history();
+        let hashes: Vec<EntryHash> = history.iter().map(|node| node.hash).collect();
+        assert_eq!(hashes.len(), 4);
+        assert_eq!(hashes[0], entry4_hash);
+        assert_eq!(hashes[3], entry1_hash);
+        assert_eq!(
+            hashes[1..3].iter().copied().collect::<BTreeSet<_>>(),
+            children
+        );
+        assert_eq!(history[0].children, children);
+        assert_eq!(&history[0].entry, register.get(entry4_hash)?);
+        assert!(history[3].children.is_empty());
+
+        let hashes_of = |entries: BTreeSet<(EntryHash, Entry)>| -> BTreeSet<EntryHash> {
+            entries.into_iter().map(|(hash, _)| hash).collect()
+        };
+        assert_eq!(hashes_of(register.children(entry4_hash)?), children);
+        assert_eq!(hashes_of(register.parents(entry1_hash)?), children);
+        assert_eq!(
+            hashes_of(register.parents(entry2_hash)?),
+            BTreeSet::from([entry4_hash])
+        );
+        assert!(register.children(entry1_hash)?.is_empty());
+        assert!(register.parents(entry4_hash)?.is_empty());
+
+        let ancestors: Vec<EntryHash> = register
+            .ancestors(entry4_hash)?
+            .into_iter()
+            .map(|node| node.hash)
+            .collect();
+        assert_eq!(ancestors, hashes[1..]);
+        let ancestors: Vec<EntryHash> = register
+            .ancestors(entry2_hash)?
+            .into_iter()
+            .map(|node| node.hash)
+            .collect();
+        assert_eq!(ancestors, vec![entry1_hash]);
+
+        let non_existing_hash = EntryHash::default();
+        assert_eq!(
+            register.children(non_existing_hash),
+            Err(Error::NoSuchEntry)
+        );
+        assert_eq!(register.parents(non_existing_hash), Err(Error::NoSuchEntry));
+        assert_eq!(
+            register.ancestors(non_existing_hash),
+            Err(Error::NoSuchEntry)
+        );
+
+        Ok(())
+    }
+
     #[test]
     fn register_query_public_policy() -> eyre::Result<()> {
         let name = xor_name::rand::random();
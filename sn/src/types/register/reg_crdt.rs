@@ -44,6 +44,17 @@ impl Display for EntryHash {
     }
 }
 
+/// An entry of a Register, along with the hashes of the entries it was written on top of.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct EntryNode {
+    /// Hash of the entry.
+    pub hash: EntryHash,
+    /// The entry itself.
+    pub entry: Entry,
+    /// Hashes of the entries this one was written on top of.
+    pub children: BTreeSet<EntryHash>,
+}
+
 /// CRDT Data operation applicable to other Register replica.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct CrdtOperation<T> {
@@ -153,4 +164,94 @@ impl RegisterCrdt {
             .map(|(hash, node)| (EntryHash(hash), node.value.clone()))
             .collect()
     }
+
+    /// Get the entries the entry corresponding to `hash` was written on top of, if it exists.
+    pub(super) fn children(&self, hash: EntryHash) -> Option<BTreeSet<(EntryHash, Entry)>> {
+        let _ = self.data.node(hash.0)?;
+        Some(
+            self.data
+                .children(hash.0)
+                .hashes_and_nodes()
+                .map(|(hash, node)| (EntryHash(hash), node.value.clone()))
+                .collect(),
+        )
+    }
+
+    /// Get the entries written on top of the entry corresponding to `hash`, if it exists.
+    pub(super) fn parents(&self, hash: EntryHash) -> Option<BTreeSet<(EntryHash, Entry)>> {
+        let _ = self.data.node(hash.0)?;
+        Some(
+            self.data
+                .parents(hash.0)
+                .hashes_and_nodes()
+                .map(|(hash, node)| (EntryHash(hash), node.value.clone()))
+                .collect(),
+        )
+    }
+
+    /// Walk the Merkle DAG down from the current entries, see [`RegisterCrdt::walk`].
+    pub(super) fn history(&self) -> Vec<EntryNode> {
+        self.walk(self.data.read().hashes())
+    }
+
+    /// Get the entries reachable from the children of the entry corresponding to `hash`,
+    /// if it exists, listed in the same order as in the whole history.
+    pub(super) fn ancestors(&self, hash: EntryHash) -> Option<Vec<EntryNode>> {
+        let node = self.data.node(hash.0)?;
+        let reachable: BTreeSet<EntryHash> = self
+            .walk(node.children.clone())
+            .into_iter()
+            .map(|node| node.hash)
+            .collect();
+        Some(
+            self.history()
+                .into_iter()
+                .filter(|node| reachable.contains(&node.hash))
+                .collect(),
+        )
+    }
+
+    /// Return all the entries reachable from the `from` ones through their children,
+    /// each of them listed before the entries it was written on top of.
+    ///
+    /// Entries are visited depth-first and the ones visited last are listed first,
+    /// so the order is deterministic for a given DAG.
+    fn walk(&self, from: BTreeSet<crdts::merkle_reg::Hash>) -> Vec<EntryNode> {
+        let mut visited = BTreeSet::new();
+        let mut order = vec![];
+        for start in from {
+            // The DAG can be as deep as the Register cap, so it's traversed without recursion
+            let mut stack = vec![(start, false)];
+            while let Some((hash, expanded)) = stack.pop() {
+                if expanded {
+                    order.push(hash);
+                    continue;
+                }
+                if !visited.insert(hash) {
+                    continue;
+                }
+                stack.push((hash, true));
+                if let Some(node) = self.data.node(hash) {
+                    for child in &node.children {
+                        if !visited.contains(child) {
+                            stack.push((*child, false));
+                        }
+                    }
+                }
+            }
+        }
+
+        // A node is pushed once all its children were, so reversing lists it before them
+        order
+            .into_iter()
+            .rev()
+            .filter_map(|hash| {
+                self.data.node(hash).map(|node| EntryNode {
+                    hash: EntryHash(hash),
+                    entry: node.value.clone(),
+                    children: node.children.iter().copied().map(EntryHash).collect(),
+                })
+            })
+            .collect()
+    }
 }
@@ -8,7 +8,6 @@
 
 use super::{metadata::get_metadata, FilesMapChange, ProcessedFiles};
 use crate::{Error, Result, Safe, XorUrl};
-use bytes::Bytes;
 use log::info;
 use safe_network::client::Error as ClientError;
 use std::{
@@ -19,24 +18,21 @@ use walkdir::{DirEntry, WalkDir};
 
 const MAX_RECURSIVE_DEPTH: usize = 10_000;
 
-// Upload a file to the Network
+// Upload a file to the Network, as private if the Safe instance is set to store private files
 pub(crate) async fn upload_file_to_net(safe: &Safe, path: &Path) -> Result<XorUrl> {
-    let data = fs::read(path).map_err(|err| {
+    // The content is streamed from the file, so just make sure it can be read before uploading
+    let _ = fs::File::open(path).map_err(|err| {
         Error::InvalidInput(format!("Failed to read file from local location: {}", err))
     })?;
-    let data = Bytes::from(data);
 
+    let scope = safe.files_scope();
     let mut mime_type_for_xorurl = mime_guess::from_path(&path).first_raw();
-    let result = match safe
-        .store_public_bytes(data.to_owned(), mime_type_for_xorurl)
-        .await
-    {
+    let result = match safe.upload_file(path, mime_type_for_xorurl, scope).await {
         Ok(xorurl) => Ok(xorurl),
         Err(Error::InvalidMediaType(_)) => {
             // Let's then upload it and set media-type to be simply raw content
             mime_type_for_xorurl = None;
-            safe.store_public_bytes(data.clone(), mime_type_for_xorurl)
-                .await
+            safe.upload_file(path, mime_type_for_xorurl, scope).await
         }
         other_err => other_err,
     };
@@ -48,7 +44,7 @@ pub(crate) async fn upload_file_to_net(safe: &Safe, path: &Path) -> Result<XorUr
         // Use a dry runner only for this next operation
         let dry_runner = Safe::dry_runner(Some(safe.xorurl_base));
         let xorurl = dry_runner
-            .store_public_bytes(data, mime_type_for_xorurl)
+            .upload_file(path, mime_type_for_xorurl, scope)
             .await?;
 
         Err(Error::ContentUploadVerificationFailed(xorurl))
@@ -9,6 +9,8 @@
 mod file_system;
 mod files_map;
 mod metadata;
+#[cfg(feature = "fuse")]
+mod mount;
 mod realpath;
 
 use crate::{
@@ -22,19 +24,25 @@ use file_system::{
 use files_map::add_or_update_file_item;
 use log::{debug, info, warn};
 use relative_path::RelativePath;
-use safe_network::{client::Client, types::BytesAddress};
+use safe_network::{
+    client::{Client, UploadJournal},
+    types::BytesAddress,
+};
 use std::{
     collections::{BTreeMap, HashSet},
     iter::FromIterator,
     path::{Path, PathBuf},
     str,
 };
+use tokio::io::AsyncWrite;
 
 pub(crate) use files_map::{file_map_for_path, get_file_link_and_metadata};
 pub(crate) use metadata::FileMeta;
 pub(crate) use realpath::RealPath;
 
 pub use files_map::{FileInfo, FilesMap, FilesMapChange, GetAttr};
+#[cfg(feature = "fuse")]
+pub use mount::FilesMount;
 
 // List of files uploaded with details if they were added, updated or removed from FilesContainer
 pub type ProcessedFiles = BTreeMap<PathBuf, FilesMapChange>;
@@ -128,6 +136,7 @@ impl Safe {
                 .await?;
 
             client.publish_register_ops(reg_op).await?;
+            self.discard_upload_journals(&processed_files).await?;
 
             // We return versioned xorurl
             reg_url.set_content_version(Some(VersionHash::from(&entry_hash)));
@@ -159,6 +168,36 @@ impl Safe {
         self.fetch_files_container(&safe_url).await
     }
 
+    /// # List the versions of an existing FilesContainer.
+    ///
+    /// Returns all the versions the FilesContainer ever had, from the latest one (or ones, if it
+    /// was updated concurrently) down to the first one. Any of them can be fetched by setting it
+    /// as the version of the FilesContainer URL, e.g. `safe://<xorurl>?v=<version>`.
+    ///
+    /// ## Example
+    ///
+    /// ```no_run
+    /// # use sn_api::Safe;
+    /// # let mut safe = Safe::default();
+    /// # let rt = tokio::runtime::Runtime::new().unwrap();
+    /// # rt.block_on(async {
+    /// #   safe.connect(None, None, None).await.unwrap();
+    ///     let (xorurl, _processed_files, _files_map) = safe.files_container_create_from("./testdata", None, true, true).await.unwrap();
+    ///     let versions = safe.files_container_versions(&xorurl).await.unwrap();
+    ///     println!("FilesContainer versions: {:?}", versions);
+    /// # });
+    /// ```
+    pub async fn files_container_versions(&self, url: &str) -> Result<Vec<VersionHash>> {
+        debug!("Getting versions of files container from: {:?}", url);
+        let safe_url = self.parse_and_resolve_url(url).await?;
+
+        let history = self.register_fetch_history(&safe_url).await?;
+        Ok(history
+            .iter()
+            .map(|node| VersionHash::from(&node.hash))
+            .collect())
+    }
+
     /// Fetch a FilesContainer from a SafeUrl without performing any type of URL resolution
     pub(crate) async fn fetch_files_container(
         &self,
@@ -208,7 +247,7 @@ impl Safe {
 
         // Using the FilesMap XOR-URL we can now fetch the FilesMap and deserialise it
         let files_map_url = SafeUrl::from_xorurl(files_map_xorurl)?;
-        let serialised_files_map = self.fetch_public_data(&files_map_url, None).await?;
+        let serialised_files_map = self.fetch_data(&files_map_url, None).await?;
         let files_map = serde_json::from_slice(serialised_files_map.chunk()).map_err(|err| {
             Error::ContentError(format!(
                 "Couldn't deserialise the FilesMap stored in the FilesContainer: {:?}",
@@ -406,7 +445,7 @@ impl Safe {
         let (safe_url, current_version, current_files_map) =
             validate_files_add_params(self, "", url, update_nrs).await?;
 
-        let new_file_xorurl = self.store_public_bytes(data, None).await?;
+        let new_file_xorurl = self.store_bytes(data, None, self.files_scope()).await?;
 
         let dst_path = Path::new(safe_url.path());
         let (processed_files, new_files_map, success_count) =
@@ -540,6 +579,7 @@ impl Safe {
                     update_nrs,
                 )
                 .await?;
+            self.discard_upload_journals(&processed_files).await?;
 
             Ok((Some((new_version, new_files_map)), processed_files))
         }
@@ -608,30 +648,146 @@ impl Safe {
         bytes: Bytes,
         media_type: Option<&str>,
     ) -> Result<XorUrl> {
-        let content_type = media_type.map_or_else(
-            || Ok(ContentType::Raw),
-            |media_type_str| {
-                if SafeUrl::is_media_type_supported(media_type_str) {
-                    Ok(ContentType::MediaType(media_type_str.to_string()))
-                } else {
-                    Err(Error::InvalidMediaType(format!(
-                        "Media-type '{}' not supported. You can pass 'None' as the 'media_type' for this content to be treated as raw",
-                        media_type_str
-                    )))
-                }
-            },
-        )?;
+        self.store_bytes(bytes, media_type, Scope::Public).await
+    }
+
+    /// # Store a private file
+    ///
+    /// Same as [`Safe::store_public_bytes`], but the data is encrypted with the
+    /// owner's key, so only the owner can read it back.
+    ///
+    /// ## Example
+    /// ```no_run
+    /// # use sn_api::Safe;
+    /// # let mut safe = Safe::default();
+    /// # let rt = tokio::runtime::Runtime::new().unwrap();
+    /// # rt.block_on(async {
+    /// #   safe.connect(None, None, None).await.unwrap();
+    ///     let data = bytes::Bytes::from_static(b"Something super secret");
+    ///     let xorurl = safe.store_private_bytes(data.clone(), Some("text/plain")).await.unwrap();
+    ///     let received_data = safe.files_get_public(&xorurl, None).await.unwrap();
+    ///     assert_eq!(received_data, data);
+    /// # });
+    /// ```
+    pub async fn store_private_bytes(
+        &self,
+        bytes: Bytes,
+        media_type: Option<&str>,
+    ) -> Result<XorUrl> {
+        self.store_bytes(bytes, media_type, Scope::Private).await
+    }
+
+    /// # Store a public file from a local path
+    ///
+    /// Same as [`Safe::store_public_bytes`], but the content is read from a local file one chunk
+    /// at a time, so files larger than the available memory can be stored.
+    ///
+    /// With `resume_uploads` set, the Chunks stored are recorded in an upload journal, so if this
+    /// fails it can be called again the same way to only send the Chunks which were not stored yet.
+    ///
+    /// ## Example
+    /// ```no_run
+    /// # use sn_api::Safe;
+    /// # use std::path::Path;
+    /// # let mut safe = Safe::default();
+    /// # let rt = tokio::runtime::Runtime::new().unwrap();
+    /// # rt.block_on(async {
+    /// #   safe.connect(None, None, None).await.unwrap();
+    ///     let path = Path::new("./testdata/test.md");
+    ///     let xorurl = safe.store_public_file(path, Some("text/markdown")).await.unwrap();
+    ///     let received_data = safe.files_get_public(&xorurl, None).await.unwrap();
+    ///     assert_eq!(received_data, std::fs::read(path).unwrap());
+    /// # });
+    /// ```
+    pub async fn store_public_file(&self, path: &Path, media_type: Option<&str>) -> Result<XorUrl> {
+        self.store_file(path, media_type, Scope::Public).await
+    }
+
+    /// # Store a private file from a local path
+    ///
+    /// Same as [`Safe::store_public_file`], but the data is encrypted with the
+    /// owner's key, so only the owner can read it back.
+    pub async fn store_private_file(
+        &self,
+        path: &Path,
+        media_type: Option<&str>,
+    ) -> Result<XorUrl> {
+        self.store_file(path, media_type, Scope::Private).await
+    }
+
+    // Stores a file, discarding its upload journal once done
+    async fn store_file(
+        &self,
+        path: &Path,
+        media_type: Option<&str>,
+        scope: Scope,
+    ) -> Result<XorUrl> {
+        let xorurl = self.upload_file(path, media_type, scope).await?;
+        if self.resume_uploads && !self.dry_run_mode {
+            UploadJournal::discard(&UploadJournal::default_dir(), path, scope).await?;
+        }
+
+        Ok(xorurl)
+    }
+
+    // Stores a file, keeping its upload journal if uploads are resumable, so the operation it's
+    // part of can be resumed without sending its Chunks again until the journal is discarded.
+    pub(crate) async fn upload_file(
+        &self,
+        path: &Path,
+        media_type: Option<&str>,
+        scope: Scope,
+    ) -> Result<XorUrl> {
+        let content_type = content_type_for(media_type)?;
+
+        let address = if self.dry_run_mode {
+            debug!("Calculating network address for file {}", path.display());
+            Client::calculate_address_from_path(path, scope, &self.owner_keypair()).await?
+        } else {
+            debug!("Storing file {}", path.display());
+            let client = self.get_safe_client()?;
+            let address = if self.resume_uploads {
+                let mut journal =
+                    UploadJournal::open(&UploadJournal::default_dir(), path, scope).await?;
+                client
+                    .resume_upload_with_options(&mut journal, &self.upload_options)
+                    .await?
+            } else {
+                client
+                    .upload_from_path_with_options(path, scope, &self.upload_options)
+                    .await?
+            };
+
+            // Verify all the data can be read back, without holding it in memory
+            let _ = client
+                .read_to_writer(address, &mut tokio::io::sink())
+                .await?;
+            address
+        };
+        let xorurl = SafeUrl::encode_bytes(address, content_type, self.xorurl_base)?;
+
+        Ok(xorurl)
+    }
+
+    // Stores the bytes with the given scope, verifying they can be read back
+    async fn store_bytes(
+        &self,
+        bytes: Bytes,
+        media_type: Option<&str>,
+        scope: Scope,
+    ) -> Result<XorUrl> {
+        let content_type = content_type_for(media_type)?;
 
         let address = if self.dry_run_mode {
             debug!(
                 "Calculating network address for {} bytes of data",
                 bytes.len()
             );
-            Client::calculate_address(bytes, Scope::Public)?
+            Client::calculate_address(bytes, scope, &self.owner_keypair())?
         } else {
             debug!("Storing {} bytes of data", bytes.len());
             let client = self.get_safe_client()?;
-            let (address, _) = client.upload_and_verify(bytes, Scope::Public).await?;
+            let (address, _) = client.upload_and_verify(bytes, scope).await?;
             address
         };
         let xorurl = SafeUrl::encode_bytes(address, content_type, self.xorurl_base)?;
@@ -639,6 +795,43 @@ impl Safe {
         Ok(xorurl)
     }
 
+    // Scope the files uploaded into FilesContainers are stored with
+    pub(crate) fn files_scope(&self) -> Scope {
+        if self.private_files {
+            Scope::Private
+        } else {
+            Scope::Public
+        }
+    }
+
+    /// # Get the sizes of the Chunks a local file would be stored as
+    ///
+    /// The file is encrypted one chunk at a time, as it would be when uploaded into a
+    /// FilesContainer, without storing anything onto the network. Along with
+    /// [`Safe::get_store_cost`], it allows to get a quote of the cost of storing it.
+    pub async fn files_chunk_sizes(&self, path: &Path) -> Result<Vec<usize>> {
+        let (_, chunk_sizes) =
+            Client::chunk_sizes_from_path(path, self.files_scope(), &self.owner_keypair()).await?;
+        Ok(chunk_sizes)
+    }
+
+    // Removes the upload journals of the local files processed, once
+    // the FilesContainer they were uploaded for was updated.
+    async fn discard_upload_journals(&self, processed_files: &ProcessedFiles) -> Result<()> {
+        if !self.resume_uploads {
+            return Ok(());
+        }
+
+        let journals_dir = UploadJournal::default_dir();
+        for (path, change) in processed_files.iter() {
+            if (change.is_added() || change.is_updated()) && path.is_file() {
+                UploadJournal::discard(&journals_dir, path, self.files_scope()).await?;
+            }
+        }
+
+        Ok(())
+    }
+
     /// # Get a file
     /// Get file from the network.
     ///
@@ -658,19 +851,66 @@ impl Safe {
     pub async fn files_get_public(&self, url: &str, range: Range) -> Result<Bytes> {
         // TODO: do we want ownership from other PKs yet?
         let safe_url = self.parse_and_resolve_url(url).await?;
-        self.fetch_public_data(&safe_url, range).await
+        self.fetch_data(&safe_url, range).await
     }
 
-    /// Fetch an file from a SafeUrl without performing any type of URL resolution
-    pub(crate) async fn fetch_public_data(
+    /// # Get a file into a writer
+    /// Get file from the network, writing its content into `writer` as it's fetched, so files
+    /// larger than the available memory can be retrieved. Returns the number of bytes written.
+    ///
+    /// Both public and private files can be retrieved, the latter being decrypted with the owner's key.
+    ///
+    /// ## Example
+    /// ```no_run
+    /// # use sn_api::Safe;
+    /// # let mut safe = Safe::default();
+    /// # let rt = tokio::runtime::Runtime::new().unwrap();
+    /// # rt.block_on(async {
+    /// #   safe.connect(None, None, None).await.unwrap();
+    ///     let data = b"Something super good";
+    ///     let xorurl = safe.store_public_bytes(bytes::Bytes::from_static(data), None).await.unwrap();
+    ///     let mut received_data = vec![];
+    ///     let _ = safe.files_get_to_writer(&xorurl, &mut received_data).await.unwrap();
+    ///     assert_eq!(received_data, data);
+    /// # });
+    /// ```
+    pub async fn files_get_to_writer(
         &self,
-        safe_url: &SafeUrl,
-        range: Range,
-    ) -> Result<Bytes> {
+        url: &str,
+        writer: &mut (impl AsyncWrite + Unpin),
+    ) -> Result<u64> {
+        let safe_url = self.parse_and_resolve_url(url).await?;
         match safe_url.data_type() {
             DataType::File => {
-                self.get_bytes(BytesAddress::Public(safe_url.xorname()), range)
+                let address = BytesAddress::new(safe_url.xorname(), safe_url.scope());
+                debug!("Attempting to fetch data from {:?}", address.name());
+                let client = self.get_safe_client()?;
+                let written = client
+                    .read_to_writer(address, writer)
                     .await
+                    .map_err(|e| Error::NetDataError(format!("Failed to GET file: {:?}", e)))?;
+
+                debug!(
+                    "{} bytes of data successfully retrieved from: {:?}",
+                    written,
+                    address.name()
+                );
+                Ok(written)
+            }
+            other => Err(Error::ContentError(format!("{}", other))),
+        }
+    }
+
+    /// Fetch a file from a SafeUrl without performing any type of URL resolution.
+    /// Private files are decrypted with the owner's key.
+    pub(crate) async fn fetch_data(&self, safe_url: &SafeUrl, range: Range) -> Result<Bytes> {
+        match safe_url.data_type() {
+            DataType::File => {
+                self.get_bytes(
+                    BytesAddress::new(safe_url.xorname(), safe_url.scope()),
+                    range,
+                )
+                .await
             }
             other => {
                 return Err(Error::ContentError(format!("{}", other)));
@@ -1218,13 +1458,31 @@ async fn files_map_create(
     Ok(files_map)
 }
 
+// Parses an optional media type into the content type to encode in the XOR-URL
+fn content_type_for(media_type: Option<&str>) -> Result<ContentType> {
+    media_type.map_or_else(
+        || Ok(ContentType::Raw),
+        |media_type_str| {
+            if SafeUrl::is_media_type_supported(media_type_str) {
+                Ok(ContentType::MediaType(media_type_str.to_string()))
+            } else {
+                Err(Error::InvalidMediaType(format!(
+                    "Media-type '{}' not supported. You can pass 'None' as the 'media_type' for this content to be treated as raw",
+                    media_type_str
+                )))
+            }
+        },
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::{
         app::test_helpers::{new_safe_instance, random_nrs_name},
         register::EntryHash,
-        retry_loop, retry_loop_for_pattern,
+        resolver::SafeData,
+        retry_loop, retry_loop_for_pattern, UploadEvent,
     };
     use anyhow::{anyhow, bail, Result};
     use assert_matches::assert_matches;
@@ -1340,6 +1598,114 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_files_store_public_file() -> Result<()> {
+        let safe = new_safe_instance().await?;
+        let filename = Path::new("./testdata/test.md");
+        let content = std::fs::read(filename)?;
+
+        let file_xorurl = safe
+            .store_public_file(filename, Some("text/markdown"))
+            .await?;
+        let expected_xorurl = safe
+            .store_public_bytes(Bytes::from(content.clone()), Some("text/markdown"))
+            .await?;
+        assert_eq!(file_xorurl, expected_xorurl);
+
+        let mut retrieved = vec![];
+        let written = retry_loop!(safe.files_get_to_writer(&file_xorurl, &mut retrieved));
+        assert_eq!(written, content.len() as u64);
+        assert_eq!(retrieved, content);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_files_container_versions() -> Result<()> {
+        let safe = new_safe_instance().await?;
+        let (xorurl, _, _) = safe
+            .files_container_create_from("./testdata/test.md", None, false, false)
+            .await?;
+        let (first_version, _) =
+            retry_loop_for_pattern!(safe.files_container_get(&xorurl), Ok(Some(_)))?
+                .ok_or_else(|| anyhow!("files container was unexpectedly empty"))?;
+
+        let (version, _) = safe
+            .files_container_add("./testdata/another.md", &xorurl, false, false, false)
+            .await?
+            .0
+            .ok_or_else(|| anyhow!("files container was unexpectedly empty"))?;
+
+        let versions =
+            retry_loop_for_pattern!(safe.files_container_versions(&xorurl), Ok(v) if v.len() == 2)?;
+        assert_eq!(versions, vec![version, first_version]);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_files_store_private() -> Result<()> {
+        let safe = new_safe_instance().await?;
+        let random_content: String = thread_rng().sample_iter(&Alphanumeric).take(20).collect();
+
+        let file_xorurl = safe
+            .store_private_bytes(Bytes::from(random_content.to_owned()), None)
+            .await?;
+        assert_eq!(SafeUrl::from_url(&file_xorurl)?.scope(), Scope::Private);
+
+        let retrieved = retry_loop!(safe.fetch(&file_xorurl, None));
+        match retrieved {
+            SafeData::PrivateFile { data, .. } => assert_eq!(data, random_content.as_bytes()),
+            other => bail!("Content fetched is not a PrivateFile: {:?}", other),
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_files_container_create_from_with_progress() -> Result<()> {
+        let mut safe = new_safe_instance().await?;
+        let (progress, mut events) = tokio::sync::mpsc::unbounded_channel();
+        safe.upload_options.concurrency = 2;
+        safe.upload_options.progress = Some(progress);
+
+        let filename = Path::new("./testdata/test.md");
+        let _ = safe
+            .files_container_create_from(&filename.display().to_string(), None, false, false)
+            .await?;
+        drop(safe);
+
+        let mut bytes_done = 0;
+        while let Some(event) = events.recv().await {
+            if let UploadEvent::BytesDone(bytes) = event {
+                bytes_done += bytes;
+            }
+        }
+        assert_eq!(bytes_done, std::fs::metadata(filename)?.len());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_files_container_create_from_private_file() -> Result<()> {
+        let mut safe = new_safe_instance().await?;
+        safe.private_files = true;
+        let filename = Path::new("./testdata/test.md");
+        let (_, processed_files, _) = safe
+            .files_container_create_from(&filename.display().to_string(), None, false, false)
+            .await?;
+
+        let file_xorurl = processed_files[filename]
+            .link()
+            .ok_or_else(|| anyhow!("Missing xorurl link of uploaded test file"))?;
+        assert_eq!(SafeUrl::from_url(file_xorurl)?.scope(), Scope::Private);
+
+        let retrieved = retry_loop!(safe.files_get_public(file_xorurl, None));
+        assert_eq!(retrieved, std::fs::read(filename)?);
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_files_container_create_from_file() -> Result<()> {
         let safe = new_safe_instance().await?;
@@ -0,0 +1,877 @@
+// Copyright 2022 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use super::{file_map_for_path, FileInfo, FilesMap};
+use crate::{
+    app::consts::*, resolver::Range, resolver::SafeData, ContentType, Error, Result, Safe, SafeUrl,
+};
+use bytes::Bytes;
+use fuser::{
+    BackgroundSession, FileAttr, FileType, Filesystem, KernelConfig, MountOption, ReplyAttr,
+    ReplyCreate, ReplyData, ReplyDirectory, ReplyEmpty, ReplyEntry, ReplyOpen, ReplyWrite, Request,
+    TimeOrNow, FUSE_ROOT_ID,
+};
+use libc::{
+    c_int, EACCES, EBADF, EIO, EISDIR, ENOENT, ENOTDIR, EROFS, O_ACCMODE, O_RDONLY, O_TRUNC,
+};
+use log::{debug, warn};
+use std::{
+    collections::{BTreeMap, HashMap},
+    ffi::OsStr,
+    path::Path,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use tokio::runtime::Handle;
+
+// How long the kernel can cache the attributes and entries we reply with
+const TTL: Duration = Duration::from_secs(1);
+
+const PREDICATE_SYMLINK_TARGET: &str = "symlink_target";
+
+/// A FilesContainer mounted as a local filesystem, which is unmounted when this is dropped.
+#[derive(Debug)]
+pub struct FilesMount {
+    session: BackgroundSession,
+}
+
+impl FilesMount {
+    /// The local folder the FilesContainer is mounted at.
+    pub fn mountpoint(&self) -> &Path {
+        &self.session.mountpoint
+    }
+
+    /// Unmount the FilesContainer.
+    pub fn unmount(self) {
+        drop(self.session);
+    }
+}
+
+impl Safe {
+    /// # Mount a FilesContainer as a local filesystem.
+    ///
+    /// The FilesContainer at the URL, which can be an NRS-URL and include a version and the path
+    /// of a folder to mount, is exposed as a filesystem at the `mountpoint` local folder, using
+    /// the sizes, mode bits and times of its FileInfos. Files are fetched from the network as
+    /// they are read, only fetching the ranges being read.
+    ///
+    /// The filesystem is read-only unless `writable` is set, in which case files written to
+    /// are stored in the FilesContainer when closed, and files removed are removed from it.
+    /// If the URL is an NRS-URL, the NRS name is updated to link to each new version.
+    /// Only the latest version of a FilesContainer can be mounted as writable.
+    ///
+    /// This must be called from within a multi-threaded Tokio runtime, which is used to run
+    /// the network operations of the filesystem until it's unmounted.
+    ///
+    /// ## Example
+    ///
+    /// ```no_run
+    /// # use sn_api::Safe;
+    /// # use std::path::Path;
+    /// # let mut safe = Safe::default();
+    /// # let rt = tokio::runtime::Runtime::new().unwrap();
+    /// # rt.block_on(async {
+    /// #   safe.connect(None, None, None).await.unwrap();
+    ///     let (xorurl, _, _) = safe.files_container_create_from("./testdata", None, true, false).await.unwrap();
+    ///     let mount = safe.files_container_mount(&xorurl, Path::new("/mnt/testdata"), false).await.unwrap();
+    ///     let content = std::fs::read_to_string(mount.mountpoint().join("test.md")).unwrap();
+    ///     assert!(content.starts_with("hello tests!"));
+    /// # });
+    /// ```
+    pub async fn files_container_mount(
+        &self,
+        url: &str,
+        mountpoint: &Path,
+        writable: bool,
+    ) -> Result<FilesMount> {
+        debug!("Mounting FilesContainer at {} on {:?}", url, mountpoint);
+        let safe_url = SafeUrl::from_url(url)?;
+        if writable && safe_url.content_version().is_some() {
+            return Err(Error::InvalidInput(
+                "A specific version of a FilesContainer cannot be mounted as writable".to_string(),
+            ));
+        }
+
+        let base_path = safe_url.path_decoded()?.trim_end_matches('/').to_string();
+        let files_map = match self.files_container_get(url).await? {
+            Some((_, files_map)) => files_map,
+            None => FilesMap::default(),
+        };
+        let files_map = if base_path.is_empty() {
+            files_map
+        } else {
+            let is_dir = files_map
+                .get(&base_path)
+                .and_then(|info| info.get(PREDICATE_TYPE))
+                .is_none_or(|file_type| file_type == MIMETYPE_FILESYSTEM_DIR);
+            if !is_dir {
+                return Err(Error::InvalidInput(format!(
+                    "The path {} is not a folder of the FilesContainer",
+                    base_path
+                )));
+            }
+            file_map_for_path(files_map, &base_path)?
+        };
+
+        let mut write_url = safe_url.clone();
+        write_url.set_path("");
+        write_url.set_content_version(None);
+
+        let filesystem = MountedFiles {
+            safe: self.clone(),
+            runtime: Handle::current(),
+            update_nrs: safe_url.content_type() == ContentType::NrsMapContainer,
+            write_url,
+            base_path,
+            writable,
+            tree: FileTree::new(&files_map),
+            writes: HashMap::new(),
+            next_fh: 1,
+            uid: 0,
+            gid: 0,
+        };
+
+        let options = [
+            MountOption::FSName(url.to_string()),
+            MountOption::Subtype("safe".to_string()),
+            MountOption::NoDev,
+            MountOption::NoSuid,
+            if writable {
+                MountOption::RW
+            } else {
+                MountOption::RO
+            },
+        ];
+        let session = fuser::spawn_mount2(filesystem, mountpoint, &options).map_err(|err| {
+            Error::FileSystemError(format!(
+                "Failed to mount FilesContainer at {:?}: {}",
+                mountpoint, err
+            ))
+        })?;
+
+        Ok(FilesMount { session })
+    }
+}
+
+// A file or folder of the mounted FilesContainer
+struct Node {
+    parent: u64,
+    name: String,
+    // Path relative to the mounted folder, e.g. "/docs/intro.md"
+    path: String,
+    kind: FileType,
+    // Empty for the folders which are only implied by the paths of the files in them
+    info: FileInfo,
+    children: BTreeMap<String, u64>,
+}
+
+// The files and folders of a FilesContainer, indexed by inode number
+struct FileTree {
+    nodes: HashMap<u64, Node>,
+    next_ino: u64,
+}
+
+impl FileTree {
+    fn new(files_map: &FilesMap) -> Self {
+        let root = Node {
+            parent: FUSE_ROOT_ID,
+            name: String::new(),
+            path: String::new(),
+            kind: FileType::Directory,
+            info: FileInfo::default(),
+            children: BTreeMap::new(),
+        };
+        let mut tree = Self {
+            nodes: vec![(FUSE_ROOT_ID, root)].into_iter().collect(),
+            next_ino: FUSE_ROOT_ID + 1,
+        };
+        for (path, info) in files_map {
+            let _ = tree.insert(path, info.clone());
+        }
+        tree
+    }
+
+    fn get(&self, ino: u64) -> Option<&Node> {
+        self.nodes.get(&ino)
+    }
+
+    fn lookup(&self, parent: u64, name: &str) -> Option<u64> {
+        self.nodes.get(&parent)?.children.get(name).copied()
+    }
+
+    // Adds the file or folder at the path, or updates its FileInfo if it's already there,
+    // adding the folders it's in if they are missing.
+    fn insert(&mut self, path: &str, info: FileInfo) -> u64 {
+        let mut ino = FUSE_ROOT_ID;
+        for name in path.split('/').filter(|name| !name.is_empty()) {
+            ino = match self.lookup(ino, name) {
+                Some(child) => child,
+                None => {
+                    let child = self.next_ino;
+                    self.next_ino += 1;
+                    let mut path = format!("/{}", name);
+                    if let Some(parent) = self.nodes.get_mut(&ino) {
+                        let _ = parent.children.insert(name.to_string(), child);
+                        path.insert_str(0, &parent.path);
+                    }
+                    let node = Node {
+                        parent: ino,
+                        name: name.to_string(),
+                        path,
+                        kind: FileType::Directory,
+                        info: FileInfo::default(),
+                        children: BTreeMap::new(),
+                    };
+                    let _ = self.nodes.insert(child, node);
+                    child
+                }
+            };
+        }
+
+        if ino != FUSE_ROOT_ID {
+            if let Some(node) = self.nodes.get_mut(&ino) {
+                node.kind = file_kind(&info);
+                node.info = info;
+            }
+        }
+        ino
+    }
+
+    // Removes a file or folder, along with everything in it
+    fn remove(&mut self, ino: u64) {
+        if let Some(node) = self.nodes.remove(&ino) {
+            if let Some(parent) = self.nodes.get_mut(&node.parent) {
+                let _ = parent.children.remove(&node.name);
+            }
+            for child in node.children.values() {
+                self.remove(*child);
+            }
+        }
+    }
+}
+
+fn file_kind(info: &FileInfo) -> FileType {
+    match info.get(PREDICATE_TYPE).map(String::as_str) {
+        Some(MIMETYPE_FILESYSTEM_DIR) => FileType::Directory,
+        Some(MIMETYPE_FILESYSTEM_SYMLINK) => FileType::Symlink,
+        _ => FileType::RegularFile,
+    }
+}
+
+// Parses one of the times of a FileInfo, which are seconds since the UNIX epoch
+fn file_time(info: &FileInfo, key: &str) -> SystemTime {
+    info.get(key)
+        .and_then(|secs| secs.parse::<u64>().ok())
+        .map_or(UNIX_EPOCH, |secs| UNIX_EPOCH + Duration::from_secs(secs))
+}
+
+// The content of a file opened for writing, stored in the FilesContainer when it's flushed
+struct OpenWrite {
+    ino: u64,
+    data: Vec<u8>,
+    dirty: bool,
+}
+
+// The filesystem serving a mounted FilesContainer
+struct MountedFiles {
+    safe: Safe,
+    runtime: Handle,
+    // URL of the FilesContainer to write the changes to, without path nor version
+    write_url: SafeUrl,
+    update_nrs: bool,
+    // Path of the mounted folder in the FilesContainer, empty if it's its root
+    base_path: String,
+    writable: bool,
+    tree: FileTree,
+    writes: HashMap<u64, OpenWrite>,
+    next_fh: u64,
+    uid: u32,
+    gid: u32,
+}
+
+impl MountedFiles {
+    fn attr(&self, ino: u64) -> Option<FileAttr> {
+        let node = self.tree.get(ino)?;
+        let size = match self.writes.values().find(|write| write.ino == ino) {
+            Some(write) => write.data.len() as u64,
+            None => node
+                .info
+                .get(PREDICATE_SIZE)
+                .and_then(|size| size.parse::<u64>().ok())
+                .unwrap_or(0),
+        };
+        let default_perm = if node.kind == FileType::Directory {
+            0o755
+        } else {
+            0o644
+        };
+        let perm = node
+            .info
+            .get(PREDICATE_MODE_BITS)
+            .and_then(|mode| mode.parse::<u32>().ok())
+            .map_or(default_perm, |mode| (mode & 0o7777) as u16);
+        let perm = if self.writable { perm } else { perm & !0o222 };
+        let mtime = file_time(&node.info, PREDICATE_MODIFIED);
+
+        Some(FileAttr {
+            ino,
+            size,
+            blocks: size.div_ceil(512),
+            atime: mtime,
+            mtime,
+            ctime: mtime,
+            crtime: file_time(&node.info, PREDICATE_CREATED),
+            kind: node.kind,
+            perm,
+            nlink: if node.kind == FileType::Directory {
+                2
+            } else {
+                1
+            },
+            uid: self.uid,
+            gid: self.gid,
+            rdev: 0,
+            blksize: 512,
+            flags: 0,
+        })
+    }
+
+    // The URL of a file in the FilesContainer being written to
+    fn file_url(&self, path: &str) -> String {
+        let mut url = self.write_url.clone();
+        url.set_path(&format!("{}{}", self.base_path, path));
+        url.to_string()
+    }
+
+    // Fetches the content of a file, or a range of it
+    fn fetch(&self, ino: u64, range: Range) -> Result<Bytes> {
+        let link = match self
+            .tree
+            .get(ino)
+            .and_then(|node| node.info.get(PREDICATE_LINK))
+        {
+            Some(link) => link,
+            // a file created but not stored yet
+            None => return Ok(Bytes::new()),
+        };
+        match self.runtime.block_on(self.safe.fetch(link, range))? {
+            SafeData::PublicFile { data, .. } | SafeData::PrivateFile { data, .. } => Ok(data),
+            other => Err(Error::ContentError(format!(
+                "Content at {} is not a file: {:?}",
+                link, other
+            ))),
+        }
+    }
+
+    // Stores the content of a file in the FilesContainer, updating its FileInfo
+    fn store(&mut self, ino: u64, data: Vec<u8>) -> Result<()> {
+        let path = match self.tree.get(ino) {
+            Some(node) => node.path.clone(),
+            None => {
+                return Err(Error::ContentNotFound(format!(
+                    "No file with inode {}",
+                    ino
+                )))
+            }
+        };
+        let url = self.file_url(&path);
+        debug!("Storing {} bytes at {}", data.len(), url);
+        let (result, _) = self
+            .runtime
+            .block_on(self.safe.files_container_add_from_raw(
+                Bytes::from(data),
+                &url,
+                true,
+                self.update_nrs,
+            ))?;
+
+        let full_path = format!("{}{}", self.base_path, path);
+        if let Some(info) = result.and_then(|(_, files_map)| files_map.get(&full_path).cloned()) {
+            let _ = self.tree.insert(&path, info);
+        }
+        Ok(())
+    }
+
+    // Stores the content of a file opened for writing, if it changed
+    fn store_write(&mut self, fh: u64) -> Result<()> {
+        let (ino, data) = match self.writes.get_mut(&fh) {
+            Some(write) if write.dirty => {
+                write.dirty = false;
+                (write.ino, write.data.clone())
+            }
+            _ => return Ok(()),
+        };
+        self.store(ino, data)
+    }
+
+    fn open_write(&mut self, ino: u64, data: Vec<u8>, dirty: bool) -> u64 {
+        let fh = self.next_fh;
+        self.next_fh += 1;
+        let _ = self.writes.insert(fh, OpenWrite { ino, data, dirty });
+        fh
+    }
+}
+
+// The errno to reply with when an operation fails with the error
+fn errno(err: &Error) -> c_int {
+    warn!("Mounted FilesContainer operation failed: {}", err);
+    match err {
+        Error::ContentNotFound(_) | Error::EntryNotFound(_) => ENOENT,
+        Error::AccessDenied(_) => EACCES,
+        _ => EIO,
+    }
+}
+
+impl Filesystem for MountedFiles {
+    fn init(
+        &mut self,
+        req: &Request<'_>,
+        _config: &mut KernelConfig,
+    ) -> std::result::Result<(), c_int> {
+        // The files belong to whoever mounted the FilesContainer
+        self.uid = req.uid();
+        self.gid = req.gid();
+        Ok(())
+    }
+
+    fn lookup(&mut self, _req: &Request<'_>, parent: u64, name: &OsStr, reply: ReplyEntry) {
+        match name
+            .to_str()
+            .and_then(|name| self.tree.lookup(parent, name))
+            .and_then(|ino| self.attr(ino))
+        {
+            Some(attr) => reply.entry(&TTL, &attr, 0),
+            None => reply.error(ENOENT),
+        }
+    }
+
+    fn getattr(&mut self, _req: &Request<'_>, ino: u64, reply: ReplyAttr) {
+        match self.attr(ino) {
+            Some(attr) => reply.attr(&TTL, &attr),
+            None => reply.error(ENOENT),
+        }
+    }
+
+    fn setattr(
+        &mut self,
+        _req: &Request<'_>,
+        ino: u64,
+        _mode: Option<u32>,
+        _uid: Option<u32>,
+        _gid: Option<u32>,
+        size: Option<u64>,
+        _atime: Option<TimeOrNow>,
+        _mtime: Option<TimeOrNow>,
+        _ctime: Option<SystemTime>,
+        fh: Option<u64>,
+        _crtime: Option<SystemTime>,
+        _chgtime: Option<SystemTime>,
+        _bkuptime: Option<SystemTime>,
+        _flags: Option<u32>,
+        reply: ReplyAttr,
+    ) {
+        // Only truncating files is supported, other changes are ignored
+        if let Some(size) = size {
+            if !self.writable {
+                return reply.error(EROFS);
+            }
+            let open_write = fh.filter(|fh| self.writes.contains_key(fh)).or_else(|| {
+                self.writes
+                    .iter()
+                    .find(|(_, write)| write.ino == ino)
+                    .map(|(fh, _)| *fh)
+            });
+            let result = match open_write.and_then(|fh| self.writes.get_mut(&fh)) {
+                Some(write) => {
+                    write.data.resize(size as usize, 0);
+                    write.dirty = true;
+                    Ok(())
+                }
+                None => self.fetch(ino, None).and_then(|data| {
+                    let mut data = data.to_vec();
+                    data.resize(size as usize, 0);
+                    self.store(ino, data)
+                }),
+            };
+            if let Err(err) = result {
+                return reply.error(errno(&err));
+            }
+        }
+
+        match self.attr(ino) {
+            Some(attr) => reply.attr(&TTL, &attr),
+            None => reply.error(ENOENT),
+        }
+    }
+
+    fn readlink(&mut self, _req: &Request<'_>, ino: u64, reply: ReplyData) {
+        match self
+            .tree
+            .get(ino)
+            .and_then(|node| node.info.get(PREDICATE_SYMLINK_TARGET))
+        {
+            Some(target) => reply.data(target.as_bytes()),
+            None => reply.error(ENOENT),
+        }
+    }
+
+    fn unlink(&mut self, _req: &Request<'_>, parent: u64, name: &OsStr, reply: ReplyEmpty) {
+        if !self.writable {
+            return reply.error(EROFS);
+        }
+        let ino = match name
+            .to_str()
+            .and_then(|name| self.tree.lookup(parent, name))
+        {
+            Some(ino) => ino,
+            None => return reply.error(ENOENT),
+        };
+        let node = match self.tree.get(ino) {
+            Some(node) if node.kind == FileType::Directory => return reply.error(EISDIR),
+            Some(node) => node,
+            None => return reply.error(ENOENT),
+        };
+
+        // Files created but not stored yet are only known locally
+        if node.info.contains_key(PREDICATE_LINK) {
+            let url = self.file_url(&node.path);
+            debug!("Removing {}", url);
+            if let Err(err) = self.runtime.block_on(self.safe.files_container_remove_path(
+                &url,
+                false,
+                self.update_nrs,
+            )) {
+                return reply.error(errno(&err));
+            }
+        }
+        self.tree.remove(ino);
+        reply.ok();
+    }
+
+    fn open(&mut self, _req: &Request<'_>, ino: u64, flags: i32, reply: ReplyOpen) {
+        let node = match self.tree.get(ino) {
+            Some(node) => node,
+            None => return reply.error(ENOENT),
+        };
+        if flags & O_ACCMODE == O_RDONLY {
+            // Reads go straight to the network
+            return reply.opened(0, 0);
+        }
+
+        if !self.writable {
+            return reply.error(EROFS);
+        }
+        if node.kind == FileType::Directory {
+            return reply.error(EISDIR);
+        }
+        let truncate = flags & O_TRUNC != 0;
+        let data = if truncate {
+            Vec::new()
+        } else {
+            match self.fetch(ino, None) {
+                Ok(data) => data.to_vec(),
+                Err(err) => return reply.error(errno(&err)),
+            }
+        };
+        let fh = self.open_write(ino, data, truncate);
+        reply.opened(fh, 0);
+    }
+
+    fn read(
+        &mut self,
+        _req: &Request<'_>,
+        ino: u64,
+        fh: u64,
+        offset: i64,
+        size: u32,
+        _flags: i32,
+        _lock_owner: Option<u64>,
+        reply: ReplyData,
+    ) {
+        let offset = offset.max(0) as u64;
+        if let Some(write) = self.writes.get(&fh) {
+            let start = (offset as usize).min(write.data.len());
+            let end = (start + size as usize).min(write.data.len());
+            return reply.data(&write.data[start..end]);
+        }
+
+        let file_size = match self.attr(ino) {
+            Some(attr) => attr.size,
+            None => return reply.error(ENOENT),
+        };
+        let end = (offset + u64::from(size)).min(file_size);
+        if offset >= end {
+            return reply.data(&[]);
+        }
+        match self.fetch(ino, Some((Some(offset), Some(end)))) {
+            Ok(data) => reply.data(&data),
+            Err(err) => reply.error(errno(&err)),
+        }
+    }
+
+    fn write(
+        &mut self,
+        _req: &Request<'_>,
+        _ino: u64,
+        fh: u64,
+        offset: i64,
+        data: &[u8],
+        _write_flags: u32,
+        _flags: i32,
+        _lock_owner: Option<u64>,
+        reply: ReplyWrite,
+    ) {
+        let write = match self.writes.get_mut(&fh) {
+            Some(write) => write,
+            None => return reply.error(EBADF),
+        };
+        let start = offset.max(0) as usize;
+        let end = start + data.len();
+        if write.data.len() < end {
+            write.data.resize(end, 0);
+        }
+        write.data[start..end].copy_from_slice(data);
+        write.dirty = true;
+        reply.written(data.len() as u32);
+    }
+
+    fn flush(
+        &mut self,
+        _req: &Request<'_>,
+        _ino: u64,
+        fh: u64,
+        _lock_owner: u64,
+        reply: ReplyEmpty,
+    ) {
+        match self.store_write(fh) {
+            Ok(()) => reply.ok(),
+            Err(err) => reply.error(errno(&err)),
+        }
+    }
+
+    fn release(
+        &mut self,
+        _req: &Request<'_>,
+        _ino: u64,
+        fh: u64,
+        _flags: i32,
+        _lock_owner: Option<u64>,
+        _flush: bool,
+        reply: ReplyEmpty,
+    ) {
+        let result = self.store_write(fh);
+        let _ = self.writes.remove(&fh);
+        match result {
+            Ok(()) => reply.ok(),
+            Err(err) => reply.error(errno(&err)),
+        }
+    }
+
+    fn fsync(
+        &mut self,
+        _req: &Request<'_>,
+        _ino: u64,
+        fh: u64,
+        _datasync: bool,
+        reply: ReplyEmpty,
+    ) {
+        match self.store_write(fh) {
+            Ok(()) => reply.ok(),
+            Err(err) => reply.error(errno(&err)),
+        }
+    }
+
+    fn readdir(
+        &mut self,
+        _req: &Request<'_>,
+        ino: u64,
+        _fh: u64,
+        offset: i64,
+        mut reply: ReplyDirectory,
+    ) {
+        let node = match self.tree.get(ino) {
+            Some(node) if node.kind == FileType::Directory => node,
+            Some(_) => return reply.error(ENOTDIR),
+            None => return reply.error(ENOENT),
+        };
+
+        let mut entries = vec![
+            (ino, FileType::Directory, "."),
+            (node.parent, FileType::Directory, ".."),
+        ];
+        for (name, child) in &node.children {
+            if let Some(child_node) = self.tree.get(*child) {
+                entries.push((*child, child_node.kind, name));
+            }
+        }
+        for (i, (ino, kind, name)) in entries.into_iter().enumerate().skip(offset.max(0) as usize) {
+            // the offset of an entry is the one to continue from after it
+            if reply.add(ino, (i + 1) as i64, kind, name) {
+                break;
+            }
+        }
+        reply.ok();
+    }
+
+    fn create(
+        &mut self,
+        _req: &Request<'_>,
+        parent: u64,
+        name: &OsStr,
+        mode: u32,
+        umask: u32,
+        _flags: i32,
+        reply: ReplyCreate,
+    ) {
+        if !self.writable {
+            return reply.error(EROFS);
+        }
+        let (parent_path, name) = match (self.tree.get(parent), name.to_str()) {
+            (Some(node), Some(name)) if node.kind == FileType::Directory => {
+                (node.path.clone(), name)
+            }
+            (Some(_), Some(_)) => return reply.error(ENOTDIR),
+            _ => return reply.error(ENOENT),
+        };
+
+        // It gets the FileInfo of the FilesContainer once stored
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |now| now.as_secs())
+            .to_string();
+        let info: FileInfo = vec![
+            (PREDICATE_TYPE.to_string(), "Raw".to_string()),
+            (PREDICATE_SIZE.to_string(), "0".to_string()),
+            (PREDICATE_CREATED.to_string(), now.clone()),
+            (PREDICATE_MODIFIED.to_string(), now),
+            (PREDICATE_MODE_BITS.to_string(), (mode & !umask).to_string()),
+        ]
+        .into_iter()
+        .collect();
+        let ino = self.tree.insert(&format!("{}/{}", parent_path, name), info);
+        let fh = self.open_write(ino, Vec::new(), true);
+
+        match self.attr(ino) {
+            Some(attr) => reply.created(&TTL, &attr, 0, fh, 0),
+            None => reply.error(ENOENT),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::{anyhow, Result};
+
+    fn file_info(file_type: &str, size: &str) -> FileInfo {
+        vec![
+            (PREDICATE_TYPE.to_string(), file_type.to_string()),
+            (PREDICATE_SIZE.to_string(), size.to_string()),
+            (PREDICATE_MODIFIED.to_string(), "1600000000".to_string()),
+        ]
+        .into_iter()
+        .collect()
+    }
+
+    #[test]
+    fn test_file_tree_from_files_map() -> Result<()> {
+        let mut files_map = FilesMap::new();
+        let _ = files_map.insert("/test.md".to_string(), file_info("text/markdown", "12"));
+        let _ = files_map.insert(
+            "/subfolder/sub2.md".to_string(),
+            file_info("text/markdown", "4"),
+        );
+        let _ = files_map.insert(
+            "/emptyfolder".to_string(),
+            file_info(MIMETYPE_FILESYSTEM_DIR, "0"),
+        );
+        let _ = files_map.insert(
+            "/link".to_string(),
+            file_info(MIMETYPE_FILESYSTEM_SYMLINK, "0"),
+        );
+
+        let tree = FileTree::new(&files_map);
+        let root = tree
+            .get(FUSE_ROOT_ID)
+            .ok_or_else(|| anyhow!("missing root"))?;
+        assert_eq!(
+            root.children.keys().collect::<Vec<_>>(),
+            vec!["emptyfolder", "link", "subfolder", "test.md"]
+        );
+
+        let test_md = tree
+            .lookup(FUSE_ROOT_ID, "test.md")
+            .ok_or_else(|| anyhow!("missing file"))?;
+        let node = tree.get(test_md).ok_or_else(|| anyhow!("missing file"))?;
+        assert_eq!(node.kind, FileType::RegularFile);
+        assert_eq!(node.path, "/test.md");
+
+        let link = tree
+            .lookup(FUSE_ROOT_ID, "link")
+            .ok_or_else(|| anyhow!("missing symlink"))?;
+        assert_eq!(
+            tree.get(link).map(|node| node.kind),
+            Some(FileType::Symlink)
+        );
+
+        // folders implied by the paths of their files are there too
+        let subfolder = tree
+            .lookup(FUSE_ROOT_ID, "subfolder")
+            .ok_or_else(|| anyhow!("missing folder"))?;
+        let node = tree
+            .get(subfolder)
+            .ok_or_else(|| anyhow!("missing folder"))?;
+        assert_eq!(node.kind, FileType::Directory);
+        assert!(node.info.is_empty());
+        let sub2 = tree
+            .lookup(subfolder, "sub2.md")
+            .ok_or_else(|| anyhow!("missing file"))?;
+        assert_eq!(
+            tree.get(sub2).map(|node| node.path.as_str()),
+            Some("/subfolder/sub2.md")
+        );
+        assert_eq!(tree.get(sub2).map(|node| node.parent), Some(subfolder));
+        Ok(())
+    }
+
+    #[test]
+    fn test_file_tree_insert_and_remove() -> Result<()> {
+        let mut tree = FileTree::new(&FilesMap::new());
+        let ino = tree.insert("/a/b/c.md", file_info("text/markdown", "1"));
+        let a = tree
+            .lookup(FUSE_ROOT_ID, "a")
+            .ok_or_else(|| anyhow!("missing folder"))?;
+        let b = tree
+            .lookup(a, "b")
+            .ok_or_else(|| anyhow!("missing folder"))?;
+        assert_eq!(tree.lookup(b, "c.md"), Some(ino));
+
+        // inserting it again only updates its FileInfo
+        let updated = tree.insert("/a/b/c.md", file_info("text/markdown", "2"));
+        assert_eq!(updated, ino);
+        assert_eq!(
+            tree.get(ino).and_then(|node| node.info.get(PREDICATE_SIZE)),
+            Some(&"2".to_string())
+        );
+
+        tree.remove(a);
+        assert_eq!(tree.lookup(FUSE_ROOT_ID, "a"), None);
+        assert!(tree.get(b).is_none());
+        assert!(tree.get(ino).is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn test_file_time() {
+        let info = file_info("text/markdown", "1");
+        assert_eq!(
+            file_time(&info, PREDICATE_MODIFIED),
+            UNIX_EPOCH + Duration::from_secs(1_600_000_000)
+        );
+        assert_eq!(file_time(&info, PREDICATE_CREATED), UNIX_EPOCH);
+    }
+}
@@ -14,10 +14,15 @@ pub mod multimap;
 pub mod nrs;
 pub mod register;
 pub mod resolver;
+pub mod wallet;
 
 pub use crate::safeurl::*;
 pub use consts::DEFAULT_XORURL_BASE;
 pub use helpers::parse_tokens_amount;
+pub use safe_network::client::{
+    ChunkCache, ChunkCacheConfig, ChunkCacheStats, UploadEvent, UploadJournal, UploadOptions,
+};
+pub use safe_network::messaging::data::DataKind;
 pub use xor_name::{XorName, XOR_NAME_LEN};
 
 // --------------------------------------------------------------------
@@ -28,7 +33,7 @@ mod helpers;
 mod keys;
 
 #[cfg(test)]
-mod test_helpers;
+pub(crate) mod test_helpers;
 
 use super::{common, constants, Error, Result};
 
@@ -36,7 +41,7 @@ use crate::NodeConfig;
 
 use rand::rngs::OsRng;
 use safe_network::client::{Client, ClientConfig, DEFAULT_OPERATION_TIMEOUT};
-use safe_network::types::Keypair;
+use safe_network::types::{Keypair, Token};
 use tracing::debug;
 
 use std::path::Path;
@@ -49,6 +54,18 @@ pub struct Safe {
     client: Option<Client>,
     pub xorurl_base: XorUrlBase,
     pub dry_run_mode: bool,
+    /// Record the uploads of local files in upload journals, resuming them from the journals of
+    /// previous uploads which failed instead of starting them over
+    pub resume_uploads: bool,
+    /// Store the local files uploaded into FilesContainers as private, encrypted with the owner's key
+    pub private_files: bool,
+    /// How the Chunks of the local files uploaded into FilesContainers are stored,
+    /// including where to report the progress of the uploads
+    pub upload_options: UploadOptions,
+    /// The on-disk cache the retrieved Chunks are kept in, so other instances don't need to
+    /// fetch them again. It's `~/.safe/client/cache` by default, and disabled if set to `None`
+    /// before connecting.
+    pub chunk_cache: Option<ChunkCacheConfig>,
 }
 
 impl Safe {
@@ -58,6 +75,10 @@ impl Safe {
             client: None,
             xorurl_base: xorurl_base.unwrap_or(DEFAULT_XORURL_BASE),
             dry_run_mode: true,
+            resume_uploads: false,
+            private_files: false,
+            upload_options: UploadOptions::default(),
+            chunk_cache: Some(ChunkCacheConfig::default()),
         }
     }
 
@@ -73,6 +94,10 @@ impl Safe {
             client: None,
             xorurl_base: xorurl_base.unwrap_or(DEFAULT_XORURL_BASE),
             dry_run_mode: false,
+            resume_uploads: false,
+            private_files: false,
+            upload_options: UploadOptions::default(),
+            chunk_cache: Some(ChunkCacheConfig::default()),
         };
 
         safe.connect(bootstrap_config, keypair, config_path, timeout)
@@ -96,7 +121,7 @@ impl Safe {
         debug!("Client to be instantiated with specific pk?: {:?}", keypair);
         debug!("Bootstrap contacts list set to: {:?}", bootstrap_config);
 
-        let config = ClientConfig::new(
+        let mut config = ClientConfig::new(
             None,
             None,
             bootstrap_config.0,
@@ -106,6 +131,7 @@ impl Safe {
             None,
         )
         .await;
+        config.chunk_cache = self.chunk_cache.clone();
 
         self.client = Some(
             Client::new(config, bootstrap_config.1, keypair)
@@ -128,12 +154,32 @@ impl Safe {
         self.client.is_some()
     }
 
+    /// Get a quote of the cost of storing data of the given size and kind on the network
+    pub async fn get_store_cost(&self, bytes_len: u64, data_kind: DataKind) -> Result<Token> {
+        debug!(
+            "Getting cost of storing {} bytes of {:?} data",
+            bytes_len, data_kind
+        );
+        let client = self.get_safe_client()?;
+        let cost = client.get_store_cost(bytes_len, data_kind).await?;
+        Ok(cost)
+    }
+
     /// Generate a new random Ed25519 keypair
     pub fn new_keypair(&self) -> Keypair {
         let mut rng = OsRng;
         Keypair::new_ed25519(&mut rng)
     }
 
+    // The keypair the data stored is owned by, which is a random one when not connected,
+    // e.g. to calculate the addresses of private data in dry runs
+    pub(crate) fn owner_keypair(&self) -> Keypair {
+        match &self.client {
+            Some(client) => client.keypair(),
+            None => self.new_keypair(),
+        }
+    }
+
     // Private helper to obtain the Client instance
     pub(crate) fn get_safe_client(&self) -> Result<&Client> {
         match &self.client {
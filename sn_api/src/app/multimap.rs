@@ -6,7 +6,7 @@
 // KIND, either express or implied. Please review the Licences for the specific language governing
 // permissions and limitations relating to use of the SAFE Network Software.
 
-use super::register::EntryHash;
+use super::register::{EntryHash, EntryNode};
 
 use crate::safeurl::{ContentType, SafeUrl, XorUrl};
 use crate::{Error, Result, Safe};
@@ -37,6 +37,14 @@ impl Safe {
             .await
     }
 
+    /// Return all the current key-value pairs of a Multimap on the network
+    pub async fn multimap_read(&self, url: &str) -> Result<Multimap> {
+        debug!("Getting all values from Multimap at: {}", url);
+        let safeurl = self.parse_and_resolve_url(url).await?;
+
+        self.fetch_multimap(&safeurl).await
+    }
+
     /// Return the value of a Multimap on the network corresponding to the key provided
     pub async fn multimap_get_by_key(&self, url: &str, key: &[u8]) -> Result<Multimap> {
         debug!("Getting value by key from Multimap at: {}", url);
@@ -218,6 +226,28 @@ impl Safe {
         }
     }
 
+    // Crate's helper to return the whole history of a Multimap on the network, without
+    // resolving the SafeUrl, along with each entry parsed as a 'MultimapKeyValue',
+    // or `None` if it's a tombstone
+    pub(crate) async fn fetch_multimap_history(
+        &self,
+        safeurl: &SafeUrl,
+    ) -> Result<Vec<(EntryNode, Option<MultimapKeyValue>)>> {
+        let history = self.register_fetch_history(safeurl).await?;
+
+        history
+            .into_iter()
+            .map(|node| {
+                let key_val = if node.entry == MULTIMAP_REMOVED_MARK {
+                    None
+                } else {
+                    Some(Self::decode_multimap_entry(&node.entry)?)
+                };
+                Ok((node, key_val))
+            })
+            .collect()
+    }
+
     fn decode_multimap_entry(entry: &[u8]) -> Result<MultimapKeyValue> {
         rmp_serde::from_slice(entry)
             .map_err(|err| Error::ContentError(format!("Couldn't parse Multimap entry: {:?}", err)))
@@ -309,6 +339,42 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_multimap_read() -> Result<()> {
+        let safe = new_safe_instance().await?;
+        let key_val = (b"key".to_vec(), b"value".to_vec());
+        let key_val2 = (b"key2".to_vec(), b"value2".to_vec());
+
+        let xorurl = safe.multimap_create(None, 25_000, false).await?;
+        let _ = retry_loop!(safe.multimap_read(&xorurl));
+
+        let hash = safe
+            .multimap_insert(&xorurl, key_val.clone(), BTreeSet::new())
+            .await?;
+        let hash2 = safe
+            .multimap_insert(&xorurl, key_val2.clone(), BTreeSet::new())
+            .await?;
+
+        let received_data =
+            retry_loop_for_pattern!(safe.multimap_read(&xorurl), Ok(v) if v.len() == 2)?;
+        assert_eq!(
+            received_data,
+            vec![(hash, key_val), (hash2, key_val2)]
+                .into_iter()
+                .collect()
+        );
+
+        // Removed entries are not part of the current key-value pairs
+        let _ = safe
+            .multimap_remove(&xorurl, vec![hash].into_iter().collect())
+            .await?;
+        let received_data =
+            retry_loop_for_pattern!(safe.multimap_read(&xorurl), Ok(v) if v.len() == 1)?;
+        assert!(received_data.iter().all(|(h, _)| *h == hash2));
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_multimap_get_by_hash() -> Result<()> {
         let safe = new_safe_instance().await?;
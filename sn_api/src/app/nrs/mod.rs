@@ -188,6 +188,41 @@ impl Safe {
         Ok((url, nrs_map))
     }
 
+    /// # Lists the versions of a public name
+    /// Returns all the versions the given public name was associated to a link in, from the latest
+    /// down to the first one, along with the link. Versions where the public name was removed
+    /// have no link.
+    /// Any of the versions returned can be passed to `nrs_get` to get the NrsMap at that version.
+    pub async fn nrs_versions(
+        &self,
+        public_name: &str,
+    ) -> Result<Vec<(VersionHash, Option<SafeUrl>)>> {
+        info!("Getting versions of public name: {}", public_name);
+
+        let url = validate_nrs_public_name(public_name)?;
+        let history = self.fetch_multimap_history(&url).await?;
+
+        // Removals are tombstones written on top of the entries of the public name
+        let mut name_hashes = BTreeSet::new();
+        let mut versions = vec![];
+        for (node, key_val) in history.iter().rev() {
+            match key_val {
+                Some((key, link)) if key == public_name.as_bytes() => {
+                    let _ = name_hashes.insert(node.hash);
+                    let link = SafeUrl::from_url(str::from_utf8(link)?)?;
+                    versions.push((VersionHash::from(&node.hash), Some(link)));
+                }
+                None if !node.children.is_disjoint(&name_hashes) => {
+                    versions.push((VersionHash::from(&node.hash), None));
+                }
+                _ => {}
+            }
+        }
+        versions.reverse();
+
+        Ok(versions)
+    }
+
     /// Get the mapping of all subNames and their associated SafeUrl for the Nrs Map Container at the given public name
     pub async fn nrs_get_subnames_map(
         &self,
@@ -672,6 +707,53 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_nrs_versions() -> Result<()> {
+        let site_name = random_nrs_name();
+        let safe = new_safe_instance().await?;
+
+        let files_container =
+            TestDataFilesContainer::get_container(["/testdata/test.md", "/testdata/another.md"])
+                .await?;
+        let public_name = &format!("test.{site_name}");
+
+        safe.nrs_create(&site_name).await?;
+        let first_url = safe
+            .nrs_associate(public_name, &files_container["/testdata/test.md"])
+            .await?;
+        // another public name's versions are not listed
+        safe.nrs_associate(
+            &format!("another.{site_name}"),
+            &files_container["/testdata/another.md"],
+        )
+        .await?;
+        let second_url = safe
+            .nrs_associate(public_name, &files_container["/testdata/another.md"])
+            .await?;
+        let removed_url = safe.nrs_remove(public_name).await?;
+
+        let versions = safe.nrs_versions(public_name).await?;
+        assert_eq!(
+            versions,
+            vec![
+                (removed_url.content_version().unwrap(), None),
+                (
+                    second_url.content_version().unwrap(),
+                    Some(files_container["/testdata/another.md"].clone())
+                ),
+                (
+                    first_url.content_version().unwrap(),
+                    Some(files_container["/testdata/test.md"].clone())
+                ),
+            ]
+        );
+
+        // A version listed can be used to get the public name's link at that version
+        let (url, _) = safe.nrs_get(public_name, Some(versions[2].0)).await?;
+        assert_eq!(url, Some(files_container["/testdata/test.md"].clone()));
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_nrs_remove_with_topname() -> Result<()> {
         let site_name = random_nrs_name();
@@ -6,19 +6,22 @@
 // KIND, either express or implied. Please review the Licences for the specific language governing
 // permissions and limitations relating to use of the SAFE Network Software.
 
-pub use safe_network::types::register::{Entry, EntryHash};
+pub use safe_network::types::register::{
+    Action, Entry, EntryHash, EntryNode, Permissions, Policy, PrivatePermissions,
+    PublicPermissions, User,
+};
 
-use crate::safeurl::{ContentType, SafeUrl, XorUrl};
+use crate::safeurl::{ContentType, SafeUrl, VersionHash, XorUrl};
 use crate::{Error, Result, Safe};
 
+use futures::{Stream, StreamExt};
 use log::debug;
 use rand::Rng;
 use safe_network::{
     client::Error as ClientError,
     types::{
-        register::{
-            Policy, PrivatePermissions, PrivatePolicy, PublicPermissions, PublicPolicy, User,
-        },
+        register::Register,
+        register::{PrivatePolicy, PublicPolicy},
         DataAddress, Error as SafeNdError, RegisterAddress, Scope,
     },
 };
@@ -102,6 +105,102 @@ impl Safe {
         self.register_fetch_entry(&safeurl, hash).await
     }
 
+    /// Read the Policy of a Register on the network
+    pub async fn register_get_policy(&self, url: &str) -> Result<Policy> {
+        debug!("Getting Register policy from: {:?}", url);
+        let safeurl = self.parse_and_resolve_url(url).await?;
+        let address = self.get_register_address(&safeurl)?;
+
+        let client = self.get_safe_client()?;
+        client.get_register_policy(address).await.map_err(|err| {
+            Error::NetDataError(format!(
+                "Failed to retrieve policy from Register data: {:?}",
+                err
+            ))
+        })
+    }
+
+    /// Read all the entries of a Register on the network, from the latest entry (or entries
+    /// if branching) down to the first ones written.
+    ///
+    /// Every entry is listed before the entries it was written on top of. Along with the hashes
+    /// of their children, the entries returned make up the whole Merkle DAG of the Register.
+    pub async fn register_history(&self, url: &str) -> Result<Vec<EntryNode>> {
+        debug!("Getting Register history from: {:?}", url);
+        let safeurl = self.parse_and_resolve_url(url).await?;
+
+        self.register_fetch_history(&safeurl).await
+    }
+
+    /// Read the entries of a Register on the network which the entry
+    /// with the given hash was written on top of, i.e. its previous versions
+    pub async fn register_entry_children(
+        &self,
+        url: &str,
+        hash: EntryHash,
+    ) -> Result<BTreeSet<(EntryHash, Entry)>> {
+        debug!("Getting children of entry {:?} from: {:?}", hash, url);
+        let safeurl = self.parse_and_resolve_url(url).await?;
+        let register = self.register_fetch(&safeurl).await?;
+
+        register
+            .children(hash)
+            .map_err(|_| Error::HashNotFound(hash))
+    }
+
+    /// Read the entries of a Register on the network which were
+    /// written on top of the entry with the given hash, i.e. its next versions
+    pub async fn register_entry_parents(
+        &self,
+        url: &str,
+        hash: EntryHash,
+    ) -> Result<BTreeSet<(EntryHash, Entry)>> {
+        debug!("Getting parents of entry {:?} from: {:?}", hash, url);
+        let safeurl = self.parse_and_resolve_url(url).await?;
+        let register = self.register_fetch(&safeurl).await?;
+
+        register
+            .parents(hash)
+            .map_err(|_| Error::HashNotFound(hash))
+    }
+
+    /// Read all the entries of a Register on the network preceding the entry with the
+    /// given hash, from its children down to the first entries written
+    pub async fn register_entry_ancestors(
+        &self,
+        url: &str,
+        hash: EntryHash,
+    ) -> Result<Vec<EntryNode>> {
+        debug!("Getting ancestors of entry {:?} from: {:?}", hash, url);
+        let safeurl = self.parse_and_resolve_url(url).await?;
+        let register = self.register_fetch(&safeurl).await?;
+
+        register
+            .ancestors(hash)
+            .map_err(|_| Error::HashNotFound(hash))
+    }
+
+    /// Fetch the history of a Register from a SafeUrl without performing any type of URL resolution
+    pub(crate) async fn register_fetch_history(&self, url: &SafeUrl) -> Result<Vec<EntryNode>> {
+        let address = self.get_register_address(url)?;
+        let client = self.get_safe_client()?;
+        client.get_register_history(address).await.map_err(|err| {
+            Error::NetDataError(format!(
+                "Failed to retrieve history of Register data: {:?}",
+                err
+            ))
+        })
+    }
+
+    // Fetch a whole Register from a SafeUrl without performing any type of URL resolution
+    async fn register_fetch(&self, url: &SafeUrl) -> Result<Register> {
+        let address = self.get_register_address(url)?;
+        let client = self.get_safe_client()?;
+        client.get_register(address).await.map_err(|err| {
+            Error::NetDataError(format!("Failed to retrieve Register data: {:?}", err))
+        })
+    }
+
     /// Fetch a Register from a SafeUrl without performing any type of URL resolution
     /// Supports version hashes:
     /// e.g. safe://mysafeurl?v=ce56a3504c8f27bfeb13bdf9051c2e91409230ea
@@ -203,6 +302,22 @@ impl Safe {
         Ok(entry_hash)
     }
 
+    /// Watch the content behind a URL, e.g. a FilesContainer or an NRS Map, for new versions.
+    ///
+    /// The returned stream yields the hash of every new version written from now on, which
+    /// the network pushes to us as soon as it accepts it, i.e. it may take a moment before the
+    /// new version can be fetched. The content stops being watched once the stream is dropped.
+    pub async fn watch(&self, url: &str) -> Result<impl Stream<Item = VersionHash>> {
+        debug!("Watching content at: {:?}", url);
+        let safeurl = self.parse_and_resolve_url(url).await?;
+        let address = self.get_register_address(&safeurl)?;
+
+        let client = self.get_safe_client()?;
+        let edits = client.watch_register(address).await?;
+
+        Ok(edits.map(|op| VersionHash::from(&EntryHash(op.crdt_op.hash()))))
+    }
+
     pub(crate) fn get_register_address(&self, url: &SafeUrl) -> Result<RegisterAddress> {
         let address = match url.address() {
             DataAddress::Register(reg_address) => reg_address,
@@ -232,8 +347,11 @@ fn public_policy(owner: User) -> Policy {
 
 #[cfg(test)]
 mod tests {
-    use crate::{app::test_helpers::new_safe_instance, ContentType};
-    use anyhow::Result;
+    use super::{EntryHash, Policy, User};
+    use crate::{app::test_helpers::new_safe_instance, retry_loop, ContentType, VersionHash};
+    use anyhow::{anyhow, Result};
+    use futures::StreamExt;
+    use std::{collections::BTreeSet, time::Duration};
 
     #[tokio::test]
     async fn test_register_create() -> Result<()> {
@@ -268,4 +386,89 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_register_get_policy() -> Result<()> {
+        let safe = new_safe_instance().await?;
+        let owner = User::Key(safe.get_safe_client()?.public_key());
+
+        let xorurl = safe
+            .register_create(None, 25_000, false, ContentType::Raw)
+            .await?;
+        let xorurl_priv = safe
+            .register_create(None, 25_000, true, ContentType::Raw)
+            .await?;
+
+        let policy = retry_loop!(safe.register_get_policy(&xorurl));
+        let policy_priv = retry_loop!(safe.register_get_policy(&xorurl_priv));
+
+        assert!(matches!(policy, Policy::Public(_)));
+        assert!(matches!(policy_priv, Policy::Private(_)));
+        assert_eq!(policy.owner(), &owner);
+        assert_eq!(policy_priv.owner(), &owner);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_register_history() -> Result<()> {
+        let safe = new_safe_instance().await?;
+
+        let xorurl = safe
+            .register_create(None, 25_000, false, ContentType::Raw)
+            .await?;
+
+        let first = safe
+            .register_write(&xorurl, b"first".to_vec(), Default::default())
+            .await?;
+        let second = safe
+            .register_write(&xorurl, b"second".to_vec(), BTreeSet::from([first]))
+            .await?;
+        let third = safe
+            .register_write(&xorurl, b"third".to_vec(), BTreeSet::from([second]))
+            .await?;
+
+        let history = retry_loop!(safe.register_history(&xorurl));
+        let hashes: Vec<EntryHash> = history.iter().map(|node| node.hash).collect();
+        assert_eq!(hashes, vec![third, second, first]);
+        assert_eq!(history[0].entry, b"third".to_vec());
+        assert_eq!(history[0].children, BTreeSet::from([second]));
+
+        let children = safe.register_entry_children(&xorurl, third).await?;
+        assert_eq!(children, BTreeSet::from([(second, b"second".to_vec())]));
+        let parents = safe.register_entry_parents(&xorurl, first).await?;
+        assert_eq!(parents, BTreeSet::from([(second, b"second".to_vec())]));
+        let ancestors: Vec<EntryHash> = safe
+            .register_entry_ancestors(&xorurl, third)
+            .await?
+            .into_iter()
+            .map(|node| node.hash)
+            .collect();
+        assert_eq!(ancestors, vec![second, first]);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_register_watch() -> Result<()> {
+        let safe = new_safe_instance().await?;
+
+        let xorurl = safe
+            .register_create(None, 25_000, false, ContentType::Raw)
+            .await?;
+        let _ = retry_loop!(safe.register_read(&xorurl));
+
+        let mut versions = Box::pin(safe.watch(&xorurl).await?);
+
+        let hash = safe
+            .register_write(&xorurl, b"first".to_vec(), Default::default())
+            .await?;
+
+        let version = tokio::time::timeout(Duration::from_secs(30), versions.next())
+            .await?
+            .ok_or_else(|| anyhow!("The stream of versions ended unexpectedly"))?;
+        assert_eq!(version, VersionHash::from(&hash));
+
+        Ok(())
+    }
 }
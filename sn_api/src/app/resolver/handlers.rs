@@ -86,6 +86,19 @@ impl Safe {
         Ok(safe_data)
     }
 
+    pub(crate) fn resolve_wallet(&self, input_url: SafeUrl) -> Result<SafeData> {
+        ensure_no_subnames(&input_url, "wallet")?;
+
+        let safe_data = SafeData::Wallet {
+            xorurl: input_url.to_xorurl_string(),
+            xorname: input_url.xorname(),
+            type_tag: input_url.type_tag(),
+            resolved_from: input_url.to_string(),
+        };
+
+        Ok(safe_data)
+    }
+
     pub(crate) async fn resolve_raw(
         &self,
         input_url: SafeUrl,
@@ -253,20 +266,35 @@ impl Safe {
             )));
         };
 
+        let address = BytesAddress::new(input_url.xorname(), input_url.scope());
         let data = if retrieve_data {
-            self.get_bytes(BytesAddress::Public(input_url.xorname()), range)
-                .await?
+            self.get_bytes(address, range).await?
         } else {
             Bytes::new()
         };
 
-        let safe_data = SafeData::PublicFile {
-            xorurl: input_url.to_xorurl_string(),
-            xorname: input_url.xorname(),
-            data,
-            media_type,
-            metadata: metadata.clone(),
-            resolved_from: input_url.to_string(),
+        let xorurl = input_url.to_xorurl_string();
+        let xorname = input_url.xorname();
+        let metadata = metadata.clone();
+        let resolved_from = input_url.to_string();
+        let safe_data = if address.is_private() {
+            SafeData::PrivateFile {
+                xorurl,
+                xorname,
+                data,
+                media_type,
+                metadata,
+                resolved_from,
+            }
+        } else {
+            SafeData::PublicFile {
+                xorurl,
+                xorname,
+                data,
+                media_type,
+                metadata,
+                resolved_from,
+            }
         };
 
         Ok(safe_data)
@@ -232,9 +232,7 @@ impl Safe {
                 )
                 .await
             }
-            ContentType::Wallet { .. } => Err(Error::EmptyContent(
-                "Temporarily disabled feature".to_string(),
-            )),
+            ContentType::Wallet => self.resolve_wallet(input_url),
         }
     }
 }
@@ -47,6 +47,15 @@ pub enum SafeData {
         metadata: Option<FileInfo>,
         resolved_from: String,
     },
+    /// A file encrypted with the owner's key, its data is the decrypted content.
+    PrivateFile {
+        xorurl: String,
+        xorname: XorName,
+        data: Bytes,
+        media_type: Option<String>,
+        metadata: Option<FileInfo>,
+        resolved_from: String,
+    },
     NrsMapContainer {
         xorurl: String,
         xorname: XorName,
@@ -84,6 +93,13 @@ pub enum SafeData {
         data: BTreeSet<(EntryHash, Entry)>,
         resolved_from: String,
     },
+    /// Use `Safe::wallet_balance` to obtain the balance of the Wallet.
+    Wallet {
+        xorurl: String,
+        xorname: XorName,
+        type_tag: u64,
+        resolved_from: String,
+    },
 }
 
 impl SafeData {
@@ -93,11 +109,13 @@ impl SafeData {
             SafeKey { xorurl, .. }
             | FilesContainer { xorurl, .. }
             | PublicFile { xorurl, .. }
+            | PrivateFile { xorurl, .. }
             | NrsMapContainer { xorurl, .. }
             | NrsEntry { xorurl, .. }
             | Multimap { xorurl, .. }
             | PublicRegister { xorurl, .. }
-            | PrivateRegister { xorurl, .. } => xorurl.clone(),
+            | PrivateRegister { xorurl, .. }
+            | Wallet { xorurl, .. } => xorurl.clone(),
         }
     }
 
@@ -107,10 +125,12 @@ impl SafeData {
             SafeKey { resolved_from, .. }
             | FilesContainer { resolved_from, .. }
             | PublicFile { resolved_from, .. }
+            | PrivateFile { resolved_from, .. }
             | NrsEntry { resolved_from, .. }
             | Multimap { resolved_from, .. }
             | PublicRegister { resolved_from, .. }
-            | PrivateRegister { resolved_from, .. } => Some(resolved_from.clone()),
+            | PrivateRegister { resolved_from, .. }
+            | Wallet { resolved_from, .. } => Some(resolved_from.clone()),
             NrsMapContainer { .. } => None,
         }
     }
@@ -122,8 +142,10 @@ impl SafeData {
             | Multimap { .. }
             | NrsMapContainer { .. }
             | PublicFile { .. }
+            | PrivateFile { .. }
             | PublicRegister { .. }
-            | PrivateRegister { .. } => None,
+            | PrivateRegister { .. }
+            | Wallet { .. } => None,
             FilesContainer { resolves_into, .. } => resolves_into.clone(),
             NrsEntry { resolves_into, .. } => Some(resolves_into.clone()),
         }
@@ -136,9 +158,12 @@ impl SafeData {
             | Multimap { .. }
             | PublicRegister { .. }
             | PrivateRegister { .. }
+            | Wallet { .. }
             | NrsMapContainer { .. }
             | NrsEntry { .. } => None,
-            FilesContainer { metadata, .. } | PublicFile { metadata, .. } => metadata.clone(),
+            FilesContainer { metadata, .. }
+            | PublicFile { metadata, .. }
+            | PrivateFile { metadata, .. } => metadata.clone(),
         }
     }
 }
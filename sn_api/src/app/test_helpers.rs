@@ -146,7 +146,7 @@ fn read_default_peers_from_file() -> Result<(String, BTreeSet<SocketAddr>)> {
     Ok(info)
 }
 
-fn get_bootstrap_contacts() -> Result<NodeConfig> {
+pub fn get_bootstrap_contacts() -> Result<NodeConfig> {
     let (genesis_key_hex, bootstrap_contacts) = match var(TEST_BOOTSTRAPPING_PEERS) {
         Ok(val) => serde_json::from_str(&val).with_context(|| {
             format!(
@@ -0,0 +1,679 @@
+// Copyright 2022 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use super::{
+    helpers::parse_tokens_amount,
+    register::{EntryHash, EntryNode},
+};
+
+use crate::safeurl::{ContentType, SafeUrl, VersionHash, XorUrl};
+use crate::{Error, Result, Safe, XorName};
+
+use futures::future::{BoxFuture, FutureExt};
+use log::{debug, warn};
+use rand::Rng;
+use safe_network::types::Token;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Type tag used for the Registers backing Wallets
+pub(crate) const WALLET_TYPE_TAG: u64 = 1_000;
+
+/// An operation recorded in a Wallet. A Wallet is a public Register only its owner can
+/// write to, its balance is the result of replaying all the operations it holds.
+///
+/// Operations are replayed in causal order, and a Debit which isn't covered by the balance
+/// at its position, e.g. one of two concurrent transfers which together overdraw the Wallet,
+/// is ignored. Every reader thus settles on the same balance, and the same valid transfers.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WalletOp {
+    /// Tokens created out of thin air, only meant for development/test networks. They're
+    /// only accounted for by clients built with the `wallet-mint` feature.
+    Mint { amount: Token },
+    /// Tokens sent out to another Wallet
+    Debit { amount: Token, to: XorUrl },
+    /// Tokens received from a Debit, referenced by its versioned URL in the sender's Wallet
+    Credit { amount: Token, transfer: XorUrl },
+}
+
+impl Safe {
+    /// Create an empty Wallet on the network, owned by the client's key
+    pub async fn wallet_create(&self) -> Result<XorUrl> {
+        debug!("Creating a Wallet");
+        self.register_create(None, WALLET_TYPE_TAG, false, ContentType::Wallet)
+            .await
+    }
+
+    /// Return the balance of a Wallet on the network.
+    ///
+    /// The balance is computed by replaying the Wallet's history. Credits are only
+    /// accounted for if the Debit they reference is found in the sender's Wallet,
+    /// and was covered by the sender's balance.
+    pub async fn wallet_balance(&self, url: &str) -> Result<Token> {
+        debug!("Getting balance of Wallet at: {}", url);
+        let safeurl = self.parse_and_resolve_wallet_url(url).await?;
+        self.fetch_wallet_balance(&safeurl).await
+    }
+
+    /// Transfer tokens from a Wallet to another Wallet.
+    ///
+    /// A Debit is written in the source Wallet, its versioned URL is returned so it can
+    /// then be deposited by the owner of the destination Wallet with `wallet_deposit`.
+    pub async fn wallet_transfer(&self, from: &str, to: &str, amount: &str) -> Result<XorUrl> {
+        let amount = parse_non_zero_amount(amount)?;
+        debug!("Transferring {} from Wallet {} to {}", amount, from, to);
+        let from_url = self.parse_and_resolve_wallet_url(from).await?;
+        let to_url = self.parse_and_resolve_wallet_url(to).await?;
+        if from_url.xorname() == to_url.xorname() {
+            return Err(Error::InvalidInput(
+                "The source and destination Wallets must be different".to_string(),
+            ));
+        }
+
+        if !self.dry_run_mode {
+            let balance = self.fetch_wallet_balance(&from_url).await?;
+            if balance < amount {
+                return Err(Error::NotEnoughBalance(format!(
+                    "Wallet at \"{}\" has a balance of {}, which is not enough to transfer {}",
+                    from, balance, amount
+                )));
+            }
+        }
+
+        let op = WalletOp::Debit {
+            amount,
+            to: to_url.to_xorurl_string(),
+        };
+        let hash = self.wallet_write_op(&from_url, &op).await?;
+
+        let mut transfer_url = from_url;
+        transfer_url.set_content_version(Some(VersionHash::from(&hash)));
+        Ok(transfer_url.to_xorurl_string())
+    }
+
+    /// Deposit into a Wallet the tokens of a transfer made to it, returning the amount credited.
+    ///
+    /// The transfer is the versioned URL of the Debit, as returned by `wallet_transfer`.
+    pub async fn wallet_deposit(&self, url: &str, transfer: &str) -> Result<Token> {
+        debug!("Depositing transfer {} into Wallet at: {}", transfer, url);
+        let safeurl = self.parse_and_resolve_wallet_url(url).await?;
+        let transfer_url = SafeUrl::from_url(transfer)?;
+
+        let amount = self
+            .verify_transfer(&safeurl, &transfer_url, &mut Settlement::default())
+            .await?;
+
+        let already_deposited =
+            self.fetch_wallet_ops(&safeurl)
+                .await?
+                .into_iter()
+                .any(|op| match op {
+                    WalletOp::Credit { transfer, .. } => SafeUrl::from_url(&transfer)
+                        .is_ok_and(|url| same_transfer(&url, &transfer_url)),
+                    _ => false,
+                });
+        if already_deposited {
+            return Err(Error::InvalidInput(format!(
+                "The transfer {} was already deposited into Wallet at \"{}\"",
+                transfer, url
+            )));
+        }
+
+        let op = WalletOp::Credit {
+            amount,
+            transfer: transfer_url.to_xorurl_string(),
+        };
+        let _ = self.wallet_write_op(&safeurl, &op).await?;
+
+        Ok(amount)
+    }
+
+    /// Mint new tokens into a Wallet.
+    ///
+    /// The network doesn't validate the token supply, thus minted tokens are only meaningful
+    /// on development/test networks, where this is the way to get some tokens to play with.
+    /// It's only available with the `wallet-mint` feature, without which minted tokens
+    /// aren't accounted for in balances either.
+    #[cfg(feature = "wallet-mint")]
+    pub async fn wallet_mint(&self, url: &str, amount: &str) -> Result<EntryHash> {
+        let amount = parse_non_zero_amount(amount)?;
+        debug!("Minting {} into Wallet at: {}", amount, url);
+        let safeurl = self.parse_and_resolve_wallet_url(url).await?;
+
+        self.wallet_write_op(&safeurl, &WalletOp::Mint { amount })
+            .await
+    }
+
+    /// Return all the operations recorded in a Wallet on the network, latest first
+    pub async fn wallet_history(&self, url: &str) -> Result<Vec<(EntryHash, WalletOp)>> {
+        debug!("Getting history of Wallet at: {}", url);
+        let safeurl = self.parse_and_resolve_wallet_url(url).await?;
+
+        self.register_fetch_history(&safeurl)
+            .await?
+            .into_iter()
+            .map(|node| Ok((node.hash, decode_wallet_op(&node.entry)?)))
+            .collect()
+    }
+
+    // Crate's helper to compute the balance of a Wallet without resolving the SafeUrl
+    pub(crate) async fn fetch_wallet_balance(&self, safeurl: &SafeUrl) -> Result<Token> {
+        let ledger = self
+            .settle_wallet(safeurl, None, &mut Settlement::default())
+            .await?;
+        Ok(ledger.balance)
+    }
+
+    // Fetch and decode all operations of a Wallet without resolving the SafeUrl
+    async fn fetch_wallet_ops(&self, safeurl: &SafeUrl) -> Result<Vec<WalletOp>> {
+        self.register_fetch_history(safeurl)
+            .await?
+            .iter()
+            .map(|node| decode_wallet_op(&node.entry))
+            .collect()
+    }
+
+    // Replay the operations of a Wallet in causal order, up to and including the given
+    // entry if any. Debits not covered by the balance at their position are ignored, as
+    // are Credits of transfers which can't be verified.
+    fn settle_wallet<'a>(
+        &'a self,
+        safeurl: &'a SafeUrl,
+        until: Option<EntryHash>,
+        settlement: &'a mut Settlement,
+    ) -> BoxFuture<'a, Result<Ledger>> {
+        async move {
+            let wallet = (safeurl.xorname(), until);
+            if settlement.settling.contains(&wallet) {
+                return Err(Error::ContentError(format!(
+                    "The transfers of Wallet at \"{}\" depend on themselves",
+                    safeurl
+                )));
+            }
+            let history = match settlement.histories.get(&wallet.0) {
+                Some(history) => history.clone(),
+                None => {
+                    let history = causal_order(self.register_fetch_history(safeurl).await?);
+                    let _prev = settlement.histories.insert(wallet.0, history.clone());
+                    history
+                }
+            };
+
+            settlement.settling.push(wallet);
+            let ledger = self
+                .replay_wallet_ops(safeurl, &history, until, settlement)
+                .await;
+            let _ = settlement.settling.pop();
+            ledger
+        }
+        .boxed()
+    }
+
+    // Apply the operations of a Wallet's history, in causal order, to an empty ledger
+    async fn replay_wallet_ops(
+        &self,
+        safeurl: &SafeUrl,
+        history: &[EntryNode],
+        until: Option<EntryHash>,
+        settlement: &mut Settlement,
+    ) -> Result<Ledger> {
+        let mut ledger = Ledger::default();
+        let mut deposited: Vec<SafeUrl> = vec![];
+        let overflow = || Error::ContentError(format!("Wallet at \"{}\" overflows", safeurl));
+
+        for node in history {
+            match decode_wallet_op(&node.entry)? {
+                #[cfg(feature = "wallet-mint")]
+                WalletOp::Mint { amount } => {
+                    ledger.balance = ledger.balance.checked_add(amount).ok_or_else(overflow)?;
+                }
+                #[cfg(not(feature = "wallet-mint"))]
+                WalletOp::Mint { amount } => {
+                    warn!("Ignoring Mint {:?} of {}", node.hash, amount);
+                }
+                WalletOp::Debit { amount, .. } => match ledger.balance.checked_sub(amount) {
+                    Some(balance) => {
+                        ledger.balance = balance;
+                        let _ = ledger.debits.insert(node.hash);
+                    }
+                    None => warn!(
+                        "Ignoring Debit {:?} of {} which exceeds the balance of {}",
+                        node.hash, amount, ledger.balance
+                    ),
+                },
+                WalletOp::Credit { amount, transfer } => {
+                    let transfer_url = SafeUrl::from_url(&transfer)?;
+                    if deposited
+                        .iter()
+                        .any(|url| same_transfer(url, &transfer_url))
+                    {
+                        warn!("Ignoring duplicated deposit of transfer {}", transfer);
+                        continue;
+                    }
+                    match self
+                        .verify_transfer(safeurl, &transfer_url, settlement)
+                        .await
+                    {
+                        Ok(verified) if verified == amount => {
+                            ledger.balance =
+                                ledger.balance.checked_add(amount).ok_or_else(overflow)?;
+                            deposited.push(transfer_url);
+                        }
+                        Ok(verified) => warn!(
+                            "Ignoring deposit of {} from transfer {} which was of {}",
+                            amount, transfer, verified
+                        ),
+                        Err(err) => warn!("Ignoring deposit of transfer {}: {}", transfer, err),
+                    }
+                }
+            }
+
+            if Some(node.hash) == until {
+                return Ok(ledger);
+            }
+        }
+
+        match until {
+            Some(hash) => Err(Error::HashNotFound(hash)),
+            None => Ok(ledger),
+        }
+    }
+
+    // Check the transfer is a Debit made to the given Wallet, which was covered by the
+    // balance of the sender's Wallet, returning its amount
+    async fn verify_transfer(
+        &self,
+        wallet: &SafeUrl,
+        transfer: &SafeUrl,
+        settlement: &mut Settlement,
+    ) -> Result<Token> {
+        if transfer.content_type() != ContentType::Wallet {
+            return Err(Error::InvalidInput(format!(
+                "The transfer {} doesn't target a Wallet",
+                transfer
+            )));
+        }
+        let version = transfer.content_version().ok_or_else(|| {
+            Error::InvalidInput(format!(
+                "The transfer {} is missing the version of the Debit entry",
+                transfer
+            ))
+        })?;
+
+        let entry = self
+            .register_fetch_entry(transfer, version.entry_hash())
+            .await?;
+        let amount = match decode_wallet_op(&entry)? {
+            WalletOp::Debit { amount, to }
+                if SafeUrl::from_url(&to)?.xorname() == wallet.xorname() =>
+            {
+                amount
+            }
+            WalletOp::Debit { .. } => {
+                return Err(Error::InvalidInput(format!(
+                    "The transfer {} was not made to Wallet at \"{}\"",
+                    transfer, wallet
+                )))
+            }
+            _ => {
+                return Err(Error::InvalidInput(format!(
+                    "The entry at {} is not a transfer",
+                    transfer
+                )))
+            }
+        };
+
+        let sender = self
+            .settle_wallet(transfer, Some(version.entry_hash()), settlement)
+            .await?;
+        if !sender.debits.contains(&version.entry_hash()) {
+            return Err(Error::NotEnoughBalance(format!(
+                "The transfer {} exceeds the balance its Wallet had at the time",
+                transfer
+            )));
+        }
+
+        Ok(amount)
+    }
+
+    // Write an operation into a Wallet on top of all its current entries
+    async fn wallet_write_op(&self, safeurl: &SafeUrl, op: &WalletOp) -> Result<EntryHash> {
+        let data = rmp_serde::to_vec_named(op).map_err(|err| {
+            Error::Serialisation(format!(
+                "Couldn't serialise the Wallet operation '{:?}': {:?}",
+                op, err
+            ))
+        })?;
+
+        if self.dry_run_mode {
+            return Ok(EntryHash(rand::thread_rng().gen::<[u8; 32]>()));
+        }
+
+        let address = self.get_register_address(safeurl)?;
+        let mut latest = safeurl.clone();
+        latest.set_content_version(None);
+        let parents: BTreeSet<EntryHash> = match self.register_fetch_entries(&latest).await {
+            Ok(entries) => entries.into_iter().map(|(hash, _)| hash).collect(),
+            Err(Error::EmptyContent(_)) => BTreeSet::new(),
+            Err(err) => return Err(err),
+        };
+
+        let client = self.get_safe_client()?;
+        let (entry_hash, op_batch) = client.write_to_register(address, data, parents).await?;
+        client.publish_register_ops(op_batch).await?;
+
+        Ok(entry_hash)
+    }
+
+    async fn parse_and_resolve_wallet_url(&self, url: &str) -> Result<SafeUrl> {
+        let safeurl = self.parse_and_resolve_url(url).await?;
+        if safeurl.content_type() != ContentType::Wallet {
+            return Err(Error::InvalidInput(format!(
+                "The URL \"{}\" doesn't target a Wallet",
+                url
+            )));
+        }
+        Ok(safeurl)
+    }
+}
+
+fn decode_wallet_op(entry: &[u8]) -> Result<WalletOp> {
+    rmp_serde::from_slice(entry)
+        .map_err(|err| Error::ContentError(format!("Couldn't parse Wallet entry: {:?}", err)))
+}
+
+// Sort the entries of a Wallet so each of them comes after the entries it was written on top of.
+// Concurrent entries are sorted by hash, so that entries written later on top of them don't
+// change their relative order, nor thus which of the Debits are covered by the balance.
+fn causal_order(history: Vec<EntryNode>) -> Vec<EntryNode> {
+    let mut nodes: BTreeMap<EntryHash, EntryNode> =
+        history.into_iter().map(|node| (node.hash, node)).collect();
+
+    // Number of children of each entry yet to be ordered, and the entries on top of each one
+    let mut pending = BTreeMap::new();
+    let mut parents: BTreeMap<EntryHash, Vec<EntryHash>> = BTreeMap::new();
+    for node in nodes.values() {
+        let children: Vec<_> = node
+            .children
+            .iter()
+            .filter(|child| nodes.contains_key(child))
+            .collect();
+        for child in &children {
+            parents.entry(**child).or_default().push(node.hash);
+        }
+        let _prev = pending.insert(node.hash, children.len());
+    }
+
+    let mut ready: BTreeSet<EntryHash> = pending
+        .iter()
+        .filter(|(_, count)| **count == 0)
+        .map(|(hash, _)| *hash)
+        .collect();
+    let mut order = vec![];
+    while let Some(hash) = ready.iter().next().copied() {
+        let _ = ready.remove(&hash);
+        for parent in parents.remove(&hash).unwrap_or_default() {
+            if let Some(count) = pending.get_mut(&parent) {
+                *count -= 1;
+                if *count == 0 {
+                    let _ = ready.insert(parent);
+                }
+            }
+        }
+        if let Some(node) = nodes.remove(&hash) {
+            order.push(node);
+        }
+    }
+
+    order
+}
+
+fn parse_non_zero_amount(amount: &str) -> Result<Token> {
+    let amount = parse_tokens_amount(amount)?;
+    if amount == Token::zero() {
+        return Err(Error::InvalidAmount(
+            "The amount must be greater than zero".to_string(),
+        ));
+    }
+    Ok(amount)
+}
+
+// State of a Wallet resulting from the replay of its operations
+#[derive(Debug)]
+struct Ledger {
+    balance: Token,
+    // Debits which were covered by the balance, i.e. the valid transfers out of the Wallet
+    debits: BTreeSet<EntryHash>,
+}
+
+impl Default for Ledger {
+    fn default() -> Self {
+        Self {
+            balance: Token::zero(),
+            debits: BTreeSet::new(),
+        }
+    }
+}
+
+// Wallets involved in the computation of a balance, as the Credits of a Wallet are only
+// accounted for once the sender's Wallet is settled in turn
+#[derive(Default)]
+struct Settlement {
+    // Histories of the Wallets fetched so far, in causal order
+    histories: BTreeMap<XorName, Vec<EntryNode>>,
+    // Wallets being settled, along with the entry they're settled up to
+    settling: Vec<(XorName, Option<EntryHash>)>,
+}
+
+// Two transfer URLs refer to the same transfer if they point to the same Debit entry
+fn same_transfer(a: &SafeUrl, b: &SafeUrl) -> bool {
+    a.xorname() == b.xorname()
+        && a.type_tag() == b.type_tag()
+        && a.content_version() == b.content_version()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::app::test_helpers::new_safe_instance;
+    use anyhow::Result;
+    use safe_network::types::Token;
+
+    #[cfg(feature = "wallet-mint")]
+    use super::WalletOp;
+    #[cfg(feature = "wallet-mint")]
+    use crate::{retry_loop_for_pattern, Error, SafeUrl, VersionHash};
+    #[cfg(feature = "wallet-mint")]
+    use anyhow::bail;
+    #[cfg(feature = "wallet-mint")]
+    use std::collections::BTreeSet;
+
+    #[tokio::test]
+    async fn test_wallet_create() -> Result<()> {
+        let safe = new_safe_instance().await?;
+        let xorurl = safe.wallet_create().await?;
+
+        let balance = safe.wallet_balance(&xorurl).await?;
+        assert_eq!(balance, Token::zero());
+
+        Ok(())
+    }
+
+    #[cfg(feature = "wallet-mint")]
+    #[tokio::test]
+    async fn test_wallet_mint() -> Result<()> {
+        let safe = new_safe_instance().await?;
+        let xorurl = safe.wallet_create().await?;
+
+        let _ = safe.wallet_mint(&xorurl, "1.5").await?;
+        let balance =
+            retry_loop_for_pattern!(safe.wallet_balance(&xorurl), Ok(b) if *b != Token::zero())?;
+        assert_eq!(balance, Token::from_nano(1_500_000_000));
+
+        match safe.wallet_mint(&xorurl, "0").await {
+            Err(Error::InvalidAmount(_)) => Ok(()),
+            other => bail!("Unexpected result minting zero tokens: {:?}", other),
+        }
+    }
+
+    #[cfg(feature = "wallet-mint")]
+    #[tokio::test]
+    async fn test_wallet_transfer_and_deposit() -> Result<()> {
+        let safe = new_safe_instance().await?;
+        let from = safe.wallet_create().await?;
+        let to = safe.wallet_create().await?;
+
+        let _ = safe.wallet_mint(&from, "10").await?;
+        let _ = retry_loop_for_pattern!(safe.wallet_balance(&from), Ok(b) if *b != Token::zero())?;
+
+        match safe.wallet_transfer(&from, &to, "10.1").await {
+            Err(Error::NotEnoughBalance(_)) => {}
+            other => bail!("Unexpected result transferring too much: {:?}", other),
+        }
+
+        let transfer = safe.wallet_transfer(&from, &to, "2.5").await?;
+        assert!(SafeUrl::from_url(&transfer)?.content_version().is_some());
+        let balance = retry_loop_for_pattern!(safe.wallet_balance(&from), Ok(b) if *b != Token::from_nano(10_000_000_000))?;
+        assert_eq!(balance, Token::from_nano(7_500_000_000));
+
+        let amount = safe.wallet_deposit(&to, &transfer).await?;
+        assert_eq!(amount, Token::from_nano(2_500_000_000));
+        let balance =
+            retry_loop_for_pattern!(safe.wallet_balance(&to), Ok(b) if *b != Token::zero())?;
+        assert_eq!(balance, amount);
+
+        // the same transfer cannot be deposited twice, nor into a different wallet
+        let _ = retry_loop_for_pattern!(safe.wallet_history(&to), Ok(ops) if !ops.is_empty())?;
+        match safe.wallet_deposit(&to, &transfer).await {
+            Err(Error::InvalidInput(_)) => {}
+            other => bail!("Unexpected result depositing twice: {:?}", other),
+        }
+        match safe.wallet_deposit(&from, &transfer).await {
+            Err(Error::InvalidInput(_)) => {}
+            other => bail!("Unexpected result depositing into sender: {:?}", other),
+        }
+
+        let history = safe.wallet_history(&to).await?;
+        assert!(
+            matches!(history[0].1, WalletOp::Credit { amount, .. } if amount == Token::from_nano(2_500_000_000))
+        );
+
+        Ok(())
+    }
+
+    #[cfg(feature = "wallet-mint")]
+    #[tokio::test]
+    async fn test_wallet_concurrent_debits_settle_for_good() -> Result<()> {
+        let safe = new_safe_instance().await?;
+        let from = safe.wallet_create().await?;
+        let to = safe.wallet_create().await?;
+
+        let minted = safe.wallet_mint(&from, "10").await?;
+        let _ = retry_loop_for_pattern!(safe.wallet_balance(&from), Ok(b) if *b != Token::zero())?;
+
+        // two concurrent Debits, which together overdraw the Wallet
+        let debit = rmp_serde::to_vec_named(&WalletOp::Debit {
+            amount: Token::from_nano(6_000_000_000),
+            to: to.clone(),
+        })?;
+        let from_url = SafeUrl::from_url(&from)?;
+        let mut transfers = vec![];
+        for _ in 0..2 {
+            let hash = safe
+                .register_write(&from, debit.clone(), BTreeSet::from([minted]))
+                .await?;
+            let mut transfer_url = from_url.clone();
+            transfer_url.set_content_version(Some(VersionHash::from(&hash)));
+            transfers.push(transfer_url.to_xorurl_string());
+        }
+        let _ = retry_loop_for_pattern!(safe.wallet_history(&from), Ok(ops) if ops.len() == 3)?;
+        assert_eq!(
+            safe.wallet_balance(&from).await?,
+            Token::from_nano(4_000_000_000)
+        );
+
+        // only one of them is honoured, and it stays so once an entry is written on top of both
+        let mut honoured = vec![];
+        for transfer in &transfers {
+            match safe.wallet_deposit(&to, transfer).await {
+                Ok(_) => honoured.push(transfer.clone()),
+                Err(Error::NotEnoughBalance(_)) => {}
+                Err(err) => bail!("Unexpected error depositing a transfer: {:?}", err),
+            }
+        }
+        assert_eq!(honoured.len(), 1);
+
+        let _ = safe.wallet_mint(&from, "1").await?;
+        let _ = retry_loop_for_pattern!(safe.wallet_history(&from), Ok(ops) if ops.len() == 4)?;
+        assert_eq!(
+            safe.wallet_balance(&from).await?,
+            Token::from_nano(5_000_000_000)
+        );
+        let _ = retry_loop_for_pattern!(safe.wallet_history(&to), Ok(ops) if !ops.is_empty())?;
+        assert_eq!(
+            safe.wallet_balance(&to).await?,
+            Token::from_nano(6_000_000_000)
+        );
+        for transfer in transfers.iter().filter(|t| !honoured.contains(t)) {
+            match safe.wallet_deposit(&to, transfer).await {
+                Err(Error::NotEnoughBalance(_)) => {}
+                other => bail!(
+                    "Unexpected result depositing the other transfer: {:?}",
+                    other
+                ),
+            }
+        }
+
+        Ok(())
+    }
+
+    #[cfg(feature = "wallet-mint")]
+    #[tokio::test]
+    async fn test_wallet_overdrawing_transfers() -> Result<()> {
+        let safe = new_safe_instance().await?;
+        let from = safe.wallet_create().await?;
+        let to = safe.wallet_create().await?;
+
+        let _ = safe.wallet_mint(&from, "10").await?;
+        let _ = retry_loop_for_pattern!(safe.wallet_balance(&from), Ok(b) if *b != Token::zero())?;
+
+        // write the Debits without checking the balance, as two concurrent transfers would
+        let from_url = SafeUrl::from_url(&from)?;
+        let debit = WalletOp::Debit {
+            amount: Token::from_nano(6_000_000_000),
+            to: to.clone(),
+        };
+        let mut transfers = vec![];
+        for _ in 0..2 {
+            let hash = safe.wallet_write_op(&from_url, &debit).await?;
+            let _ = retry_loop_for_pattern!(safe.wallet_history(&from), Ok(ops) if ops.iter().any(|(h, _)| *h == hash))?;
+            let mut transfer_url = from_url.clone();
+            transfer_url.set_content_version(Some(VersionHash::from(&hash)));
+            transfers.push(transfer_url.to_xorurl_string());
+        }
+
+        // the second Debit isn't covered by the balance, thus it's ignored
+        let balance = safe.wallet_balance(&from).await?;
+        assert_eq!(balance, Token::from_nano(4_000_000_000));
+
+        let amount = safe.wallet_deposit(&to, &transfers[0]).await?;
+        assert_eq!(amount, Token::from_nano(6_000_000_000));
+        match safe.wallet_deposit(&to, &transfers[1]).await {
+            Err(Error::NotEnoughBalance(_)) => {}
+            other => bail!(
+                "Unexpected result depositing an overdrawing transfer: {:?}",
+                other
+            ),
+        }
+
+        let balance =
+            retry_loop_for_pattern!(safe.wallet_balance(&to), Ok(b) if *b != Token::zero())?;
+        assert_eq!(balance, amount);
+
+        Ok(())
+    }
+}
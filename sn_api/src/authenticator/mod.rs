@@ -7,18 +7,29 @@
 // permissions and limitations relating to use of the SAFE Network Software.
 
 use crate::{
-    ipc::{req::AuthReq, resp::AuthGranted},
-    Result, SafeAuthReq,
+    common::auth_types::{AuthedApp, AuthedAppsList},
+    ipc::{
+        req::{AuthReq, IpcReq},
+        resp::{AuthGranted, IpcResp},
+        IpcMsg, NodeConfig,
+    },
+    Error, Result, SafeAuthReq,
 };
 use hmac::Hmac;
-use rand::rngs::StdRng;
+use log::{debug, info, trace};
+use rand::rngs::{OsRng, StdRng};
 use rand_core::SeedableRng;
-use safe_network::client::client_api::Client;
-use safe_network::types::{Keypair, RegisterAddress};
+use safe_network::{
+    client::{client_api::Client, ClientConfig, Error as ClientError, ErrorMsg},
+    types::{
+        register::{Entry, EntryHash, Policy, PrivatePermissions, PrivatePolicy, User},
+        Keypair, RegisterAddress,
+    },
+};
+use serde::{Deserialize, Serialize};
 use sha3::Sha3_256;
 use std::{
-    collections::HashSet,
-    net::SocketAddr,
+    collections::{BTreeMap, BTreeSet},
     path::{Path, PathBuf},
 };
 use tiny_keccak::{Hasher, Sha3};
@@ -26,8 +37,7 @@ use xor_name::{XorName, XOR_NAME_LEN};
 
 const SHA3_512_HASH_LEN: usize = 64;
 
-// Type tag value used for the Map which holds the Safe's content on the network.
-#[allow(dead_code)]
+// Type tag value used for the Register which holds the Safe's content on the network.
 const SAFE_TYPE_TAG: u64 = 1_300;
 
 /// Derive Passphrase, Password and Salt (in order).
@@ -89,32 +99,41 @@ pub fn generate_network_address(passphrase: &[u8], salt: &[u8]) -> Result<XorNam
     Ok(id)
 }
 
+// Each entry in the Safe's Register holds the information of an authorised app.
+// An app's entry is superseded by writing a new one for it which has the former
+// entry as child, thus the Register's current entries always reflect the latest
+// state of the apps known to the Safe. A revoked app's entry has no keypair.
+#[derive(Serialize, Deserialize, Debug)]
+struct AppEntry {
+    id: String,
+    name: String,
+    vendor: String,
+    keypair: Option<Keypair>,
+}
+
+// Apps known to a Safe, mapped from their app id to their latest entry,
+// along with the hashes of the Register entries holding it.
+type AppEntries = BTreeMap<String, (BTreeSet<EntryHash>, AppEntry)>;
+
 // Authenticator API
 #[derive(Default)]
 pub struct SafeAuthenticator {
     // We keep the client instantiated with the derived keypair, along
-    // with the address of the Map which holds its Safe on the network.
-    #[allow(dead_code)]
+    // with the address of the Register which holds its Safe on the network.
     safe: Option<(Client, RegisterAddress)>,
-    #[allow(dead_code)]
     config_path: Option<PathBuf>,
-    #[allow(dead_code)]
-    bootstrap_contacts: Option<HashSet<SocketAddr>>,
+    bootstrap_config: Option<NodeConfig>,
 }
 
 impl SafeAuthenticator {
-    pub fn new(
-        _config_dir_path: Option<&Path>,
-        _bootstrap_contacts: Option<HashSet<SocketAddr>>,
-    ) -> Self {
-        unimplemented!("Authenticator hasn't yet been updated to work with the new Safe Network");
-        // let config_path = config_dir_path.map(|p| p.to_path_buf());
-
-        // Self {
-        //     safe: None,
-        //     config_path,
-        //     bootstrap_contacts,
-        // }
+    pub fn new(config_dir_path: Option<&Path>, bootstrap_config: Option<NodeConfig>) -> Self {
+        let config_path = config_dir_path.map(|p| p.to_path_buf());
+
+        Self {
+            safe: None,
+            config_path,
+            bootstrap_config,
+        }
     }
 
     /// # Create Safe
@@ -129,15 +148,14 @@ impl SafeAuthenticator {
     /// ## Example
     /// ```ignore
     /// use sn_api::SafeAuthenticator;
-    /// let mut safe_auth = SafeAuthenticator::new(None);
+    /// let mut safe_auth = SafeAuthenticator::new(None, None);
     /// # fn random_str() -> String { (0..4).map(|_| rand::random::<char>()).collect() }
     /// let my_secret = "mysecretstring";
     /// let my_password = "mypassword";
     /// # let my_secret = &(random_str());
     /// # let my_password = &(random_str());
-    /// # let sk = "83c055c5efdc483bd967adba5c1769daee0a17bc5fa2b6e129cd6b596c217617";
     /// # async_std::task::block_on(async {
-    /// let acc_created = safe_auth.create(sk, my_secret, my_password).await;
+    /// let acc_created = safe_auth.create(my_secret, my_password).await;
     /// match acc_created {
     ///    Ok(()) => assert!(true), // This should pass
     ///    Err(_) => assert!(false)
@@ -150,17 +168,16 @@ impl SafeAuthenticator {
     /// the function will return an error:
     /// ```ignore
     /// use sn_api::{SafeAuthenticator, Error};
-    /// let mut safe_auth = SafeAuthenticator::new(None);
+    /// let mut safe_auth = SafeAuthenticator::new(None, None);
     /// # fn random_str() -> String { (0..4).map(|_| rand::random::<char>()).collect() }
     /// /// Using an already existing Safe's passphrase and password:
     /// let my_secret = "mysecretstring";
     /// let my_password = "mypassword";
     /// # let my_secret = &(random_str());
     /// # let my_password = &(random_str());
-    /// # let sk = "83c055c5efdc483bd967adba5c1769daee0a17bc5fa2b6e129cd6b596c217617";
     /// # async_std::task::block_on(async {
-    /// # safe_auth.create(sk, my_secret, my_password).await.unwrap();
-    /// let acc_not_created = safe_auth.create(sk, my_secret, my_password).await;
+    /// # safe_auth.create(my_secret, my_password).await.unwrap();
+    /// let acc_not_created = safe_auth.create(my_secret, my_password).await;
     /// match acc_not_created {
     ///    Ok(_) => assert!(false), // This should not pass
     ///    Err(Error::AuthError(message)) => {
@@ -170,53 +187,64 @@ impl SafeAuthenticator {
     /// }
     /// # });
     ///```
-    pub async fn create(&mut self, _passphrase: &str, _password: &str) -> Result<()> {
-        unimplemented!("Authenticator hasn't yet been updated to work with the new Safe Network");
-        // debug!("Attempting to create a Safe from provided passphrase and password.");
-
-        // let (location, keypair) = derive_location_and_keypair(passphrase, password)?;
-        // let data_owner = keypair.public_key();
-
-        // debug!("Creating Safe to be owned by PublicKey: {:?}", data_owner);
-
-        // let client = Client::new(
-        //     Some(keypair),
-        //     self.config_path.as_deref(),
-        //     self.bootstrap_contacts.clone(),
-        //     DEFAULT_OPERATION_TIMEOUT,
-        // )
-        // .await?;
-        // trace!("Client instantiated properly!");
-
-        // // Create Map data to store the list of keypairs generated for
-        // // each of the user's applications.
-        // let permission_set = MapPermissionSet::new()
-        //     .allow(MapAction::Read)
-        //     .allow(MapAction::Insert)
-        //     .allow(MapAction::Update)
-        //     .allow(MapAction::Delete)
-        //     .allow(MapAction::ManagePermissions);
-
-        // let mut permission_map = BTreeMap::new();
-        // permission_map.insert(data_owner, permission_set);
-
-        // // TODO: encrypt content
-        // let map_address = client
-        //     .store_seq_map(
-        //         location,
-        //         SAFE_TYPE_TAG,
-        //         data_owner,
-        //         None,
-        //         Some(permission_map),
-        //     )
-        //     .await
-        //     .map_err(|err| {
-        //         Error::AuthenticatorError(format!("Failed to store Safe on a Map: {}", err))
-        //     })?;
-        // debug!("Map stored successfully for new Safe!");
-
-        // self.safe = Some((client, map_address));
-        // Ok(())
+    pub async fn create(&mut self, passphrase: &str, password: &str) -> Result<()> {
+        debug!("Attempting to create a Safe from provided passphrase and password.");
+
+        let (location, keypair) = derive_location_and_keypair(passphrase, password)?;
+        let data_owner = User::Key(keypair.public_key());
+
+        debug!("Creating Safe to be owned by PublicKey: {:?}", data_owner);
+
+        let client = self.connect(keypair).await?;
+        trace!("Client instantiated properly!");
+
+        let address = RegisterAddress::Private {
+            name: location,
+            tag: SAFE_TYPE_TAG,
+        };
+
+        // Make sure we are not overwriting an existing Safe
+        match client.get_register(address).await {
+            Ok(_) => {
+                return Err(Error::AuthError(
+                    "Failed to create a Safe: a Safe already exists with the provided credentials"
+                        .to_string(),
+                ))
+            }
+            Err(ClientError::ErrorMsg {
+                source: ErrorMsg::DataNotFound(_),
+                ..
+            }) => {}
+            Err(err) => {
+                return Err(Error::AuthError(format!(
+                    "Failed to create a Safe: {}",
+                    err
+                )))
+            }
+        }
+
+        // Create a private Register to store the list of keypairs
+        // generated for each of the user's applications.
+        let mut permissions = BTreeMap::new();
+        let _ = permissions.insert(data_owner, PrivatePermissions::new(true, true));
+        let policy = Policy::Private(PrivatePolicy {
+            owner: data_owner,
+            permissions,
+        });
+
+        let (_, op_batch) = client
+            .create_register(location, SAFE_TYPE_TAG, policy)
+            .await
+            .map_err(|err| {
+                Error::AuthError(format!("Failed to create a Safe on a Register: {}", err))
+            })?;
+        client.publish_register_ops(op_batch).await.map_err(|err| {
+            Error::AuthError(format!("Failed to create a Safe on a Register: {}", err))
+        })?;
+        debug!("Register stored successfully for new Safe!");
+
+        self.safe = Some((client, address));
+        Ok(())
     }
 
     /// # Unlock
@@ -226,16 +254,15 @@ impl SafeAuthenticator {
     /// ## Example
     /// ```ignore
     /// use sn_api::SafeAuthenticator;
-    /// let mut safe_auth = SafeAuthenticator::new(None);
+    /// let mut safe_auth = SafeAuthenticator::new(None, None);
     /// # fn random_str() -> String { (0..4).map(|_| rand::random::<char>()).collect() }
     /// /// Using an already existing Safe's passphrase and password:
     /// let my_secret = "mysecretstring";
     /// let my_password = "mypassword";
     /// # let my_secret = &(random_str());
     /// # let my_password = &(random_str());
-    /// # let sk = "83c055c5efdc483bd967adba5c1769daee0a17bc5fa2b6e129cd6b596c217617";
     /// # async_std::task::block_on(async {
-    /// # safe_auth.create(sk, my_secret, my_password).await.unwrap();
+    /// # safe_auth.create(my_secret, my_password).await.unwrap();
     /// let logged_in = safe_auth.unlock(my_secret, my_password).await;
     /// match logged_in {
     ///    Ok(()) => assert!(true), // This should pass
@@ -248,7 +275,7 @@ impl SafeAuthenticator {
     /// If the Safe does not exist, the function will return an appropriate error:
     ///```ignore
     /// use sn_api::{SafeAuthenticator, Error};
-    /// let mut safe_auth = SafeAuthenticator::new(None);
+    /// let mut safe_auth = SafeAuthenticator::new(None, None);
     /// # async_std::task::block_on(async {
     /// let not_logged_in = safe_auth.unlock("non", "existant").await;
     /// match not_logged_in {
@@ -260,260 +287,448 @@ impl SafeAuthenticator {
     /// }
     /// # });
     ///```
-    pub async fn unlock(&mut self, _passphrase: &str, _password: &str) -> Result<()> {
-        unimplemented!("Authenticator hasn't yet been updated to work with the new Safe Network");
-
-        // debug!("Attempting to unlock a Safe...");
-
-        // let (location, keypair) = derive_location_and_keypair(passphrase, password)?;
-
-        // debug!(
-        //     "Unlocking Safe owned by PublicKey: {:?}",
-        //     keypair.public_key()
-        // );
-
-        // let client = Client::new(
-        //     Some(keypair),
-        //     self.config_path.as_deref(),
-        //     self.bootstrap_contacts.clone(),
-        //     DEFAULT_OPERATION_TIMEOUT,
-        // )
-        // .await?;
-        // trace!("Client instantiated properly!");
-
-        // let map_address = MapAddress::Seq {
-        //     name: location,
-        //     tag: SAFE_TYPE_TAG,
-        // };
-
-        // // Attempt to retrieve Map to make sure it actually exists
-        // let _ = client.get_map(map_address).await?;
-        // debug!("Safe unlocked successfully!");
-
-        // self.safe = Some((client, map_address));
-        // Ok(())
+    pub async fn unlock(&mut self, passphrase: &str, password: &str) -> Result<()> {
+        debug!("Attempting to unlock a Safe...");
+
+        let (location, keypair) = derive_location_and_keypair(passphrase, password)?;
+
+        debug!(
+            "Unlocking Safe owned by PublicKey: {:?}",
+            keypair.public_key()
+        );
+
+        let client = self.connect(keypair).await?;
+        trace!("Client instantiated properly!");
+
+        let address = RegisterAddress::Private {
+            name: location,
+            tag: SAFE_TYPE_TAG,
+        };
+
+        // Attempt to retrieve the Register to make sure it actually exists
+        let _ = client
+            .get_register(address)
+            .await
+            .map_err(|err| Error::AuthError(format!("Failed to log in: {}", err)))?;
+        debug!("Safe unlocked successfully!");
+
+        self.safe = Some((client, address));
+        Ok(())
     }
 
     pub fn lock(&mut self) -> Result<()> {
-        unimplemented!("Authenticator hasn't yet been updated to work with the new Safe Network");
-
-        // debug!("Locking Safe...");
-        // self.safe = None;
-        // Ok(())
+        debug!("Locking Safe...");
+        self.safe = None;
+        Ok(())
     }
 
     pub fn is_a_safe_unlocked(&self) -> bool {
-        unimplemented!("Authenticator hasn't yet been updated to work with the new Safe Network");
-
-        // let is_a_safe_unlocked = self.safe.is_some();
-        // debug!(
-        //     "Is there a Safe currently unlocked?: {}",
-        //     is_a_safe_unlocked
-        // );
-        // is_a_safe_unlocked
+        let is_a_safe_unlocked = self.safe.is_some();
+        debug!(
+            "Is there a Safe currently unlocked?: {}",
+            is_a_safe_unlocked
+        );
+        is_a_safe_unlocked
+    }
+
+    pub async fn decode_req(&self, req: &str) -> Result<SafeAuthReq> {
+        match IpcMsg::from_string(req) {
+            Ok(IpcMsg::Req(IpcReq::Auth(app_auth_req))) => {
+                debug!("Auth request string decoded: {:?}", app_auth_req);
+                Ok(SafeAuthReq::Auth(app_auth_req))
+            }
+            Ok(other) => Err(Error::AuthError(format!(
+                "Failed to decode string as an authorisation request, it's a: '{:?}'",
+                other
+            ))),
+            Err(error) => Err(Error::AuthenticatorError(format!(
+                "Failed to decode request: {:?}",
+                error
+            ))),
+        }
     }
 
-    pub async fn decode_req(&self, _req: &str) -> Result<SafeAuthReq> {
-        unimplemented!("Authenticator hasn't yet been updated to work with the new Safe Network");
-
-        // match IpcMsg::from_string(req) {
-        //     Ok(IpcMsg::Req(IpcReq::Auth(app_auth_req))) => {
-        //         debug!("Auth request string decoded: {:?}", app_auth_req);
-        //         Ok(SafeAuthReq::Auth(app_auth_req))
-        //     }
-        //     Ok(other) => Err(Error::AuthError(format!(
-        //         "Failed to decode string as an authorisation request, it's a: '{:?}'",
-        //         other
-        //     ))),
-        //     Err(error) => Err(Error::AuthenticatorError(format!(
-        //         "Failed to decode request: {:?}",
-        //         error
-        //     ))),
-        // }
+    /// Get the list of applications currently authorised in the unlocked Safe.
+    pub async fn authed_apps(&self) -> Result<AuthedAppsList> {
+        let (client, address) = self.unlocked_safe()?;
+        let apps = read_app_entries(client, *address).await?;
+
+        Ok(apps
+            .into_values()
+            .filter(|(_, app)| app.keypair.is_some())
+            .map(|(_, app)| AuthedApp {
+                id: app.id,
+                name: app.name,
+                vendor: app.vendor,
+            })
+            .collect())
     }
 
-    // TODO: update terminology around apps auth here
-    pub async fn revoke_app(&self, _y: &str) -> Result<()> {
-        unimplemented!()
+    /// Revoke the keypair assigned to an application. If the application is authorised
+    /// again afterwards, a new keypair is generated for it.
+    pub async fn revoke_app(&self, app_id: &str) -> Result<()> {
+        debug!("Revoking keypair of application: {}", app_id);
+        let (client, address) = self.unlocked_safe()?;
+        let mut apps = read_app_entries(client, *address).await?;
+
+        match apps.remove(app_id) {
+            Some((hashes, mut app)) if app.keypair.is_some() => {
+                app.keypair = None;
+                write_app_entry(client, *address, &app, hashes).await?;
+                debug!("Keypair of application ('{}') revoked", app_id);
+                Ok(())
+            }
+            _ => Err(Error::AuthError(format!(
+                "No application with id '{}' is currently authorised in the Safe",
+                app_id
+            ))),
+        }
     }
 
     /// Decode requests and trigger application authorisation against the current client
-    pub async fn authorise_app(&self, _req: &str) -> Result<String> {
-        unimplemented!("Authenticator hasn't yet been updated to work with the new Safe Network");
-
-        // let ipc_req = IpcMsg::from_string(req).map_err(|err| {
-        //     Error::AuthenticatorError(format!("Failed to decode authorisation request: {:?}", err))
-        // })?;
-
-        // debug!("Auth request string decoded: {:?}", ipc_req);
-
-        // match ipc_req {
-        //     IpcMsg::Req(IpcReq::Auth(app_auth_req)) => {
-        //         info!("Request was recognised as an application auth request");
-        //         debug!("Decoded request: {:?}", app_auth_req);
-        //         self.gen_auth_response(app_auth_req).await
-        //     }
-        //     IpcMsg::Req(IpcReq::Unregistered(user_data)) => {
-        //         info!("Request was recognised as an unregistered auth request");
-        //         debug!("Decoded request: {:?}", user_data);
-
-        //         self.gen_unreg_auth_response()
-        //     }
-        //     IpcMsg::Resp { .. } | IpcMsg::Err(..) => Err(Error::AuthError(
-        //         "The request was not recognised as a valid auth request".to_string(),
-        //     )),
-        // }
+    pub async fn authorise_app(&self, req: &str) -> Result<String> {
+        let ipc_req = IpcMsg::from_string(req).map_err(|err| {
+            Error::AuthenticatorError(format!("Failed to decode authorisation request: {:?}", err))
+        })?;
+
+        debug!("Auth request string decoded: {:?}", ipc_req);
+
+        match ipc_req {
+            IpcMsg::Req(IpcReq::Auth(app_auth_req)) => {
+                info!("Request was recognised as an application auth request");
+                debug!("Decoded request: {:?}", app_auth_req);
+                self.gen_auth_response(app_auth_req).await
+            }
+            IpcMsg::Req(IpcReq::Unregistered(user_data)) => {
+                info!("Request was recognised as an unregistered auth request");
+                debug!("Decoded request: {:?}", user_data);
+
+                self.gen_unreg_auth_response()
+            }
+            IpcMsg::Resp { .. } | IpcMsg::Err(..) => Err(Error::AuthError(
+                "The request was not recognised as a valid auth request".to_string(),
+            )),
+        }
     }
 
     /// Authenticate an app request.
     ///
     /// First, this function searches for an app info in the Safe.
     /// If the app is found, then the `AuthGranted` struct is returned based on that information.
-    /// If the app is not found in the Safe, then it will be authenticated.
-    pub async fn authenticate(&self, _auth_req: AuthReq) -> Result<AuthGranted> {
-        unimplemented!("Authenticator hasn't yet been updated to work with the new Safe Network");
-
-        // debug!(
-        //     "Retrieving/generating keypair for an application: {:?}",
-        //     auth_req
-        // );
-        // if let Some((client, map_address)) = &self.safe {
-        //     let app_id = auth_req.app_id.as_bytes().to_vec();
-        //     let keypair = match client.get_map_value(*map_address, app_id.clone()).await {
-        //         Ok(value) => {
-        //             // This app already has its own keypair
-        //             trace!(
-        //                 "The app ('{}') already has a Keypair in the Safe",
-        //                 auth_req.app_id
-        //             );
-
-        //             // TODO: support for scenario when app was previously revoked,
-        //             // in which case we should generate a new keypair
-
-        //             let keypair_bytes = match value {
-        //                 MapValue::Seq(seq_value) => seq_value.data,
-        //                 MapValue::Unseq(data) => data,
-        //             };
-        //             let keypair_str = String::from_utf8(keypair_bytes).map_err(|_err| {
-        //                 Error::AuthError(
-        //                     "The Safe contains an invalid keypair associated to this app"
-        //                         .to_string(),
-        //                 )
-        //             })?;
-        //             let keypair: Keypair = serde_json::from_str(&keypair_str).map_err(|_err| {
-        //                 Error::AuthError(
-        //                     "The Safe contains an invalid keypair associated to this app"
-        //                         .to_string(),
-        //                 )
-        //             })?;
-
-        //             debug!(
-        //                 "Keypair for the app being authorised ('{}') retrieved from the Safe: {}",
-        //                 auth_req.app_id,
-        //                 keypair.public_key()
-        //             );
-
-        //             keypair
-        //         }
-        //         Err(ClientError::ErrorMsg {
-        //             source: NoSuchEntry,
-        //             ..
-        //         }) => {
-        //             // This is the first time this app is being authorised,
-        //             // thus let's generate a keypair for it
-        //             trace!(
-        //                 "The app ('{}') was not assigned a Keypair yet in the Safe. Generating one for it...",
-        //                 auth_req.app_id
-        //             );
-        //             let mut rng = OsRng;
-        //             let keypair = Keypair::new_ed25519(&mut rng);
-
-        //             let keypair_str = serde_json::to_string(&keypair).map_err(|err| {
-        //                 Error::AuthError(format!(
-        //                     "Failed to serialised keypair to store it in the Safe: {}",
-        //                     err
-        //                 ))
-        //             })?;
-
-        //             debug!(
-        //                 "New keypair generated for app ('{}') being authorised: {}",
-        //                 auth_req.app_id,
-        //                 keypair.public_key()
-        //             );
-
-        //             // Store the keypair in the Safe, mapped to the app id
-        //             let map_actions =
-        //                 MapSeqEntryActions::new().ins(app_id, keypair_str.as_bytes().to_vec(), 0);
-
-        //             client
-        //                 .edit_map_entries(*map_address, MapEntryActions::Seq(map_actions))
-        //                 .await?;
-
-        //             keypair
-        //         }
-        //         Err(err) => {
-        //             return Err(Error::AuthError(format!(
-        //                 "Failed to retrieve keypair from the Safe: {}",
-        //                 err
-        //             )))
-        //         }
-        //     };
-
-        //     Ok(AuthGranted {
-        //         app_keypair: keypair,
-        //         bootstrap_config: self.bootstrap_contacts.clone(),
-        //     })
-        // } else {
-        //     Err(Error::AuthenticatorError(
-        //         "No Safe is currently unlocked".to_string(),
-        //     ))
-        // }
+    /// If the app is not found in the Safe, or its keypair was revoked, then it will be authenticated.
+    pub async fn authenticate(&self, auth_req: AuthReq) -> Result<AuthGranted> {
+        debug!(
+            "Retrieving/generating keypair for an application: {:?}",
+            auth_req
+        );
+        let (client, address) = self.unlocked_safe()?;
+        let mut apps = read_app_entries(client, *address).await?;
+
+        let keypair = match apps.remove(&auth_req.app_id) {
+            Some((
+                _,
+                AppEntry {
+                    keypair: Some(keypair),
+                    ..
+                },
+            )) => {
+                // This app already has its own keypair
+                debug!(
+                    "Keypair for the app being authorised ('{}') retrieved from the Safe: {}",
+                    auth_req.app_id,
+                    keypair.public_key()
+                );
+                keypair
+            }
+            revoked_app => {
+                // This is the first time this app is being authorised, or its
+                // keypair was revoked, thus let's generate a keypair for it
+                trace!(
+                    "The app ('{}') has no Keypair assigned in the Safe. Generating one for it...",
+                    auth_req.app_id
+                );
+                let mut rng = OsRng;
+                let keypair = Keypair::new_ed25519(&mut rng);
+
+                debug!(
+                    "New keypair generated for app ('{}') being authorised: {}",
+                    auth_req.app_id,
+                    keypair.public_key()
+                );
+
+                // Store the keypair in the Safe, superseding the revoked entry if any
+                let hashes = revoked_app.map(|(hashes, _)| hashes).unwrap_or_default();
+                let app = AppEntry {
+                    id: auth_req.app_id,
+                    name: auth_req.app_name,
+                    vendor: auth_req.app_vendor,
+                    keypair: Some(keypair.clone()),
+                };
+                write_app_entry(client, *address, &app, hashes).await?;
+
+                keypair
+            }
+        };
+
+        Ok(AuthGranted {
+            app_keypair: keypair,
+            bootstrap_config: self.bootstrap_config.clone(),
+        })
+    }
+
+    // Helper function to instantiate a client with the given keypair
+    async fn connect(&self, keypair: Keypair) -> Result<Client> {
+        let (genesis_key, bootstrap_nodes) = self.bootstrap_config.clone().ok_or_else(|| {
+            Error::AuthenticatorError("Bootstrap contacts information not available".to_string())
+        })?;
+
+        let config = ClientConfig::new(
+            None,
+            None,
+            genesis_key,
+            self.config_path.as_deref(),
+            None,
+            None,
+            None,
+        )
+        .await;
+
+        Client::new(config, bootstrap_nodes, Some(keypair))
+            .await
+            .map_err(|err| {
+                Error::ConnectionError(format!("Failed to connect to the SAFE Network: {:?}", err))
+            })
+    }
+
+    // Helper function to obtain the client and Register address of the unlocked Safe
+    fn unlocked_safe(&self) -> Result<&(Client, RegisterAddress)> {
+        self.safe
+            .as_ref()
+            .ok_or_else(|| Error::AuthenticatorError("No Safe is currently unlocked".to_string()))
     }
 
     // Helper function to generate an app authorisation response
-    // async fn gen_auth_response(&self, auth_req: AuthReq) -> Result<String> {
-    //     let auth_granted = self.authenticate(auth_req).await.map_err(|err| {
-    //         Error::AuthenticatorError(format!(
-    //             "Failed to authorise application on the network: {}",
-    //             err
-    //         ))
-    //     })?;
+    async fn gen_auth_response(&self, auth_req: AuthReq) -> Result<String> {
+        let auth_granted = self.authenticate(auth_req).await.map_err(|err| {
+            Error::AuthenticatorError(format!(
+                "Failed to authorise application on the network: {}",
+                err
+            ))
+        })?;
+
+        debug!("Encoding response with auth credentials auth granted...");
+        let resp = serde_json::to_string(&IpcMsg::Resp(IpcResp::Auth(Ok(auth_granted)))).map_err(
+            |err| Error::AuthenticatorError(format!("Failed to encode response: {:?}", err)),
+        )?;
+
+        debug!("Returning auth response generated");
+
+        Ok(resp)
+    }
 
-    //     debug!("Encoding response with auth credentials auth granted...");
-    //     let resp = serde_json::to_string(&IpcMsg::Resp(IpcResp::Auth(Ok(auth_granted)))).map_err(
-    //         |err| Error::AuthenticatorError(format!("Failed to encode response: {:?}", err)),
-    //     )?;
+    // Helper function to generate an unregistered authorisation response
+    fn gen_unreg_auth_response(&self) -> Result<String> {
+        let bootstrap_config = self.bootstrap_config.clone().ok_or_else(|| {
+            Error::AuthenticatorError("Bootstrap contacts information not available".to_string())
+        })?;
+
+        debug!("Encoding response... {:?}", bootstrap_config);
+        let resp =
+            serde_json::to_string(&IpcMsg::Resp(IpcResp::Unregistered(Ok(bootstrap_config))))
+                .map_err(|err| {
+                    Error::AuthenticatorError(format!("Failed to encode response: {:?}", err))
+                })?;
+
+        debug!("Returning unregistered auth response generated: {:?}", resp);
+        Ok(resp)
+    }
+}
 
-    //     debug!("Returning auth response generated");
+// Read the latest entry of each of the apps stored in the Safe's Register
+async fn read_app_entries(client: &Client, address: RegisterAddress) -> Result<AppEntries> {
+    let entries = client.read_register(address).await.map_err(|err| {
+        Error::AuthError(format!(
+            "Failed to read the apps list from the Safe: {}",
+            err
+        ))
+    })?;
+
+    let mut apps = AppEntries::new();
+    for (hash, entry) in entries {
+        let app: AppEntry = rmp_serde::from_slice(&entry).map_err(|_err| {
+            Error::AuthError("The Safe contains an invalid entry for an app".to_string())
+        })?;
+
+        // Concurrent writes for the same app leave several current entries,
+        // we keep track of all of them so the next write supersedes them all.
+        let (hashes, _) = apps
+            .entry(app.id.clone())
+            .or_insert_with(|| (BTreeSet::new(), app));
+        let _ = hashes.insert(hash);
+    }
 
-    //     Ok(resp)
-    // }
+    Ok(apps)
+}
 
-    // Helper function to generate an unregistered authorisation response
-    // fn gen_unreg_auth_response(&self) -> Result<String> {
-    //     let bootstrap_contacts = self.bootstrap_contacts.clone().ok_or_else(|| {
-    //         Error::AuthenticatorError("Bootstrap contacts information not available".to_string())
-    //     })?;
-
-    //     debug!("Encoding response... {:?}", bootstrap_contacts);
-    //     let resp =
-    //         serde_json::to_string(&IpcMsg::Resp(IpcResp::Unregistered(Ok(bootstrap_contacts))))
-    //             .map_err(|err| {
-    //                 Error::AuthenticatorError(format!("Failed to encode response: {:?}", err))
-    //             })?;
-
-    //     debug!("Returning unregistered auth response generated: {:?}", resp);
-    //     Ok(resp)
-    // }
+// Store an app's entry in the Safe's Register, superseding the entries with given hashes
+async fn write_app_entry(
+    client: &Client,
+    address: RegisterAddress,
+    app: &AppEntry,
+    children: BTreeSet<EntryHash>,
+) -> Result<()> {
+    let entry: Entry = rmp_serde::to_vec_named(app).map_err(|err| {
+        Error::Serialisation(format!(
+            "Failed to serialise app entry to store it in the Safe: {}",
+            err
+        ))
+    })?;
+
+    let (_, op_batch) = client
+        .write_to_register(address, entry, children)
+        .await
+        .map_err(|err| Error::AuthError(format!("Failed to store app in the Safe: {}", err)))?;
+    client
+        .publish_register_ops(op_batch)
+        .await
+        .map_err(|err| Error::AuthError(format!("Failed to store app in the Safe: {}", err)))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use anyhow::{Context, Result};
+    use crate::{
+        app::test_helpers::{get_bootstrap_contacts, random_nrs_name},
+        retry_loop, retry_loop_for_pattern,
+    };
+    use anyhow::{bail, Context, Result};
     use proptest::prelude::*;
     use safe_network::types::PublicKey;
 
+    // Instantiate an authenticator with a Safe created from random credentials
+    async fn new_unlocked_authenticator() -> Result<(SafeAuthenticator, String, String)> {
+        let mut safe_auth = SafeAuthenticator::new(None, Some(get_bootstrap_contacts()?));
+        let passphrase = random_nrs_name();
+        let password = random_nrs_name();
+        safe_auth.create(&passphrase, &password).await?;
+        Ok((safe_auth, passphrase, password))
+    }
+
+    fn new_auth_req(app_id: &str) -> AuthReq {
+        AuthReq {
+            req_id: 1,
+            app_id: app_id.to_string(),
+            app_name: "Test App".to_string(),
+            app_vendor: "Test Vendor".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_create_lock_and_unlock_safe() -> Result<()> {
+        let (mut safe_auth, passphrase, password) = new_unlocked_authenticator().await?;
+        assert!(safe_auth.is_a_safe_unlocked());
+
+        safe_auth.lock()?;
+        assert!(!safe_auth.is_a_safe_unlocked());
+
+        retry_loop!(safe_auth.unlock(&passphrase, &password));
+        assert!(safe_auth.is_a_safe_unlocked());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_create_existing_safe_fails() -> Result<()> {
+        let (mut safe_auth, passphrase, password) = new_unlocked_authenticator().await?;
+
+        let _ = retry_loop_for_pattern!(safe_auth.create(&passphrase, &password), Err(Error::AuthError(msg)) if msg.contains("Failed to create a Safe"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_unlock_non_existing_safe_fails() -> Result<()> {
+        let mut safe_auth = SafeAuthenticator::new(None, Some(get_bootstrap_contacts()?));
+
+        match safe_auth
+            .unlock(&random_nrs_name(), &random_nrs_name())
+            .await
+        {
+            Err(Error::AuthError(msg)) if msg.contains("Failed to log in") => {}
+            other => bail!("Unexpected result unlocking non-existing Safe: {:?}", other),
+        }
+        assert!(!safe_auth.is_a_safe_unlocked());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_authenticate_without_unlocked_safe_fails() -> Result<()> {
+        let safe_auth = SafeAuthenticator::new(None, Some(get_bootstrap_contacts()?));
+
+        match safe_auth.authenticate(new_auth_req("test-app")).await {
+            Err(Error::AuthenticatorError(msg)) => {
+                assert_eq!(msg, "No Safe is currently unlocked")
+            }
+            other => bail!("Unexpected result authenticating app: {:?}", other),
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_authenticate_app() -> Result<()> {
+        let (safe_auth, _, _) = new_unlocked_authenticator().await?;
+
+        let granted = retry_loop!(safe_auth.authenticate(new_auth_req("test-app")));
+        let apps = retry_loop_for_pattern!(safe_auth.authed_apps(), Ok(apps) if !apps.is_empty())?;
+        assert_eq!(apps.len(), 1);
+        assert_eq!(apps[0].id, "test-app");
+
+        // The same keypair is returned when the app is authorised again
+        let granted_again = safe_auth.authenticate(new_auth_req("test-app")).await?;
+        assert_eq!(granted.app_keypair, granted_again.app_keypair);
+        assert_eq!(granted.bootstrap_config, safe_auth.bootstrap_config);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_revoke_app() -> Result<()> {
+        let (safe_auth, _, _) = new_unlocked_authenticator().await?;
+
+        let granted = retry_loop!(safe_auth.authenticate(new_auth_req("test-app")));
+        let _ = retry_loop_for_pattern!(safe_auth.authed_apps(), Ok(apps) if !apps.is_empty())?;
+
+        safe_auth.revoke_app("test-app").await?;
+        let _ = retry_loop_for_pattern!(safe_auth.authed_apps(), Ok(apps) if apps.is_empty())?;
+
+        // A new keypair is generated when the app is authorised again
+        let granted_again = safe_auth.authenticate(new_auth_req("test-app")).await?;
+        assert_ne!(granted.app_keypair, granted_again.app_keypair);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_authorise_app_from_ipc_req() -> Result<()> {
+        let (safe_auth, _, _) = new_unlocked_authenticator().await?;
+
+        let req = IpcMsg::new_auth_req("test-app", "Test App", "Test Vendor").to_string()?;
+        match safe_auth.decode_req(&req).await? {
+            SafeAuthReq::Auth(auth_req) => assert_eq!(auth_req.app_id, "test-app"),
+            other => bail!("Unexpected request decoded: {:?}", other),
+        }
+
+        let resp = retry_loop!(safe_auth.authorise_app(&req));
+        match IpcMsg::from_string(&resp)? {
+            IpcMsg::Resp(IpcResp::Auth(Ok(_))) => {}
+            other => bail!("Unexpected auth response: {:?}", other),
+        }
+
+        Ok(())
+    }
+
     #[test]
     fn get_deterministic_pk_from_known_seed() -> Result<()> {
         let seed = b"bacon";
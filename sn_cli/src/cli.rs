@@ -10,16 +10,21 @@ use crate::{
     operations::auth_and_connect::connect,
     operations::config::{Config, SnLaunchToolNetworkLauncher},
     subcommands::{
+        cache::cache_commander,
         cat::cat_commander,
         config::config_commander,
         dog::dog_commander,
-        files::files_commander,
+        files::{files_commander, FilesSubCommands},
+        gateway::gateway_commander,
         keys::key_commander,
+        multimap::multimap_commander,
         networks::networks_commander,
         node::node_commander,
         nrs::nrs_commander,
+        register::register_commander,
         setup::setup_commander,
         update::update_commander,
+        wallet::wallet_commander,
         xorurl::{xorurl_commander, xorurl_of_files},
         OutputFmt, SubCommands,
     },
@@ -34,6 +39,8 @@ use tracing::debug;
 
 const DEFAULT_OPERATION_TIMEOUT_SECS: u64 = 120; // 2mins
 
+const DRY_RUN_CONNECT_TIMEOUT_SECS: u64 = 15;
+
 const SN_CLI_QUERY_TIMEOUT: &str = "SN_CLI_QUERY_TIMEOUT";
 
 #[derive(StructOpt, Debug)]
@@ -101,6 +108,7 @@ pub async fn run() -> Result<()> {
             node_commander(cmd, &mut get_config().await?, &mut launcher).await
         }
         SubCommands::Keys(cmd) => key_commander(cmd, output_fmt, &safe).await,
+        SubCommands::Cache(cmd) => cache_commander(cmd, output_fmt).await,
         SubCommands::Xorurl {
             cmd,
             location,
@@ -127,7 +135,15 @@ pub async fn run() -> Result<()> {
             // available to connect to the network with them (unless dry-run was set),
             // otherwise the connection created will be with read-only access and some
             // of these commands will fail if they require write access.
-            if !safe.dry_run_mode {
+            if safe.dry_run_mode {
+                if let SubCommands::Files(FilesSubCommands::Put { .. }) = other {
+                    // The network quotes the storage cost of what would have been uploaded,
+                    // so we connect to it if possible, the dry run goes ahead regardless.
+                    if let Err(err) = connect_for_dry_run(&mut safe).await {
+                        debug!("Dry run continues without a connection to the network: {err}");
+                    }
+                }
+            } else {
                 let timeout_secs: u64 = match env::var(SN_CLI_QUERY_TIMEOUT) {
                     Ok(timeout) => timeout.parse::<u64>().map_err(|_| {
                         eyre!(
@@ -151,7 +167,13 @@ pub async fn run() -> Result<()> {
                 SubCommands::Cat(cmd) => cat_commander(cmd, output_fmt, &safe).await,
                 SubCommands::Dog(cmd) => dog_commander(cmd, output_fmt, &safe).await,
                 SubCommands::Files(cmd) => files_commander(cmd, output_fmt, &safe).await,
+                SubCommands::Gateway(cmd) => gateway_commander(cmd, output_fmt, &safe).await,
                 SubCommands::Nrs(cmd) => nrs_commander(cmd, output_fmt, &safe).await,
+                SubCommands::Register(cmd) => register_commander(cmd, output_fmt, &safe).await,
+                SubCommands::Multimap(cmd) => multimap_commander(cmd, output_fmt, &safe).await,
+                SubCommands::Wallet(cmd) => {
+                    wallet_commander(cmd, output_fmt, &safe, &get_config().await?).await
+                }
                 _ => Err(eyre!("Unknown safe subcommand")),
             }
         }
@@ -160,6 +182,16 @@ pub async fn run() -> Result<()> {
     result
 }
 
+// Connect to the network within a short timeout, as dry runs don't require a connection
+async fn connect_for_dry_run(safe: &mut Safe) -> Result<()> {
+    connect(
+        safe,
+        get_config().await?,
+        Duration::from_secs(DRY_RUN_CONNECT_TIMEOUT_SECS),
+    )
+    .await
+}
+
 /// Gets the configuration, which is used by various parts of the application.
 ///
 /// The SN_CLI_CONFIG_PATH allows the user to define a custom location as an alternative to
@@ -15,7 +15,8 @@ use cli::run;
 use color_eyre::{eyre::eyre, Help, Report, Result};
 use human_panic::{handle_dump, Metadata};
 use std::panic::set_hook;
-use tracing::{self, debug};
+use tracing::{self, debug, Instrument};
+#[cfg(not(feature = "otlp"))]
 use tracing_subscriber::filter::EnvFilter;
 
 extern crate comfy_table;
@@ -30,6 +31,11 @@ const APP_VENDOR: &str = "MaidSafe.net Ltd";
 #[tokio::main]
 async fn main() -> Result<(), Report> {
     color_eyre::install()?;
+    // Spans are then exported, for the msgs sent by the cmd to be followed through the network
+    #[cfg(feature = "otlp")]
+    safe_network::otlp::init("safe")
+        .map_err(|error| eyre!(error).wrap_err("Failed to set up the export of spans"))?;
+    #[cfg(not(feature = "otlp"))]
     if let Ok(filter) = std::env::var("RUST_LOG") {
         let filter = EnvFilter::try_new(filter).map_err(|e| {
             eyre!(
@@ -73,5 +79,9 @@ async fn main() -> Result<(), Report> {
         eprintln!("{}", error_msg);
     }));
 
-    run().await
+    // Root span of the cmd, so everything it does belongs to a single trace
+    let result = run().instrument(tracing::info_span!("safe")).await;
+    #[cfg(feature = "otlp")]
+    safe_network::otlp::shutdown();
+    result
 }
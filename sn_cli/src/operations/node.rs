@@ -9,12 +9,14 @@
 use super::helpers::download_and_install_node;
 use crate::operations::config::NetworkLauncher;
 use color_eyre::{eyre::bail, eyre::eyre, eyre::WrapErr, Result};
+use safe_network::node::{AdminRequest, AdminResponse, DYSFUNCTION_REPORT_FILENAME};
 use sn_api::NodeConfig;
+use sn_dysfunction::DysfunctionReport;
 use std::{
-    fs::create_dir_all,
+    fs::{self, create_dir_all},
     io::{self, Write},
     net::SocketAddr,
-    path::PathBuf,
+    path::{Path, PathBuf},
     process::{Command, Stdio},
 };
 use tracing::debug;
@@ -184,6 +186,72 @@ pub fn node_shutdown(node_path: Option<PathBuf>) -> Result<()> {
     kill_nodes(node_exec_name)
 }
 
+/// Reads the dysfunction reports dumped by the nodes whose root dir is either one of the
+/// given dirs, or directly within one of them
+pub fn node_dysfunction_reports(dirs: &[PathBuf]) -> Result<Vec<(PathBuf, DysfunctionReport)>> {
+    let mut reports = vec![];
+    for dir in dirs {
+        if dir.join(DYSFUNCTION_REPORT_FILENAME).exists() {
+            reports.push((dir.clone(), read_dysfunction_report(dir)?));
+            continue;
+        }
+        if !dir.is_dir() {
+            debug!("No nodes found at {}", dir.display());
+            continue;
+        }
+
+        let mut node_dirs = vec![];
+        for entry in fs::read_dir(dir)
+            .wrap_err_with(|| format!("Failed to read nodes dir '{}'", dir.display()))?
+        {
+            let path = entry?.path();
+            if path.join(DYSFUNCTION_REPORT_FILENAME).exists() {
+                node_dirs.push(path);
+            }
+        }
+        node_dirs.sort();
+
+        for node_dir in node_dirs {
+            let report = read_dysfunction_report(&node_dir)?;
+            reports.push((node_dir, report));
+        }
+    }
+
+    Ok(reports)
+}
+
+/// Sends a request to the admin interface of the node running with the given root dir
+#[cfg(unix)]
+pub async fn node_admin_request(root_dir: &Path, request: AdminRequest) -> Result<AdminResponse> {
+    let response = safe_network::node::admin_request(root_dir, request)
+        .await
+        .wrap_err_with(|| {
+            format!(
+                "Failed to reach the admin interface of the node at '{}', is it running?",
+                root_dir.display()
+            )
+        })?;
+
+    match response {
+        AdminResponse::Error(error) => bail!("The node failed to answer: {}", error),
+        response => Ok(response),
+    }
+}
+
+/// The admin interface is served over a Unix socket, which other platforms can't reach
+#[cfg(not(unix))]
+pub async fn node_admin_request(_root_dir: &Path, _request: AdminRequest) -> Result<AdminResponse> {
+    bail!("The node admin interface is only available on Unix platforms")
+}
+
+fn read_dysfunction_report(node_dir: &Path) -> Result<DysfunctionReport> {
+    let path = node_dir.join(DYSFUNCTION_REPORT_FILENAME);
+    let contents = fs::read(&path)
+        .wrap_err_with(|| format!("Failed to read dysfunction report '{}'", path.display()))?;
+    serde_json::from_slice(&contents)
+        .wrap_err_with(|| format!("Failed to parse dysfunction report '{}'", path.display()))
+}
+
 fn get_initial_sn_launch_args(
     node_directory_path: PathBuf,
     node_data_dir_name: &str,
@@ -232,8 +300,10 @@ fn get_node_bin_path(node_path: Option<PathBuf>) -> Result<PathBuf> {
 
 #[cfg(not(target_os = "windows"))]
 fn kill_nodes(exec_name: &str) -> Result<()> {
+    // Nodes handle SIGTERM by leaving their section first, which is pointless when the whole
+    // local network is being stopped, so they're killed straight away instead
     let output = Command::new("killall")
-        .arg(exec_name)
+        .args(["-KILL", exec_name])
         .output()
         .wrap_err_with(|| {
             format!(
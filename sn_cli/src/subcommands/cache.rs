@@ -0,0 +1,69 @@
+// Copyright 2022 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use super::{helpers::serialise_output, OutputFmt};
+use color_eyre::{eyre::WrapErr, Result};
+use serde::Serialize;
+use sn_api::{ChunkCache, ChunkCacheConfig};
+use structopt::StructOpt;
+
+#[derive(StructOpt, Debug)]
+pub enum CacheSubCommands {
+    #[structopt(name = "clear")]
+    /// Remove all the Chunks from the local cache
+    Clear,
+    #[structopt(name = "stats")]
+    /// Show how much of the local cache is in use
+    Stats,
+}
+
+// Cache usage as shown in the serialised output
+#[derive(Serialize)]
+struct StatsOutput {
+    dir: String,
+    chunks: usize,
+    size: u64,
+    max_size: u64,
+}
+
+pub async fn cache_commander(cmd: CacheSubCommands, output_fmt: OutputFmt) -> Result<()> {
+    let cache = ChunkCache::new(&ChunkCacheConfig::default());
+    match cmd {
+        CacheSubCommands::Clear => {
+            cache
+                .clear()
+                .await
+                .wrap_err("Failed to clear the Chunks cache")?;
+            if OutputFmt::Pretty == output_fmt {
+                println!("Chunks cache cleared");
+            }
+            Ok(())
+        }
+        CacheSubCommands::Stats => {
+            let stats = cache
+                .stats()
+                .await
+                .wrap_err("Failed to read the Chunks cache")?;
+            let stats = StatsOutput {
+                dir: stats.dir.display().to_string(),
+                chunks: stats.chunks,
+                size: stats.size,
+                max_size: stats.max_size,
+            };
+
+            if OutputFmt::Pretty == output_fmt {
+                println!("Chunks cache at: {}", stats.dir);
+                println!("Chunks: {}", stats.chunks);
+                println!("Size: {} of {} bytes", stats.size, stats.max_size);
+            } else {
+                println!("{}", serialise_output(&stats, output_fmt));
+            }
+            Ok(())
+        }
+    }
+}
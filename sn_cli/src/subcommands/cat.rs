@@ -79,7 +79,7 @@ pub async fn cat_commander(cmd: CatCommands, output_fmt: OutputFmt, safe: &Safe)
                 );
             }
         }
-        SafeData::PublicFile { data, .. } => {
+        SafeData::PublicFile { data, .. } | SafeData::PrivateFile { data, .. } => {
             if cmd.hexdump {
                 // Render hex representation of file
                 println!("{}", pretty_hex::pretty_hex(data));
@@ -104,6 +104,17 @@ pub async fn cat_commander(cmd: CatCommands, output_fmt: OutputFmt, safe: &Safe)
         SafeData::SafeKey { .. } => {
             println!("No content to show since the URL targets a SafeKey. Use the 'dog' command to obtain additional information about the targeted SafeKey.");
         }
+        SafeData::Wallet { xorurl, .. } => {
+            let balance = safe.wallet_balance(xorurl).await?;
+            if OutputFmt::Pretty == output_fmt {
+                println!("Wallet at \"{}\" has a balance of {}", url, balance);
+            } else {
+                println!(
+                    "{}",
+                    serialise_output(&(url.to_string(), balance.to_string()), output_fmt)
+                );
+            }
+        }
         SafeData::Multimap { .. }
         | SafeData::NrsEntry { .. }
         | SafeData::PrivateRegister { .. }
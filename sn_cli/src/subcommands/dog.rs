@@ -14,8 +14,8 @@ use super::{
 };
 use color_eyre::Result;
 use sn_api::{
-    resolver::{ContentType, SafeData},
-    Safe, SafeUrl,
+    resolver::{ContentType, DataType, SafeData},
+    Safe, SafeUrl, XorName,
 };
 use structopt::StructOpt;
 use tracing::debug;
@@ -108,27 +108,37 @@ pub async fn dog_commander(cmd: DogCommands, output_fmt: OutputFmt, safe: &Safe)
                     media_type,
                     resolved_from,
                     ..
+                } => print_file_details(xorurl, xorname, media_type, resolved_from, "PublicFile"),
+                SafeData::PrivateFile {
+                    xorurl,
+                    xorname,
+                    media_type,
+                    resolved_from,
+                    ..
+                } => print_file_details(xorurl, xorname, media_type, resolved_from, "PrivateFile"),
+                SafeData::SafeKey {
+                    xorurl,
+                    xorname,
+                    resolved_from,
                 } => {
                     println!("Resolved from: {}", resolved_from);
-                    println!("= File =");
+                    println!("= SafeKey =");
                     println!("XOR-URL: {}", xorurl);
                     println!("XOR name: 0x{}", xorname_to_hex(xorname));
-                    println!("Native data type: PublicFile");
-                    println!(
-                        "Media type: {}",
-                        media_type.clone().unwrap_or_else(|| "Unknown".to_string())
-                    );
+                    println!("Native data type: SafeKey");
                 }
-                SafeData::SafeKey {
+                SafeData::Wallet {
                     xorurl,
                     xorname,
+                    type_tag,
                     resolved_from,
                 } => {
                     println!("Resolved from: {}", resolved_from);
-                    println!("= SafeKey =");
+                    println!("= Wallet =");
                     println!("XOR-URL: {}", xorurl);
+                    println!("Type tag: {}", type_tag);
                     println!("XOR name: 0x{}", xorname_to_hex(xorname));
-                    println!("Native data type: SafeKey");
+                    println!("Native data type: {}", DataType::Register);
                 }
                 SafeData::Multimap { .. }
                 | SafeData::PrivateRegister { .. }
@@ -140,3 +150,21 @@ pub async fn dog_commander(cmd: DogCommands, output_fmt: OutputFmt, safe: &Safe)
 
     Ok(())
 }
+
+fn print_file_details(
+    xorurl: &str,
+    xorname: &XorName,
+    media_type: &Option<String>,
+    resolved_from: &str,
+    data_type: &str,
+) {
+    println!("Resolved from: {}", resolved_from);
+    println!("= File =");
+    println!("XOR-URL: {}", xorurl);
+    println!("XOR name: 0x{}", xorname_to_hex(xorname));
+    println!("Native data type: {}", data_type);
+    println!(
+        "Media type: {}",
+        media_type.clone().unwrap_or_else(|| "Unknown".to_string())
+    );
+}
@@ -18,24 +18,49 @@ use ansi_term::Colour;
 use bytes::Bytes;
 use color_eyre::{eyre::bail, eyre::eyre, Result};
 use comfy_table::Table;
+use indicatif::{ProgressBar, ProgressStyle};
 use serde::Serialize;
 use sn_api::{
     files::{FilesMap, ProcessedFiles},
     nrs::VersionHash,
     resolver::SafeData,
-    Safe, SafeUrl, XorUrl,
+    DataKind, Safe, SafeUrl, Token, UploadEvent, UploadOptions, XorUrl,
 };
 use std::{
     collections::{BTreeMap, HashMap},
     path::{Component, Path, PathBuf},
 };
 use structopt::StructOpt;
+use tokio::sync::mpsc;
 use tracing::debug;
+use walkdir::WalkDir;
 
 type FileDetails = BTreeMap<String, String>;
 
 const UNKNOWN_FILE_NAME: &str = "<unknown>";
 
+/// Options to control how the files are uploaded
+#[derive(StructOpt, Debug)]
+pub struct UploadArgs {
+    /// Maximum number of chunks being uploaded at the same time (default is 8)
+    #[structopt(long = "concurrency")]
+    concurrency: Option<usize>,
+    /// Number of times a chunk is sent again after failing to be stored (default is 0)
+    #[structopt(long = "retries")]
+    retries: Option<usize>,
+}
+
+impl UploadArgs {
+    fn apply(&self, options: &mut UploadOptions) {
+        if let Some(concurrency) = self.concurrency {
+            options.concurrency = concurrency;
+        }
+        if let Some(retries) = self.retries {
+            options.retries = retries;
+        }
+    }
+}
+
 // Differentiates between nodes in a file system.
 #[derive(Debug, Serialize, PartialEq)]
 enum FileTreeNodeType {
@@ -110,6 +135,14 @@ pub enum FilesSubCommands {
         /// Follow symlinks
         #[structopt(short = "l", long = "follow-links")]
         follow_links: bool,
+        /// Record the upload in local journals, so if it fails it can be resumed with this same flag, only sending the data which wasn't stored yet
+        #[structopt(long = "resume")]
+        resume: bool,
+        /// Upload the files as private, encrypted so only their owner can read them
+        #[structopt(long = "private")]
+        private: bool,
+        #[structopt(flatten)]
+        upload: UploadArgs,
     },
     /// Get a file or folder from the SAFE Network
     Get {
@@ -146,6 +179,14 @@ pub enum FilesSubCommands {
         /// Automatically update the NRS name to link to the new version of the FilesContainer. This is only allowed if an NRS URL was provided, and if the NRS name is currently linked to a specific version of the FilesContainer
         #[structopt(short = "u", long = "update-nrs")]
         update_nrs: bool,
+        /// Record the sync in local journals, so if it fails it can be resumed with this same flag, only sending the data which wasn't stored yet
+        #[structopt(long = "resume")]
+        resume: bool,
+        /// Upload the new and updated files as private, encrypted so only their owner can read them
+        #[structopt(long = "private")]
+        private: bool,
+        #[structopt(flatten)]
+        upload: UploadArgs,
     },
     #[structopt(name = "add")]
     /// Add a file to an existing FilesContainer on the network
@@ -169,6 +210,9 @@ pub enum FilesSubCommands {
         /// Follow symlinks
         #[structopt(short = "l", long = "follow-links")]
         follow_links: bool,
+        /// Upload the file as private, encrypted so only its owner can read it
+        #[structopt(long = "private")]
+        private: bool,
     },
     #[structopt(name = "rm")]
     /// Remove a file from an existing FilesContainer on the network
@@ -197,6 +241,18 @@ pub enum FilesSubCommands {
         #[structopt(short = "d", long = "details")]
         details: bool,
     },
+    #[cfg(feature = "fuse")]
+    #[structopt(name = "mount")]
+    /// Mount an existing FilesContainer as a local filesystem, until Ctrl+C is pressed
+    Mount {
+        /// The target FilesContainer to mount, optionally including a version and the path of a folder to mount
+        target: String,
+        /// The local folder to mount the FilesContainer at
+        mountpoint: PathBuf,
+        /// Store the files written to and remove the files removed from the mounted folder in the FilesContainer, when it's the latest version of it
+        #[structopt(short = "w", long = "writable")]
+        writable: bool,
+    },
 }
 
 pub async fn files_commander(
@@ -210,14 +266,30 @@ pub async fn files_commander(
             dst,
             recursive,
             follow_links,
+            resume,
+            private,
+            upload,
         } => {
             // create FilesContainer from a given path to local files/folders
             if safe.dry_run_mode && OutputFmt::Pretty == output_fmt {
                 notice_dry_run();
             }
-            let (files_container_xorurl, processed_files, _) = safe
+            let mut safe = with_upload_options(safe, resume, private);
+            upload.apply(&mut safe.upload_options);
+            let progress_bar = if !safe.dry_run_mode && OutputFmt::Pretty == output_fmt {
+                let total = local_files_size(Path::new(&location), recursive, follow_links);
+                Some(upload_progress_bar(&mut safe, total))
+            } else {
+                None
+            };
+
+            let result = safe
                 .files_container_create_from(&location, dst.as_deref(), recursive, follow_links)
-                .await?;
+                .await;
+            if let Some(progress_bar) = progress_bar {
+                progress_bar.finish_and_clear();
+            }
+            let (files_container_xorurl, processed_files, _) = result?;
 
             // Now let's just print out a list of the files uploaded/processed
             if OutputFmt::Pretty == output_fmt {
@@ -229,6 +301,25 @@ pub async fn files_commander(
 
                 let (table, _) = gen_processed_files_table(&processed_files, true);
                 println!("{table}");
+
+                if safe.dry_run_mode {
+                    match estimate_store_cost(&safe, Path::new(&location), recursive, follow_links)
+                        .await
+                    {
+                        Ok(cost) => println!("Estimated storage cost: {}", cost),
+                        Err(err) => println!("Storage cost could not be estimated: {}", err),
+                    }
+                }
+            } else if safe.dry_run_mode {
+                // The estimate is null if it couldn't be made, e.g. when not connected
+                let cost =
+                    estimate_store_cost(&safe, Path::new(&location), recursive, follow_links)
+                        .await
+                        .map_err(|err| debug!("Storage cost could not be estimated: {}", err))
+                        .ok();
+                let url = files_container_xorurl;
+                let output = (url, &processed_files, cost.map(|cost| cost.to_string()));
+                println!("{}", serialise_output(&output, output_fmt));
             } else {
                 print_serialized_output(files_container_xorurl, None, &processed_files, output_fmt);
             }
@@ -242,12 +333,17 @@ pub async fn files_commander(
             follow_links,
             delete,
             update_nrs,
+            resume,
+            private,
+            upload,
         } => {
             let target = get_from_arg_or_stdin(target, None)?;
             let mut target_url = get_target_url(&target)?;
             if safe.dry_run_mode && OutputFmt::Pretty == output_fmt {
                 notice_dry_run();
             }
+            let mut safe = with_upload_options(safe, resume, private);
+            upload.apply(&mut safe.upload_options);
             // Update the FilesContainer on the Network
             let (content, processed_files) = safe
                 .files_container_sync(
@@ -297,6 +393,7 @@ pub async fn files_commander(
             update_nrs,
             follow_links,
             force,
+            private,
         } => {
             // Validate that location and target are not both "", ie stdin.
             let target_url = target.unwrap_or_else(|| "".to_string());
@@ -310,6 +407,7 @@ pub async fn files_commander(
             if safe.dry_run_mode && OutputFmt::Pretty == output_fmt {
                 notice_dry_run();
             }
+            let safe = &with_upload_options(safe, false, private);
 
             let (content, processed_files) =
                 // If location is empty then we read arg from STDIN, which can still be a safe:// URL
@@ -352,6 +450,29 @@ pub async fn files_commander(
             output_processed_files_list(output_fmt, &processed_files, Some(version), target_url);
             Ok(())
         }
+        #[cfg(feature = "fuse")]
+        FilesSubCommands::Mount {
+            target,
+            mountpoint,
+            writable,
+        } => {
+            let mount = safe
+                .files_container_mount(&target, &mountpoint, writable)
+                .await?;
+            if OutputFmt::Pretty == output_fmt {
+                println!(
+                    "FilesContainer at \"{}\" mounted {}at \"{}\"",
+                    target,
+                    if writable { "" } else { "read-only " },
+                    mount.mountpoint().display()
+                );
+                println!("Press Ctrl+C to unmount it");
+            }
+
+            tokio::signal::ctrl_c().await?;
+            mount.unmount();
+            Ok(())
+        }
         FilesSubCommands::Ls { target } => {
             let target_url =
                 get_from_arg_or_stdin(target, Some("...awaiting target URl from STDIN"))?;
@@ -369,7 +490,7 @@ pub async fn files_commander(
                     let (total, filtered_filesmap) = filter_files_map(&files_map, &target_url)?;
                     (version, filtered_filesmap, total)
                 }
-                SafeData::PublicFile { metadata, .. } => {
+                SafeData::PublicFile { metadata, .. } | SafeData::PrivateFile { metadata, .. } => {
                     if let Some(file_item) = metadata {
                         let mut files_map = FilesMap::new();
                         let name = match file_item.get("name") {
@@ -463,6 +584,116 @@ async fn process_tree_command(
     Ok(())
 }
 
+// A copy of the Safe instance which resumes uploads from their journals and/or stores
+// private files if requested, so the settings only apply to the command being run
+fn with_upload_options(safe: &Safe, resume: bool, private: bool) -> Safe {
+    let mut safe = safe.clone();
+    safe.resume_uploads = resume;
+    safe.private_files = private;
+    safe
+}
+
+// Shows a progress bar of the bytes stored by the uploads done with the Safe instance,
+// which are expected to add up to `total` bytes
+fn upload_progress_bar(safe: &mut Safe, total: u64) -> ProgressBar {
+    let progress_bar = ProgressBar::new(total);
+    progress_bar.set_style(
+        ProgressStyle::default_bar()
+            .template("{spinner} [{elapsed_precise}] [{bar:40}] {bytes}/{total_bytes} ({eta})")
+            .progress_chars("=> "),
+    );
+
+    let (progress, mut events) = mpsc::unbounded_channel();
+    safe.upload_options.progress = Some(progress);
+    let updated_bar = progress_bar.clone();
+    let _handle = tokio::spawn(async move {
+        while let Some(event) = events.recv().await {
+            match event {
+                UploadEvent::BytesDone(bytes) => updated_bar.inc(bytes),
+                UploadEvent::ChunkFailed {
+                    name,
+                    error,
+                    retrying: true,
+                } => updated_bar.println(format!(
+                    "Failed to store chunk {}, retrying: {}",
+                    name, error
+                )),
+                _ => {}
+            }
+        }
+    });
+
+    progress_bar
+}
+
+// Estimate the cost of storing the files found at `location`, Chunk by Chunk, along with the
+// Register of their FilesContainer. The costs are quoted by the network, thus it needs to be reachable.
+async fn estimate_store_cost(
+    safe: &Safe,
+    location: &Path,
+    recursive: bool,
+    follow_links: bool,
+) -> Result<Token> {
+    if !safe.is_connected() {
+        bail!("not connected to the network");
+    }
+
+    // Most Chunks are of the maximum size, so each size is only quoted once
+    let mut chunks_by_size = BTreeMap::<usize, u64>::new();
+    for (path, size) in local_files(location, recursive, follow_links) {
+        if size == 0 {
+            // Empty files aren't stored as any Chunk
+            continue;
+        }
+        for chunk_size in safe.files_chunk_sizes(&path).await? {
+            *chunks_by_size.entry(chunk_size).or_default() += 1;
+        }
+    }
+
+    let overflow = || eyre!("the cost exceeds the maximum amount of tokens");
+    let mut cost = safe.get_store_cost(0, DataKind::Register).await?;
+    for (chunk_size, count) in chunks_by_size {
+        let chunk_cost = safe
+            .get_store_cost(chunk_size as u64, DataKind::Chunk)
+            .await?;
+        let chunks_cost = chunk_cost
+            .as_nano()
+            .checked_mul(count)
+            .map(Token::from_nano)
+            .ok_or_else(overflow)?;
+        cost = cost.checked_add(chunks_cost).ok_or_else(overflow)?;
+    }
+
+    Ok(cost)
+}
+
+// Total size of the local files found at `location`, as they would be uploaded
+fn local_files_size(location: &Path, recursive: bool, follow_links: bool) -> u64 {
+    local_files(location, recursive, follow_links)
+        .map(|(_, size)| size)
+        .sum()
+}
+
+// Paths and sizes of the local files found at `location`, as they would be uploaded
+fn local_files(
+    location: &Path,
+    recursive: bool,
+    follow_links: bool,
+) -> impl Iterator<Item = (PathBuf, u64)> {
+    let max_depth = if recursive { usize::MAX } else { 1 };
+    WalkDir::new(location)
+        .follow_links(follow_links)
+        .max_depth(max_depth)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let metadata = entry.metadata().ok()?;
+            metadata
+                .is_file()
+                .then(|| (entry.into_path(), metadata.len()))
+        })
+}
+
 fn print_serialized_output(
     xorurl: XorUrl,
     change_version: Option<VersionHash>,
@@ -0,0 +1,568 @@
+// Copyright 2022 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use super::{helpers::serialise_output, OutputFmt};
+use bytes::Bytes;
+use color_eyre::{eyre::eyre, Result};
+use hyper::{
+    header::{ACCEPT_RANGES, CONTENT_LENGTH, CONTENT_RANGE, CONTENT_TYPE, LOCATION, RANGE},
+    service::{make_service_fn, service_fn},
+    Body, Method, Request, Response, Server, StatusCode,
+};
+use percent_encoding::{utf8_percent_encode, AsciiSet, CONTROLS};
+use sn_api::{
+    files::{FileInfo, FilesMap},
+    nrs::NrsMap,
+    resolver::SafeData,
+    Error as ApiError, Safe,
+};
+use std::{collections::BTreeMap, convert::Infallible, net::SocketAddr};
+use structopt::StructOpt;
+use tracing::{debug, info, warn};
+
+// Characters to escape in the links of the listings we generate
+const LINK_ESCAPE_SET: &AsciiSet = &CONTROLS
+    .add(b' ')
+    .add(b'"')
+    .add(b'#')
+    .add(b'%')
+    .add(b'<')
+    .add(b'>')
+    .add(b'?')
+    .add(b'`');
+
+const MIMETYPE_FILESYSTEM_DIR: &str = "inode/directory";
+const MIMETYPE_DEFAULT: &str = "application/octet-stream";
+const MIMETYPE_HTML: &str = "text/html; charset=utf-8";
+const MIMETYPE_JSON: &str = "application/json";
+
+const INDEX_FILE: &str = "index.html";
+
+#[derive(StructOpt, Debug)]
+pub struct GatewayCommands {
+    /// Local address to serve the content on, e.g. 127.0.0.1:8080. Use port 0 to pick any free port
+    #[structopt(long = "listen", default_value = "127.0.0.1:8080")]
+    listen: SocketAddr,
+}
+
+pub async fn gateway_commander(
+    cmd: GatewayCommands,
+    output_fmt: OutputFmt,
+    safe: &Safe,
+) -> Result<()> {
+    let safe = safe.clone();
+    let make_service = make_service_fn(move |_conn| {
+        let safe = safe.clone();
+        async move {
+            Ok::<_, Infallible>(service_fn(move |req| {
+                let safe = safe.clone();
+                async move { Ok::<_, Infallible>(serve(&safe, req).await) }
+            }))
+        }
+    });
+
+    let server = Server::try_bind(&cmd.listen)
+        .map_err(|err| eyre!("Failed to listen on {}: {}", cmd.listen, err))?
+        .serve(make_service);
+
+    let addr = server.local_addr();
+    info!("Gateway listening on {}", addr);
+    if OutputFmt::Pretty == output_fmt {
+        println!(
+            "Serving Safe Network content at http://{}/<NRS-URL or XOR-URL>/<path>",
+            addr
+        );
+        println!("Press Ctrl+C to stop");
+    } else {
+        println!(
+            "{}",
+            serialise_output(&format!("http://{}", addr), output_fmt)
+        );
+    }
+
+    server
+        .await
+        .map_err(|err| eyre!("Gateway failed to serve content: {}", err))
+}
+
+// Serves a single HTTP request, turning any error into an error response
+async fn serve(safe: &Safe, req: Request<Body>) -> Response<Body> {
+    debug!("Gateway request: {} {}", req.method(), req.uri());
+    match handle_request(safe, &req).await {
+        Ok(response) => response,
+        Err(err) => {
+            let status = error_status(&err);
+            warn!("Failed to serve {}: {:?}", req.uri(), err);
+            let mut response = Response::new(Body::from(format!("{}\n", err)));
+            *response.status_mut() = status;
+            response
+        }
+    }
+}
+
+async fn handle_request(safe: &Safe, req: &Request<Body>) -> Result<Response<Body>> {
+    if req.method() != Method::GET && req.method() != Method::HEAD {
+        return Ok(Response::builder()
+            .status(StatusCode::METHOD_NOT_ALLOWED)
+            .body(Body::empty())?);
+    }
+
+    let path = req.uri().path();
+    let (host, _) = path
+        .trim_start_matches('/')
+        .split_once('/')
+        .unwrap_or_else(|| (path.trim_start_matches('/'), ""));
+    if host.is_empty() {
+        return html_response(
+            "<p>Browse Safe Network content at /&lt;NRS-URL or XOR-URL&gt;/&lt;path&gt;</p>"
+                .to_string(),
+        );
+    }
+
+    let url = safe_url(path, req.uri().query());
+
+    let safe_data = inspect_target(safe, &url).await?;
+    match safe_data {
+        SafeData::FilesContainer {
+            files_map,
+            resolves_into: None,
+            ..
+        } => {
+            // Relative links only work from a path ending with a slash
+            if !path.ends_with('/') {
+                let mut location = format!("{}/", path);
+                if let Some(query) = req.uri().query() {
+                    location.push('?');
+                    location.push_str(query);
+                }
+                return Ok(Response::builder()
+                    .status(StatusCode::MOVED_PERMANENTLY)
+                    .header(LOCATION, location)
+                    .body(Body::empty())?);
+            }
+
+            let entries = dir_entries(&files_map);
+            if let Some(Some(_)) = entries.get(INDEX_FILE) {
+                let index_url = safe_url(&format!("{}{}", path, INDEX_FILE), req.uri().query());
+                let index = inspect_target(safe, &index_url).await?;
+                return serve_file(safe, req, &index_url, index).await;
+            }
+
+            html_response(dir_listing(path, &entries))
+        }
+        SafeData::NrsMapContainer { nrs_map, .. } => html_response(nrs_listing(path, &nrs_map)),
+        file @ SafeData::PublicFile { .. } | file @ SafeData::PrivateFile { .. } => {
+            serve_file(safe, req, &url, file).await
+        }
+        _ => {
+            let safe_data = safe.fetch(&url, None).await?;
+            let json = serde_json::to_string_pretty(&safe_data)?;
+            Ok(Response::builder()
+                .header(CONTENT_TYPE, MIMETYPE_JSON)
+                .body(Body::from(json))?)
+        }
+    }
+}
+
+// The safe:// URL of the content at the given gateway path, e.g. /mysite/a.html -> safe://mysite/a.html
+fn safe_url(path: &str, query: Option<&str>) -> String {
+    match query {
+        Some(query) => format!("safe:/{}?{}", path, query),
+        None => format!("safe:/{}", path),
+    }
+}
+
+// Resolves the URL without fetching the content, returning the last step of the resolution
+async fn inspect_target(safe: &Safe, url: &str) -> Result<SafeData> {
+    safe.inspect(url).await?.pop().ok_or_else(|| {
+        eyre!(ApiError::ContentNotFound(format!(
+            "Failed to resolve {}",
+            url
+        )))
+    })
+}
+
+// Serves the content of a file, or only a range of it if requested
+async fn serve_file(
+    safe: &Safe,
+    req: &Request<Body>,
+    url: &str,
+    file: SafeData,
+) -> Result<Response<Body>> {
+    let (media_type, metadata) = match file {
+        SafeData::PublicFile {
+            media_type,
+            metadata,
+            ..
+        }
+        | SafeData::PrivateFile {
+            media_type,
+            metadata,
+            ..
+        } => (media_type, metadata),
+        other => return Err(eyre!("Content at {} is not a file: {:?}", url, other)),
+    };
+    let content_type = content_type(media_type, metadata.as_ref());
+
+    // The size is only in the metadata of files linked from a FilesContainer,
+    // for any other file the whole content needs to be fetched to know it.
+    let known_size = metadata
+        .as_ref()
+        .and_then(|info| info.get("size"))
+        .and_then(|size| size.parse::<u64>().ok());
+    let (mut content, size) = match known_size {
+        Some(size) => (None, size),
+        None => {
+            let content = fetch_file(safe, url, None).await?;
+            let size = content.len() as u64;
+            (Some(content), size)
+        }
+    };
+
+    let range = req
+        .headers()
+        .get(RANGE)
+        .and_then(|value| value.to_str().ok());
+    let response = Response::builder()
+        .header(ACCEPT_RANGES, "bytes")
+        .header(CONTENT_TYPE, content_type);
+    let (response, range) = match byte_range(range, size) {
+        ByteRange::Full => (
+            response.status(StatusCode::OK).header(CONTENT_LENGTH, size),
+            None,
+        ),
+        ByteRange::Partial(start, end) => (
+            response
+                .status(StatusCode::PARTIAL_CONTENT)
+                .header(CONTENT_LENGTH, end - start + 1)
+                .header(CONTENT_RANGE, format!("bytes {}-{}/{}", start, end, size)),
+            Some((start, end)),
+        ),
+        ByteRange::Unsatisfiable => {
+            return Ok(response
+                .status(StatusCode::RANGE_NOT_SATISFIABLE)
+                .header(CONTENT_RANGE, format!("bytes */{}", size))
+                .body(Body::empty())?);
+        }
+    };
+
+    if req.method() == Method::HEAD {
+        return Ok(response.body(Body::empty())?);
+    }
+
+    let body = match (content.take(), range) {
+        (Some(content), None) => content,
+        (Some(content), Some((start, end))) => content.slice(start as usize..=end as usize),
+        (None, None) => fetch_file(safe, url, None).await?,
+        (None, Some((start, end))) => {
+            fetch_file(safe, url, Some((Some(start), Some(end + 1)))).await?
+        }
+    };
+
+    Ok(response.body(Body::from(body))?)
+}
+
+async fn fetch_file(safe: &Safe, url: &str, range: sn_api::resolver::Range) -> Result<Bytes> {
+    match safe.fetch(url, range).await? {
+        SafeData::PublicFile { data, .. } | SafeData::PrivateFile { data, .. } => Ok(data),
+        other => Err(eyre!("Content at {} is not a file: {:?}", url, other)),
+    }
+}
+
+fn html_response(html: String) -> Result<Response<Body>> {
+    Ok(Response::builder()
+        .header(CONTENT_TYPE, MIMETYPE_HTML)
+        .body(Body::from(html))?)
+}
+
+// Maps the error which prevented from serving a request to an HTTP status
+fn error_status(err: &color_eyre::Report) -> StatusCode {
+    match err.downcast_ref::<ApiError>() {
+        Some(
+            ApiError::ContentNotFound(_)
+            | ApiError::ContentError(_)
+            | ApiError::EmptyContent(_)
+            | ApiError::VersionNotFound(_)
+            | ApiError::HashNotFound(_)
+            | ApiError::EntryNotFound(_),
+        ) => StatusCode::NOT_FOUND,
+        Some(
+            ApiError::InvalidXorUrl(_)
+            | ApiError::InvalidInput(_)
+            | ApiError::InvalidMediaType(_)
+            | ApiError::UrlError(_),
+        ) => StatusCode::BAD_REQUEST,
+        Some(ApiError::AccessDenied(_)) => StatusCode::FORBIDDEN,
+        Some(_) => StatusCode::BAD_GATEWAY,
+        None => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+// The Content-Type to serve a file with, as set in its FileInfo or its URL
+fn content_type(media_type: Option<String>, metadata: Option<&FileInfo>) -> String {
+    metadata
+        .and_then(|info| info.get("type").cloned())
+        .or(media_type)
+        .filter(|media_type| media_type.contains('/') && !media_type.starts_with("inode/"))
+        .unwrap_or_else(|| MIMETYPE_DEFAULT.to_string())
+}
+
+// A byte range requested with a Range header
+#[derive(Debug, PartialEq)]
+enum ByteRange {
+    // No range, or one we don't support, was requested, the whole content is served
+    Full,
+    // First and last (inclusive) positions of the bytes to serve
+    Partial(u64, u64),
+    // The range requested is out of the content's bounds
+    Unsatisfiable,
+}
+
+// Parses the value of a Range header for content of the given size.
+// Only single ranges are supported, requests for multiple ranges get the whole content.
+fn byte_range(header: Option<&str>, size: u64) -> ByteRange {
+    let spec = match header.and_then(|value| value.trim().strip_prefix("bytes=")) {
+        Some(spec) if !spec.contains(',') => spec.trim(),
+        _ => return ByteRange::Full,
+    };
+    let (first, last) = match spec.split_once('-') {
+        Some(positions) => positions,
+        None => return ByteRange::Full,
+    };
+    let parse = |pos: &str| {
+        if pos.is_empty() {
+            Ok(None)
+        } else {
+            pos.parse::<u64>().map(Some)
+        }
+    };
+    let (first, last) = match (parse(first), parse(last)) {
+        (Ok(first), Ok(last)) => (first, last),
+        _ => return ByteRange::Full,
+    };
+
+    match (first, last) {
+        (Some(first), last) => {
+            if first >= size {
+                ByteRange::Unsatisfiable
+            } else {
+                match last {
+                    Some(last) if last < first => ByteRange::Full,
+                    Some(last) => ByteRange::Partial(first, last.min(size - 1)),
+                    None => ByteRange::Partial(first, size - 1),
+                }
+            }
+        }
+        (None, Some(suffix)) => {
+            if suffix == 0 || size == 0 {
+                ByteRange::Unsatisfiable
+            } else {
+                ByteRange::Partial(size.saturating_sub(suffix), size - 1)
+            }
+        }
+        (None, None) => ByteRange::Full,
+    }
+}
+
+// The entries found right under the root of a FilesMap, with the FileInfo of the files.
+// Folders (implicit or not) have no FileInfo.
+fn dir_entries(files_map: &FilesMap) -> BTreeMap<String, Option<FileInfo>> {
+    let mut entries = BTreeMap::new();
+    for (path, file_info) in files_map {
+        let path = path.trim_start_matches('/');
+        match path.split_once('/') {
+            Some((dir, _)) => {
+                let _ = entries.insert(dir.to_string(), None);
+            }
+            None if path.is_empty() => {}
+            None => {
+                let is_dir = file_info
+                    .get("type")
+                    .is_some_and(|file_type| file_type == MIMETYPE_FILESYSTEM_DIR);
+                let entry = entries.entry(path.to_string()).or_insert(None);
+                if !is_dir {
+                    *entry = Some(file_info.clone());
+                }
+            }
+        }
+    }
+    entries
+}
+
+// Generates an HTML page listing the entries of a folder
+fn dir_listing(path: &str, entries: &BTreeMap<String, Option<FileInfo>>) -> String {
+    let mut rows = String::new();
+    if path.trim_matches('/').contains('/') {
+        rows.push_str("<tr><td><a href=\"../\">../</a></td><td></td><td></td></tr>\n");
+    }
+    for (name, file_info) in entries {
+        let (name, size, modified) = match file_info {
+            Some(info) => (
+                name.clone(),
+                info.get("size").cloned().unwrap_or_default(),
+                info.get("modified").cloned().unwrap_or_default(),
+            ),
+            None => (format!("{}/", name), String::new(), String::new()),
+        };
+        rows.push_str(&format!(
+            "<tr><td><a href=\"{}\">{}</a></td><td>{}</td><td>{}</td></tr>\n",
+            utf8_percent_encode(&name, LINK_ESCAPE_SET),
+            escape_html(&name),
+            escape_html(&size),
+            escape_html(&modified),
+        ));
+    }
+
+    listing_page(
+        &format!("Index of {}", path),
+        "<tr><th>Name</th><th>Size</th><th>Modified</th></tr>\n",
+        &rows,
+    )
+}
+
+// Generates an HTML page listing the public names of an NRS Map
+fn nrs_listing(path: &str, nrs_map: &NrsMap) -> String {
+    let mut rows = String::new();
+    for (public_name, link) in nrs_map.get_map_summary() {
+        rows.push_str(&format!(
+            "<tr><td><a href=\"/{}/\">{}</a></td><td>{}</td></tr>\n",
+            utf8_percent_encode(&public_name, LINK_ESCAPE_SET),
+            escape_html(&public_name),
+            escape_html(&link),
+        ));
+    }
+
+    listing_page(
+        &format!("NRS Map Container at {}", path),
+        "<tr><th>Public name</th><th>Link</th></tr>\n",
+        &rows,
+    )
+}
+
+fn listing_page(title: &str, header: &str, rows: &str) -> String {
+    let title = escape_html(title);
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head><meta charset=\"utf-8\"><title>{}</title></head>\n<body>\n<h1>{}</h1>\n<table>\n{}{}</table>\n</body>\n</html>\n",
+        title, title, header, rows
+    )
+}
+
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod gateway_tests {
+    use super::{byte_range, content_type, dir_entries, dir_listing, ByteRange};
+    use sn_api::files::{FileInfo, FilesMap};
+
+    fn file_info(file_type: &str, size: &str) -> FileInfo {
+        vec![
+            ("type".to_string(), file_type.to_string()),
+            ("size".to_string(), size.to_string()),
+            ("link".to_string(), "safe://link".to_string()),
+        ]
+        .into_iter()
+        .collect()
+    }
+
+    #[test]
+    fn byte_range_should_parse_single_ranges() {
+        assert_eq!(byte_range(None, 100), ByteRange::Full);
+        assert_eq!(byte_range(Some("bytes=0-9"), 100), ByteRange::Partial(0, 9));
+        assert_eq!(
+            byte_range(Some("bytes=90-"), 100),
+            ByteRange::Partial(90, 99)
+        );
+        assert_eq!(
+            byte_range(Some("bytes=-10"), 100),
+            ByteRange::Partial(90, 99)
+        );
+        assert_eq!(
+            byte_range(Some("bytes=-200"), 100),
+            ByteRange::Partial(0, 99)
+        );
+        assert_eq!(
+            byte_range(Some("bytes=50-500"), 100),
+            ByteRange::Partial(50, 99)
+        );
+    }
+
+    #[test]
+    fn byte_range_should_ignore_invalid_or_multiple_ranges() {
+        assert_eq!(byte_range(Some("bytes=0-9,20-29"), 100), ByteRange::Full);
+        assert_eq!(byte_range(Some("bytes=9-0"), 100), ByteRange::Full);
+        assert_eq!(byte_range(Some("bytes=a-b"), 100), ByteRange::Full);
+        assert_eq!(byte_range(Some("items=0-9"), 100), ByteRange::Full);
+        assert_eq!(byte_range(Some("bytes=-"), 100), ByteRange::Full);
+    }
+
+    #[test]
+    fn byte_range_should_reject_ranges_out_of_bounds() {
+        assert_eq!(
+            byte_range(Some("bytes=100-"), 100),
+            ByteRange::Unsatisfiable
+        );
+        assert_eq!(byte_range(Some("bytes=-0"), 100), ByteRange::Unsatisfiable);
+        assert_eq!(byte_range(Some("bytes=0-"), 0), ByteRange::Unsatisfiable);
+    }
+
+    #[test]
+    fn content_type_should_prefer_file_info_type() {
+        let info = file_info("text/markdown", "12");
+        assert_eq!(
+            content_type(Some("text/plain".to_string()), Some(&info)),
+            "text/markdown"
+        );
+        assert_eq!(
+            content_type(Some("text/plain".to_string()), None),
+            "text/plain"
+        );
+        let info = file_info("Raw", "12");
+        assert_eq!(content_type(None, Some(&info)), "application/octet-stream");
+    }
+
+    #[test]
+    fn dir_listing_should_list_entries_under_root_only() {
+        let mut files_map = FilesMap::new();
+        let _ = files_map.insert("/index.md".to_string(), file_info("text/markdown", "12"));
+        let _ = files_map.insert("/sub".to_string(), file_info("inode/directory", "0"));
+        let _ = files_map.insert("/sub/a b.txt".to_string(), file_info("text/plain", "3"));
+        let _ = files_map.insert("/other/c.txt".to_string(), file_info("text/plain", "5"));
+
+        let entries = dir_entries(&files_map);
+        assert_eq!(
+            entries.keys().collect::<Vec<_>>(),
+            vec!["index.md", "other", "sub"]
+        );
+        assert!(entries["index.md"].is_some());
+        assert!(entries["sub"].is_none());
+        assert!(entries["other"].is_none());
+
+        let listing = dir_listing("/mysite/", &entries);
+        assert!(listing.contains("<a href=\"index.md\">index.md</a>"));
+        assert!(listing.contains("<a href=\"sub/\">sub/</a>"));
+        assert!(!listing.contains("a b.txt"));
+        assert!(!listing.contains("../"));
+
+        let entries = dir_entries(&files_map);
+        let listing = dir_listing("/mysite/sub/", &entries);
+        assert!(listing.contains("<a href=\"../\">../</a>"));
+    }
+}
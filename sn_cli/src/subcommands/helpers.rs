@@ -15,6 +15,7 @@ use serde::ser::Serialize;
 use sn_api::{
     files::{FilesMapChange, ProcessedFiles},
     nrs::NrsMap,
+    register::EntryHash,
     SafeUrl,
 };
 use std::io::{stdin, stdout, Read, Write};
@@ -219,3 +220,18 @@ pub fn get_target_url(link: &str) -> Result<SafeUrl> {
     }
     Ok(SafeUrl::from_url(link)?)
 }
+
+/// Parse a hex encoded Register entry hash.
+pub fn parse_entry_hash(hash: &str) -> Result<EntryHash> {
+    let bytes = hex::decode(hash).map_err(|err| {
+        eyre!(
+            "Invalid entry hash '{}', it must be hex encoded: {}",
+            hash,
+            err
+        )
+    })?;
+    let hash_bytes: [u8; 32] = bytes
+        .try_into()
+        .map_err(|_| eyre!("Invalid entry hash '{}', it must be 32 bytes long", hash))?;
+    Ok(EntryHash(hash_bytes))
+}
@@ -6,19 +6,24 @@
 // KIND, either express or implied. Please review the Licences for the specific language governing
 // permissions and limitations relating to use of the SAFE Network Software.
 
+pub mod cache;
 pub mod cat;
 pub mod config;
 pub mod dog;
 pub mod files;
 mod files_get;
+pub mod gateway;
 mod helpers;
 pub mod keys;
+pub mod multimap;
 pub mod networks;
 pub mod node;
 pub mod nrs;
+pub mod register;
 pub mod safe_id;
 pub mod setup;
 pub mod update;
+pub mod wallet;
 pub mod xorurl;
 
 use structopt::{clap::AppSettings, StructOpt};
@@ -107,6 +112,41 @@ pub enum SubCommands {
     )]
     /// Manage public names on the SAFE Network
     Nrs(nrs::NrsSubCommands),
+    #[structopt(
+        name = "register",
+        no_version,
+        global_settings(&[AppSettings::DisableVersion]),
+    )]
+    /// Manage Registers on the SAFE Network
+    Register(register::RegisterSubCommands),
+    #[structopt(
+        name = "multimap",
+        no_version,
+        global_settings(&[AppSettings::DisableVersion]),
+    )]
+    /// Manage Multimaps on the SAFE Network
+    Multimap(multimap::MultimapSubCommands),
+    #[structopt(
+        name = "wallet",
+        no_version,
+        global_settings(&[AppSettings::DisableVersion]),
+    )]
+    /// Manage Wallets and transfer tokens on the SAFE Network
+    Wallet(wallet::WalletSubCommands),
+    #[structopt(
+        name = "cache",
+        no_version,
+        global_settings(&[AppSettings::DisableVersion]),
+    )]
+    /// Manage the local cache of Chunks retrieved from the SAFE Network
+    Cache(cache::CacheSubCommands),
+    #[structopt(
+        name = "gateway",
+        no_version,
+        global_settings(&[AppSettings::DisableVersion]),
+    )]
+    /// Serve content from the SAFE Network over HTTP, to browse it with an ordinary browser
+    Gateway(gateway::GatewayCommands),
     #[structopt(
         name = "keys",
         no_version,
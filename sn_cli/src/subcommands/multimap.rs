@@ -0,0 +1,304 @@
+// Copyright 2022 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use super::{
+    helpers::{notice_dry_run, parse_entry_hash, serialise_output},
+    OutputFmt,
+};
+use color_eyre::{eyre::eyre, Result};
+use comfy_table::Table;
+use serde::Serialize;
+use sn_api::{multimap::Multimap, register::EntryHash, Safe};
+use std::{collections::BTreeSet, str::FromStr};
+use structopt::StructOpt;
+
+#[derive(StructOpt, Debug)]
+pub enum MultimapSubCommands {
+    #[structopt(name = "create")]
+    /// Create a new Multimap
+    Create {
+        /// The type tag of the Multimap
+        #[structopt(long = "type-tag", default_value = "25000")]
+        type_tag: u64,
+        /// Create a private Multimap, only readable and writeable by its owner
+        #[structopt(long = "private", conflicts_with = "public")]
+        private: bool,
+        /// Create a public Multimap, readable by anyone (this is the default)
+        #[structopt(long = "public")]
+        public: bool,
+    },
+    #[structopt(name = "insert")]
+    /// Insert a key-value pair into a Multimap
+    Insert {
+        /// The safe:// URL of the Multimap
+        url: String,
+        /// The key of the entry
+        key: String,
+        /// The value of the entry
+        value: String,
+        /// Hash (hex encoded) of an entry the new one replaces, it can be provided multiple times
+        #[structopt(long = "replace")]
+        replace: Vec<String>,
+        /// The encoding of the key provided: utf8, hex or base64
+        #[structopt(long = "key-encoding", default_value = "utf8")]
+        key_encoding: Encoding,
+        /// The encoding of the value provided: utf8, hex or base64
+        #[structopt(long = "value-encoding", default_value = "utf8")]
+        value_encoding: Encoding,
+    },
+    #[structopt(name = "remove")]
+    /// Remove entries from a Multimap by their hash
+    Remove {
+        /// The safe:// URL of the Multimap
+        url: String,
+        /// Hash (hex encoded) of the entries to remove
+        #[structopt(required = true, min_values = 1)]
+        hashes: Vec<String>,
+    },
+    #[structopt(name = "get")]
+    /// List the current entries of a Multimap
+    Get {
+        /// The safe:// URL of the Multimap
+        url: String,
+        /// Only list the entries with this key
+        #[structopt(long = "key")]
+        key: Option<String>,
+        /// The encoding of the keys, both the one provided and the ones shown: utf8, hex or base64
+        #[structopt(long = "key-encoding", default_value = "utf8")]
+        key_encoding: Encoding,
+        /// The encoding of the values shown: utf8, hex or base64
+        #[structopt(long = "value-encoding", default_value = "utf8")]
+        value_encoding: Encoding,
+    },
+    #[structopt(name = "entry")]
+    /// Read an entry of a Multimap by its hash
+    Entry {
+        /// The safe:// URL of the Multimap
+        url: String,
+        /// The hash (hex encoded) of the entry
+        hash: String,
+        /// The encoding of the key shown: utf8, hex or base64
+        #[structopt(long = "key-encoding", default_value = "utf8")]
+        key_encoding: Encoding,
+        /// The encoding of the value shown: utf8, hex or base64
+        #[structopt(long = "value-encoding", default_value = "utf8")]
+        value_encoding: Encoding,
+    },
+}
+
+/// Encoding of Multimap keys and values on the command line
+#[derive(Debug, Clone, Copy)]
+pub enum Encoding {
+    Utf8,
+    Hex,
+    Base64,
+}
+
+impl FromStr for Encoding {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "utf8" => Ok(Self::Utf8),
+            "hex" => Ok(Self::Hex),
+            "base64" => Ok(Self::Base64),
+            other => Err(format!(
+                "Invalid encoding '{}', supported encodings are: utf8, hex, base64",
+                other
+            )),
+        }
+    }
+}
+
+impl Encoding {
+    fn decode(&self, input: &str) -> Result<Vec<u8>> {
+        match self {
+            Self::Utf8 => Ok(input.as_bytes().to_vec()),
+            Self::Hex => {
+                hex::decode(input).map_err(|err| eyre!("Invalid hex input '{}': {}", input, err))
+            }
+            Self::Base64 => base64::decode(input)
+                .map_err(|err| eyre!("Invalid base64 input '{}': {}", input, err)),
+        }
+    }
+
+    fn encode(&self, bytes: &[u8]) -> Result<String> {
+        match self {
+            Self::Utf8 => String::from_utf8(bytes.to_vec()).map_err(|_| {
+                eyre!("Content is not valid UTF-8, use the hex or base64 encoding to show it")
+            }),
+            Self::Hex => Ok(hex::encode(bytes)),
+            Self::Base64 => Ok(base64::encode(bytes)),
+        }
+    }
+}
+
+// Multimap entry as shown in the serialised output
+#[derive(Serialize)]
+struct EntryOutput {
+    hash: String,
+    key: String,
+    value: String,
+}
+
+impl EntryOutput {
+    fn new(
+        hash: &EntryHash,
+        key: &[u8],
+        value: &[u8],
+        key_encoding: Encoding,
+        value_encoding: Encoding,
+    ) -> Result<Self> {
+        Ok(Self {
+            hash: hex::encode(hash.0),
+            key: key_encoding.encode(key)?,
+            value: value_encoding.encode(value)?,
+        })
+    }
+}
+
+pub async fn multimap_commander(
+    cmd: MultimapSubCommands,
+    output_fmt: OutputFmt,
+    safe: &Safe,
+) -> Result<()> {
+    match cmd {
+        MultimapSubCommands::Create {
+            type_tag,
+            private,
+            public,
+        } => {
+            // Public is the default, the flag just allows to make it explicit
+            let private = private && !public;
+            let xorurl = safe.multimap_create(None, type_tag, private).await?;
+
+            if OutputFmt::Pretty == output_fmt {
+                println!(
+                    "{} Multimap created at: \"{}\"",
+                    if private { "Private" } else { "Public" },
+                    xorurl
+                );
+            } else {
+                println!("{}", serialise_output(&xorurl, output_fmt));
+            }
+
+            if safe.dry_run_mode {
+                notice_dry_run();
+            }
+            Ok(())
+        }
+        MultimapSubCommands::Insert {
+            url,
+            key,
+            value,
+            replace,
+            key_encoding,
+            value_encoding,
+        } => {
+            let entry = (key_encoding.decode(&key)?, value_encoding.decode(&value)?);
+            let replace = parse_entry_hashes(&replace)?;
+
+            let hash = safe.multimap_insert(&url, entry, replace).await?;
+            let hash = hex::encode(hash.0);
+
+            if OutputFmt::Pretty == output_fmt {
+                println!("Entry inserted into Multimap at \"{}\": {}", url, hash);
+            } else {
+                println!("{}", serialise_output(&hash, output_fmt));
+            }
+
+            if safe.dry_run_mode {
+                notice_dry_run();
+            }
+            Ok(())
+        }
+        MultimapSubCommands::Remove { url, hashes } => {
+            let to_remove = parse_entry_hashes(&hashes)?;
+
+            let hash = safe.multimap_remove(&url, to_remove).await?;
+            let hash = hex::encode(hash.0);
+
+            if OutputFmt::Pretty == output_fmt {
+                println!(
+                    "Entries removed from Multimap at \"{}\", tombstone entry: {}",
+                    url, hash
+                );
+            } else {
+                println!("{}", serialise_output(&hash, output_fmt));
+            }
+
+            if safe.dry_run_mode {
+                notice_dry_run();
+            }
+            Ok(())
+        }
+        MultimapSubCommands::Get {
+            url,
+            key,
+            key_encoding,
+            value_encoding,
+        } => {
+            let multimap = match key {
+                Some(key) => {
+                    let key = key_encoding.decode(&key)?;
+                    safe.multimap_get_by_key(&url, &key).await?
+                }
+                None => safe.multimap_read(&url).await?,
+            };
+            let entries = entries_output(&multimap, key_encoding, value_encoding)?;
+
+            if OutputFmt::Pretty == output_fmt {
+                println!("Current entries of Multimap at \"{}\":", url);
+                let mut table = Table::new();
+                table.add_row(vec!["Hash", "Key", "Value"]);
+                for entry in entries.iter() {
+                    table.add_row(vec![&entry.hash, &entry.key, &entry.value]);
+                }
+                println!("{table}");
+            } else {
+                println!("{}", serialise_output(&entries, output_fmt));
+            }
+            Ok(())
+        }
+        MultimapSubCommands::Entry {
+            url,
+            hash,
+            key_encoding,
+            value_encoding,
+        } => {
+            let hash = parse_entry_hash(&hash)?;
+            let (key, value) = safe.multimap_get_by_hash(&url, hash).await?;
+            let entry = EntryOutput::new(&hash, &key, &value, key_encoding, value_encoding)?;
+
+            if OutputFmt::Pretty == output_fmt {
+                println!("Key: {}", entry.key);
+                println!("Value: {}", entry.value);
+            } else {
+                println!("{}", serialise_output(&entry, output_fmt));
+            }
+            Ok(())
+        }
+    }
+}
+
+fn entries_output(
+    multimap: &Multimap,
+    key_encoding: Encoding,
+    value_encoding: Encoding,
+) -> Result<Vec<EntryOutput>> {
+    multimap
+        .iter()
+        .map(|(hash, (key, value))| {
+            EntryOutput::new(hash, key, value, key_encoding, value_encoding)
+        })
+        .collect()
+}
+
+fn parse_entry_hashes(hashes: &[String]) -> Result<BTreeSet<EntryHash>> {
+    hashes.iter().map(|hash| parse_entry_hash(hash)).collect()
+}
@@ -7,14 +7,27 @@
 // permissions and limitations relating to use of the SAFE Network Software.
 
 use crate::operations::{config::Config, config::NetworkLauncher, node::*};
-use color_eyre::{eyre::eyre, Help, Result};
+use chrono::{DateTime, Utc};
+use color_eyre::{eyre::eyre, Help, Report, Result};
+use comfy_table::Table;
+use safe_network::node::{
+    AdminRequest, AdminResponse, LeaveSummary, NodeStatus, RecordedEvent, SectionPeers,
+    StorageUsage, ADMIN_SOCKET_FILENAME,
+};
 use sn_api::PublicKey;
-use std::{collections::BTreeSet, net::SocketAddr, path::PathBuf};
+use sn_dysfunction::ScoreType;
+use std::{
+    collections::BTreeSet,
+    net::SocketAddr,
+    path::{Path, PathBuf},
+};
 use structopt::StructOpt;
 use tracing::debug;
 
 const NODES_DATA_DIR_NAME: &str = "baby-fleming-nodes";
 const LOCAL_NODE_DIR_NAME: &str = "local-node";
+// Name sn_launch_tool gives to the dir of the genesis node, within the nodes data dir
+const GENESIS_NODE_DIR_NAME: &str = "sn-node-genesis";
 
 #[derive(StructOpt, Debug)]
 pub enum NodeSubCommands {
@@ -125,6 +138,65 @@ pub enum NodeSubCommands {
         #[structopt(long = "node-path", env = "SN_NODE_PATH")]
         node_path: Option<PathBuf>,
     },
+    #[structopt(name = "dysfunction")]
+    /// Show the dysfunction scores elders keep of the nodes in their section
+    Dysfunction {
+        /// Root dir of the node to show the scores of. If not provided, the scores of the nodes
+        /// launched with the `join` and `run-baby-fleming` commands are shown.
+        #[structopt(long = "root-dir")]
+        root_dir: Option<PathBuf>,
+        /// Path of the directory where sn_node is located (default is ~/.safe/node/). The SN_NODE_PATH env var can also be used to set the path
+        #[structopt(long = "node-dir-path", env = "SN_NODE_PATH")]
+        node_dir_path: Option<PathBuf>,
+    },
+    #[structopt(name = "status")]
+    /// Show who a running node is and where it stands in the network
+    Status {
+        /// Root dir of the node to ask. If not provided, the node launched with the `join`
+        /// command is asked, or else the genesis node launched with `run-baby-fleming`.
+        #[structopt(long = "root-dir")]
+        root_dir: Option<PathBuf>,
+        /// Path of the directory where sn_node is located (default is ~/.safe/node/). The SN_NODE_PATH env var can also be used to set the path
+        #[structopt(long = "node-dir-path", env = "SN_NODE_PATH")]
+        node_dir_path: Option<PathBuf>,
+        /// Also show the latest events emitted by the node
+        #[structopt(long = "events")]
+        events: bool,
+    },
+    #[structopt(name = "peers")]
+    /// Show the members of a running node's section
+    Peers {
+        /// Root dir of the node to ask. If not provided, the node launched with the `join`
+        /// command is asked, or else the genesis node launched with `run-baby-fleming`.
+        #[structopt(long = "root-dir")]
+        root_dir: Option<PathBuf>,
+        /// Path of the directory where sn_node is located (default is ~/.safe/node/). The SN_NODE_PATH env var can also be used to set the path
+        #[structopt(long = "node-dir-path", env = "SN_NODE_PATH")]
+        node_dir_path: Option<PathBuf>,
+    },
+    #[structopt(name = "storage")]
+    /// Show how much data a running node holds
+    Storage {
+        /// Root dir of the node to ask. If not provided, the node launched with the `join`
+        /// command is asked, or else the genesis node launched with `run-baby-fleming`.
+        #[structopt(long = "root-dir")]
+        root_dir: Option<PathBuf>,
+        /// Path of the directory where sn_node is located (default is ~/.safe/node/). The SN_NODE_PATH env var can also be used to set the path
+        #[structopt(long = "node-dir-path", env = "SN_NODE_PATH")]
+        node_dir_path: Option<PathBuf>,
+    },
+    #[structopt(name = "leave")]
+    /// Have a running node hand its data over and leave its section, then shut down. Only
+    /// nodes which aren't elders can leave
+    Leave {
+        /// Root dir of the node to ask. If not provided, the node launched with the `join`
+        /// command is asked, or else the genesis node launched with `run-baby-fleming`.
+        #[structopt(long = "root-dir")]
+        root_dir: Option<PathBuf>,
+        /// Path of the directory where sn_node is located (default is ~/.safe/node/). The SN_NODE_PATH env var can also be used to set the path
+        #[structopt(long = "node-dir-path", env = "SN_NODE_PATH")]
+        node_dir_path: Option<PathBuf>,
+    },
     #[structopt(name = "update")]
     /// Update to latest sn_node released version
     Update {
@@ -247,11 +319,279 @@ pub async fn node_commander(
             Ok(())
         }
         Some(NodeSubCommands::Killall { node_path }) => node_shutdown(node_path),
+        Some(NodeSubCommands::Dysfunction {
+            root_dir,
+            node_dir_path,
+        }) => {
+            let dirs = match root_dir {
+                Some(root_dir) => vec![root_dir],
+                None => {
+                    let node_directory_path = node_dir_path.unwrap_or_else(|| {
+                        let mut default_node_dir_path = config.node_config_path.clone();
+                        default_node_dir_path.pop();
+                        default_node_dir_path
+                    });
+                    vec![
+                        node_directory_path.join(LOCAL_NODE_DIR_NAME),
+                        node_directory_path.join(NODES_DATA_DIR_NAME),
+                    ]
+                }
+            };
+            print_dysfunction_reports(&dirs)
+        }
+        Some(NodeSubCommands::Status {
+            root_dir,
+            node_dir_path,
+            events,
+        }) => {
+            let root_dir = admin_root_dir(config, root_dir, node_dir_path);
+            match node_admin_request(&root_dir, AdminRequest::Status).await? {
+                AdminResponse::Status(status) => print_node_status(&root_dir, status),
+                response => return Err(unexpected_admin_response(response)),
+            }
+            if events {
+                match node_admin_request(&root_dir, AdminRequest::Events).await? {
+                    AdminResponse::Events(events) => print_node_events(events),
+                    response => return Err(unexpected_admin_response(response)),
+                }
+            }
+            Ok(())
+        }
+        Some(NodeSubCommands::Peers {
+            root_dir,
+            node_dir_path,
+        }) => {
+            let root_dir = admin_root_dir(config, root_dir, node_dir_path);
+            match node_admin_request(&root_dir, AdminRequest::Peers).await? {
+                AdminResponse::Peers(peers) => {
+                    print_section_peers(peers);
+                    Ok(())
+                }
+                response => Err(unexpected_admin_response(response)),
+            }
+        }
+        Some(NodeSubCommands::Storage {
+            root_dir,
+            node_dir_path,
+        }) => {
+            let root_dir = admin_root_dir(config, root_dir, node_dir_path);
+            match node_admin_request(&root_dir, AdminRequest::Storage).await? {
+                AdminResponse::Storage(storage) => {
+                    print_storage_usage(&root_dir, storage);
+                    Ok(())
+                }
+                response => Err(unexpected_admin_response(response)),
+            }
+        }
+        Some(NodeSubCommands::Leave {
+            root_dir,
+            node_dir_path,
+        }) => {
+            let root_dir = admin_root_dir(config, root_dir, node_dir_path);
+            match node_admin_request(&root_dir, AdminRequest::Leave).await? {
+                AdminResponse::Left(summary) => {
+                    print_leave_summary(&root_dir, summary);
+                    Ok(())
+                }
+                response => Err(unexpected_admin_response(response)),
+            }
+        }
         Some(NodeSubCommands::Update { node_path }) => node_update(node_path),
         None => Err(eyre!("Missing node subcommand")),
     }
 }
 
+// Root dir of the node the admin commands ask, when none was picked: the node joined to a
+// network if it's running, or else the genesis node of the local network
+fn admin_root_dir(
+    config: &Config,
+    root_dir: Option<PathBuf>,
+    node_dir_path: Option<PathBuf>,
+) -> PathBuf {
+    if let Some(root_dir) = root_dir {
+        return root_dir;
+    }
+
+    let node_directory_path = node_dir_path.unwrap_or_else(|| {
+        let mut default_node_dir_path = config.node_config_path.clone();
+        default_node_dir_path.pop();
+        default_node_dir_path
+    });
+    let local_node_dir = node_directory_path.join(LOCAL_NODE_DIR_NAME);
+    if local_node_dir.join(ADMIN_SOCKET_FILENAME).exists() {
+        local_node_dir
+    } else {
+        node_directory_path
+            .join(NODES_DATA_DIR_NAME)
+            .join(GENESIS_NODE_DIR_NAME)
+    }
+}
+
+fn unexpected_admin_response(response: AdminResponse) -> Report {
+    eyre!("Unexpected response from the node: {:?}", response)
+}
+
+fn print_node_status(root_dir: &Path, status: NodeStatus) {
+    println!("Status of node at {}:", root_dir.display());
+    let mut table = Table::new();
+    table.add_row(vec!["Name".to_string(), format!("{:x}", status.name)]);
+    table.add_row(vec!["Age".to_string(), status.age.to_string()]);
+    table.add_row(vec![
+        "Elder".to_string(),
+        if status.is_elder { "yes" } else { "no" }.to_string(),
+    ]);
+    table.add_row(vec!["Address".to_string(), status.addr.to_string()]);
+    table.add_row(vec!["Prefix".to_string(), format!("{:?}", status.prefix)]);
+    table.add_row(vec![
+        "Section key".to_string(),
+        hex::encode(status.section_key.to_bytes()),
+    ]);
+    table.add_row(vec![
+        "Section chain length".to_string(),
+        status.section_chain_len.to_string(),
+    ]);
+    table.add_row(vec![
+        "Section size".to_string(),
+        status.section_size.to_string(),
+    ]);
+    table.add_row(vec![
+        "Genesis key".to_string(),
+        hex::encode(status.genesis_key.to_bytes()),
+    ]);
+    println!("{table}");
+}
+
+fn print_node_events(events: Vec<RecordedEvent>) {
+    if events.is_empty() {
+        println!("No events emitted by the node yet.");
+        return;
+    }
+
+    println!("Latest events emitted by the node:");
+    let mut table = Table::new();
+    table.add_row(vec!["Time", "Event"]);
+    for event in events {
+        let time: DateTime<Utc> = event.time.into();
+        table.add_row(vec![time.to_rfc3339(), event.event]);
+    }
+    println!("{table}");
+}
+
+fn print_section_peers(peers: SectionPeers) {
+    println!(
+        "Members of section {:?} (section key: {}):",
+        peers.prefix,
+        hex::encode(peers.section_key.to_bytes())
+    );
+    let mut table = Table::new();
+    table.add_row(vec!["Name", "Address", "Age", "Elder"]);
+    for member in peers.members {
+        table.add_row(vec![
+            format!("{:x}", member.name),
+            member.addr.to_string(),
+            member.age.to_string(),
+            if member.is_elder { "yes" } else { "no" }.to_string(),
+        ]);
+    }
+    println!("{table}");
+}
+
+fn print_storage_usage(root_dir: &Path, storage: StorageUsage) {
+    println!("Storage of node at {}:", root_dir.display());
+    let used_percentage = if storage.max_capacity == 0 {
+        0.0
+    } else {
+        100.0 * storage.used_space as f64 / storage.max_capacity as f64
+    };
+    let mut table = Table::new();
+    table.add_row(vec![
+        "Used space".to_string(),
+        format!(
+            "{} of {} bytes ({:.2}%)",
+            storage.used_space, storage.max_capacity, used_percentage
+        ),
+    ]);
+    table.add_row(vec!["Chunks".to_string(), storage.chunks.to_string()]);
+    table.add_row(vec!["Registers".to_string(), storage.registers.to_string()]);
+    table.add_row(vec![
+        "Chunk store".to_string(),
+        storage.chunk_store.to_string(),
+    ]);
+    println!("{table}");
+}
+
+fn print_leave_summary(root_dir: &Path, summary: LeaveSummary) {
+    println!("Node at {} handed its data over:", root_dir.display());
+    let mut table = Table::new();
+    table.add_row(vec!["Chunks".to_string(), summary.chunks.to_string()]);
+    table.add_row(vec!["Registers".to_string(), summary.registers.to_string()]);
+    table.add_row(vec![
+        "Recipients".to_string(),
+        summary.recipients.to_string(),
+    ]);
+    println!("{table}");
+    if summary.confirmed {
+        println!("Its section agreed it has left, it's now shutting down.");
+    } else {
+        println!(
+            "Its section hadn't agreed it has left yet when it stopped waiting, it's shutting down anyway."
+        );
+    }
+}
+
+fn print_dysfunction_reports(dirs: &[PathBuf]) -> Result<()> {
+    let reports = node_dysfunction_reports(dirs)?;
+    if reports.is_empty() {
+        println!("No dysfunction reports found, only elders track the dysfunction of other nodes.");
+        return Ok(());
+    }
+
+    let score_types = [
+        ScoreType::Communication,
+        ScoreType::Knowledge,
+        ScoreType::Op,
+    ];
+    for (node_dir, report) in reports {
+        println!(
+            "Dysfunction scores tracked by node at {} (mean score: {:.2}):",
+            node_dir.display(),
+            report.mean
+        );
+
+        let mut table = Table::new();
+        let mut header = vec!["Node".to_string()];
+        header.extend(
+            score_types
+                .iter()
+                .map(|score_type| format!("{:?}", score_type)),
+        );
+        header.extend(["Total".to_string(), "Severity".to_string()]);
+        table.add_row(&header);
+
+        for (name, node_report) in report.nodes {
+            let mut row = vec![format!("{:x}", name)];
+            row.extend(score_types.iter().map(|score_type| {
+                node_report
+                    .scores
+                    .get(score_type)
+                    .map(|score| format!("{:.2}", score))
+                    .unwrap_or_default()
+            }));
+            row.push(format!("{:.2}", node_report.total));
+            row.push(
+                node_report
+                    .severity
+                    .map(|severity| format!("{:?}", severity))
+                    .unwrap_or_else(|| "-".to_string()),
+            );
+            table.add_row(&row);
+        }
+        println!("{table}");
+    }
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod test {
     use crate::operations::config::NetworkLauncher;
@@ -1386,3 +1726,80 @@ mod join_command {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod admin_commands {
+    use super::test::FakeNetworkLauncher;
+    use super::{
+        admin_root_dir, node_commander, NodeSubCommands, GENESIS_NODE_DIR_NAME,
+        LOCAL_NODE_DIR_NAME, NODES_DATA_DIR_NAME,
+    };
+    use crate::operations::config::Config;
+    use assert_fs::prelude::*;
+    use color_eyre::Result;
+    use safe_network::node::ADMIN_SOCKET_FILENAME;
+
+    async fn test_config(tmp_dir: &assert_fs::TempDir) -> Result<Config> {
+        let cli_config_file = tmp_dir.child(".safe/cli/config.json");
+        let node_config_file = tmp_dir.child(".safe/node/node_connection_info.config");
+        Config::new(
+            cli_config_file.path().to_path_buf(),
+            node_config_file.path().to_path_buf(),
+        )
+        .await
+    }
+
+    #[tokio::test]
+    async fn should_default_to_the_genesis_node_of_the_local_network() -> Result<()> {
+        let tmp_dir = assert_fs::TempDir::new()?;
+        let config = test_config(&tmp_dir).await?;
+
+        let root_dir = admin_root_dir(&config, None, None);
+
+        assert_eq!(
+            root_dir,
+            tmp_dir
+                .path()
+                .join(".safe/node")
+                .join(NODES_DATA_DIR_NAME)
+                .join(GENESIS_NODE_DIR_NAME)
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn should_default_to_the_joined_node_when_it_is_running() -> Result<()> {
+        let tmp_dir = assert_fs::TempDir::new()?;
+        let config = test_config(&tmp_dir).await?;
+        let node_dir = assert_fs::TempDir::new()?;
+        node_dir
+            .child(LOCAL_NODE_DIR_NAME)
+            .child(ADMIN_SOCKET_FILENAME)
+            .touch()?;
+
+        let root_dir = admin_root_dir(&config, None, Some(node_dir.path().to_path_buf()));
+
+        assert_eq!(root_dir, node_dir.path().join(LOCAL_NODE_DIR_NAME));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn should_fail_when_the_node_is_not_running() -> Result<()> {
+        let tmp_dir = assert_fs::TempDir::new()?;
+        let mut config = test_config(&tmp_dir).await?;
+        let root_dir = assert_fs::TempDir::new()?;
+        let mut launcher = Box::new(FakeNetworkLauncher {
+            launch_args: Vec::new(),
+        });
+
+        let cmd = NodeSubCommands::Status {
+            root_dir: Some(root_dir.path().to_path_buf()),
+            node_dir_path: None,
+            events: false,
+        };
+        let result = node_commander(Some(cmd), &mut config, &mut launcher).await;
+
+        assert!(result.is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,283 @@
+// Copyright 2022 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use super::{
+    helpers::{get_from_arg_or_stdin, notice_dry_run, parse_entry_hash, serialise_output},
+    OutputFmt,
+};
+use color_eyre::{eyre::eyre, Result};
+use comfy_table::Table;
+use serde::Serialize;
+use sn_api::{
+    register::{Action, Permissions, Policy, User},
+    ContentType, Safe, XorName,
+};
+use std::collections::BTreeSet;
+use structopt::StructOpt;
+
+#[derive(StructOpt, Debug)]
+pub enum RegisterSubCommands {
+    #[structopt(name = "create")]
+    /// Create a new Register
+    Create {
+        /// The xorname (hex encoded) to create the Register at. A random one is used if not provided
+        #[structopt(long = "name")]
+        name: Option<String>,
+        /// The type tag of the Register
+        #[structopt(long = "type-tag", default_value = "25000")]
+        type_tag: u64,
+        /// Create a private Register, only readable and writeable by its owner
+        #[structopt(long = "private", conflicts_with = "public")]
+        private: bool,
+        /// Create a public Register, readable by anyone (this is the default)
+        #[structopt(long = "public")]
+        public: bool,
+    },
+    #[structopt(name = "read")]
+    /// Read the current entries of a Register
+    Read {
+        /// The safe:// URL of the Register
+        url: String,
+    },
+    #[structopt(name = "write")]
+    /// Write a new entry to a Register
+    Write {
+        /// The safe:// URL of the Register
+        url: String,
+        /// The content of the entry. It's read from STDIN if not provided
+        entry: Option<String>,
+        /// Hash (hex encoded) of an entry the new entry supersedes, it can be provided multiple
+        /// times. If none is provided, the new entry supersedes all current entries
+        #[structopt(long = "parent")]
+        parents: Vec<String>,
+    },
+    #[structopt(name = "entry")]
+    /// Read an entry of a Register by its hash
+    Entry {
+        /// The safe:// URL of the Register
+        url: String,
+        /// The hash (hex encoded) of the entry
+        hash: String,
+    },
+    #[structopt(name = "policy")]
+    /// Show the owner and the permissions of a Register
+    Policy {
+        /// The safe:// URL of the Register
+        url: String,
+    },
+}
+
+// Register entry as shown in the serialised output
+#[derive(Serialize)]
+struct EntryOutput {
+    hash: String,
+    entry: String,
+}
+
+// Permissions of a user as shown in the serialised output
+#[derive(Serialize)]
+struct PermissionsOutput {
+    user: String,
+    read: bool,
+    write: bool,
+}
+
+// Register policy as shown in the serialised output
+#[derive(Serialize)]
+struct PolicyOutput {
+    scope: String,
+    owner: String,
+    permissions: Vec<PermissionsOutput>,
+}
+
+pub async fn register_commander(
+    cmd: RegisterSubCommands,
+    output_fmt: OutputFmt,
+    safe: &Safe,
+) -> Result<()> {
+    match cmd {
+        RegisterSubCommands::Create {
+            name,
+            type_tag,
+            private,
+            public,
+        } => {
+            // Public is the default, the flag just allows to make it explicit
+            let private = private && !public;
+            let name = name.map(|name| parse_xorname(&name)).transpose()?;
+            let xorurl = safe
+                .register_create(name, type_tag, private, ContentType::Raw)
+                .await?;
+
+            if OutputFmt::Pretty == output_fmt {
+                println!(
+                    "{} Register created at: \"{}\"",
+                    if private { "Private" } else { "Public" },
+                    xorurl
+                );
+            } else {
+                println!("{}", serialise_output(&xorurl, output_fmt));
+            }
+
+            if safe.dry_run_mode {
+                notice_dry_run();
+            }
+            Ok(())
+        }
+        RegisterSubCommands::Read { url } => {
+            let entries = safe.register_read(&url).await?;
+            let entries = entries
+                .into_iter()
+                .map(|(hash, entry)| EntryOutput {
+                    hash: hex::encode(hash.0),
+                    entry: String::from_utf8_lossy(&entry).to_string(),
+                })
+                .collect::<Vec<_>>();
+
+            if OutputFmt::Pretty == output_fmt {
+                println!("Current entries of Register at \"{}\":", url);
+                let mut table = Table::new();
+                table.add_row(vec!["Hash", "Entry"]);
+                for entry in entries.iter() {
+                    table.add_row(vec![&entry.hash, &entry.entry]);
+                }
+                println!("{table}");
+            } else {
+                println!("{}", serialise_output(&entries, output_fmt));
+            }
+            Ok(())
+        }
+        RegisterSubCommands::Write {
+            url,
+            entry,
+            parents,
+        } => {
+            let entry = get_from_arg_or_stdin(entry, None)?;
+            let parents = if parents.is_empty() && !safe.dry_run_mode {
+                safe.register_read(&url)
+                    .await?
+                    .into_iter()
+                    .map(|(hash, _)| hash)
+                    .collect()
+            } else {
+                parents
+                    .iter()
+                    .map(|hash| parse_entry_hash(hash))
+                    .collect::<Result<BTreeSet<_>>>()?
+            };
+
+            let hash = safe
+                .register_write(&url, entry.into_bytes(), parents)
+                .await?;
+            let hash = hex::encode(hash.0);
+
+            if OutputFmt::Pretty == output_fmt {
+                println!("Entry written to Register at \"{}\": {}", url, hash);
+            } else {
+                println!("{}", serialise_output(&hash, output_fmt));
+            }
+
+            if safe.dry_run_mode {
+                notice_dry_run();
+            }
+            Ok(())
+        }
+        RegisterSubCommands::Entry { url, hash } => {
+            let entry = safe
+                .register_read_entry(&url, parse_entry_hash(&hash)?)
+                .await?;
+            let entry = EntryOutput {
+                hash,
+                entry: String::from_utf8_lossy(&entry).to_string(),
+            };
+
+            if OutputFmt::Pretty == output_fmt {
+                println!("{}", entry.entry);
+            } else {
+                println!("{}", serialise_output(&entry, output_fmt));
+            }
+            Ok(())
+        }
+        RegisterSubCommands::Policy { url } => {
+            let policy = policy_output(&safe.register_get_policy(&url).await?);
+
+            if OutputFmt::Pretty == output_fmt {
+                println!("{} Register at \"{}\":", policy.scope, url);
+                println!("Owner: {}", policy.owner);
+                let mut table = Table::new();
+                table.add_row(vec!["User", "Read", "Write"]);
+                for perms in policy.permissions.iter() {
+                    table.add_row(vec![
+                        perms.user.clone(),
+                        perms.read.to_string(),
+                        perms.write.to_string(),
+                    ]);
+                }
+                println!("{table}");
+            } else {
+                println!("{}", serialise_output(&policy, output_fmt));
+            }
+            Ok(())
+        }
+    }
+}
+
+fn policy_output(policy: &Policy) -> PolicyOutput {
+    let (scope, users): (&str, Vec<User>) = match policy {
+        Policy::Public(policy) => ("Public", policy.permissions.keys().copied().collect()),
+        Policy::Private(policy) => ("Private", policy.permissions.keys().copied().collect()),
+    };
+
+    let permissions = users
+        .into_iter()
+        .filter_map(|user| {
+            let (read, write) = match policy.permissions(user)? {
+                Permissions::Public(perms) => (
+                    perms.is_allowed(Action::Read).unwrap_or(true),
+                    perms.is_allowed(Action::Write).unwrap_or(false),
+                ),
+                Permissions::Private(perms) => (
+                    perms.is_allowed(Action::Read),
+                    perms.is_allowed(Action::Write),
+                ),
+            };
+            Some(PermissionsOutput {
+                user: user_to_string(&user),
+                read,
+                write,
+            })
+        })
+        .collect();
+
+    PolicyOutput {
+        scope: scope.to_string(),
+        owner: user_to_string(policy.owner()),
+        permissions,
+    }
+}
+
+fn user_to_string(user: &User) -> String {
+    match user {
+        User::Anyone => "Anyone".to_string(),
+        User::Key(pk) => format!("{:x}", pk),
+    }
+}
+
+fn parse_xorname(name: &str) -> Result<XorName> {
+    let bytes = hex::decode(name).map_err(|err| {
+        eyre!(
+            "Invalid xorname '{}', it must be hex encoded: {}",
+            name,
+            err
+        )
+    })?;
+    let name_bytes: [u8; 32] = bytes
+        .try_into()
+        .map_err(|_| eyre!("Invalid xorname '{}', it must be 32 bytes long", name))?;
+    Ok(XorName(name_bytes))
+}
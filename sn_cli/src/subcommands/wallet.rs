@@ -0,0 +1,192 @@
+// Copyright 2022 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use super::{
+    helpers::{notice_dry_run, serialise_output},
+    OutputFmt,
+};
+use crate::operations::config::Config;
+use color_eyre::Result;
+use sn_api::Safe;
+use structopt::StructOpt;
+
+#[cfg(feature = "wallet-mint")]
+use color_eyre::{eyre::eyre, Help};
+#[cfg(feature = "wallet-mint")]
+use std::{
+    collections::BTreeSet,
+    net::{IpAddr, SocketAddr},
+};
+
+#[derive(StructOpt, Debug)]
+pub enum WalletSubCommands {
+    #[structopt(name = "create")]
+    /// Create a new Wallet, owned by the current credentials
+    Create {},
+    #[structopt(name = "balance")]
+    /// Query the balance of a Wallet
+    Balance {
+        /// The safe:// URL of the Wallet
+        url: String,
+    },
+    #[structopt(name = "transfer")]
+    /// Transfer tokens from a Wallet to another Wallet, the recipient then deposits the
+    /// transfer URL output by this command
+    Transfer {
+        /// The amount of tokens to transfer, e.g. 1.25
+        amount: String,
+        /// The safe:// URL of the Wallet to transfer the tokens from
+        #[structopt(long = "from")]
+        from: String,
+        /// The safe:// URL of the Wallet to transfer the tokens to
+        #[structopt(long = "to")]
+        to: String,
+    },
+    #[structopt(name = "deposit")]
+    /// Deposit into a Wallet the tokens of a transfer made to it
+    Deposit {
+        /// The safe:// URL of the Wallet
+        url: String,
+        /// The transfer URL output by the 'wallet transfer' command
+        transfer: String,
+    },
+    #[cfg(feature = "wallet-mint")]
+    #[structopt(name = "mint")]
+    /// Mint test tokens into a Wallet, only allowed on local networks
+    Mint {
+        /// The safe:// URL of the Wallet
+        url: String,
+        /// The amount of tokens to mint, e.g. 1.25
+        amount: String,
+    },
+}
+
+#[cfg_attr(not(feature = "wallet-mint"), allow(unused_variables))]
+pub async fn wallet_commander(
+    cmd: WalletSubCommands,
+    output_fmt: OutputFmt,
+    safe: &Safe,
+    config: &Config,
+) -> Result<()> {
+    match cmd {
+        WalletSubCommands::Create {} => {
+            let xorurl = safe.wallet_create().await?;
+
+            if OutputFmt::Pretty == output_fmt {
+                println!("Wallet created at: \"{}\"", xorurl);
+            } else {
+                println!("{}", serialise_output(&xorurl, output_fmt));
+            }
+
+            if safe.dry_run_mode {
+                notice_dry_run();
+            }
+            Ok(())
+        }
+        WalletSubCommands::Balance { url } => {
+            let balance = safe.wallet_balance(&url).await?;
+
+            if OutputFmt::Pretty == output_fmt {
+                println!("Wallet at \"{}\" has a balance of {}", url, balance);
+            } else {
+                println!(
+                    "{}",
+                    serialise_output(&(url, balance.to_string()), output_fmt)
+                );
+            }
+            Ok(())
+        }
+        WalletSubCommands::Transfer { amount, from, to } => {
+            let transfer = safe.wallet_transfer(&from, &to, &amount).await?;
+
+            if OutputFmt::Pretty == output_fmt {
+                println!("Transfer of {} tokens made at: \"{}\"", amount, transfer);
+                println!(
+                    "The owner of the Wallet at \"{}\" needs to deposit it to receive the tokens",
+                    to
+                );
+            } else {
+                println!("{}", serialise_output(&transfer, output_fmt));
+            }
+
+            if safe.dry_run_mode {
+                notice_dry_run();
+            }
+            Ok(())
+        }
+        WalletSubCommands::Deposit { url, transfer } => {
+            let amount = safe.wallet_deposit(&url, &transfer).await?;
+
+            if OutputFmt::Pretty == output_fmt {
+                println!("Deposited {} tokens into Wallet at \"{}\"", amount, url);
+            } else {
+                println!(
+                    "{}",
+                    serialise_output(&(url, amount.to_string()), output_fmt)
+                );
+            }
+
+            if safe.dry_run_mode {
+                notice_dry_run();
+            }
+            Ok(())
+        }
+        #[cfg(feature = "wallet-mint")]
+        WalletSubCommands::Mint { url, amount } => {
+            let (_, (_, contacts)) = config.read_current_node_config().await?;
+            if !is_local_network(&contacts) {
+                return Err(eyre!("Tokens can only be minted on a local network")
+                    .suggestion("Switch to a local network with 'safe networks switch'"));
+            }
+
+            let _ = safe.wallet_mint(&url, &amount).await?;
+
+            if OutputFmt::Pretty == output_fmt {
+                println!("Minted {} tokens into Wallet at \"{}\"", amount, url);
+            } else {
+                println!("{}", serialise_output(&(url, amount), output_fmt));
+            }
+
+            if safe.dry_run_mode {
+                notice_dry_run();
+            }
+            Ok(())
+        }
+    }
+}
+
+// A network is considered local if all its contacts are on this machine or a private network
+#[cfg(feature = "wallet-mint")]
+fn is_local_network(contacts: &BTreeSet<SocketAddr>) -> bool {
+    !contacts.is_empty()
+        && contacts.iter().all(|contact| match contact.ip() {
+            IpAddr::V4(ip) => ip.is_loopback() || ip.is_private() || ip.is_link_local(),
+            IpAddr::V6(ip) => ip.is_loopback(),
+        })
+}
+
+#[cfg(all(test, feature = "wallet-mint"))]
+mod wallet_tests {
+    use super::is_local_network;
+    use std::collections::BTreeSet;
+
+    #[test]
+    fn is_local_network_should_only_accept_local_contacts() {
+        let local: BTreeSet<_> = ["127.0.0.1:12000", "192.168.1.10:12000", "[::1]:12000"]
+            .iter()
+            .map(|addr| addr.parse().unwrap())
+            .collect();
+        assert!(is_local_network(&local));
+
+        let mut remote = local;
+        let _ = remote.insert("8.8.8.8:12000".parse().unwrap());
+        assert!(!is_local_network(&remote));
+
+        assert!(!is_local_network(&BTreeSet::new()));
+    }
+}
@@ -0,0 +1,32 @@
+// Copyright 2022 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use assert_cmd::prelude::*;
+use color_eyre::{eyre::eyre, Result};
+use predicates::prelude::*;
+use sn_cmd_test_utilities::util::{safe_cmd, safe_cmd_stdout};
+
+#[test]
+fn calling_safe_cache_stats() -> Result<()> {
+    let output = safe_cmd_stdout(["cache", "stats", "--json"], Some(0))?;
+    let stats: serde_json::Value = serde_json::from_str(&output)
+        .map_err(|_| eyre!("Failed to parse output of `safe cache stats`: {}", output))?;
+
+    assert!(stats["dir"].is_string());
+    assert!(stats["chunks"].is_u64());
+    assert!(stats["size"].as_u64() <= stats["max_size"].as_u64());
+    Ok(())
+}
+
+#[test]
+fn calling_safe_cache_clear() -> Result<()> {
+    safe_cmd(["cache", "clear"], Some(0))?
+        .assert()
+        .stdout(predicate::str::contains("Chunks cache cleared"));
+    Ok(())
+}
@@ -10,13 +10,13 @@ use assert_cmd::prelude::*;
 use assert_fs::prelude::*;
 use color_eyre::{eyre::eyre, Report, Result};
 use predicates::prelude::*;
-use sn_api::{SafeUrl, VersionHash};
+use sn_api::{SafeUrl, Scope, VersionHash};
 use sn_cmd_test_utilities::util::{
     get_directory_file_count, get_directory_len, get_file_len, get_random_nrs_string,
-    mk_emptyfolder, parse_files_container_output, parse_files_put_or_sync_output,
-    parse_files_tree_output, parse_nrs_register_output, safe_cmd, safe_cmd_stderr, safe_cmd_stdout,
-    safeurl_from, test_symlinks_are_valid, upload_path, upload_test_symlinks_folder,
-    upload_testfolder_trailing_slash, CLI, SAFE_PROTOCOL,
+    mk_emptyfolder, parse_files_container_output, parse_files_put_dry_run_output,
+    parse_files_put_or_sync_output, parse_files_tree_output, parse_nrs_register_output, safe_cmd,
+    safe_cmd_stderr, safe_cmd_stdout, safeurl_from, test_symlinks_are_valid, upload_path,
+    upload_test_symlinks_folder, upload_testfolder_trailing_slash, CLI, SAFE_PROTOCOL,
 };
 use std::{path::Path, process::Command, str::FromStr};
 
@@ -53,6 +53,121 @@ fn calling_safe_files_put() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn calling_safe_files_put_resume() -> Result<()> {
+    let content = safe_cmd_stdout(
+        ["files", "put", TEST_FOLDER, "--recursive", "--json"],
+        Some(0),
+    )?;
+    let (_, processed_files) = parse_files_put_or_sync_output(&content)?;
+
+    // Resuming when there is nothing left to resume results in the same files uploaded
+    let content = safe_cmd_stdout(
+        [
+            "files",
+            "put",
+            TEST_FOLDER,
+            "--recursive",
+            "--resume",
+            "--json",
+        ],
+        Some(0),
+    )?;
+    let (_, resumed_processed_files) = parse_files_put_or_sync_output(&content)?;
+
+    assert_eq!(resumed_processed_files.len(), processed_files.len());
+    for (path, change) in processed_files.iter() {
+        assert_eq!(resumed_processed_files[path].link(), change.link());
+    }
+    Ok(())
+}
+
+#[test]
+fn calling_safe_files_put_with_upload_options() -> Result<()> {
+    let content = safe_cmd_stdout(
+        [
+            "files",
+            "put",
+            TEST_FOLDER,
+            "--recursive",
+            "--concurrency",
+            "2",
+            "--retries",
+            "1",
+            "--json",
+        ],
+        Some(0),
+    )?;
+    let (container_xorurl, processed_files) = parse_files_put_or_sync_output(&content)?;
+    assert!(container_xorurl.starts_with("safe://"));
+    assert!(processed_files.values().all(|change| change.is_added()));
+    Ok(())
+}
+
+#[test]
+fn calling_safe_files_put_private() -> Result<()> {
+    let content = safe_cmd_stdout(["files", "put", TEST_FILE, "--private", "--json"], Some(0))?;
+    let (_, processed_files) = parse_files_put_or_sync_output(&content)?;
+
+    let file_url = processed_files[Path::new(TEST_FILE)]
+        .link()
+        .ok_or_else(|| eyre!("Missing xorurl link of uploaded test file"))?;
+    assert_eq!(SafeUrl::from_url(file_url)?.scope(), Scope::Private);
+
+    safe_cmd(["cat", file_url], Some(0))?
+        .assert()
+        .stdout(predicate::str::contains("hello tests!"));
+    Ok(())
+}
+
+#[test]
+fn calling_safe_files_sync_private() -> Result<()> {
+    let tmp_data_dir = assert_fs::TempDir::new()?;
+    tmp_data_dir.copy_from("../resources/testdata", &["test.md"])?;
+    let content = safe_cmd_stdout(
+        [
+            "files",
+            "put",
+            &tmp_data_dir.path().display().to_string(),
+            "--recursive",
+            "--private",
+            "--json",
+        ],
+        Some(0),
+    )?;
+    let (container_xorurl, _) = parse_files_put_or_sync_output(&content)?;
+    let mut url = SafeUrl::from_url(&container_xorurl)?;
+    url.set_content_version(None);
+
+    let new_file = tmp_data_dir.child("new.md");
+    new_file.write_str("hello private sync!")?;
+    let content = safe_cmd_stdout(
+        [
+            "files",
+            "sync",
+            &format!("{}/", tmp_data_dir.path().display()),
+            &url.to_string(),
+            "--recursive",
+            "--private",
+            "--json",
+        ],
+        Some(0),
+    )?;
+    let (_, processed_files) = parse_files_put_or_sync_output(&content)?;
+
+    let file_url = processed_files
+        .iter()
+        .find(|(path, _)| path.ends_with("new.md"))
+        .and_then(|(_, change)| change.link())
+        .ok_or_else(|| eyre!("Missing xorurl link of synced file"))?;
+    assert_eq!(SafeUrl::from_url(file_url)?.scope(), Scope::Private);
+
+    safe_cmd(["cat", file_url], Some(0))?
+        .assert()
+        .stdout(predicate::str::contains("hello private sync!"));
+    Ok(())
+}
+
 #[test]
 #[ignore = "dry_run"]
 fn calling_safe_files_put_dry_run() -> Result<()> {
@@ -70,7 +185,8 @@ fn calling_safe_files_put_dry_run() -> Result<()> {
         Some(0),
     )?;
 
-    let (_, processed_files) = parse_files_put_or_sync_output(&content)?;
+    let (_, processed_files, cost) = parse_files_put_dry_run_output(&content)?;
+    assert!(cost.is_some());
     let mut cmd = Command::cargo_bin(CLI).map_err(|e| eyre!(e.to_string()))?;
     cmd.args(&vec![
         "cat",
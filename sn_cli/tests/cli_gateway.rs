@@ -0,0 +1,124 @@
+// Copyright 2022 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use assert_cmd::prelude::*;
+use color_eyre::{eyre::eyre, Result};
+use sn_cmd_test_utilities::util::{parse_files_put_or_sync_output, safe_cmd_stdout, CLI};
+use std::{
+    io::{BufRead, BufReader, Read, Write},
+    net::TcpStream,
+    process::{Child, Command, Stdio},
+};
+
+const TEST_FOLDER: &str = "../resources/testdata/";
+
+// Kills the gateway when the test ends, whatever the outcome
+struct Gateway(Child);
+
+impl Drop for Gateway {
+    fn drop(&mut self) {
+        let _ = self.0.kill();
+    }
+}
+
+// Starts a gateway on any free port, returning it along with its http://<address>
+fn start_gateway() -> Result<(Gateway, String)> {
+    let child = Command::cargo_bin(CLI)
+        .map_err(|e| eyre!(e.to_string()))?
+        .args(["gateway", "--listen", "127.0.0.1:0", "--json"])
+        .stdout(Stdio::piped())
+        .spawn()?;
+    let mut gateway = Gateway(child);
+    let stdout = gateway
+        .0
+        .stdout
+        .take()
+        .ok_or_else(|| eyre!("Failed to read the gateway's output"))?;
+    let mut line = String::new();
+    let _ = BufReader::new(stdout).read_line(&mut line)?;
+    let addr: String = serde_json::from_str(line.trim())?;
+    Ok((gateway, addr))
+}
+
+// Sends a GET request to the gateway, returning the whole HTTP response as text
+fn http_get(addr: &str, path: &str, headers: &[&str]) -> Result<String> {
+    let mut stream = TcpStream::connect(addr.trim_start_matches("http://"))?;
+    let mut request = format!(
+        "GET {} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n",
+        path
+    );
+    for header in headers {
+        request.push_str(header);
+        request.push_str("\r\n");
+    }
+    request.push_str("\r\n");
+    stream.write_all(request.as_bytes())?;
+
+    let mut response = String::new();
+    let _ = stream.read_to_string(&mut response)?;
+    Ok(response)
+}
+
+// The gateway path of a file in a FilesContainer, e.g. safe://<xorurl>?v=<version> -> /<xorurl>/<path>?v=<version>
+fn gateway_path(url: &str, path: &str) -> String {
+    let url = url.trim_start_matches("safe://");
+    match url.split_once('?') {
+        Some((host, query)) => format!("/{}/{}?{}", host, path, query),
+        None => format!("/{}/{}", url, path),
+    }
+}
+
+#[test]
+fn calling_safe_gateway_serves_files_and_listings() -> Result<()> {
+    let content = safe_cmd_stdout(
+        ["files", "put", TEST_FOLDER, "--recursive", "--json"],
+        Some(0),
+    )?;
+    let (container_xorurl, _) = parse_files_put_or_sync_output(&content)?;
+
+    let (_gateway, addr) = start_gateway()?;
+
+    let response = http_get(&addr, &gateway_path(&container_xorurl, "test.md"), &[])?;
+    assert!(response.starts_with("HTTP/1.1 200"), "{}", response);
+    assert!(
+        response.contains("content-type: text/markdown"),
+        "{}",
+        response
+    );
+    assert!(response.ends_with("hello tests!"), "{}", response);
+
+    let response = http_get(
+        &addr,
+        &gateway_path(&container_xorurl, "test.md"),
+        &["Range: bytes=6-10"],
+    )?;
+    assert!(response.starts_with("HTTP/1.1 206"), "{}", response);
+    assert!(
+        response.contains("content-range: bytes 6-10/12"),
+        "{}",
+        response
+    );
+    assert!(response.ends_with("tests"), "{}", response);
+
+    let response = http_get(&addr, &gateway_path(&container_xorurl, "subfolder/"), &[])?;
+    assert!(response.starts_with("HTTP/1.1 200"), "{}", response);
+    assert!(response.contains("content-type: text/html"), "{}", response);
+    assert!(
+        response.contains("<a href=\"subexists.md\">"),
+        "{}",
+        response
+    );
+
+    let response = http_get(&addr, &gateway_path(&container_xorurl, "subfolder"), &[])?;
+    assert!(response.starts_with("HTTP/1.1 301"), "{}", response);
+
+    let response = http_get(&addr, &gateway_path(&container_xorurl, "missing.md"), &[])?;
+    assert!(response.starts_with("HTTP/1.1 404"), "{}", response);
+
+    Ok(())
+}
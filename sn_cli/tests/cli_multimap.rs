@@ -0,0 +1,141 @@
+// Copyright 2022 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use assert_cmd::prelude::*;
+use color_eyre::{eyre::eyre, Result};
+use predicates::prelude::*;
+use sn_cmd_test_utilities::util::{safe_cmd, safe_cmd_stdout, SAFE_PROTOCOL};
+
+fn create_multimap() -> Result<String> {
+    let output = safe_cmd_stdout(["multimap", "create", "--json"], Some(0))?;
+    serde_json::from_str(&output).map_err(|_| {
+        eyre!(
+            "Failed to parse output of `safe multimap create`: {}",
+            output
+        )
+    })
+}
+
+fn insert<const N: usize>(args: [&str; N]) -> Result<String> {
+    let output = safe_cmd_stdout(
+        ["multimap", "insert"]
+            .into_iter()
+            .chain(args)
+            .chain(["--json"]),
+        Some(0),
+    )?;
+    serde_json::from_str(&output).map_err(|_| {
+        eyre!(
+            "Failed to parse output of `safe multimap insert`: {}",
+            output
+        )
+    })
+}
+
+fn get<const N: usize>(args: [&str; N]) -> Result<Vec<serde_json::Value>> {
+    let output = safe_cmd_stdout(
+        ["multimap", "get"]
+            .into_iter()
+            .chain(args)
+            .chain(["--json"]),
+        Some(0),
+    )?;
+    serde_json::from_str(&output)
+        .map_err(|_| eyre!("Failed to parse output of `safe multimap get`: {}", output))
+}
+
+#[test]
+fn calling_safe_multimap_create_pretty() -> Result<()> {
+    safe_cmd(["multimap", "create"], Some(0))?
+        .assert()
+        .stdout(predicate::str::contains("Public Multimap created at:"))
+        .stdout(predicate::str::contains(SAFE_PROTOCOL));
+    Ok(())
+}
+
+#[test]
+fn calling_safe_multimap_insert_and_get() -> Result<()> {
+    let url = create_multimap()?;
+
+    let hash = insert([&url, "key", "value"])?;
+    let _ = insert([&url, "other-key", "other value"])?;
+
+    let entries = get([&url])?;
+    assert_eq!(entries.len(), 2);
+
+    let entries = get([&url, "--key", "key"])?;
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0]["hash"], hash);
+    assert_eq!(entries[0]["key"], "key");
+    assert_eq!(entries[0]["value"], "value");
+
+    Ok(())
+}
+
+#[test]
+fn calling_safe_multimap_insert_with_replace() -> Result<()> {
+    let url = create_multimap()?;
+
+    let first_hash = insert([&url, "key", "first"])?;
+    let second_hash = insert([&url, "key", "second", "--replace", &first_hash])?;
+
+    let entries = get([&url, "--key", "key"])?;
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0]["hash"], second_hash);
+    assert_eq!(entries[0]["value"], "second");
+
+    // The replaced entry can still be read by its hash
+    safe_cmd(["multimap", "entry", &url, &first_hash], Some(0))?
+        .assert()
+        .stdout(predicate::str::contains("Value: first"));
+
+    Ok(())
+}
+
+#[test]
+fn calling_safe_multimap_remove() -> Result<()> {
+    let url = create_multimap()?;
+
+    let hash = insert([&url, "key", "value"])?;
+    let _ = insert([&url, "other-key", "other value"])?;
+
+    safe_cmd(["multimap", "remove", &url, &hash], Some(0))?;
+
+    let entries = get([&url])?;
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0]["key"], "other-key");
+
+    Ok(())
+}
+
+#[test]
+fn calling_safe_multimap_with_encodings() -> Result<()> {
+    let url = create_multimap()?;
+
+    // "key" is 6b6579 in hex, and the value is not valid UTF-8
+    let _ = insert([
+        &url,
+        "6b6579",
+        "/wA=",
+        "--key-encoding",
+        "hex",
+        "--value-encoding",
+        "base64",
+    ])?;
+
+    let entries = get([&url, "--key", "key", "--value-encoding", "hex"])?;
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0]["key"], "key");
+    assert_eq!(entries[0]["value"], "ff00");
+
+    safe_cmd(["multimap", "get", &url], Some(1))?
+        .assert()
+        .stderr(predicate::str::contains("Content is not valid UTF-8"));
+
+    Ok(())
+}
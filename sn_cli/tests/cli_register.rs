@@ -0,0 +1,154 @@
+// Copyright 2022 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use assert_cmd::prelude::*;
+use color_eyre::{eyre::eyre, Result};
+use predicates::prelude::*;
+use sn_cmd_test_utilities::util::{safe_cmd, safe_cmd_stdout, SAFE_PROTOCOL};
+
+fn create_register(private: bool) -> Result<String> {
+    let flag = if private { "--private" } else { "--public" };
+    let output = safe_cmd_stdout(["register", "create", flag, "--json"], Some(0))?;
+    let xorurl: String = serde_json::from_str(&output).map_err(|_| {
+        eyre!(
+            "Failed to parse output of `safe register create`: {}",
+            output
+        )
+    })?;
+    Ok(xorurl)
+}
+
+fn read_register(url: &str) -> Result<Vec<serde_json::Value>> {
+    let output = safe_cmd_stdout(["register", "read", url, "--json"], Some(0))?;
+    serde_json::from_str(&output)
+        .map_err(|_| eyre!("Failed to parse output of `safe register read`: {}", output))
+}
+
+#[test]
+fn calling_safe_register_create_pretty() -> Result<()> {
+    safe_cmd(["register", "create"], Some(0))?
+        .assert()
+        .stdout(predicate::str::contains("Public Register created at:"))
+        .stdout(predicate::str::contains(SAFE_PROTOCOL));
+    Ok(())
+}
+
+#[test]
+fn calling_safe_register_write_and_read() -> Result<()> {
+    let url = create_register(false)?;
+
+    let output = safe_cmd_stdout(
+        ["register", "write", &url, "first entry", "--json"],
+        Some(0),
+    )?;
+    let first_hash: String = serde_json::from_str(&output).map_err(|_| {
+        eyre!(
+            "Failed to parse output of `safe register write`: {}",
+            output
+        )
+    })?;
+
+    let entries = read_register(&url)?;
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0]["hash"], first_hash);
+    assert_eq!(entries[0]["entry"], "first entry");
+
+    // Without parents the new entry supersedes the current ones
+    let output = safe_cmd_stdout(
+        ["register", "write", &url, "second entry", "--json"],
+        Some(0),
+    )?;
+    let second_hash: String = serde_json::from_str(&output).map_err(|_| {
+        eyre!(
+            "Failed to parse output of `safe register write`: {}",
+            output
+        )
+    })?;
+
+    let entries = read_register(&url)?;
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0]["hash"], second_hash);
+
+    // The superseded entry can still be read by its hash
+    safe_cmd(["register", "entry", &url, &first_hash], Some(0))?
+        .assert()
+        .stdout(predicate::str::contains("first entry"));
+
+    Ok(())
+}
+
+#[test]
+fn calling_safe_register_write_with_parents() -> Result<()> {
+    let url = create_register(true)?;
+
+    let output = safe_cmd_stdout(["register", "write", &url, "root", "--json"], Some(0))?;
+    let root_hash: String = serde_json::from_str(&output).map_err(|_| {
+        eyre!(
+            "Failed to parse output of `safe register write`: {}",
+            output
+        )
+    })?;
+
+    // Two concurrent branches from the same parent
+    safe_cmd(
+        [
+            "register", "write", &url, "branch a", "--parent", &root_hash,
+        ],
+        Some(0),
+    )?;
+    safe_cmd(
+        [
+            "register", "write", &url, "branch b", "--parent", &root_hash,
+        ],
+        Some(0),
+    )?;
+
+    let entries = read_register(&url)?;
+    assert_eq!(entries.len(), 2);
+
+    Ok(())
+}
+
+#[test]
+fn calling_safe_register_write_with_invalid_parent() -> Result<()> {
+    let url = create_register(false)?;
+
+    safe_cmd(
+        ["register", "write", &url, "entry", "--parent", "not-a-hash"],
+        Some(1),
+    )?
+    .assert()
+    .stderr(predicate::str::contains("Invalid entry hash 'not-a-hash'"));
+
+    Ok(())
+}
+
+#[test]
+fn calling_safe_register_policy() -> Result<()> {
+    let url = create_register(true)?;
+
+    let output = safe_cmd_stdout(["register", "policy", &url, "--json"], Some(0))?;
+    let policy: serde_json::Value = serde_json::from_str(&output).map_err(|_| {
+        eyre!(
+            "Failed to parse output of `safe register policy`: {}",
+            output
+        )
+    })?;
+    assert_eq!(policy["scope"], "Private");
+
+    let owner = policy["owner"].clone();
+    let permissions = policy["permissions"]
+        .as_array()
+        .ok_or_else(|| eyre!("Missing permissions in policy output: {}", output))?;
+    assert_eq!(permissions.len(), 1);
+    assert_eq!(permissions[0]["user"], owner);
+    assert_eq!(permissions[0]["read"], true);
+    assert_eq!(permissions[0]["write"], true);
+
+    Ok(())
+}
@@ -313,6 +313,17 @@ pub mod util {
         })
     }
 
+    pub fn parse_files_put_dry_run_output(
+        output: &str,
+    ) -> Result<(String, ProcessedFiles, Option<String>)> {
+        serde_json::from_str(output).map_err(|_| {
+            eyre!(
+                "Failed to parse output of `safe files put --dry-run`: {}",
+                output
+            )
+        })
+    }
+
     pub fn parse_nrs_register_output(
         output: &str,
     ) -> Result<(String, SafeUrl, (String, String, String))> {
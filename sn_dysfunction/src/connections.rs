@@ -25,7 +25,7 @@ impl DysfunctionDetection {
 #[cfg(test)]
 mod tests {
     use crate::tests::{init_test_logger, ELDER_COUNT};
-    use crate::{DysfunctionDetection, DysfunctionSeverity};
+    use crate::{DysfunctionDetection, DysfunctionReport, DysfunctionSeverity, ScoreType};
 
     use eyre::Error;
     use xor_name::{rand::random as random_xorname, XorName};
@@ -155,4 +155,57 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn conn_dysfunction_report() -> Result<()> {
+        init_test_logger();
+        let _outer_span = tracing::info_span!("conn_dysfunction_report").entered();
+
+        let adults = (0..10).map(|_| random_xorname()).collect::<Vec<XorName>>();
+
+        let dysfunctional_detection = DysfunctionDetection::new(adults.clone(), ELDER_COUNT);
+
+        for adult in &adults {
+            for _ in 0..NORMAL_CONNECTION_PROBLEM_COUNT {
+                dysfunctional_detection.track_comm_issue(*adult).await?;
+            }
+        }
+        let bad_adult = adults[0];
+        for _ in 0..DYSFUNCTIONAL_CONNECTION_PROBLEM_COUNT {
+            dysfunctional_detection.track_comm_issue(bad_adult).await?;
+        }
+
+        let report = dysfunctional_detection.report().await?;
+        assert_eq!(report.nodes.len(), adults.len());
+
+        let dysfunctional_nodes = dysfunctional_detection
+            .get_nodes_beyond_severity(DysfunctionSeverity::Dysfunctional)
+            .await?;
+        for (name, node_report) in &report.nodes {
+            let sum: f32 = node_report.scores.values().sum();
+            assert_eq!(node_report.total, sum);
+
+            let expected = if dysfunctional_nodes.contains(name) {
+                Some(DysfunctionSeverity::Dysfunctional)
+            } else {
+                None
+            };
+            assert_eq!(node_report.severity, expected);
+        }
+
+        let json = serde_json::to_string(&report)?;
+        assert_eq!(serde_json::from_str::<DysfunctionReport>(&json)?, report);
+
+        let bad_report = &report.nodes[&bad_adult];
+        assert_eq!(
+            bad_report.severity,
+            Some(DysfunctionSeverity::Dysfunctional)
+        );
+        assert!(
+            bad_report.scores[&ScoreType::Communication] > bad_report.scores[&ScoreType::Op],
+            "comm issues should dominate the score"
+        );
+
+        Ok(())
+    }
 }
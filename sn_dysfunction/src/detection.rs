@@ -6,12 +6,13 @@
 // KIND, either express or implied. Please review the Licences for the specific language governing
 // permissions and limitations relating to use of the SAFE Network Software.
 
-use crate::{error::Result, get_mean_of, DysfunctionDetection, TimedTracker};
+use crate::{error::Result, get_mean_of, DysfunctionDetection};
+use serde::{Deserialize, Serialize};
 use std::collections::{BTreeMap, BTreeSet};
 use xor_name::XorName;
 
 use std::time::Duration;
-static RECENT_ISSUE_DURATION: Duration = Duration::from_secs(60 * 15);
+pub(crate) static RECENT_ISSUE_DURATION: Duration = Duration::from_secs(60 * 15);
 
 static CONN_WEIGHTING: f32 = 20.0;
 static OP_WEIGHTING: f32 = 1.5;
@@ -21,16 +22,32 @@ static KNOWLEDGE_WEIGHTING: f32 = 60.0;
 static DYSFUNCTION_MEAN_RATIO: f32 = 3.5;
 static SUSPECT_MEAN_RATIO: f32 = 1.5;
 
-#[derive(Clone, Debug)]
-enum ScoreType {
-    Timed(TimedTracker),
+/// The kinds of issues nodes are scored on
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum ScoreType {
+    /// Failures to communicate with the node
+    Communication,
+    /// The node being behind on network knowledge
+    Knowledge,
+    /// Operations sent to the node which it didn't fulfill
     Op,
 }
 
+impl ScoreType {
+    /// How much this kind of issue weighs in a node's total score
+    fn weighting(&self) -> f32 {
+        match self {
+            Self::Communication => CONN_WEIGHTING,
+            Self::Knowledge => KNOWLEDGE_WEIGHTING,
+            Self::Op => OP_WEIGHTING,
+        }
+    }
+}
+
 /// Severity of dysfunction... Is it not yet fully dysfunctional? But out of line with neighbours?
 /// Then it's Suspicious, or, if it's gone too far we can check if it's Dysfunctional.
 /// These can be passed in to the `check_for_maliciousness` function.
-#[derive(Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum DysfunctionSeverity {
     /// A node is deemed suspicous (more dysfunction than neighbours, but not yet fully dysfunctional)
     Suspicious,
@@ -38,6 +55,80 @@ pub enum DysfunctionSeverity {
     Dysfunctional,
 }
 
+impl DysfunctionSeverity {
+    // Ratio to the mean score a node's score has to reach to be of this severity
+    fn mean_ratio(&self) -> f32 {
+        match self {
+            Self::Dysfunctional => DYSFUNCTION_MEAN_RATIO,
+            Self::Suspicious => SUSPECT_MEAN_RATIO,
+        }
+    }
+}
+
+/// A tracked node's scores, as compared to its neighbours
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct NodeDysfunctionReport {
+    /// Weighted score per kind of issue
+    pub scores: BTreeMap<ScoreType, f32>,
+    /// Sum of the weighted scores
+    pub total: f32,
+    /// The node's current severity of dysfunction, if it has any
+    pub severity: Option<DysfunctionSeverity>,
+}
+
+/// Scores of all tracked nodes, for operators to inspect
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct DysfunctionReport {
+    /// Mean of the nodes' total scores, which the severities are relative to
+    pub mean: f32,
+    /// Scores per tracked node
+    #[serde(with = "hex_names")]
+    pub nodes: BTreeMap<XorName, NodeDysfunctionReport>,
+}
+
+// Keys the nodes by the hex encoding of their names, as some formats, like JSON,
+// only support strings as keys
+mod hex_names {
+    use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+    use std::collections::BTreeMap;
+    use xor_name::{XorName, XOR_NAME_LEN};
+
+    pub(super) fn serialize<S: Serializer, T: Serialize>(
+        nodes: &BTreeMap<XorName, T>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(
+            nodes
+                .iter()
+                .map(|(name, value)| (format!("{:x}", name), value)),
+        )
+    }
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>, T: Deserialize<'de>>(
+        deserializer: D,
+    ) -> Result<BTreeMap<XorName, T>, D::Error> {
+        BTreeMap::<String, T>::deserialize(deserializer)?
+            .into_iter()
+            .map(|(hex, value)| {
+                let name = parse_name(&hex)
+                    .ok_or_else(|| D::Error::custom(format!("invalid node name: {}", hex)))?;
+                Ok((name, value))
+            })
+            .collect()
+    }
+
+    fn parse_name(hex: &str) -> Option<XorName> {
+        if hex.len() != 2 * XOR_NAME_LEN || !hex.is_ascii() {
+            return None;
+        }
+        let mut name = [0; XOR_NAME_LEN];
+        for (byte, digits) in name.iter_mut().zip(hex.as_bytes().chunks(2)) {
+            *byte = u8::from_str_radix(std::str::from_utf8(digits).ok()?, 16).ok()?;
+        }
+        Some(XorName(name))
+    }
+}
+
 impl DysfunctionDetection {
     /// Helper func to get vec of a node and their neighbours for comparison
     pub fn get_node_and_neighbours_vec(&self) -> Vec<(XorName, Vec<XorName>)> {
@@ -56,10 +147,14 @@ impl DysfunctionDetection {
 
         // loop over all node/neighbour comparisons
         for (node, neighbours) in self.get_node_and_neighbours_vec() {
-            let score_type = score_type.clone();
+            let timed_tracker = match score_type {
+                ScoreType::Communication => Some(&self.communication_issues),
+                ScoreType::Knowledge => Some(&self.knowledge_issues),
+                ScoreType::Op => None,
+            };
 
-            let (count_at_node, all_neighbourhood_counts) = match score_type {
-                ScoreType::Timed(tracker) => {
+            let (count_at_node, all_neighbourhood_counts) = match timed_tracker {
+                Some(tracker) => {
                     let count = if let Some(entry) = tracker.get(&node) {
                         entry.value().read().await.len()
                     } else {
@@ -81,7 +176,7 @@ impl DysfunctionDetection {
 
                     (count, all_neighbourhood_counts)
                 }
-                ScoreType::Op => {
+                None => {
                     let count = if let Some(entry) = self.unfulfilled_ops.get(&node) {
                         entry.value().read().await.len()
                     } else {
@@ -122,34 +217,46 @@ impl DysfunctionDetection {
         score_map
     }
 
-    async fn get_weighted_scores(&self) -> (BTreeMap<XorName, f32>, f32) {
-        trace!("Getting weighted scores");
+    // Weighted scores of each node per kind of issue
+    async fn get_weighted_scores_by_type(&self) -> BTreeMap<XorName, BTreeMap<ScoreType, f32>> {
         let ops_scores = self.calculate_scores(ScoreType::Op).await;
+        let conn_scores = self.calculate_scores(ScoreType::Communication).await;
+        let knowledge_scores = self.calculate_scores(ScoreType::Knowledge).await;
 
-        let conn_scores = self
-            .calculate_scores(ScoreType::Timed(self.communication_issues.clone()))
-            .await;
-        let knowledge_scores = self
-            .calculate_scores(ScoreType::Timed(self.knowledge_issues.clone()))
-            .await;
-
-        let mut final_scores = BTreeMap::default();
-        let mut scores_only = vec![];
-        // now we loop to get final scores
+        let mut scores_by_type = BTreeMap::default();
         for (name, score) in ops_scores {
             trace!("Ops sore: {name}, {score}");
 
-            let ops_score = score * OP_WEIGHTING;
+            let ops_score = score * ScoreType::Op.weighting();
 
             let node_conn_score = *conn_scores.get(&name).unwrap_or(&1.0);
-            let node_conn_score = node_conn_score * CONN_WEIGHTING;
+            let node_conn_score = node_conn_score * ScoreType::Communication.weighting();
 
             let node_knowledge_score = *knowledge_scores.get(&name).unwrap_or(&1.0);
-            let node_knowledge_score = node_knowledge_score * KNOWLEDGE_WEIGHTING;
+            let node_knowledge_score = node_knowledge_score * ScoreType::Knowledge.weighting();
 
             trace!("Conns score: {name}, {node_conn_score}");
             trace!("Knowledge score: {name}, {node_knowledge_score}");
-            let final_score = ops_score + node_conn_score + node_knowledge_score;
+
+            let scores = BTreeMap::from([
+                (ScoreType::Op, ops_score),
+                (ScoreType::Communication, node_conn_score),
+                (ScoreType::Knowledge, node_knowledge_score),
+            ]);
+            let _prev = scores_by_type.insert(name, scores);
+        }
+
+        scores_by_type
+    }
+
+    async fn get_weighted_scores(&self) -> (BTreeMap<XorName, f32>, f32) {
+        trace!("Getting weighted scores");
+
+        let mut final_scores = BTreeMap::default();
+        let mut scores_only = vec![];
+        // now we loop to get final scores
+        for (name, scores) in self.get_weighted_scores_by_type().await {
+            let final_score = scores.values().sum();
 
             scores_only.push(final_score);
             let _prev = final_scores.insert(name, final_score);
@@ -191,10 +298,7 @@ impl DysfunctionDetection {
 
         let (final_scores, mean) = self.get_weighted_scores().await;
 
-        let to_beat = match severity {
-            DysfunctionSeverity::Dysfunctional => mean * DYSFUNCTION_MEAN_RATIO,
-            DysfunctionSeverity::Suspicious => mean * SUSPECT_MEAN_RATIO,
-        };
+        let to_beat = mean * severity.mean_ratio();
 
         for (name, nodes_score) in final_scores {
             trace!(
@@ -210,4 +314,38 @@ impl DysfunctionDetection {
 
         Ok(dysfunctional_nodes)
     }
+
+    /// Report the current scores of all tracked nodes, along with their severity of dysfunction
+    pub async fn report(&self) -> Result<DysfunctionReport> {
+        self.cleanup_time_sensistive_checks().await?;
+
+        let scores_by_type = self.get_weighted_scores_by_type().await;
+        let totals: Vec<f32> = scores_by_type
+            .values()
+            .map(|scores| scores.values().sum())
+            .collect();
+        let mean = f32::max(get_mean_of(&totals).unwrap_or(1.0), 1.0);
+
+        let nodes = scores_by_type
+            .into_iter()
+            .map(|(name, scores)| {
+                let total = scores.values().sum();
+                let severity = [
+                    DysfunctionSeverity::Dysfunctional,
+                    DysfunctionSeverity::Suspicious,
+                ]
+                .into_iter()
+                .find(|severity| total >= mean * severity.mean_ratio());
+
+                let report = NodeDysfunctionReport {
+                    scores,
+                    total,
+                    severity,
+                };
+                (name, report)
+            })
+            .collect();
+
+        Ok(DysfunctionReport { mean, nodes })
+    }
 }
@@ -48,6 +48,7 @@ mod detection;
 mod error;
 mod network_knowledge;
 mod operations;
+mod trackers;
 
 use xor_name::XorName;
 
@@ -58,7 +59,8 @@ use std::sync::Arc;
 use std::time::Instant;
 use tokio::sync::RwLock;
 
-pub use detection::DysfunctionSeverity;
+pub use detection::{DysfunctionReport, DysfunctionSeverity, NodeDysfunctionReport, ScoreType};
+pub use trackers::DysfunctionTrackers;
 
 pub use error::Error;
 
@@ -148,6 +150,7 @@ impl DysfunctionDetection {
         for key in &all_keys {
             if !current_members.contains(key) {
                 let _prev = self.communication_issues.remove(key);
+                let _prev = self.knowledge_issues.remove(key);
                 let _prev = self.unfulfilled_ops.remove(key);
                 let _prev = self.closest_nodes_to.remove(key);
             }
@@ -0,0 +1,219 @@
+// Copyright 2022 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use crate::{
+    detection::RECENT_ISSUE_DURATION, error::Result, DysfunctionDetection, NodeIdentifier,
+    OperationId, TimedTracker,
+};
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+use std::time::{Duration, Instant, SystemTime};
+
+/// A snapshot of all the issues tracked against nodes, which can be persisted
+/// so that a restarted node doesn't forget about the nodes it suspects.
+///
+/// As `Instant`s can't outlive the process, timed issues are stored as their age
+/// at the time the snapshot was taken.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct DysfunctionTrackers {
+    saved_at: Option<SystemTime>,
+    communication_issues: BTreeMap<NodeIdentifier, Vec<Duration>>,
+    knowledge_issues: BTreeMap<NodeIdentifier, Vec<Duration>>,
+    unfulfilled_ops: BTreeMap<NodeIdentifier, Vec<OperationId>>,
+}
+
+impl DysfunctionDetection {
+    /// Take a snapshot of the issues currently tracked against nodes
+    pub async fn export_trackers(&self) -> DysfunctionTrackers {
+        let now = Instant::now();
+
+        let mut unfulfilled_ops = BTreeMap::new();
+        for entry in self.unfulfilled_ops.iter() {
+            let ops = entry.value().read().await.clone();
+            let _prev = unfulfilled_ops.insert(*entry.key(), ops);
+        }
+
+        DysfunctionTrackers {
+            saved_at: Some(SystemTime::now()),
+            communication_issues: export_timed(&self.communication_issues, now).await,
+            knowledge_issues: export_timed(&self.knowledge_issues, now).await,
+            unfulfilled_ops,
+        }
+    }
+
+    /// Restore the issues from a snapshot taken with `export_trackers`, in addition to any already tracked.
+    /// Timed issues which are no longer recent by now are dropped.
+    pub async fn import_trackers(&self, trackers: DysfunctionTrackers) -> Result<()> {
+        // Account for the time which passed since the snapshot was taken,
+        // none if the clock went backwards since
+        let elapsed = trackers
+            .saved_at
+            .and_then(|saved_at| SystemTime::now().duration_since(saved_at).ok())
+            .unwrap_or(Duration::ZERO);
+        let now = Instant::now();
+
+        import_timed(
+            &self.communication_issues,
+            trackers.communication_issues,
+            elapsed,
+            now,
+        )
+        .await;
+        import_timed(
+            &self.knowledge_issues,
+            trackers.knowledge_issues,
+            elapsed,
+            now,
+        )
+        .await;
+
+        for (node, ops) in trackers.unfulfilled_ops {
+            let entry = self.unfulfilled_ops.entry(node).or_default().clone();
+            let mut tracked = entry.write().await;
+            for op_id in ops {
+                if !tracked.contains(&op_id) {
+                    tracked.push(op_id);
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+async fn export_timed(
+    tracker: &TimedTracker,
+    now: Instant,
+) -> BTreeMap<NodeIdentifier, Vec<Duration>> {
+    let mut exported = BTreeMap::new();
+    for entry in tracker.iter() {
+        let ages = entry
+            .value()
+            .read()
+            .await
+            .iter()
+            .map(|time| now.saturating_duration_since(*time))
+            .collect();
+        let _prev = exported.insert(*entry.key(), ages);
+    }
+    exported
+}
+
+async fn import_timed(
+    tracker: &TimedTracker,
+    issues: BTreeMap<NodeIdentifier, Vec<Duration>>,
+    elapsed: Duration,
+    now: Instant,
+) {
+    for (node, ages) in issues {
+        let mut times: VecDeque<Instant> = ages
+            .into_iter()
+            .map(|age| age + elapsed)
+            .filter(|age| *age < RECENT_ISSUE_DURATION)
+            .filter_map(|age| now.checked_sub(age))
+            .collect();
+        if times.is_empty() {
+            continue;
+        }
+
+        let entry = tracker.entry(node).or_default().clone();
+        let mut tracked = entry.write().await;
+        tracked.append(&mut times);
+        tracked.make_contiguous().sort();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DysfunctionTrackers;
+    use crate::{tests::init_test_logger, DysfunctionDetection};
+
+    use eyre::Error;
+    use std::time::{Duration, SystemTime};
+    use xor_name::{rand::random as random_xorname, XorName};
+
+    type Result<T, E = Error> = std::result::Result<T, E>;
+
+    #[tokio::test]
+    async fn trackers_survive_a_restart() -> Result<()> {
+        init_test_logger();
+        let adults = (0..10).map(|_| random_xorname()).collect::<Vec<XorName>>();
+        let dysfunctional_detection = DysfunctionDetection::new(adults.clone(), 3);
+
+        dysfunctional_detection.track_comm_issue(adults[0]).await?;
+        dysfunctional_detection
+            .add_a_pending_request_operation(adults[2], [1; 32])
+            .await;
+
+        let trackers = dysfunctional_detection.export_trackers().await;
+        let serialised = bincode::serialize(&trackers)?;
+        let trackers: DysfunctionTrackers = bincode::deserialize(&serialised)?;
+
+        let restarted = DysfunctionDetection::new(adults.clone(), 3);
+        restarted.import_trackers(trackers.clone()).await?;
+
+        let reexported = restarted.export_trackers().await;
+        assert_eq!(reexported.communication_issues.len(), 1);
+        assert!(reexported.communication_issues.contains_key(&adults[0]));
+        assert_eq!(reexported.unfulfilled_ops, trackers.unfulfilled_ops);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn stale_issues_are_not_imported() -> Result<()> {
+        init_test_logger();
+        let adults = (0..10).map(|_| random_xorname()).collect::<Vec<XorName>>();
+        let dysfunctional_detection = DysfunctionDetection::new(adults.clone(), 3);
+
+        let mut trackers = DysfunctionTrackers {
+            saved_at: Some(SystemTime::now() - Duration::from_secs(60 * 10)),
+            ..Default::default()
+        };
+        // Still recent by now
+        let _prev = trackers
+            .communication_issues
+            .insert(adults[0], vec![Duration::from_secs(60)]);
+        // Recent when saved, but no longer is
+        let _prev = trackers
+            .communication_issues
+            .insert(adults[1], vec![Duration::from_secs(60 * 10)]);
+
+        dysfunctional_detection.import_trackers(trackers).await?;
+
+        let exported = dysfunctional_detection.export_trackers().await;
+        assert_eq!(exported.communication_issues.len(), 1);
+        assert!(exported.communication_issues.contains_key(&adults[0]));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn issues_are_imported_if_the_clock_went_backwards() -> Result<()> {
+        init_test_logger();
+        let adults = (0..10).map(|_| random_xorname()).collect::<Vec<XorName>>();
+        let dysfunctional_detection = DysfunctionDetection::new(adults.clone(), 3);
+
+        let mut trackers = DysfunctionTrackers {
+            saved_at: Some(SystemTime::now() + Duration::from_secs(60 * 10)),
+            ..Default::default()
+        };
+        let _prev = trackers
+            .communication_issues
+            .insert(adults[0], vec![Duration::from_secs(60)]);
+
+        dysfunctional_detection.import_trackers(trackers).await?;
+
+        let exported = dysfunctional_detection.export_trackers().await;
+        assert_eq!(exported.communication_issues.len(), 1);
+        assert!(exported.communication_issues.contains_key(&adults[0]));
+
+        Ok(())
+    }
+}
@@ -117,6 +117,10 @@ async fn main() -> Result<()> {
             build_args.push("--features");
             build_args.push("unstable-wiremsg-debuginfo");
         }
+        if cfg!(feature = "otlp") {
+            build_args.push("--features");
+            build_args.push("otlp");
+        }
 
         info!("Building current sn_node");
         debug!("Building current sn_node with args: {:?}", build_args);